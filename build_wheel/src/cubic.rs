@@ -1,10 +1,12 @@
 use feos_core::cubic::PengRobinson;
 use feos_core::python::cubic::{
-    PyBinaryRecord, PyPengRobinsonParameters, PyPengRobinsonRecord, PyPureRecord,
+    PyBinaryRecord, PyNrtl, PyParameterDatabase, PyPengRobinsonParameters, PyPengRobinsonRecord,
+    PyPureRecord,
 };
 use feos_core::*;
 use numpy::convert::ToPyArray;
 use numpy::{PyArray1, PyArray2};
+use pyo3::basic::CompareOp;
 use pyo3::exceptions::{PyIndexError, PyValueError};
 use pyo3::prelude::*;
 use quantity::python::*;
@@ -33,6 +35,67 @@ impl PyPengRobinson {
     fn new(parameters: PyPengRobinsonParameters) -> Self {
         Self(Rc::new(PengRobinson::new(parameters.0.clone())))
     }
+
+    /// Create the Peng-Robinson equation of state directly from critical constants.
+    ///
+    /// Parameters
+    /// ----------
+    /// tc : List[float]
+    ///     Critical temperatures in Kelvin.
+    /// pc : List[float]
+    ///     Critical pressures in Pascal.
+    /// acentric_factor : List[float]
+    ///     Acentric factors.
+    /// molarweight : List[float]
+    ///     Molar weights in g/mol.
+    ///
+    /// Returns
+    /// -------
+    /// PengRobinson
+    #[staticmethod]
+    #[pyo3(text_signature = "(tc, pc, acentric_factor, molarweight)")]
+    fn from_critical_constants(
+        tc: Vec<f64>,
+        pc: Vec<f64>,
+        acentric_factor: Vec<f64>,
+        molarweight: Vec<f64>,
+    ) -> PyResult<Self> {
+        Ok(Self(PengRobinson::from_critical_constants(
+            &tc,
+            &pc,
+            &acentric_factor,
+            &molarweight,
+        )?))
+    }
+
+    /// The parameters of the equation of state.
+    #[getter]
+    fn get_parameters(&self) -> PyPengRobinsonParameters {
+        PyPengRobinsonParameters(self.0.parameters().clone())
+    }
+
+    fn __copy__(&self) -> Self {
+        self.clone()
+    }
+
+    /// Creates an independent copy of the equation of state.
+    fn __deepcopy__(&self, _memo: &PyAny) -> Self {
+        Self(Rc::new((*self.0).clone()))
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python) -> PyResult<PyObject> {
+        let eq = || -> PyResult<bool> {
+            Ok(self
+                .get_parameters()
+                .parameters_eq(&other.get_parameters())?
+                && self.0.settings_eq(&other.0))
+        };
+        Ok(match op {
+            CompareOp::Eq => eq()?.into_py(py),
+            CompareOp::Ne => (!eq()?).into_py(py),
+            _ => py.NotImplemented(),
+        })
+    }
 }
 
 impl_equation_of_state!(PyPengRobinson);
@@ -41,16 +104,22 @@ impl_virial_coefficients!(PyPengRobinson);
 impl_state!(PengRobinson, PyPengRobinson);
 impl_state_molarweight!(PengRobinson, PyPengRobinson);
 impl_phase_equilibrium!(PengRobinson, PyPengRobinson);
+impl_planar_interface!(PengRobinson);
 
 #[pymodule]
 pub fn cubic(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyPengRobinson>()?;
     m.add_class::<PyPengRobinsonParameters>()?;
+    m.add_class::<PyParameterDatabase>()?;
     m.add_class::<PyPengRobinsonRecord>()?;
+    m.add_class::<PyNrtl>()?;
     m.add_class::<PyPureRecord>()?;
     m.add_class::<PyBinaryRecord>()?;
     m.add_class::<PyState>()?;
+    m.add_class::<PyStabilityResult>()?;
     m.add_class::<PyPhaseDiagram>()?;
     m.add_class::<PyPhaseEquilibrium>()?;
+    m.add_class::<PyMultiPhaseEquilibrium>()?;
+    m.add_class::<PyPlanarInterface>()?;
     Ok(())
 }
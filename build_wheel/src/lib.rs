@@ -1,33 +1,55 @@
-use feos_core::python::joback::PyJobackRecord;
+use feos_core::python::dippr::{PyDippr, PyDipprRecord};
+use feos_core::python::errors::add_exceptions;
+use feos_core::python::joback::{PyJoback, PyJobackRecord};
+use feos_core::python::nasa::{PyNasa, PyNasaRecord};
+use feos_core::python::options::PyOptions;
 use feos_core::python::parameter::*;
-use feos_core::{Contributions, Verbosity};
-use feos_core::parameter::IdentifierOption;
+use feos_core::{
+    Acceleration, Basis, Contributions, JacobianUpdate, MolefracsNormalization, Verbosity,
+};
+use feos_core::parameter::{DuplicatePolicy, IdentifierOption};
 use pyo3::prelude::*;
 use pyo3::wrap_pymodule;
 use quantity::python::__PYO3_PYMODULE_DEF_QUANTITY;
 
 mod cubic;
+mod srk;
 mod user_defined;
 use cubic::__PYO3_PYMODULE_DEF_CUBIC;
+use srk::__PYO3_PYMODULE_DEF_SRK;
 use user_defined::__PYO3_PYMODULE_DEF_USER_DEFINED;
 
 #[pymodule]
 pub fn feos_core(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyIdentifier>()?;
     m.add_class::<Verbosity>()?;
+    m.add_class::<JacobianUpdate>()?;
+    m.add_class::<Acceleration>()?;
     m.add_class::<Contributions>()?;
+    m.add_class::<Basis>()?;
+    m.add_class::<MolefracsNormalization>()?;
     m.add_class::<PyChemicalRecord>()?;
     m.add_class::<PyJobackRecord>()?;
+    m.add_class::<PyJoback>()?;
+    m.add_class::<PyNasaRecord>()?;
+    m.add_class::<PyNasa>()?;
+    m.add_class::<PyDipprRecord>()?;
+    m.add_class::<PyDippr>()?;
     m.add_class::<IdentifierOption>()?;
+    m.add_class::<DuplicatePolicy>()?;
+    m.add_class::<PyOptions>()?;
+    add_exceptions(py, m)?;
 
     m.add_wrapped(wrap_pymodule!(user_defined))?;
     m.add_wrapped(wrap_pymodule!(cubic))?;
+    m.add_wrapped(wrap_pymodule!(srk))?;
     m.add_wrapped(wrap_pymodule!(quantity))?;
 
     py.run(
         "\
 import sys
 sys.modules['feos_core.cubic'] = cubic
+sys.modules['feos_core.srk'] = srk
 sys.modules['feos_core.user_defined'] = user_defined
 quantity.SINumber.__module__ = 'feos_core.si'
 quantity.SIArray1.__module__ = 'feos_core.si'
@@ -0,0 +1,126 @@
+use feos_core::cubic::{SoaveRedlichKwong, SoaveRedlichKwongParameters};
+use feos_core::parameter::Parameter;
+use feos_core::python::cubic::{PyNrtl, PyPengRobinsonRecord};
+use feos_core::python::srk::{
+    PyBinaryRecord, PyParameterDatabase, PyPureRecord, PySoaveRedlichKwongParameters,
+};
+use feos_core::*;
+use numpy::convert::ToPyArray;
+use numpy::{PyArray1, PyArray2};
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use quantity::python::*;
+use quantity::si::*;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// A simple version of the Soave-Redlich-Kwong equation of state.
+///
+/// Parameters
+/// ----------
+/// parameters : SoaveRedlichKwongParameters
+///     The parameters of the Soave-Redlich-Kwong equation of state to use.
+///
+/// Returns
+/// -------
+/// SoaveRedlichKwong
+#[pyclass(name = "SoaveRedlichKwong", unsendable)]
+#[pyo3(text_signature = "(parameters)")]
+#[derive(Clone)]
+pub struct PySoaveRedlichKwong(pub Rc<SoaveRedlichKwong>);
+
+#[pymethods]
+impl PySoaveRedlichKwong {
+    #[new]
+    fn new(parameters: PySoaveRedlichKwongParameters) -> Self {
+        Self(Rc::new(SoaveRedlichKwong::new(parameters.0.clone())))
+    }
+
+    /// Create the Soave-Redlich-Kwong equation of state directly from critical constants.
+    ///
+    /// Parameters
+    /// ----------
+    /// tc : List[float]
+    ///     Critical temperatures in Kelvin.
+    /// pc : List[float]
+    ///     Critical pressures in Pascal.
+    /// acentric_factor : List[float]
+    ///     Acentric factors.
+    /// molarweight : List[float]
+    ///     Molar weights in g/mol.
+    ///
+    /// Returns
+    /// -------
+    /// SoaveRedlichKwong
+    #[staticmethod]
+    #[pyo3(text_signature = "(tc, pc, acentric_factor, molarweight)")]
+    fn from_critical_constants(
+        tc: Vec<f64>,
+        pc: Vec<f64>,
+        acentric_factor: Vec<f64>,
+        molarweight: Vec<f64>,
+    ) -> PyResult<Self> {
+        Ok(Self(SoaveRedlichKwong::from_critical_constants(
+            &tc,
+            &pc,
+            &acentric_factor,
+            &molarweight,
+        )?))
+    }
+
+    /// The parameters of the equation of state.
+    #[getter]
+    fn get_parameters(&self) -> PySoaveRedlichKwongParameters {
+        PySoaveRedlichKwongParameters(self.0.parameters().clone())
+    }
+
+    fn __copy__(&self) -> Self {
+        self.clone()
+    }
+
+    /// Creates an independent copy of the equation of state.
+    fn __deepcopy__(&self, _memo: &PyAny) -> Self {
+        Self(Rc::new((*self.0).clone()))
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python) -> PyResult<PyObject> {
+        let eq = || -> PyResult<bool> {
+            Ok(self
+                .get_parameters()
+                .parameters_eq(&other.get_parameters())?
+                && self.0.settings_eq(&other.0))
+        };
+        Ok(match op {
+            CompareOp::Eq => eq()?.into_py(py),
+            CompareOp::Ne => (!eq()?).into_py(py),
+            _ => py.NotImplemented(),
+        })
+    }
+}
+
+impl_equation_of_state!(PySoaveRedlichKwong);
+impl_virial_coefficients!(PySoaveRedlichKwong);
+
+impl_state!(SoaveRedlichKwong, PySoaveRedlichKwong);
+impl_state_molarweight!(SoaveRedlichKwong, PySoaveRedlichKwong);
+impl_phase_equilibrium!(SoaveRedlichKwong, PySoaveRedlichKwong);
+impl_planar_interface!(SoaveRedlichKwong);
+
+#[pymodule]
+pub fn srk(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PySoaveRedlichKwong>()?;
+    m.add_class::<PySoaveRedlichKwongParameters>()?;
+    m.add_class::<PyParameterDatabase>()?;
+    m.add_class::<PyPengRobinsonRecord>()?;
+    m.add_class::<PyNrtl>()?;
+    m.add_class::<PyPureRecord>()?;
+    m.add_class::<PyBinaryRecord>()?;
+    m.add_class::<PyState>()?;
+    m.add_class::<PyStabilityResult>()?;
+    m.add_class::<PyPhaseDiagram>()?;
+    m.add_class::<PyPhaseEquilibrium>()?;
+    m.add_class::<PyMultiPhaseEquilibrium>()?;
+    m.add_class::<PyPlanarInterface>()?;
+    Ok(())
+}
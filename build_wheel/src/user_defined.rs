@@ -16,6 +16,8 @@ use std::rc::Rc;
 /// ----------
 /// obj : python class object
 ///     The class that implements the equation of state.
+///     The method `molar_weight` is optional. Without it,
+///     mass-based properties raise a ValueError.
 ///
 /// Returns
 /// -------
@@ -23,7 +25,7 @@ use std::rc::Rc;
 ///
 /// Raises
 /// ------
-/// RunTimeError
+/// AttributeError
 ///     If the class does not implement all necessary methods.
 #[pyclass(name = "UserDefinedEos", unsendable)]
 #[derive(Clone)]
@@ -59,7 +61,9 @@ pub fn user_defined(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyStateD3DV3>()?;
     m.add_class::<PyUserDefinedEos>()?;
     m.add_class::<PyState>()?;
+    m.add_class::<PyStabilityResult>()?;
     m.add_class::<PyPhaseDiagram>()?;
     m.add_class::<PyPhaseEquilibrium>()?;
+    m.add_class::<PyMultiPhaseEquilibrium>()?;
     Ok(())
 }
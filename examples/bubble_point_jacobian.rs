@@ -0,0 +1,99 @@
+//! Compare the run times of bubble and dew point calculations of
+//! multicomponent n-alkane mixtures for the different treatments of the
+//! Jacobian in the outer loop of the iteration ([JacobianUpdate]).
+//!
+//! Run with `cargo run --release --example bubble_point_jacobian`.
+use feos_core::cubic::PengRobinson;
+use feos_core::{Contributions, EosResult, JacobianUpdate, PhaseEquilibrium, SolverOptions};
+use ndarray::Array1;
+use quantity::si::*;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Critical temperature, critical pressure, acentric factor
+/// and molar weight of propane to n-decane.
+const ALKANES: [(f64, f64, f64, f64); 8] = [
+    (369.83, 4248000.0, 0.152, 44.097),
+    (425.12, 3796000.0, 0.2, 58.123),
+    (469.7, 3370000.0, 0.252, 72.15),
+    (507.6, 3025000.0, 0.301, 86.177),
+    (540.2, 2740000.0, 0.35, 100.204),
+    (568.7, 2490000.0, 0.399, 114.231),
+    (594.6, 2290000.0, 0.445, 128.258),
+    (617.7, 2110000.0, 0.49, 142.285),
+];
+
+const REPETITIONS: u32 = 100;
+
+fn alkanes(n: usize) -> EosResult<Rc<PengRobinson>> {
+    let c = &ALKANES[..n];
+    Ok(PengRobinson::from_critical_constants(
+        &c.iter().map(|c| c.0).collect::<Vec<_>>(),
+        &c.iter().map(|c| c.1).collect::<Vec<_>>(),
+        &c.iter().map(|c| c.2).collect::<Vec<_>>(),
+        &c.iter().map(|c| c.3).collect::<Vec<_>>(),
+    )?)
+}
+
+/// Average time of a bubble point calculation at given temperature and
+/// a dew point calculation at given pressure.
+fn time(
+    eos: &Rc<PengRobinson>,
+    z: &Array1<f64>,
+    jacobian: JacobianUpdate,
+) -> EosResult<(Duration, SINumber, SINumber)> {
+    let options = SolverOptions::new().jacobian(jacobian);
+    let start = Instant::now();
+    let mut result = (0.0 * PASCAL, 0.0 * KELVIN);
+    for _ in 0..REPETITIONS {
+        let bubble = PhaseEquilibrium::bubble_point(
+            eos,
+            350.0 * KELVIN,
+            z,
+            None,
+            None,
+            (options.clone(), options.clone()),
+        )?;
+        let dew = PhaseEquilibrium::dew_point(
+            eos,
+            5.0 * BAR,
+            z,
+            Some(360.0 * KELVIN),
+            None,
+            (options.clone(), options.clone()),
+        )?;
+        result = (
+            bubble.vapor().pressure(Contributions::Total),
+            dew.vapor().temperature,
+        );
+    }
+    Ok((start.elapsed() / REPETITIONS, result.0, result.1))
+}
+
+fn main() -> EosResult<()> {
+    println!("components | Jacobian | time / µs | speedup | bubble pressure | dew temperature");
+    println!("{:-<83}", "");
+    for n in [3, 5, 8] {
+        let eos = alkanes(n)?;
+        let z = Array1::from_elem(n, 1.0 / n as f64);
+        let mut reference = None;
+        for jacobian in [
+            JacobianUpdate::Exact,
+            JacobianUpdate::Reuse,
+            JacobianUpdate::Broyden,
+        ] {
+            let (duration, p, t) = time(&eos, &z, jacobian)?;
+            let reference = *reference.get_or_insert(duration);
+            println!(
+                "{:10} | {:8} | {:9.1} | {:7.2} | {:15.6} | {:.6}",
+                n,
+                format!("{:?}", jacobian),
+                duration.as_secs_f64() * 1e6,
+                reference.as_secs_f64() / duration.as_secs_f64(),
+                p,
+                t
+            );
+        }
+    }
+    Ok(())
+}
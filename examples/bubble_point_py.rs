@@ -0,0 +1,92 @@
+//! Compare the run times of the successive substitution in
+//! [PhaseEquilibrium::bubble_point_py] and [PhaseEquilibrium::dew_point_px]
+//! with the regular bubble and dew point calculations for
+//! propane/n-butane at different temperatures.
+//!
+//! Run with `cargo run --release --example bubble_point_py`.
+use feos_core::cubic::PengRobinson;
+use feos_core::{Contributions, EosResult, PhaseEquilibrium, SolverOptions};
+use ndarray::arr1;
+use quantity::si::*;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const REPETITIONS: u32 = 100;
+
+/// Average time of a bubble point and a dew point calculation at given
+/// temperature for the successive substitution and the regular calculation.
+fn time(
+    eos: &Rc<PengRobinson>,
+    temperature: SINumber,
+) -> EosResult<(Duration, Duration, SINumber, SINumber)> {
+    let z = arr1(&[0.4, 0.6]);
+
+    let start = Instant::now();
+    let mut result = (0.0 * PASCAL, 0.0 * PASCAL);
+    for _ in 0..REPETITIONS {
+        let (p_bubble, _) =
+            PhaseEquilibrium::bubble_point_py(eos, temperature, &z, None, SolverOptions::new())?;
+        let (p_dew, _) =
+            PhaseEquilibrium::dew_point_px(eos, temperature, &z, None, SolverOptions::new())?;
+        result = (p_bubble, p_dew);
+    }
+    let duration_py = start.elapsed() / REPETITIONS;
+
+    let start = Instant::now();
+    for _ in 0..REPETITIONS {
+        let bubble = PhaseEquilibrium::bubble_point(
+            eos,
+            temperature,
+            &z,
+            None,
+            None,
+            (SolverOptions::new(), SolverOptions::new()),
+        )?;
+        let dew = PhaseEquilibrium::dew_point(
+            eos,
+            temperature,
+            &z,
+            None,
+            None,
+            (SolverOptions::new(), SolverOptions::new()),
+        )?;
+        assert!(
+            ((bubble.vapor().pressure(Contributions::Total) - result.0) / result.0)
+                .into_value()?
+                .abs()
+                < 1e-8
+        );
+        assert!(
+            ((dew.vapor().pressure(Contributions::Total) - result.1) / result.1)
+                .into_value()?
+                .abs()
+                < 1e-8
+        );
+    }
+    let duration = start.elapsed() / REPETITIONS;
+    Ok((duration_py, duration, result.0, result.1))
+}
+
+fn main() -> EosResult<()> {
+    let eos = PengRobinson::from_critical_constants(
+        &[369.96, 425.2],
+        &[4250000.0, 3800000.0],
+        &[0.153, 0.199],
+        &[44.0962, 58.123],
+    )?;
+    println!("temperature | py / µs | regular / µs | speedup | bubble pressure | dew pressure");
+    println!("{:-<85}", "");
+    for t in [250.0, 275.0, 300.0, 325.0, 350.0] {
+        let (duration_py, duration, p_bubble, p_dew) = time(&eos, t * KELVIN)?;
+        println!(
+            "{:9} K | {:7.1} | {:12.1} | {:7.2} | {:15.6} | {:.6}",
+            t,
+            duration_py.as_secs_f64() * 1e6,
+            duration.as_secs_f64() * 1e6,
+            duration.as_secs_f64() / duration_py.as_secs_f64(),
+            p_bubble,
+            p_dew
+        );
+    }
+    Ok(())
+}
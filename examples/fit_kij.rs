@@ -0,0 +1,138 @@
+//! Fit the binary interaction parameter of the Peng-Robinson equation of state
+//! to isothermal bubble point pressures and print the resulting binary record.
+//!
+//! The bubble point pressures used here are generated with the Peng-Robinson
+//! equation of state itself (k_ij = 0.015) and serve as a stand-in for
+//! experimental data. The relative deviations of the pressures are minimized
+//! with the Levenberg-Marquardt algorithm using a finite difference Jacobian.
+//!
+//! Run with `cargo run --example fit_kij`.
+use feos_core::cubic::{PengRobinson, PengRobinsonParameters};
+use feos_core::parameter::{BinaryRecord, IdentifierOption, Parameter};
+use feos_core::{Contributions, EosResult, PhaseEquilibrium};
+use ndarray::{arr1, arr2, Array1, Array2, Axis};
+use num_dual::linalg::LU;
+use quantity::si::*;
+use std::rc::Rc;
+
+const PARAMETERS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/peng-robinson.json");
+
+/// Create the equation of state for a given binary interaction parameter.
+fn eos(parameters: &PengRobinsonParameters, k_ij: f64) -> EosResult<Rc<PengRobinson>> {
+    let (pure_records, _) = parameters.records();
+    let binary = arr2(&[[0.0, k_ij], [k_ij, 0.0]]);
+    let parameters = PengRobinsonParameters::from_records(pure_records.to_vec(), binary)?;
+    Ok(Rc::new(PengRobinson::new(Rc::new(parameters))))
+}
+
+/// Minimize the sum of squared residuals with the Levenberg-Marquardt algorithm.
+///
+/// The Jacobian is approximated with central differences. Steps are
+/// accepted if they decrease the objective, otherwise the damping is
+/// increased.
+fn levenberg_marquardt<F>(residuals: F, mut parameters: Array1<f64>) -> EosResult<Array1<f64>>
+where
+    F: Fn(&Array1<f64>) -> EosResult<Array1<f64>>,
+{
+    const MAX_ITER: usize = 50;
+    const TOL: f64 = 1e-12;
+    const STEP: f64 = 1e-6;
+
+    let mut lambda = 1e-3;
+    let mut r = residuals(&parameters)?;
+    let mut cost = r.dot(&r);
+    for _ in 0..MAX_ITER {
+        let mut jacobian = Array2::zeros((r.len(), parameters.len()));
+        for (j, mut column) in jacobian.axis_iter_mut(Axis(1)).enumerate() {
+            let (mut forward, mut backward) = (parameters.clone(), parameters.clone());
+            forward[j] += STEP;
+            backward[j] -= STEP;
+            column.assign(&((residuals(&forward)? - residuals(&backward)?) / (2.0 * STEP)));
+        }
+        let jtj = jacobian.t().dot(&jacobian);
+        let gradient = jacobian.t().dot(&r);
+        loop {
+            let mut a = jtj.clone();
+            a.diag_mut()
+                .zip_mut_with(&jtj.diag(), |a, &d| *a += lambda * d);
+            let step = LU::new(a)?.solve(&-&gradient);
+            let trial = &parameters + &step;
+            let r_trial = residuals(&trial)?;
+            let cost_trial = r_trial.dot(&r_trial);
+            if cost_trial < cost {
+                let converged = cost - cost_trial < TOL * (1.0 + cost);
+                parameters = trial;
+                r = r_trial;
+                cost = cost_trial;
+                lambda /= 10.0;
+                if converged {
+                    return Ok(parameters);
+                }
+                break;
+            }
+            lambda *= 10.0;
+            if lambda > 1e10 {
+                // no further decrease of the objective possible
+                return Ok(parameters);
+            }
+        }
+    }
+    Ok(parameters)
+}
+
+/// Calculate bubble point pressures for the given liquid compositions.
+fn bubble_point_pressures(
+    eos: &Rc<PengRobinson>,
+    temperature: SINumber,
+    x: &Array1<f64>,
+) -> EosResult<Vec<SINumber>> {
+    x.iter()
+        .map(|&x| {
+            let vle = PhaseEquilibrium::bubble_point(
+                eos,
+                temperature,
+                &arr1(&[x, 1.0 - x]),
+                None,
+                None,
+                Default::default(),
+            )?;
+            Ok(vle.vapor().pressure(Contributions::Total))
+        })
+        .collect()
+}
+
+fn main() -> EosResult<()> {
+    let parameters = PengRobinsonParameters::from_json(
+        vec!["propane", "butane"],
+        PARAMETERS,
+        None,
+        IdentifierOption::Name,
+    )?;
+
+    // pseudo-experimental data
+    let temperature = 300.0 * KELVIN;
+    let x = Array1::linspace(0.1, 0.9, 9);
+    let p_exp = bubble_point_pressures(&eos(&parameters, 0.015)?, temperature, &x)?;
+
+    // relative deviations of the bubble point pressures
+    let residuals = |k_ij: &Array1<f64>| -> EosResult<Array1<f64>> {
+        let p = bubble_point_pressures(&eos(&parameters, k_ij[0])?, temperature, &x)?;
+        p.iter()
+            .zip(p_exp.iter())
+            .map(|(&p, &p_exp)| Ok(((p - p_exp) / p_exp).into_value()?))
+            .collect()
+    };
+    let k_ij = levenberg_marquardt(residuals, arr1(&[0.0]))?[0];
+    let r = residuals(&arr1(&[k_ij]))?;
+    println!("k_ij = {:.6} (objective = {:.3e})", k_ij, r.dot(&r));
+
+    // updated binary record
+    let (pure_records, _) = parameters.records();
+    let record = BinaryRecord::new(
+        pure_records[0].identifier.clone(),
+        pure_records[1].identifier.clone(),
+        k_ij,
+    );
+    println!("{}", serde_json::to_string_pretty(&record)?);
+    Ok(())
+}
@@ -0,0 +1,91 @@
+//! Compare the run times of the calculation of binary phase diagrams
+//! with [PhaseDiagram::binary_vle] and of the recalculation of an existing
+//! diagram for a perturbed equation of state with [PhaseDiagram::recalculate],
+//! as it is done repeatedly in parameter fits. Points close to a critical
+//! point can be dropped in the recalculation if the critical composition
+//! moves past them.
+//!
+//! Run with `cargo run --release --example recalculate_phase_diagram`.
+use feos_core::cubic::PengRobinson;
+use feos_core::{EosResult, PhaseDiagram};
+use quantity::si::*;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const REPETITIONS: u32 = 20;
+const POINTS: usize = 51;
+
+/// Propane + n-butane or methane + n-decane with a perturbed
+/// critical temperature of the second component.
+fn eos(heavy: bool, delta_tc: f64) -> EosResult<Rc<PengRobinson>> {
+    Ok(if heavy {
+        PengRobinson::from_critical_constants(
+            &[190.56, 617.7 + delta_tc],
+            &[4599000.0, 2110000.0],
+            &[0.011, 0.49],
+            &[16.04, 142.28],
+        )?
+    } else {
+        PengRobinson::from_critical_constants(
+            &[369.96, 425.2 + delta_tc],
+            &[4250000.0, 3800000.0],
+            &[0.153, 0.199],
+            &[44.0962, 58.123],
+        )?
+    })
+}
+
+/// Average time of a function call.
+fn time<F: FnMut() -> EosResult<PhaseDiagram<SIUnit, PengRobinson>>>(
+    mut f: F,
+) -> EosResult<Duration> {
+    let start = Instant::now();
+    for _ in 0..REPETITIONS {
+        f()?;
+    }
+    Ok(start.elapsed() / REPETITIONS)
+}
+
+fn main() -> EosResult<()> {
+    println!("system            | Delta T_c / K | binary_vle / ms | recalculate / ms | speedup | dropped points");
+    println!("{:-<100}", "");
+    for (name, heavy, temperature) in [
+        ("propane/n-butane", false, 300.0 * KELVIN),
+        ("methane/n-decane", true, 400.0 * KELVIN),
+    ] {
+        let dia = PhaseDiagram::binary_vle(
+            &eos(heavy, 0.0)?,
+            temperature,
+            Some(POINTS),
+            None,
+            Default::default(),
+        )?;
+        for delta_tc in [0.1, 1.0, 5.0] {
+            let perturbed = eos(heavy, delta_tc)?;
+            let new = time(|| {
+                PhaseDiagram::binary_vle(
+                    &perturbed,
+                    temperature,
+                    Some(POINTS),
+                    None,
+                    Default::default(),
+                )
+            })?;
+            let recalculated = time(|| {
+                dia.recalculate(&perturbed, Default::default())
+                    .map(|(dia, _)| dia)
+            })?;
+            let (_, dropped) = dia.recalculate(&perturbed, Default::default())?;
+            println!(
+                "{:17} | {:13} | {:15.3} | {:16.3} | {:7.2} | {}",
+                name,
+                delta_tc,
+                new.as_secs_f64() * 1e3,
+                recalculated.as_secs_f64() * 1e3,
+                new.as_secs_f64() / recalculated.as_secs_f64(),
+                dropped.len()
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,372 @@
+//! C API for the Peng-Robinson equation of state, states and Tp-flash
+//! calculations.
+//!
+//! All quantities are passed in SI units (K, Pa, mol, m³, J, kg). Objects
+//! are returned as opaque pointers that have to be released with the
+//! corresponding `feos_*_free` function. Functions that create objects
+//! return a null pointer, all other functions return a non-zero status if
+//! an error occurs. The message of the last error in the current thread is
+//! available from [feos_last_error]. Panics are caught at the boundary of
+//! the library and reported like errors, so that they never unwind into
+//! the calling program.
+//!
+//! A shared library can be built with
+//! `cargo rustc --release --features feos-core-capi --crate-type cdylib`.
+use crate::cubic::PengRobinson;
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::phase_equilibria::{PhaseEquilibrium, SolverOptions};
+use crate::state::{Contributions, DensityInitialization, Property, State};
+use ndarray::Array1;
+use quantity::si::*;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::rc::Rc;
+use std::slice;
+
+/// Opaque handle of an equation of state.
+pub struct FeosEos(Rc<PengRobinson>);
+
+/// Opaque handle of a thermodynamic state.
+pub struct FeosState(State<SIUnit, PengRobinson>);
+
+/// Opaque handle of a vapor-liquid equilibrium.
+pub struct FeosPhaseEquilibrium(PhaseEquilibrium<SIUnit, PengRobinson, 2>);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Evaluate `f` and convert errors and panics into the message of the last error.
+fn catch<T>(f: impl FnOnce() -> EosResult<T>) -> Option<T> {
+    let message = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => return Some(value),
+        Ok(Err(e)) => e.to_string(),
+        Err(payload) => {
+            let payload = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            format!("panic: {}", payload)
+        }
+    };
+    set_last_error(message);
+    None
+}
+
+fn into_ptr<T>(f: impl FnOnce() -> EosResult<T>) -> *mut T {
+    catch(f).map_or(ptr::null_mut(), |value| Box::into_raw(Box::new(value)))
+}
+
+fn into_status(f: impl FnOnce() -> EosResult<()>) -> c_int {
+    catch(f).map_or(-1, |()| 0)
+}
+
+/// The SI unit in which a property is returned.
+fn si_unit(property: Property) -> SINumber {
+    match property {
+        Property::Temperature => KELVIN,
+        Property::Volume => METER.powi(3),
+        Property::Density => MOL / METER.powi(3),
+        Property::TotalMoles => MOL,
+        Property::Pressure => PASCAL,
+        Property::IsochoricHeatCapacity | Property::IsobaricHeatCapacity => JOULE / MOL / KELVIN,
+        Property::Entropy => JOULE / KELVIN,
+        Property::Enthalpy
+        | Property::HelmholtzEnergy
+        | Property::InternalEnergy
+        | Property::GibbsEnergy => JOULE,
+        Property::MolarEntropy => JOULE / MOL / KELVIN,
+        Property::MolarEnthalpy
+        | Property::MolarHelmholtzEnergy
+        | Property::MolarInternalEnergy
+        | Property::MolarGibbsEnergy => JOULE / MOL,
+        Property::JouleThomson => KELVIN / PASCAL,
+        Property::IsentropicCompressibility | Property::IsothermalCompressibility => 1.0 / PASCAL,
+        Property::TotalMolarWeight => KILOGRAM / MOL,
+        Property::MassDensity => KILOGRAM / METER.powi(3),
+        Property::SpecificEntropy => JOULE / KILOGRAM / KELVIN,
+        Property::SpecificEnthalpy
+        | Property::SpecificHelmholtzEnergy
+        | Property::SpecificInternalEnergy
+        | Property::SpecificGibbsEnergy => JOULE / KILOGRAM,
+        Property::SpeedOfSound => METER / SECOND,
+    }
+}
+
+/// Return the message of the last error in the current thread or a null
+/// pointer if no error occurred.
+///
+/// The pointer is valid until the next error occurs in the current thread.
+#[no_mangle]
+pub extern "C" fn feos_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+/// Create a Peng-Robinson equation of state from critical temperatures (K),
+/// critical pressures (Pa), acentric factors and molar weights (g/mol).
+///
+/// # Safety
+/// All arrays have to contain `components` values.
+#[no_mangle]
+pub unsafe extern "C" fn feos_peng_robinson_new(
+    components: usize,
+    critical_temperature: *const f64,
+    critical_pressure: *const f64,
+    acentric_factor: *const f64,
+    molarweight: *const f64,
+) -> *mut FeosEos {
+    let array = |x: *const f64| slice::from_raw_parts(x, components);
+    into_ptr(|| {
+        PengRobinson::from_critical_constants(
+            array(critical_temperature),
+            array(critical_pressure),
+            array(acentric_factor),
+            array(molarweight),
+        )
+        .map(FeosEos)
+        .map_err(EosError::from)
+    })
+}
+
+/// Release an equation of state.
+///
+/// # Safety
+/// `eos` has to be created by this library or be null.
+#[no_mangle]
+pub unsafe extern "C" fn feos_eos_free(eos: *mut FeosEos) {
+    if !eos.is_null() {
+        drop(Box::from_raw(eos));
+    }
+}
+
+/// Create a state for given temperature (K), pressure (Pa) and amount of
+/// substance of each component (mol).
+///
+/// `phase` selects the initialization of the density iteration:
+/// 0 for the stable phase, 1 for the vapor and 2 for the liquid phase.
+///
+/// # Safety
+/// `eos` has to be a valid equation of state and `moles` has to
+/// contain one value for every component.
+#[no_mangle]
+pub unsafe extern "C" fn feos_state_new_npt(
+    eos: *const FeosEos,
+    temperature: f64,
+    pressure: f64,
+    moles: *const f64,
+    phase: c_int,
+) -> *mut FeosState {
+    let eos = &(*eos).0;
+    into_ptr(|| {
+        let moles = Array1::from(slice::from_raw_parts(moles, eos.components()).to_vec()) * MOL;
+        let density_initialization = match phase {
+            0 => DensityInitialization::None,
+            1 => DensityInitialization::Vapor,
+            2 => DensityInitialization::Liquid,
+            _ => {
+                return Err(EosError::UndeterminedState(format!(
+                    "unknown phase {}",
+                    phase
+                )))
+            }
+        };
+        State::new_npt(
+            eos,
+            temperature * KELVIN,
+            pressure * PASCAL,
+            &moles,
+            density_initialization,
+        )
+        .map(FeosState)
+    })
+}
+
+/// Release a state.
+///
+/// # Safety
+/// `state` has to be created by this library or be null.
+#[no_mangle]
+pub unsafe extern "C" fn feos_state_free(state: *mut FeosState) {
+    if !state.is_null() {
+        drop(Box::from_raw(state));
+    }
+}
+
+/// Calculate a property of a state in SI units.
+///
+/// `property` is the name of the corresponding method of the Rust
+/// `State`, e.g., `"molar_enthalpy"` or `"c_p"`.
+///
+/// # Safety
+/// `state` has to be a valid state, `property` a null-terminated
+/// string and `value` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn feos_state_property(
+    state: *const FeosState,
+    property: *const c_char,
+    value: *mut f64,
+) -> c_int {
+    let state = &(*state).0;
+    into_status(|| {
+        let property: Property = CStr::from_ptr(property)
+            .to_str()
+            .map_err(|e| EosError::UnknownProperty(e.to_string()))?
+            .parse()?;
+        *value = state
+            .get(property, Contributions::Total)
+            .to_reduced(si_unit(property))?;
+        Ok(())
+    })
+}
+
+/// Write the mole fractions of a state to `molefracs`.
+///
+/// # Safety
+/// `state` has to be a valid state and `molefracs` has to
+/// provide space for one value for every component.
+#[no_mangle]
+pub unsafe extern "C" fn feos_state_molefracs(
+    state: *const FeosState,
+    molefracs: *mut f64,
+) -> c_int {
+    let x = &(*state).0.molefracs;
+    into_status(|| {
+        slice::from_raw_parts_mut(molefracs, x.len()).copy_from_slice(x.as_slice().unwrap());
+        Ok(())
+    })
+}
+
+/// Perform a Tp-flash calculation for given temperature (K), pressure (Pa)
+/// and amount of substance of each component in the feed (mol).
+///
+/// If the feed is stable, a null pointer is returned and the last error
+/// is set accordingly.
+///
+/// # Safety
+/// `eos` has to be a valid equation of state and `feed` has to
+/// contain one value for every component.
+#[no_mangle]
+pub unsafe extern "C" fn feos_tp_flash(
+    eos: *const FeosEos,
+    temperature: f64,
+    pressure: f64,
+    feed: *const f64,
+) -> *mut FeosPhaseEquilibrium {
+    let eos = &(*eos).0;
+    into_ptr(|| {
+        let feed = Array1::from(slice::from_raw_parts(feed, eos.components()).to_vec()) * MOL;
+        PhaseEquilibrium::tp_flash(
+            eos,
+            temperature * KELVIN,
+            pressure * PASCAL,
+            &feed,
+            None,
+            SolverOptions::default(),
+            None,
+        )
+        .map(FeosPhaseEquilibrium)
+    })
+}
+
+/// Release a phase equilibrium.
+///
+/// # Safety
+/// `vle` has to be created by this library or be null.
+#[no_mangle]
+pub unsafe extern "C" fn feos_phase_equilibrium_free(vle: *mut FeosPhaseEquilibrium) {
+    if !vle.is_null() {
+        drop(Box::from_raw(vle));
+    }
+}
+
+/// Return a copy of the vapor phase of a phase equilibrium.
+///
+/// # Safety
+/// `vle` has to be a valid phase equilibrium.
+#[no_mangle]
+pub unsafe extern "C" fn feos_phase_equilibrium_vapor(
+    vle: *const FeosPhaseEquilibrium,
+) -> *mut FeosState {
+    into_ptr(|| Ok(FeosState((*vle).0.vapor().clone())))
+}
+
+/// Return a copy of the liquid phase of a phase equilibrium.
+///
+/// # Safety
+/// `vle` has to be a valid phase equilibrium.
+#[no_mangle]
+pub unsafe extern "C" fn feos_phase_equilibrium_liquid(
+    vle: *const FeosPhaseEquilibrium,
+) -> *mut FeosState {
+    into_ptr(|| Ok(FeosState((*vle).0.liquid().clone())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_capi() {
+        unsafe {
+            let eos = feos_peng_robinson_new(
+                2,
+                [369.96, 425.2].as_ptr(),
+                [4250000.0, 3800000.0].as_ptr(),
+                [0.153, 0.199].as_ptr(),
+                [44.0962, 58.123].as_ptr(),
+            );
+            assert!(!eos.is_null());
+
+            // all properties are available in SI units
+            let state = feos_state_new_npt(eos, 300.0, 1e5, [0.5, 0.5].as_ptr(), 1);
+            assert!(!state.is_null());
+            let mut value = 0.0;
+            for property in Property::ALL {
+                let name = CString::new(property.name()).unwrap();
+                assert_eq!(feos_state_property(state, name.as_ptr(), &mut value), 0);
+            }
+            let name = CString::new("pressure").unwrap();
+            feos_state_property(state, name.as_ptr(), &mut value);
+            assert_relative_eq!(value, 1e5, max_relative = 1e-8);
+            let name = CString::new("unknown").unwrap();
+            assert_eq!(feos_state_property(state, name.as_ptr(), &mut value), -1);
+            assert!(!feos_last_error().is_null());
+            feos_state_free(state);
+
+            // Tp-flash
+            let vle = feos_tp_flash(eos, 300.0, 5e5, [0.5, 0.5].as_ptr());
+            assert!(!vle.is_null());
+            let (vapor, liquid) = (
+                feos_phase_equilibrium_vapor(vle),
+                feos_phase_equilibrium_liquid(vle),
+            );
+            let (mut y, mut x) = ([0.0; 2], [0.0; 2]);
+            assert_eq!(feos_state_molefracs(vapor, y.as_mut_ptr()), 0);
+            assert_eq!(feos_state_molefracs(liquid, x.as_mut_ptr()), 0);
+            assert!(y[0] > 0.5 && x[0] < 0.5);
+            feos_state_free(vapor);
+            feos_state_free(liquid);
+            feos_phase_equilibrium_free(vle);
+
+            // errors result in null pointers
+            assert!(feos_tp_flash(eos, 300.0, 1e5, [0.5, 0.5].as_ptr()).is_null());
+            feos_eos_free(eos);
+
+            // panics are reported as errors
+            assert_eq!(into_status(|| panic!("unexpected")), -1);
+            let error = CStr::from_ptr(feos_last_error()).to_str().unwrap();
+            assert_eq!(error, "panic: unexpected");
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Physical constants (CODATA 2018) and reference values that are
+//! used by the models in reduced units.
+
+/// Boltzmann constant in J/K
+pub(crate) const KB: f64 = 1.380649e-23;
+/// Avogadro constant in 1/mol
+pub(crate) const NAV: f64 = 6.02214076e23;
+/// Ideal gas constant in J/(mol K)
+pub(crate) const RGAS: f64 = KB * NAV;
+/// Cubic Angstrom in m³
+pub(crate) const A3: f64 = 1e-30;
+/// Boltzmann constant divided by a cubic Angstrom in J/(K m³)
+pub(crate) const KB_A3: f64 = KB / A3;
+/// Avogadro constant times a cubic Angstrom to convert densities from mol/m³ to 1/Å³
+pub(crate) const NAV_A3: f64 = NAV * A3;
+/// Temperature of the reference state of the ideal gas contributions in K
+pub(crate) const T0: f64 = 298.15;
+/// Default standard pressure of the ideal gas contributions in Pa
+pub(crate) const P0: f64 = 1.0e5;
@@ -1,36 +1,61 @@
-//! Implementation of the Peng-Robinson equation of state.
+//! Implementation of the Peng-Robinson and the Soave-Redlich-Kwong equation of state.
 //!
 //! This module acts as a reference on how a simple equation
 //! of state - with a single contribution to the Helmholtz energy - can be implemented.
 //! The implementation closely follows the form of the equations given in
 //! [this wikipedia article](https://en.wikipedia.org/wiki/Cubic_equations_of_state#Peng%E2%80%93Robinson_equation_of_state).
+//! Both models share the pure substance records and differ only in
+//! their universal constants and default alpha functions. Other alpha
+//! functions can be selected for every substance with [AlphaFunction].
+//! Besides the van der Waals one-fluid mixing rules, the Huron-Vidal mixing
+//! rule can be used to combine the cubic equations of state with an
+//! activity coefficient model (see [MixingRule]).
+use crate::constants::{KB_A3, NAV};
 use crate::equation_of_state::{
-    EquationOfState, HelmholtzEnergy, HelmholtzEnergyDual, IdealGasContribution,
+    Capabilities, EquationOfState, HelmholtzEnergy, HelmholtzEnergyDual, IdealGasContribution,
 };
 use crate::joback::{Joback, JobackRecord};
-use crate::parameter::{Identifier, Parameter, ParameterError, PureRecord};
-use crate::si::{GRAM, MOL};
+use crate::parameter::{
+    deserialize_with_unit, Identifier, Kelvin, Parameter, ParameterError, Pascal, PureRecord,
+};
+use crate::phase_equilibria::SaturationTable;
+use crate::si::{GRAM, JOULE, METER, MOL};
 use crate::state::StateHD;
-use crate::MolarWeight;
-use ndarray::{Array1, Array2};
-use num_dual::DualNum;
+use crate::surface_tension::InfluenceParameter;
+use crate::{EosError, EosResult, EosUnit, MolarWeight};
+use ndarray::{Array1, Array2, Axis};
+use num_dual::{Dual, Dual3, Dual3_64, Dual64, DualNum, DualVec64, HyperDual, HyperDual64};
 use quantity::si::{SIArray1, SIUnit};
+use quantity::QuantityScalar;
 use serde::{Deserialize, Serialize};
 use std::f64::consts::SQRT_2;
 use std::fmt;
+use std::ops::{Add, Mul};
 use std::rc::Rc;
 
-const KB_A3: f64 = 13806490.0;
-
 /// Peng-Robinson parameters for a single substance.
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct PengRobinsonRecord {
     /// critical temperature in Kelvin
+    #[serde(deserialize_with = "deserialize_with_unit::<_, Kelvin>")]
     tc: f64,
     /// critical pressure in Pascal
+    #[serde(deserialize_with = "deserialize_with_unit::<_, Pascal>")]
     pc: f64,
     /// acentric factor
     acentric_factor: f64,
+    /// volume translation in m³/mol
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    c: Option<f64>,
+    /// alpha function, defaults to the Soave alpha function
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alpha: Option<AlphaFunction>,
+    /// influence parameter of the square gradient theory in J m⁵/mol²
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    influence_parameter: Option<f64>,
 }
 
 impl PengRobinsonRecord {
@@ -40,6 +65,105 @@ impl PengRobinsonRecord {
             tc,
             pc,
             acentric_factor,
+            c: None,
+            alpha: None,
+            influence_parameter: None,
+        }
+    }
+
+    /// Use the given volume translation `c` (in m³/mol) according to
+    /// [Péneloux et al., 1982](https://doi.org/10.1016/0378-3812(82)80002-2).
+    ///
+    /// The molar volume of the translated equation of state is $v=v^\mathrm{PR}-c$.
+    /// Vapor pressures and phase compositions of pure substances are not affected.
+    /// Parameters can only be built if `c` is smaller than the covolume $b$.
+    pub fn with_volume_translation(mut self, c: f64) -> Self {
+        self.c = Some(c);
+        self
+    }
+
+    /// Use a volume translation that is estimated from the Rackett
+    /// compressibility factor $Z_\mathrm{RA}$ according to
+    /// $c=0.50033\left(0.25969-Z_\mathrm{RA}\right)\frac{RT_c}{p_c}$.
+    ///
+    /// If $Z_\mathrm{RA}$ is not given, it is estimated from the acentric factor
+    /// as $Z_\mathrm{RA}=0.29056-0.08775\omega$ (Yamada and Gunn, 1973).
+    pub fn with_rackett_volume_translation(self, z_ra: Option<f64>) -> Self {
+        let z_ra = z_ra.unwrap_or(0.29056 - 0.08775 * self.acentric_factor);
+        let c = 0.50033 * (0.25969 - z_ra) * KB_A3 * self.tc / self.pc * NAV * 1e-30;
+        self.with_volume_translation(c)
+    }
+
+    /// The volume translation in m³/mol, if specified.
+    pub fn volume_translation(&self) -> Option<f64> {
+        self.c
+    }
+
+    /// Use the given alpha function instead of the Soave alpha function.
+    pub fn with_alpha_function(mut self, alpha: AlphaFunction) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    /// The alpha function of the substance.
+    pub fn alpha_function(&self) -> AlphaFunction {
+        self.alpha.unwrap_or_default()
+    }
+
+    /// Use the given influence parameter (in J m⁵/mol²) for
+    /// calculations with the square gradient theory.
+    pub fn with_influence_parameter(mut self, influence_parameter: f64) -> Self {
+        self.influence_parameter = Some(influence_parameter);
+        self
+    }
+
+    /// The influence parameter in J m⁵/mol², if specified.
+    pub fn influence_parameter(&self) -> Option<f64> {
+        self.influence_parameter
+    }
+}
+
+/// Critical temperatures, critical pressures and acentric factors are added
+/// individually, so that weighted sums of records correspond to the
+/// pseudo-critical properties according to Kay's rule. Alpha functions are
+/// only kept if they are the same for both records, otherwise the Soave alpha
+/// function is used for the sum.
+impl Add for PengRobinsonRecord {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            tc: self.tc + rhs.tc,
+            pc: self.pc + rhs.pc,
+            acentric_factor: self.acentric_factor + rhs.acentric_factor,
+            c: match (self.c, rhs.c) {
+                (None, None) => None,
+                (c1, c2) => Some(c1.unwrap_or(0.0) + c2.unwrap_or(0.0)),
+            },
+            alpha: if self.alpha == rhs.alpha {
+                self.alpha
+            } else {
+                None
+            },
+            influence_parameter: match (self.influence_parameter, rhs.influence_parameter) {
+                (Some(c1), Some(c2)) => Some(c1 + c2),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Mul<f64> for PengRobinsonRecord {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Self {
+            tc: self.tc * rhs,
+            pc: self.pc * rhs,
+            acentric_factor: self.acentric_factor * rhs,
+            c: self.c.map(|c| c * rhs),
+            alpha: self.alpha,
+            influence_parameter: self.influence_parameter.map(|c| c * rhs),
         }
     }
 }
@@ -48,12 +172,278 @@ impl std::fmt::Display for PengRobinsonRecord {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "PengRobinsonRecord(tc={} K", self.tc)?;
         write!(f, ", pc={} Pa", self.pc)?;
-        write!(f, ", acentric factor={}", self.acentric_factor)
+        write!(f, ", acentric factor={}", self.acentric_factor)?;
+        if let Some(c) = self.c {
+            write!(f, ", c={} m³/mol", c)?;
+        }
+        if let Some(alpha) = self.alpha {
+            write!(f, ", alpha={}", alpha)?;
+        }
+        if let Some(influence_parameter) = self.influence_parameter {
+            write!(f, ", influence parameter={} J m⁵/mol²", influence_parameter)?;
+        }
+        write!(f, ")")
     }
 }
 
-/// Peng-Robinson parameters for one ore more substances.
-pub struct PengRobinsonParameters {
+/// Temperature dependence $\alpha(T_r)$ of the attractive parameter
+/// $a(T)=a_c\alpha(T_r)$ of a substance with the reduced temperature $T_r=T/T_c$.
+///
+/// In a pure record, the alpha function is specified with the key `alpha`,
+/// e.g., `"alpha": {"twu91": {"l": 0.3855, "m": 0.8720, "n": 1.9693}}`.
+/// All alpha functions are equal to 1 at the critical temperature.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AlphaFunction {
+    /// $\alpha=\left(1+\kappa\left(1-\sqrt{T_r}\right)\right)^2$ with the
+    /// generalized slope $\kappa(\omega)$ of the equation of state.
+    #[default]
+    Soave,
+    /// $\alpha=T_r^{N(M-1)}\exp\left(L\left(1-T_r^{NM}\right)\right)$
+    /// ([Twu et al., 1991](https://doi.org/10.1016/0378-3812(91)85010-R)).
+    Twu91 { l: f64, m: f64, n: f64 },
+    /// $\alpha=\left(1+c_1\left(1-\sqrt{T_r}\right)+c_2\left(1-\sqrt{T_r}\right)^2+c_3\left(1-\sqrt{T_r}\right)^3\right)^2$
+    /// below the critical temperature and the Soave form with $\kappa=c_1$ above
+    /// ([Mathias and Copeman, 1983](https://doi.org/10.1016/0378-3812(83)80084-3)).
+    MathiasCopeman { c1: f64, c2: f64, c3: f64 },
+}
+
+impl AlphaFunction {
+    /// Evaluate the alpha function for the reduced temperature `tr`. The slope
+    /// `kappa` of the equation of state is only used by the Soave alpha function.
+    fn alpha<D: DualNum<f64>>(&self, kappa: f64, tr: D) -> D {
+        match *self {
+            Self::Soave => (-(tr.sqrt() - 1.0) * kappa + 1.0).powi(2),
+            Self::Twu91 { l, m, n } => tr.powf(n * (m - 1.0)) * (-(tr.powf(n * m) - 1.0) * l).exp(),
+            Self::MathiasCopeman { c1, c2, c3 } => {
+                let x = -(tr.sqrt() - 1.0);
+                if tr.re() < 1.0 {
+                    (x * (x * (x * c3 + c2) + c1) + 1.0).powi(2)
+                } else {
+                    (x * c1 + 1.0).powi(2)
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for AlphaFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Soave => write!(f, "Soave"),
+            Self::Twu91 { l, m, n } => write!(f, "Twu91(l={}, m={}, n={})", l, m, n),
+            Self::MathiasCopeman { c1, c2, c3 } => {
+                write!(f, "MathiasCopeman(c1={}, c2={}, c3={})", c1, c2, c3)
+            }
+        }
+    }
+}
+
+/// Molar excess Gibbs energy of an activity coefficient model
+/// that can be evaluated using generalized (hyper) dual numbers.
+pub trait ExcessGibbsEnergyDual<D: DualNum<f64>> {
+    /// The molar excess Gibbs energy $\frac{g^E}{RT}$ for the given
+    /// temperature (in Kelvin) and mole fractions.
+    fn excess_gibbs_energy(&self, temperature: D, molefracs: &Array1<D>) -> D;
+}
+
+/// An activity coefficient model that can be used in the
+/// Huron-Vidal mixing rule of the cubic equations of state.
+///
+/// The trait is object safe, the excess Gibbs energy has to be implemented
+/// for all dual numbers in the supertraits, typically with a single
+/// generic implementation of [ExcessGibbsEnergyDual].
+pub trait ActivityCoefficientModel:
+    ExcessGibbsEnergyDual<f64>
+    + ExcessGibbsEnergyDual<Dual64>
+    + ExcessGibbsEnergyDual<Dual<DualVec64<3>, f64>>
+    + ExcessGibbsEnergyDual<HyperDual64>
+    + ExcessGibbsEnergyDual<Dual3_64>
+    + ExcessGibbsEnergyDual<HyperDual<Dual64, f64>>
+    + ExcessGibbsEnergyDual<HyperDual<DualVec64<2>, f64>>
+    + ExcessGibbsEnergyDual<HyperDual<DualVec64<3>, f64>>
+    + ExcessGibbsEnergyDual<Dual3<Dual64, f64>>
+    + ExcessGibbsEnergyDual<Dual3<DualVec64<2>, f64>>
+    + ExcessGibbsEnergyDual<Dual3<DualVec64<3>, f64>>
+    + fmt::Display
+{
+    /// Return the number of components of the model.
+    fn components(&self) -> usize;
+
+    /// Return the model for a subset of the components.
+    fn subset(&self, component_list: &[usize]) -> Rc<dyn ActivityCoefficientModel>;
+}
+
+/// The NRTL activity coefficient model
+/// ([Renon and Prausnitz, 1968](https://doi.org/10.1002/aic.690140124))
+/// $$\frac{g^E}{RT}=\sum_ix_i\frac{\sum_j\tau_{ji}G_{ji}x_j}{\sum_kG_{ki}x_k}$$
+/// with $\tau_{ij}=a_{ij}+\frac{b_{ij}}{T}$ and $G_{ij}=\exp\left(-\alpha_{ij}\tau_{ij}\right)$.
+#[derive(Clone, Debug)]
+pub struct Nrtl {
+    a: Array2<f64>,
+    /// in Kelvin
+    b: Array2<f64>,
+    alpha: Array2<f64>,
+}
+
+impl Nrtl {
+    /// Create the NRTL model from the dimensionless parameters `a`,
+    /// the parameters `b` (in Kelvin) and the non-randomness parameters `alpha`.
+    pub fn new(a: Array2<f64>, b: Array2<f64>, alpha: Array2<f64>) -> Result<Self, ParameterError> {
+        let n = a.nrows();
+        if [a.shape(), b.shape(), alpha.shape()]
+            .iter()
+            .any(|s| s != &[n, n])
+        {
+            return Err(ParameterError::IncompatibleParameters(String::from(
+                "the NRTL parameters have to be square matrices of the same size.",
+            )));
+        }
+        Ok(Self { a, b, alpha })
+    }
+}
+
+impl<D: DualNum<f64>> ExcessGibbsEnergyDual<D> for Nrtl {
+    fn excess_gibbs_energy(&self, temperature: D, molefracs: &Array1<D>) -> D {
+        let n = molefracs.len();
+        let tau = Array2::from_shape_fn((n, n), |(i, j)| {
+            temperature.recip() * self.b[(i, j)] + self.a[(i, j)]
+        });
+        let g = Array2::from_shape_fn((n, n), |(i, j)| (-tau[(i, j)] * self.alpha[(i, j)]).exp());
+        let mut g_e = D::zero();
+        for i in 0..n {
+            let mut num = D::zero();
+            let mut den = D::zero();
+            for j in 0..n {
+                num += tau[(j, i)] * g[(j, i)] * molefracs[j];
+                den += g[(j, i)] * molefracs[j];
+            }
+            g_e += molefracs[i] * num / den;
+        }
+        g_e
+    }
+}
+
+impl ActivityCoefficientModel for Nrtl {
+    fn components(&self) -> usize {
+        self.a.nrows()
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Rc<dyn ActivityCoefficientModel> {
+        let subset = |p: &Array2<f64>| {
+            p.select(Axis(0), component_list)
+                .select(Axis(1), component_list)
+        };
+        Rc::new(Self {
+            a: subset(&self.a),
+            b: subset(&self.b),
+            alpha: subset(&self.alpha),
+        })
+    }
+}
+
+impl fmt::Display for Nrtl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NRTL(a={}, b={}, alpha={})", self.a, self.b, self.alpha)
+    }
+}
+
+/// Mixing rule for the attractive parameter of the cubic equations of state.
+/// The co-volume is always calculated as $b=\sum_ix_ib_i$.
+#[derive(Clone)]
+pub enum MixingRule {
+    /// The van der Waals one-fluid mixing rule
+    /// $a=\sum_{ij}x_ix_j\sqrt{a_ia_j}\left(1-k_{ij}\right)$.
+    VanDerWaals,
+    /// The Huron-Vidal mixing rule
+    /// ([Huron and Vidal, 1979](https://doi.org/10.1016/0378-3812(79)80001-1))
+    /// $\frac{a}{b}=\sum_ix_i\frac{a_i}{b_i}-\frac{g^E_\infty}{C}$
+    /// that reproduces the excess Gibbs energy of the activity coefficient model
+    /// at infinite pressure. The constant $C$ depends on the equation of state.
+    /// Binary interaction parameters $k_{ij}$ are not used and parameters of
+    /// activity coefficient models that are fitted to low pressure data
+    /// usually have to be readjusted.
+    HuronVidal(Rc<dyn ActivityCoefficientModel>),
+}
+
+impl PartialEq for MixingRule {
+    /// Activity coefficient models are compared by their parameters
+    /// as given by their [fmt::Display] implementation.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::VanDerWaals, Self::VanDerWaals) => true,
+            (Self::HuronVidal(model1), Self::HuronVidal(model2)) => {
+                Rc::ptr_eq(model1, model2) || model1.to_string() == model2.to_string()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for MixingRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VanDerWaals => write!(f, "van der Waals"),
+            Self::HuronVidal(model) => write!(f, "Huron-Vidal with {}", model),
+        }
+    }
+}
+
+/// Universal constants and alpha functions of the implemented cubic equations of state.
+#[derive(Clone, Copy, Debug)]
+enum CubicModel {
+    PengRobinson,
+    SoaveRedlichKwong,
+}
+
+impl CubicModel {
+    /// Constants of the attractive and the repulsive parameter.
+    fn omega(self) -> (f64, f64) {
+        match self {
+            Self::PengRobinson => (0.45724, 0.07780),
+            Self::SoaveRedlichKwong => (0.42748, 0.08664),
+        }
+    }
+
+    /// Slope of the alpha function as function of the acentric factor.
+    fn kappa(self, acentric_factor: f64) -> f64 {
+        let w = acentric_factor;
+        match self {
+            Self::PengRobinson => 0.37464 + (1.54226 - 0.26992 * w) * w,
+            Self::SoaveRedlichKwong => 0.480 + (1.574 - 0.176 * w) * w,
+        }
+    }
+
+    /// Constants of the denominator of the attractive term,
+    /// i.e., $p=\frac{RT}{v-b}-\frac{a}{(v+\delta_1b)(v+\delta_2b)}$.
+    fn delta(self) -> (f64, f64) {
+        match self {
+            Self::PengRobinson => (1.0 + SQRT_2, 1.0 - SQRT_2),
+            Self::SoaveRedlichKwong => (1.0, 0.0),
+        }
+    }
+
+    /// Constant of the Huron-Vidal mixing rule, i.e., the Helmholtz energy
+    /// of the attractive term at infinite pressure ($v=b$) divided by $-\frac{a}{b}$.
+    fn huron_vidal_constant(self) -> f64 {
+        let (delta1, delta2) = self.delta();
+        ((1.0 + delta1) / (1.0 + delta2)).ln() / (delta1 - delta2)
+    }
+}
+
+impl fmt::Display for CubicModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PengRobinson => write!(f, "Peng Robinson"),
+            Self::SoaveRedlichKwong => write!(f, "Soave Redlich Kwong"),
+        }
+    }
+}
+
+/// Parameters of a cubic equation of state for one or more substances.
+#[derive(Clone)]
+struct CubicParameters {
+    model: CubicModel,
     /// Critical temperature in Kelvin
     tc: Array1<f64>,
     a: Array1<f64>,
@@ -61,6 +451,12 @@ pub struct PengRobinsonParameters {
     /// Binary interaction parameter
     k_ij: Array2<f64>,
     kappa: Array1<f64>,
+    /// Alpha functions
+    alpha: Vec<AlphaFunction>,
+    /// Volume translation
+    c: Array1<f64>,
+    /// Mixing rule of the attractive parameter
+    mixing_rule: MixingRule,
     /// Molar weight in units of g/mol
     molarweight: Array1<f64>,
     /// List of pure component records
@@ -69,23 +465,63 @@ pub struct PengRobinsonParameters {
     joback_records: Option<Vec<JobackRecord>>,
 }
 
-impl std::fmt::Display for PengRobinsonParameters {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.pure_records
+impl CubicParameters {
+    fn new(
+        model: CubicModel,
+        pure_records: Vec<PureRecord<PengRobinsonRecord, JobackRecord>>,
+        binary_records: Array2<f64>,
+    ) -> Self {
+        let n = pure_records.len();
+
+        let mut tc = Array1::zeros(n);
+        let mut a = Array1::zeros(n);
+        let mut b = Array1::zeros(n);
+        let mut molarweight = Array1::zeros(n);
+        let mut kappa = Array1::zeros(n);
+        let mut c = Array1::zeros(n);
+
+        let mut alpha = Vec::with_capacity(n);
+
+        let (omega_a, omega_b) = model.omega();
+        for (i, record) in pure_records.iter().enumerate() {
+            molarweight[i] = record.molarweight;
+            let r = &record.model_record;
+            tc[i] = r.tc;
+            a[i] = omega_a * r.tc.powi(2) * KB_A3 / r.pc;
+            b[i] = omega_b * r.tc * KB_A3 / r.pc;
+            kappa[i] = model.kappa(r.acentric_factor);
+            alpha.push(r.alpha_function());
+            c[i] = r.c.unwrap_or(0.0) / NAV * 1e30;
+        }
+
+        let joback_records = pure_records
             .iter()
-            .try_for_each(|pr| writeln!(f, "{}", pr))?;
-        writeln!(f, "\nk_ij:\n{}", self.k_ij)
+            .map(|r| r.ideal_gas_record.clone())
+            .collect();
+
+        Self {
+            model,
+            tc,
+            a,
+            b,
+            k_ij: binary_records,
+            kappa,
+            alpha,
+            c,
+            mixing_rule: MixingRule::VanDerWaals,
+            molarweight,
+            pure_records,
+            joback_records,
+        }
     }
-}
 
-impl PengRobinsonParameters {
-    /// Build a simple parameter set without binary interaction parameters.
-    pub fn new_simple(
+    /// Pure records for given critical constants and molar weights.
+    fn simple_records(
         tc: &[f64],
         pc: &[f64],
         acentric_factor: &[f64],
         molarweight: &[f64],
-    ) -> Result<Self, crate::parameter::ParameterError> {
+    ) -> Result<Vec<PureRecord<PengRobinsonRecord, JobackRecord>>, ParameterError> {
         if [pc.len(), acentric_factor.len(), molarweight.len()]
             .iter()
             .any(|&l| l != tc.len())
@@ -94,21 +530,132 @@ impl PengRobinsonParameters {
                 "each component has to have parameters.",
             )));
         }
-        let records = (0..tc.len())
+        Ok((0..tc.len())
             .map(|i| {
-                let record = PengRobinsonRecord {
-                    tc: tc[i],
-                    pc: pc[i],
-                    acentric_factor: acentric_factor[i],
-                };
+                let record = PengRobinsonRecord::new(tc[i], pc[i], acentric_factor[i]);
                 let id = Identifier::default();
                 PureRecord::new(id, molarweight[i], record, None)
             })
+            .collect())
+    }
+
+    /// Use the given mixing rule for the attractive parameter.
+    fn with_mixing_rule(mut self, mixing_rule: MixingRule) -> Result<Self, ParameterError> {
+        if let MixingRule::HuronVidal(model) = &mixing_rule {
+            if model.components() != self.tc.len() {
+                return Err(ParameterError::IncompatibleParameters(format!(
+                    "the activity coefficient model is defined for {} components, the parameters for {} components.",
+                    model.components(),
+                    self.tc.len()
+                )));
+            }
+        }
+        self.mixing_rule = mixing_rule;
+        Ok(self)
+    }
+
+    /// Parameters and mixing rule for a subset of the components.
+    fn subset(&self, component_list: &[usize]) -> Self {
+        let pure_records = component_list
+            .iter()
+            .map(|&i| self.pure_records[i].clone())
+            .collect();
+        let k_ij = self
+            .k_ij
+            .select(Axis(0), component_list)
+            .select(Axis(1), component_list);
+        Self {
+            mixing_rule: match &self.mixing_rule {
+                MixingRule::VanDerWaals => MixingRule::VanDerWaals,
+                MixingRule::HuronVidal(model) => {
+                    MixingRule::HuronVidal(model.subset(component_list))
+                }
+            },
+            ..Self::new(self.model, pure_records, k_ij)
+        }
+    }
+
+    fn ideal_gas(&self) -> Joback {
+        self.joback_records.as_ref().map_or_else(
+            || Joback::default(self.tc.len()),
+            |j| Joback::new(j.clone()),
+        )
+    }
+
+    fn influence_parameter(&self) -> EosResult<SIArray1> {
+        let c: Option<Vec<_>> = self
+            .pure_records
+            .iter()
+            .map(|r| r.model_record.influence_parameter)
             .collect();
-        Ok(PengRobinsonParameters::from_records(
-            records,
-            Array2::zeros([pc.len(); 2]),
-        ))
+        let c = c.ok_or_else(|| {
+            ParameterError::IncompatibleParameters(String::from(
+                "the square gradient theory requires influence parameters for all components.",
+            ))
+        })?;
+        Ok(Array1::from(c) * JOULE * METER.powi(5) / MOL.powi(2))
+    }
+
+    /// Check that the volume translation of every component is smaller
+    /// than its covolume, so that the translated molar volume at the
+    /// highest possible density is positive.
+    fn check_volume_translation(&self) -> Result<(), ParameterError> {
+        match (0..self.b.len()).find(|&i| self.c[i] >= self.b[i]) {
+            Some(i) => Err(ParameterError::IncompatibleParameters(format!(
+                "the volume translation of component {} ({} m³/mol) has to be smaller than its covolume ({} m³/mol).",
+                i,
+                self.c[i] * NAV * 1e-30,
+                self.b[i] * NAV * 1e-30
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn max_density(&self, moles: &Array1<f64>) -> f64 {
+        let b = (moles * &self.b).sum() / moles.sum();
+        let c = (moles * &self.c).sum() / moles.sum();
+        0.9 / (b - c)
+    }
+}
+
+impl std::fmt::Display for CubicParameters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.pure_records
+            .iter()
+            .try_for_each(|pr| writeln!(f, "{}", pr))?;
+        writeln!(f, "\nk_ij:\n{}", self.k_ij)?;
+        if let MixingRule::HuronVidal(_) = self.mixing_rule {
+            writeln!(f, "\nmixing rule: {}", self.mixing_rule)?;
+        }
+        Ok(())
+    }
+}
+
+/// Peng-Robinson parameters for one ore more substances.
+#[derive(Clone)]
+pub struct PengRobinsonParameters(CubicParameters);
+
+impl std::fmt::Display for PengRobinsonParameters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PengRobinsonParameters {
+    /// Build a simple parameter set without binary interaction parameters.
+    pub fn new_simple(
+        tc: &[f64],
+        pc: &[f64],
+        acentric_factor: &[f64],
+        molarweight: &[f64],
+    ) -> Result<Self, crate::parameter::ParameterError> {
+        let records = CubicParameters::simple_records(tc, pc, acentric_factor, molarweight)?;
+        Self::from_records(records, Array2::zeros([pc.len(); 2]))
+    }
+
+    /// Use the given mixing rule for the attractive parameter.
+    pub fn with_mixing_rule(self, mixing_rule: MixingRule) -> Result<Self, ParameterError> {
+        Ok(Self(self.0.with_mixing_rule(mixing_rule)?))
     }
 }
 
@@ -121,39 +668,80 @@ impl Parameter for PengRobinsonParameters {
     fn from_records(
         pure_records: Vec<PureRecord<Self::Pure, Self::IdealGas>>,
         binary_records: Array2<Self::Binary>,
-    ) -> Self {
-        let n = pure_records.len();
+    ) -> Result<Self, ParameterError> {
+        let parameters =
+            CubicParameters::new(CubicModel::PengRobinson, pure_records, binary_records);
+        parameters.check_volume_translation()?;
+        Ok(Self(parameters))
+    }
 
-        let mut tc = Array1::zeros(n);
-        let mut a = Array1::zeros(n);
-        let mut b = Array1::zeros(n);
-        let mut molarweight = Array1::zeros(n);
-        let mut kappa = Array1::zeros(n);
+    fn records(
+        &self,
+    ) -> (
+        &[PureRecord<PengRobinsonRecord, JobackRecord>],
+        &Array2<f64>,
+    ) {
+        (&self.0.pure_records, &self.0.k_ij)
+    }
 
-        for (i, record) in pure_records.iter().enumerate() {
-            molarweight[i] = record.molarweight;
-            let r = &record.model_record;
-            tc[i] = r.tc;
-            a[i] = 0.45724 * r.tc.powi(2) * KB_A3 / r.pc;
-            b[i] = 0.07780 * r.tc * KB_A3 / r.pc;
-            kappa[i] = 0.37464 + (1.54226 - 0.26992 * r.acentric_factor) * r.acentric_factor;
-        }
+    /// The mixing rule is applied to the subset as well.
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self(self.0.subset(component_list))
+    }
 
-        let joback_records = pure_records
-            .iter()
-            .map(|r| r.ideal_gas_record.clone())
-            .collect();
+    fn settings_eq(&self, other: &Self) -> bool {
+        self.0.mixing_rule == other.0.mixing_rule
+    }
+}
 
-        Self {
-            tc,
-            a,
-            b,
-            k_ij: binary_records,
-            kappa,
-            molarweight,
-            pure_records,
-            joback_records,
-        }
+/// Soave-Redlich-Kwong parameters for one ore more substances.
+///
+/// The parameters are constructed from the same records as
+/// the parameters of the Peng-Robinson equation of state.
+/// The estimation of the volume translation with
+/// [PengRobinsonRecord::with_rackett_volume_translation] is
+/// specific to the Peng-Robinson equation of state.
+#[derive(Clone)]
+pub struct SoaveRedlichKwongParameters(CubicParameters);
+
+impl std::fmt::Display for SoaveRedlichKwongParameters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl SoaveRedlichKwongParameters {
+    /// Build a simple parameter set without binary interaction parameters.
+    pub fn new_simple(
+        tc: &[f64],
+        pc: &[f64],
+        acentric_factor: &[f64],
+        molarweight: &[f64],
+    ) -> Result<Self, crate::parameter::ParameterError> {
+        let records = CubicParameters::simple_records(tc, pc, acentric_factor, molarweight)?;
+        Self::from_records(records, Array2::zeros([pc.len(); 2]))
+    }
+
+    /// Use the given mixing rule for the attractive parameter.
+    pub fn with_mixing_rule(self, mixing_rule: MixingRule) -> Result<Self, ParameterError> {
+        Ok(Self(self.0.with_mixing_rule(mixing_rule)?))
+    }
+}
+
+impl Parameter for SoaveRedlichKwongParameters {
+    type Pure = PengRobinsonRecord;
+    type IdealGas = JobackRecord;
+    type Binary = f64;
+
+    /// Creates parameters from pure component records.
+    fn from_records(
+        pure_records: Vec<PureRecord<Self::Pure, Self::IdealGas>>,
+        binary_records: Array2<Self::Binary>,
+    ) -> Result<Self, ParameterError> {
+        let parameters =
+            CubicParameters::new(CubicModel::SoaveRedlichKwong, pure_records, binary_records);
+        parameters.check_volume_translation()?;
+        Ok(Self(parameters))
     }
 
     fn records(
@@ -162,44 +750,86 @@ impl Parameter for PengRobinsonParameters {
         &[PureRecord<PengRobinsonRecord, JobackRecord>],
         &Array2<f64>,
     ) {
-        (&self.pure_records, &self.k_ij)
+        (&self.0.pure_records, &self.0.k_ij)
+    }
+
+    /// The mixing rule is applied to the subset as well.
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self(self.0.subset(component_list))
+    }
+
+    fn settings_eq(&self, other: &Self) -> bool {
+        self.0.mixing_rule == other.0.mixing_rule
     }
 }
 
-struct PengRobinsonContribution {
-    parameters: Rc<PengRobinsonParameters>,
+/// Access to the parameters of the cubic equation of state.
+trait AsCubicParameters {
+    fn cubic(&self) -> &CubicParameters;
+}
+
+impl AsCubicParameters for PengRobinsonParameters {
+    fn cubic(&self) -> &CubicParameters {
+        &self.0
+    }
+}
+
+impl AsCubicParameters for SoaveRedlichKwongParameters {
+    fn cubic(&self) -> &CubicParameters {
+        &self.0
+    }
+}
+
+struct CubicContribution<P> {
+    parameters: Rc<P>,
 }
 
-impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for PengRobinsonContribution {
+impl<D: DualNum<f64>, P: AsCubicParameters> HelmholtzEnergyDual<D> for CubicContribution<P>
+where
+    dyn ActivityCoefficientModel: ExcessGibbsEnergyDual<D>,
+{
     fn helmholtz_energy(&self, state: &StateHD<D>) -> D {
         // temperature dependent a parameter
-        let p = &self.parameters;
+        let p = self.parameters.cubic();
         let x = &state.molefracs;
-        let ak = (&p.tc.mapv(|tc| (D::one() - (state.temperature / tc).sqrt())) * &p.kappa + 1.0)
-            .mapv(|x| x.powi(2))
-            * &p.a;
+        let ak = Array1::from_shape_fn(p.tc.len(), |i| {
+            p.alpha[i].alpha(p.kappa[i], state.temperature / p.tc[i]) * p.a[i]
+        });
 
         // Mixing rules
-        let mut ak_mix = D::zero();
-        for i in 0..ak.len() {
-            for j in 0..ak.len() {
-                ak_mix += (ak[i] * ak[j]).sqrt() * (x[i] * x[j] * (1.0 - p.k_ij[(i, j)]));
-            }
-        }
         let b = (x * &p.b).sum();
+        let ak_mix = match &p.mixing_rule {
+            MixingRule::VanDerWaals => {
+                let mut ak_mix = D::zero();
+                for i in 0..ak.len() {
+                    for j in 0..ak.len() {
+                        ak_mix += (ak[i] * ak[j]).sqrt() * (x[i] * x[j] * (1.0 - p.k_ij[(i, j)]));
+                    }
+                }
+                ak_mix
+            }
+            MixingRule::HuronVidal(model) => {
+                let g_e = model.excess_gibbs_energy(state.temperature, x) * state.temperature;
+                ((x * &ak / &p.b).sum() - g_e / p.model.huron_vidal_constant()) * b
+            }
+        };
+        let c = (x * &p.c).sum();
 
-        // Helmholtz energy
+        // Helmholtz energy of the untranslated equation of state at
+        // the volume v + cn, corrected by the ideal gas contribution
+        let (delta1, delta2) = p.model.delta();
         let n = state.moles.sum();
         let v = state.volume;
-        n * ((v / (v - b * n)).ln()
-            - ak_mix / (b * SQRT_2 * 2.0 * state.temperature)
-                * ((v * (SQRT_2 - 1.0) + b * n) / (v * (SQRT_2 + 1.0) - b * n)).ln())
+        let v_cubic = v + c * n;
+        n * ((v / (v_cubic - b * n)).ln()
+            - ak_mix / (b * (delta1 - delta2) * state.temperature)
+                * ((v_cubic + b * n * delta1) / (v_cubic + b * n * delta2)).ln())
     }
 }
 
-impl fmt::Display for PengRobinsonContribution {
+impl<P: AsCubicParameters> fmt::Display for CubicContribution<P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Peng Robinson")
+        write!(f, "{}", self.parameters.cubic().model)
     }
 }
 
@@ -211,39 +841,127 @@ pub struct PengRobinson {
     ideal_gas: Joback,
     /// Non-ideal contributions to the Helmholtz energy
     contributions: Vec<Box<dyn HelmholtzEnergy>>,
+    /// Precomputed saturation properties of a pure component
+    saturation_table: Option<SaturationTable>,
 }
 
 impl PengRobinson {
     /// Create a new equation of state from a set of parameters.
     pub fn new(parameters: Rc<PengRobinsonParameters>) -> Self {
-        let ideal_gas = parameters.joback_records.as_ref().map_or_else(
-            || Joback::default(parameters.tc.len()),
-            |j| Joback::new(j.clone()),
-        );
-        let contributions: Vec<Box<dyn HelmholtzEnergy>> =
-            vec![Box::new(PengRobinsonContribution {
-                parameters: parameters.clone(),
-            })];
+        let ideal_gas = parameters.0.ideal_gas();
+        let contributions: Vec<Box<dyn HelmholtzEnergy>> = vec![Box::new(CubicContribution {
+            parameters: parameters.clone(),
+        })];
         Self {
             parameters,
             ideal_gas,
             contributions,
+            saturation_table: None,
+        }
+    }
+
+    /// Create a new equation of state directly from critical temperatures (in Kelvin),
+    /// critical pressures (in Pascal), acentric factors and molar weights (in g/mol)
+    /// without binary interaction parameters.
+    ///
+    /// # Example
+    /// ```
+    /// # use feos_core::{EosResult, State};
+    /// # use feos_core::cubic::PengRobinson;
+    /// # use quantity::si::*;
+    /// # fn main() -> EosResult<()> {
+    /// let eos = PengRobinson::from_critical_constants(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+    /// let cp = State::critical_point(&eos, None, None, Default::default())?;
+    /// assert!((cp.temperature.to_reduced(KELVIN)? - 369.96).abs() < 1e-2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_critical_constants(
+        tc: &[f64],
+        pc: &[f64],
+        acentric_factor: &[f64],
+        molarweight: &[f64],
+    ) -> Result<Rc<Self>, ParameterError> {
+        let parameters = PengRobinsonParameters::new_simple(tc, pc, acentric_factor, molarweight)?;
+        Ok(Rc::new(Self::new(Rc::new(parameters))))
+    }
+
+    /// Return the parameters of the equation of state.
+    pub fn parameters(&self) -> &Rc<PengRobinsonParameters> {
+        &self.parameters
+    }
+
+    /// Use the given standard pressure for the reference state of the
+    /// ideal gas contribution (see [Joback::with_standard_pressure]).
+    pub fn with_standard_pressure<U: EosUnit>(
+        mut self,
+        standard_pressure: QuantityScalar<U>,
+    ) -> EosResult<Self> {
+        self.ideal_gas = self.ideal_gas.with_standard_pressure(standard_pressure)?;
+        Ok(self)
+    }
+
+    /// Use a table of precomputed saturation properties (e.g., from
+    /// [SaturationTable::from_eos]) to initialize pure component phase
+    /// equilibria. Only available for pure components.
+    pub fn with_saturation_table(mut self, saturation_table: SaturationTable) -> EosResult<Self> {
+        if !self.is_pure() {
+            return Err(EosError::IncompatibleComponents(self.components(), 1));
+        }
+        self.saturation_table = Some(saturation_table);
+        Ok(self)
+    }
+
+    /// Compare the settings of two equations of state that are not part
+    /// of the parameter records, i.e., the mixing rule, the ideal gas
+    /// contribution (including the standard pressure) and the saturation table.
+    pub fn settings_eq(&self, other: &Self) -> bool {
+        self.parameters.settings_eq(&other.parameters)
+            && self.ideal_gas == other.ideal_gas
+            && self.saturation_table == other.saturation_table
+    }
+}
+
+impl Clone for PengRobinson {
+    /// Creates a copy with independent parameters.
+    fn clone(&self) -> Self {
+        Self {
+            ideal_gas: self.ideal_gas.clone(),
+            saturation_table: self.saturation_table.clone(),
+            ..Self::new(Rc::new((*self.parameters).clone()))
         }
     }
 }
 
 impl EquationOfState for PengRobinson {
     fn components(&self) -> usize {
-        self.parameters.b.len()
+        self.parameters.0.b.len()
     }
 
     fn subset(&self, component_list: &[usize]) -> Self {
-        Self::new(Rc::new(self.parameters.subset(component_list)))
+        Self {
+            ideal_gas: self.ideal_gas.subset(component_list),
+            saturation_table: match component_list {
+                [0] if self.is_pure() => self.saturation_table.clone(),
+                _ => None,
+            },
+            ..Self::new(Rc::new(self.parameters.subset(component_list)))
+        }
     }
 
     fn compute_max_density(&self, moles: &Array1<f64>) -> f64 {
-        let b = (moles * &self.parameters.b).sum() / moles.sum();
-        0.9 / b
+        self.parameters.0.max_density(moles)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            molar_weight: true,
+            ..Default::default()
+        }
+    }
+
+    fn saturation_table(&self) -> Option<&SaturationTable> {
+        self.saturation_table.as_ref()
     }
 
     fn residual(&self) -> &[Box<dyn HelmholtzEnergy>] {
@@ -257,18 +975,179 @@ impl EquationOfState for PengRobinson {
 
 impl MolarWeight<SIUnit> for PengRobinson {
     fn molar_weight(&self) -> SIArray1 {
-        self.parameters.molarweight.clone() * GRAM / MOL
+        self.parameters.0.molarweight.clone() * GRAM / MOL
+    }
+}
+
+impl InfluenceParameter<SIUnit> for PengRobinson {
+    fn influence_parameter(&self) -> EosResult<SIArray1> {
+        self.parameters.0.influence_parameter()
+    }
+}
+
+/// A simple version of the Soave-Redlich-Kwong equation of state.
+pub struct SoaveRedlichKwong {
+    /// Parameters
+    parameters: Rc<SoaveRedlichKwongParameters>,
+    /// Ideal gas contributions to the Helmholtz energy
+    ideal_gas: Joback,
+    /// Non-ideal contributions to the Helmholtz energy
+    contributions: Vec<Box<dyn HelmholtzEnergy>>,
+}
+
+impl SoaveRedlichKwong {
+    /// Create a new equation of state from a set of parameters.
+    pub fn new(parameters: Rc<SoaveRedlichKwongParameters>) -> Self {
+        let ideal_gas = parameters.0.ideal_gas();
+        let contributions: Vec<Box<dyn HelmholtzEnergy>> = vec![Box::new(CubicContribution {
+            parameters: parameters.clone(),
+        })];
+        Self {
+            parameters,
+            ideal_gas,
+            contributions,
+        }
+    }
+
+    /// Create a new equation of state directly from critical temperatures (in Kelvin),
+    /// critical pressures (in Pascal), acentric factors and molar weights (in g/mol)
+    /// without binary interaction parameters.
+    pub fn from_critical_constants(
+        tc: &[f64],
+        pc: &[f64],
+        acentric_factor: &[f64],
+        molarweight: &[f64],
+    ) -> Result<Rc<Self>, ParameterError> {
+        let parameters =
+            SoaveRedlichKwongParameters::new_simple(tc, pc, acentric_factor, molarweight)?;
+        Ok(Rc::new(Self::new(Rc::new(parameters))))
+    }
+
+    /// Return the parameters of the equation of state.
+    pub fn parameters(&self) -> &Rc<SoaveRedlichKwongParameters> {
+        &self.parameters
+    }
+
+    /// Use the given standard pressure for the reference state of the
+    /// ideal gas contribution (see [Joback::with_standard_pressure]).
+    pub fn with_standard_pressure<U: EosUnit>(
+        mut self,
+        standard_pressure: QuantityScalar<U>,
+    ) -> EosResult<Self> {
+        self.ideal_gas = self.ideal_gas.with_standard_pressure(standard_pressure)?;
+        Ok(self)
+    }
+
+    /// Compare the settings of two equations of state that are not part
+    /// of the parameter records, i.e., the mixing rule and the ideal gas
+    /// contribution (including the standard pressure).
+    pub fn settings_eq(&self, other: &Self) -> bool {
+        self.parameters.settings_eq(&other.parameters) && self.ideal_gas == other.ideal_gas
+    }
+}
+
+impl Clone for SoaveRedlichKwong {
+    /// Creates a copy with independent parameters.
+    fn clone(&self) -> Self {
+        Self {
+            ideal_gas: self.ideal_gas.clone(),
+            ..Self::new(Rc::new((*self.parameters).clone()))
+        }
+    }
+}
+
+impl EquationOfState for SoaveRedlichKwong {
+    fn components(&self) -> usize {
+        self.parameters.0.b.len()
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self {
+            ideal_gas: self.ideal_gas.subset(component_list),
+            ..Self::new(Rc::new(self.parameters.subset(component_list)))
+        }
+    }
+
+    fn compute_max_density(&self, moles: &Array1<f64>) -> f64 {
+        self.parameters.0.max_density(moles)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            molar_weight: true,
+            ..Default::default()
+        }
+    }
+
+    fn residual(&self) -> &[Box<dyn HelmholtzEnergy>] {
+        &self.contributions
+    }
+
+    fn ideal_gas(&self) -> &dyn IdealGasContribution {
+        &self.ideal_gas
+    }
+}
+
+impl MolarWeight<SIUnit> for SoaveRedlichKwong {
+    fn molar_weight(&self) -> SIArray1 {
+        self.parameters.0.molarweight.clone() * GRAM / MOL
+    }
+}
+
+impl InfluenceParameter<SIUnit> for SoaveRedlichKwong {
+    fn influence_parameter(&self) -> EosResult<SIArray1> {
+        self.parameters.0.influence_parameter()
+    }
+}
+
+/// Equations of state for propane and for propane + n-butane with constant
+/// ideal gas heat capacities that are shared by the tests of the crate.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+
+    fn records() -> Vec<PureRecord<PengRobinsonRecord, JobackRecord>> {
+        vec![
+            PureRecord::new(
+                Identifier::new(None, Some("propane"), None, None, None, None),
+                44.0962,
+                PengRobinsonRecord::new(369.96, 4250000.0, 0.153),
+                Some(JobackRecord::new(73.6, 0.0, 0.0, 0.0, 0.0)),
+            ),
+            PureRecord::new(
+                Identifier::new(None, Some("butane"), None, None, None, None),
+                58.123,
+                PengRobinsonRecord::new(425.2, 3800000.0, 0.199),
+                Some(JobackRecord::new(98.5, 0.0, 0.0, 0.0, 0.0)),
+            ),
+        ]
+    }
+
+    /// Pure propane.
+    pub(crate) fn propane() -> Rc<PengRobinson> {
+        let parameters =
+            PengRobinsonParameters::from_records(records()[..1].to_vec(), Array2::zeros((1, 1)))
+                .unwrap();
+        Rc::new(PengRobinson::new(Rc::new(parameters)))
+    }
+
+    /// Propane (component 0) and n-butane (component 1).
+    pub(crate) fn propane_butane() -> Rc<PengRobinson> {
+        let parameters =
+            PengRobinsonParameters::from_records(records(), Array2::zeros((2, 2))).unwrap();
+        Rc::new(PengRobinson::new(Rc::new(parameters)))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::phase_equilibria::SolverOptions;
-    use crate::state::State;
+    use crate::phase_equilibria::{PhaseEquilibrium, SolverOptions};
+    use crate::state::{DensityInitialization, State};
     use crate::Contributions;
     use crate::{EosResult, Verbosity};
     use approx::*;
+    use ndarray::{arr1, arr2};
     use quantity::si::*;
     use std::rc::Rc;
 
@@ -316,7 +1195,8 @@ mod tests {
         let propane = mixture[0].clone();
         let tc = propane.model_record.tc;
         let pc = propane.model_record.pc;
-        let parameters = PengRobinsonParameters::from_records(vec![propane], Array2::zeros((1, 1)));
+        let parameters =
+            PengRobinsonParameters::from_records(vec![propane], Array2::zeros((1, 1)))?;
         let pr = Rc::new(PengRobinson::new(Rc::new(parameters)));
         let options = SolverOptions::new().verbosity(Verbosity::Iter);
         let cp = State::critical_point(&pr, None, None, options)?;
@@ -329,4 +1209,353 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn ideal_gas_limit() -> EosResult<()> {
+        // the residual Helmholtz energy vanishes for infinite volumes
+        let parameters =
+            PengRobinsonParameters::from_records(pure_record_vec(), Array2::zeros((2, 2)))?;
+        let pr = Rc::new(PengRobinson::new(Rc::new(parameters)));
+        let t = 300.0 * KELVIN;
+        let moles = arr1(&[0.3, 0.7]) * MOL;
+        let state = State::new_nvt(&pr, t, 1e6 * METER.powi(3), &moles)?;
+        let a_res = state.helmholtz_energy(Contributions::ResidualNvt);
+        let rt = RGAS * t * moles.sum();
+        assert!((a_res / rt).into_value()?.abs() < 1e-6);
+        assert_relative_eq!(
+            state.pressure(Contributions::Total),
+            rt / state.volume,
+            max_relative = 1e-6
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn capabilities() {
+        let mixture = pure_record_vec();
+        let parameters =
+            PengRobinsonParameters::from_records(mixture, Array2::zeros((2, 2))).unwrap();
+        let pr = PengRobinson::new(Rc::new(parameters));
+        assert!(!pr.is_pure());
+        assert!(pr.subset(&[1]).is_pure());
+        let capabilities = pr.capabilities();
+        assert!(capabilities.molar_weight);
+        assert!(!capabilities.entropy_scaling);
+        assert_eq!(capabilities.max_components, None);
+    }
+
+    #[test]
+    fn soave_redlich_kwong() -> EosResult<()> {
+        let propane = pure_record_vec().remove(0);
+        let (tc, pc) = (propane.model_record.tc, propane.model_record.pc);
+        let parameters =
+            SoaveRedlichKwongParameters::from_records(vec![propane], Array2::zeros((1, 1)))?;
+        let srk = Rc::new(SoaveRedlichKwong::new(Rc::new(parameters)));
+        let cp = State::critical_point(&srk, None, None, SolverOptions::default())?;
+        assert_relative_eq!(cp.temperature, tc * KELVIN, max_relative = 1e-4);
+        assert_relative_eq!(
+            cp.pressure(Contributions::Total),
+            pc * PASCAL,
+            max_relative = 1e-4
+        );
+        // universal critical compressibility factor of 1/3
+        assert_relative_eq!(
+            (cp.pressure(Contributions::Total) / (cp.density * RGAS * cp.temperature))
+                .into_value()?,
+            1.0 / 3.0,
+            max_relative = 1e-4
+        );
+
+        // the acentric factor is reproduced
+        let t = 0.7 * tc * KELVIN;
+        let vle = PhaseEquilibrium::pure(&srk, t, None, SolverOptions::default())?;
+        let p_sat = vle.vapor().pressure(Contributions::Total);
+        let w = -(p_sat / (pc * PASCAL)).into_value()?.log10() - 1.0;
+        assert_relative_eq!(w, 0.153, max_relative = 5e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn volume_translation() -> EosResult<()> {
+        let propane = pure_record_vec().remove(0);
+        let mut translated = propane.clone();
+        translated.model_record = translated
+            .model_record
+            .with_rackett_volume_translation(None);
+        let c = translated.model_record.volume_translation().unwrap() * METER.powi(3) / MOL;
+        let eos = |record| {
+            let parameters =
+                PengRobinsonParameters::from_records(vec![record], Array2::zeros((1, 1))).unwrap();
+            Rc::new(PengRobinson::new(Rc::new(parameters)))
+        };
+        let (pr, pr_c) = (eos(propane), eos(translated));
+
+        // vapor pressures are not affected
+        let t = 300.0 * KELVIN;
+        let vle = PhaseEquilibrium::pure(&pr, t, None, SolverOptions::default())?;
+        let vle_c = PhaseEquilibrium::pure(&pr_c, t, None, SolverOptions::default())?;
+        let p = vle.vapor().pressure(Contributions::Total);
+        assert_relative_eq!(
+            vle_c.vapor().pressure(Contributions::Total),
+            p,
+            max_relative = 1e-8
+        );
+
+        // molar volumes are shifted by c
+        let liquid_volume =
+            |vle: &PhaseEquilibrium<SIUnit, PengRobinson, 2>| 1.0 / vle.liquid().density;
+        assert_relative_eq!(
+            liquid_volume(&vle) - liquid_volume(&vle_c),
+            c,
+            max_relative = 1e-6
+        );
+        let moles = arr1(&[1.0]) * MOL;
+        let state = State::new_npt(&pr, t, 50.0 * BAR, &moles, DensityInitialization::Liquid)?;
+        let state_c = State::new_npt(&pr_c, t, 50.0 * BAR, &moles, DensityInitialization::Liquid)?;
+        assert_relative_eq!(
+            1.0 / state.density - 1.0 / state_c.density,
+            c,
+            max_relative = 1e-6
+        );
+
+        // the volume translation has to be smaller than the covolume
+        let mut invalid = pure_record_vec().remove(0);
+        invalid.model_record = invalid.model_record.with_volume_translation(1e-3);
+        assert!(
+            PengRobinsonParameters::from_records(vec![invalid], Array2::zeros((1, 1))).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn alpha_functions() -> EosResult<()> {
+        let water: PureRecord<PengRobinsonRecord, JobackRecord> = serde_json::from_str(
+            r#"{
+                "identifier": {"name": "water"},
+                "model_record": {
+                    "tc": 647.14,
+                    "pc": 22064000.0,
+                    "acentric_factor": 0.344,
+                    "alpha": {"twu91": {"l": 0.3865, "m": 0.8720, "n": 2.0000}}
+                },
+                "molarweight": 18.015
+            }"#,
+        )
+        .expect("Unable to parse json.");
+        let twu = water.model_record.alpha_function();
+        assert_eq!(
+            twu,
+            AlphaFunction::Twu91 {
+                l: 0.3865,
+                m: 0.872,
+                n: 2.0
+            }
+        );
+        let eos = |record: &PureRecord<PengRobinsonRecord, JobackRecord>| {
+            let parameters =
+                PengRobinsonParameters::from_records(vec![record.clone()], Array2::zeros((1, 1)))
+                    .unwrap();
+            Rc::new(PengRobinson::new(Rc::new(parameters)))
+        };
+        let pr = eos(&water);
+        let cp = State::critical_point(&pr, None, None, SolverOptions::default())?;
+        assert_relative_eq!(cp.temperature, 647.14 * KELVIN, max_relative = 1e-4);
+
+        // the Mathias-Copeman alpha function with c1 = kappa reduces to the Soave alpha function
+        let kappa = CubicModel::PengRobinson.kappa(0.344);
+        let mut soave = water.clone();
+        soave.model_record.alpha = None;
+        let mut mathias_copeman = water;
+        mathias_copeman.model_record =
+            mathias_copeman
+                .model_record
+                .with_alpha_function(AlphaFunction::MathiasCopeman {
+                    c1: kappa,
+                    c2: 0.0,
+                    c3: 0.0,
+                });
+        let t = 450.0 * KELVIN;
+        let p_sat = |record| -> EosResult<_> {
+            let vle = PhaseEquilibrium::pure(&eos(record), t, None, SolverOptions::default())?;
+            Ok(vle.vapor().pressure(Contributions::Total))
+        };
+        assert_relative_eq!(
+            p_sat(&mathias_copeman)?,
+            p_sat(&soave)?,
+            max_relative = 1e-10
+        );
+        for tr in [0.5, 1.0, 1.5] {
+            assert_relative_eq!(
+                mathias_copeman
+                    .model_record
+                    .alpha_function()
+                    .alpha(kappa, tr),
+                AlphaFunction::Soave.alpha(kappa, tr),
+                max_relative = 1e-14
+            );
+        }
+        assert_relative_eq!(twu.alpha(kappa, 1.0), 1.0);
+        Ok(())
+    }
+
+    #[test]
+    fn huron_vidal() -> EosResult<()> {
+        let nrtl = |tau: f64| {
+            let a = arr2(&[[0.0, tau], [tau, 0.0]]);
+            Nrtl::new(a, Array2::zeros((2, 2)), Array2::from_elem((2, 2), 0.3))
+        };
+        let eos = |mixing_rule| -> EosResult<_> {
+            let parameters =
+                PengRobinsonParameters::from_records(pure_record_vec(), Array2::zeros((2, 2)))?
+                    .with_mixing_rule(mixing_rule)?;
+            Ok(Rc::new(PengRobinson::new(Rc::new(parameters))))
+        };
+        let vdw = eos(MixingRule::VanDerWaals)?;
+        let hv = eos(MixingRule::HuronVidal(Rc::new(nrtl(3.0)?)))?;
+
+        // the mixing rule is part of the settings of the parameters
+        assert!(!vdw.settings_eq(&hv));
+        assert!(hv.settings_eq(&(*hv).clone()));
+        assert!(hv.settings_eq(eos(MixingRule::HuronVidal(Rc::new(nrtl(3.0)?)))?.as_ref()));
+        assert!(!hv.settings_eq(eos(MixingRule::HuronVidal(Rc::new(nrtl(2.0)?)))?.as_ref()));
+
+        // pure substances are not affected by the mixing rule
+        let (t, p) = (250.0 * KELVIN, 50.0 * BAR);
+        let moles = arr1(&[1.0]) * MOL;
+        let liquid = |eos: &Rc<PengRobinson>| {
+            State::new_npt(eos, t, p, &moles, DensityInitialization::Liquid)
+        };
+        assert_eq!(hv.subset(&[0]).components(), 1);
+        assert_relative_eq!(
+            liquid(&Rc::new(hv.subset(&[0])))?.density,
+            liquid(&Rc::new(vdw.subset(&[0])))?.density,
+            max_relative = 1e-12
+        );
+
+        // the excess Gibbs energy of the activity coefficient model
+        // is approached at high pressures
+        let x = arr1(&[0.5, 0.5]);
+        let p = 1e9 * PASCAL;
+        let g_e = |eos: &Rc<PengRobinson>| -> EosResult<f64> {
+            let mixture = State::new_npt(eos, t, p, &(&x * MOL), DensityInitialization::Liquid)?;
+            let ln_phi = mixture.ln_phi();
+            let mut g_e = 0.0;
+            for i in 0..2 {
+                let pure = Rc::new(eos.subset(&[i]));
+                let pure = State::new_npt(&pure, t, p, &moles, DensityInitialization::Liquid)?;
+                g_e += x[i] * (ln_phi[i] - pure.ln_phi()[0]);
+            }
+            Ok(g_e)
+        };
+        let g_e_nrtl = nrtl(3.0)?.excess_gibbs_energy(250.0, &x);
+        assert_relative_eq!(g_e(&hv)?, g_e_nrtl, max_relative = 5e-2);
+
+        // strongly non-ideal mixtures demix
+        let feed = arr1(&[0.5, 0.5]) * MOL;
+        let p = 50.0 * BAR;
+        let state = |eos| State::new_npt(eos, t, p, &feed, DensityInitialization::Liquid);
+        assert!(state(&vdw)?.is_stable(SolverOptions::default())?);
+        assert!(!state(&hv)?.is_stable(SolverOptions::default())?);
+
+        // the size of the activity coefficient model is checked
+        let parameters =
+            PengRobinsonParameters::from_records(pure_record_vec(), Array2::zeros((2, 2)))?;
+        let model = Nrtl::new(
+            Array2::zeros((3, 3)),
+            Array2::zeros((3, 3)),
+            Array2::zeros((3, 3)),
+        )?;
+        assert!(parameters
+            .with_mixing_rule(MixingRule::HuronVidal(Rc::new(model)))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn critical_point_derivatives() -> EosResult<()> {
+        let parameters =
+            PengRobinsonParameters::from_records(pure_record_vec(), Array2::zeros((2, 2)))?;
+        let pr = Rc::new(PengRobinson::new(Rc::new(parameters)));
+        let critical_point = |x: f64| {
+            let moles = arr1(&[x, 1.0 - x]) * MOL;
+            State::critical_point(&pr, Some(&moles), None, SolverOptions::default())
+        };
+        let (x, h) = (0.4, 1e-5);
+        let (dtc, dpc) = critical_point(x)?.critical_point_derivatives()?;
+        let (cp_plus, cp_minus) = (critical_point(x + h)?, critical_point(x - h)?);
+        assert_relative_eq!(
+            dtc.get(0) - dtc.get(1),
+            (cp_plus.temperature - cp_minus.temperature) / (2.0 * h),
+            max_relative = 1e-5
+        );
+        assert_relative_eq!(
+            dpc.get(0) - dpc.get(1),
+            (cp_plus.pressure(Contributions::Total) - cp_minus.pressure(Contributions::Total))
+                / (2.0 * h),
+            max_relative = 1e-5
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_molar_values() -> EosResult<()> {
+        let parameters =
+            PengRobinsonParameters::from_records(pure_record_vec(), Array2::zeros((2, 2)))?;
+        let pr = PengRobinson::new(Rc::new(parameters));
+        let x = arr1(&[0.3, 0.7]);
+        let mw = (pr.molar_weight() * &x).sum();
+
+        let molar_density = arr1(&[10.0, 12.0]) * MOL / METER.powi(3);
+        let mass_density = &molar_density * mw;
+        let density = SIUnit::reference_density();
+        assert_relative_eq!(
+            pr.to_molar_values(&mass_density, density, &x)?,
+            molar_density,
+            max_relative = 1e-14
+        );
+        assert_eq!(
+            pr.to_molar_values(&molar_density, density, &x)?,
+            molar_density
+        );
+
+        let specific_enthalpy = arr1(&[-300.0, 250.0]) * JOULE / GRAM;
+        let molar_enthalpy =
+            pr.to_molar_values(&specific_enthalpy, SIUnit::reference_molar_energy(), &x)?;
+        assert_relative_eq!(
+            molar_enthalpy,
+            &specific_enthalpy * mw,
+            max_relative = 1e-14
+        );
+        assert!(pr.to_molar_values(&specific_enthalpy, density, &x).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn partial_molar_enthalpy_derivatives() -> EosResult<()> {
+        let parameters =
+            PengRobinsonParameters::from_records(pure_record_vec(), Array2::zeros((2, 2)))?;
+        let pr = Rc::new(PengRobinson::new(Rc::new(parameters)));
+        let moles = arr1(&[0.3, 0.7]) * MOL;
+        let state = |t: SINumber, p: SINumber| {
+            State::new_npt(&pr, t, p, &moles, DensityInitialization::Liquid)
+        };
+        let (t, p) = (300.0 * KELVIN, 20.0 * BAR);
+        let (dt, dp) = (1e-4 * KELVIN, 1e-2 * BAR);
+        let s = state(t, p)?;
+        let h = |s: State<SIUnit, PengRobinson>| s.partial_molar_enthalpy(Contributions::Total);
+        let dh_dt = (h(state(t + dt, p)?) - h(state(t - dt, p)?)) / (2.0 * dt);
+        let dh_dp = (h(state(t, p + dp)?) - h(state(t, p - dp)?)) / (2.0 * dp);
+        assert_relative_eq!(
+            s.partial_molar_heat_capacity(Contributions::Total),
+            dh_dt,
+            max_relative = 1e-6
+        );
+        assert_relative_eq!(s.dh_i_dp(Contributions::Total), dh_dp, max_relative = 1e-6);
+        assert_relative_eq!(
+            (s.partial_molar_heat_capacity(Contributions::Total) * &s.molefracs).sum(),
+            s.c_p(Contributions::Total),
+            max_relative = 1e-10
+        );
+        Ok(())
+    }
 }
@@ -1,12 +1,15 @@
 use crate::equation_of_state::EquationOfState;
 use crate::errors::{EosError, EosResult};
-use crate::state::State;
+use crate::state::{State, StateHD};
 use crate::EosUnit;
+use ndarray::Array1;
+use num_dual::{Dual3, Dual3_64, Dual64};
 use quantity::{QuantityArray1, QuantityScalar};
 use std::rc::Rc;
 
 pub struct SpinodalPoint<U: EosUnit> {
     pub p: QuantityScalar<U>,
+    #[allow(dead_code)]
     pub dp_drho: QuantityScalar<U>,
     pub rho: QuantityScalar<U>,
 }
@@ -18,7 +21,7 @@ pub fn density_iteration<U: EosUnit, E: EquationOfState>(
     moles: &QuantityArray1<U>,
     initial_density: QuantityScalar<U>,
 ) -> EosResult<State<U, E>> {
-    let maxdensity = eos.max_density(Some(moles))?;
+    let maxdensity = eos.max_density_at_temperature(temperature, Some(moles))?;
     let (abstol, reltol) = (1e-12, 1e-14);
     let n = moles.sum();
 
@@ -31,11 +34,13 @@ pub fn density_iteration<U: EosUnit, E: EquationOfState>(
         ));
     }
 
+    let pressure_derivatives = PressureDerivatives::new(eos, temperature, moles)?;
+
     let maxiter = 50;
     let mut iterations = 0;
     'iteration: for k in 0..maxiter {
         iterations += 1;
-        let (mut p, mut dp_drho) = State::new_nvt(eos, temperature, n / rho, moles)?.p_dpdrho();
+        let (mut p, mut dp_drho, _) = pressure_derivatives.evaluate(rho)?;
 
         // attempt to correct for poor initial density rho_init
         if dp_drho.is_sign_negative() && k == 0 {
@@ -44,7 +49,7 @@ pub fn density_iteration<U: EosUnit, E: EquationOfState>(
             } else {
                 (1.1 * initial_density).min(maxdensity)?
             };
-            let p_ = State::new_nvt(eos, temperature, n / rho, moles)?.p_dpdrho();
+            let p_ = pressure_derivatives.evaluate(rho)?;
             p = p_.0;
             dp_drho = p_.1;
         }
@@ -59,9 +64,7 @@ pub fn density_iteration<U: EosUnit, E: EquationOfState>(
 
         // correction for instable region
         if dp_drho.is_sign_negative() && k < maxiter {
-            let d2pdrho2 = State::new_nvt(eos, temperature, n / rho, moles)?
-                .d2pdrho2()
-                .2;
+            let d2pdrho2 = pressure_derivatives.evaluate(rho)?.2;
 
             if rho > 0.85 * maxdensity {
                 let sp = pressure_spinodal(eos, temperature, initial_density, moles)?;
@@ -145,7 +148,8 @@ pub fn density_iteration<U: EosUnit, E: EquationOfState>(
     if iterations == maxiter + 1 {
         Err(EosError::NotConverged("density_iteration".to_owned()))
     } else {
-        Ok(State::new_nvt(eos, temperature, n / rho, moles)?)
+        // the inputs are validated by the callers
+        State::new_nvt_unchecked(eos, temperature, n / rho, moles)
     }
 }
 
@@ -158,8 +162,8 @@ pub fn pressure_spinodal<U: EosUnit, E: EquationOfState>(
     let maxiter = 30;
     let abstol = 1e-8;
 
-    let maxdensity = eos.max_density(Some(moles))?;
-    let n = moles.sum();
+    let maxdensity = eos.max_density_at_temperature(temperature, Some(moles))?;
+    let pressure_derivatives = PressureDerivatives::new(eos, temperature, moles)?;
     let mut rho = rho_init;
 
     if rho <= 0.0 * U::reference_density() {
@@ -171,7 +175,7 @@ pub fn pressure_spinodal<U: EosUnit, E: EquationOfState>(
     }
 
     for _ in 0..maxiter {
-        let (p, dpdrho, d2pdrho2) = State::new_nvt(eos, temperature, n / rho, moles)?.d2pdrho2();
+        let (p, dpdrho, d2pdrho2) = pressure_derivatives.evaluate(rho)?;
 
         let mut delta_rho = -dpdrho / d2pdrho2;
         if delta_rho.abs() > 0.05 * maxdensity {
@@ -195,3 +199,190 @@ pub fn pressure_spinodal<U: EosUnit, E: EquationOfState>(
     }
     Err(EosError::NotConverged("pressure_spinodal".to_owned()))
 }
+
+/// Check that the pressure increases monotonically with the density between
+/// two densities, i.e., that both densities are on the same branch and no
+/// spinodal lies between them.
+pub fn is_single_branch<U: EosUnit, E: EquationOfState>(
+    eos: &Rc<E>,
+    temperature: QuantityScalar<U>,
+    moles: &QuantityArray1<U>,
+    rho_start: QuantityScalar<U>,
+    rho_end: QuantityScalar<U>,
+) -> EosResult<bool> {
+    let npoints = 200;
+    let pressure_derivatives = PressureDerivatives::new(eos, temperature, moles)?;
+    for i in 0..=npoints {
+        let rho = rho_start + (rho_end - rho_start) * (i as f64 / npoints as f64);
+        if pressure_derivatives.evaluate(rho)?.1.is_sign_negative() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Density and logarithms of the fugacity coefficients for given temperature,
+/// pressure and composition.
+///
+/// The density is found by Newton steps from the initial density. Like for
+/// [PressureDerivatives], no [State] is constructed, unless the iteration
+/// leaves the stable branch and the full [density_iteration] is required.
+pub(crate) fn density_ln_phi<U: EosUnit, E: EquationOfState>(
+    eos: &Rc<E>,
+    temperature: QuantityScalar<U>,
+    pressure: QuantityScalar<U>,
+    molefracs: &Array1<f64>,
+    initial_density: QuantityScalar<U>,
+) -> EosResult<(QuantityScalar<U>, Array1<f64>)> {
+    let (abstol, reltol) = (1e-12, 1e-14);
+    let moles = molefracs * U::reference_moles();
+    let pressure_derivatives = PressureDerivatives::new(eos, temperature, &moles)?;
+
+    let mut density = None;
+    let mut rho = initial_density;
+    for _ in 0..50 {
+        let (p, dp_drho, _) = pressure_derivatives.evaluate(rho)?;
+        if dp_drho.is_sign_negative() {
+            break;
+        }
+        let error = p - pressure;
+        rho -= error / dp_drho;
+        if rho.is_sign_negative() {
+            break;
+        }
+        if error.to_reduced(U::reference_pressure())?.abs()
+            < f64::max(abstol, (rho * reltol).to_reduced(U::reference_density())?)
+        {
+            density = Some(rho);
+            break;
+        }
+    }
+    let density = match density {
+        Some(density) => density,
+        None => density_iteration(eos, temperature, pressure, &moles, initial_density)?.density,
+    };
+
+    // ln phi_i = mu_i^res(T, V) / RT - ln Z, evaluated for a unit volume
+    let t = temperature.to_reduced(U::reference_temperature())?;
+    let rho = density.to_reduced(U::reference_density())?;
+    let ln_z = (pressure.to_reduced(U::reference_pressure())? / (rho * t)).ln();
+    let ln_phi = Array1::from_shape_fn(molefracs.len(), |i| {
+        let mut n = molefracs.mapv(|x| Dual64::from(rho * x));
+        n[i].eps[0] = 1.0;
+        let state = StateHD::new(Dual64::from(t), Dual64::from(1.0), n);
+        eos.evaluate_residual(&state).eps[0] - ln_z
+    });
+    Ok((density, ln_phi))
+}
+
+/// Evaluation of the pressure and its first two density derivatives
+/// at fixed temperature and composition.
+///
+/// The Helmholtz energy density is evaluated directly for a unit volume,
+/// so that no [State] (including its cache) has to be constructed in
+/// every step of the density and spinodal iterations.
+struct PressureDerivatives<'a, U, E> {
+    eos: &'a Rc<E>,
+    temperature: f64,
+    molefracs: Array1<f64>,
+    reference: [QuantityScalar<U>; 4],
+}
+
+impl<'a, U: EosUnit, E: EquationOfState> PressureDerivatives<'a, U, E> {
+    fn new(
+        eos: &'a Rc<E>,
+        temperature: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+    ) -> EosResult<Self> {
+        let moles = moles.to_reduced(U::reference_moles())?;
+        let density = U::reference_moles() / U::reference_volume();
+        let pressure = U::reference_energy() / U::reference_volume();
+        Ok(Self {
+            eos,
+            temperature: temperature.to_reduced(U::reference_temperature())?,
+            molefracs: &moles / moles.sum(),
+            reference: [
+                density,
+                pressure,
+                pressure / density,
+                pressure / (density * density),
+            ],
+        })
+    }
+
+    /// Return the pressure and its first and second derivative w.r.t. the density.
+    fn evaluate(
+        &self,
+        density: QuantityScalar<U>,
+    ) -> EosResult<(QuantityScalar<U>, QuantityScalar<U>, QuantityScalar<U>)> {
+        let [r_density, r_pressure, r_dp, r_d2p] = self.reference;
+        let rho = density.to_reduced(r_density)?;
+        let state = StateHD::new(
+            Dual3::from(self.temperature),
+            Dual3::from(1.0),
+            self.molefracs.mapv(|x| Dual3_64::new(rho * x, x, 0.0, 0.0)),
+        );
+        // Helmholtz energy density a and its density derivatives
+        let a = (self.eos.evaluate_residual(&state) + self.eos.ideal_gas().evaluate(&state))
+            * self.temperature;
+        let p = rho * a.v1 - a.re;
+        let dp_drho = rho * a.v2;
+        let d2p_drho2 = a.v2 + rho * a.v3;
+        Ok((p * r_pressure, dp_drho * r_dp, d2p_drho2 * r_d2p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use crate::Contributions;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    #[test]
+    fn test_pressure_derivatives() -> EosResult<()> {
+        let eos = propane_butane();
+        let (t, moles) = (300.0 * KELVIN, arr1(&[0.3, 0.7]) * MOL);
+        let pressure_derivatives = PressureDerivatives::new(&eos, t, &moles)?;
+        for &rho in &[10.0, 9000.0] {
+            let density = rho * MOL / METER.powi(3);
+            let state = State::new_nvt(&eos, t, moles.sum() / density, &moles)?;
+            let (p, dp_drho, d2p_drho2) = pressure_derivatives.evaluate(density)?;
+            let v = state.volume;
+            let dp_dv = state.dp_dv(Contributions::Total);
+            let d2p_dv2 = state.d2p_dv2(Contributions::Total);
+            assert_relative_eq!(
+                p,
+                state.pressure(Contributions::Total),
+                max_relative = 1e-12
+            );
+            assert_relative_eq!(
+                dp_drho,
+                state.dp_drho(Contributions::Total),
+                max_relative = 1e-12
+            );
+            assert_relative_eq!(
+                d2p_drho2,
+                v / (density * density) * (2.0 * dp_dv + v * d2p_dv2),
+                max_relative = 1e-10
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_density_ln_phi() -> EosResult<()> {
+        let eos = propane_butane();
+        let (t, p, x) = (300.0 * KELVIN, 5.0 * BAR, arr1(&[0.3, 0.7]));
+        for &rho in &[10.0, 9000.0] {
+            let initial_density = rho * MOL / METER.powi(3);
+            let state = density_iteration(&eos, t, p, &(&x * MOL), initial_density)?;
+            let (density, ln_phi) = density_ln_phi(&eos, t, p, &x, initial_density)?;
+            assert_relative_eq!(density, state.density, max_relative = 1e-10);
+            assert_relative_eq!(ln_phi, state.ln_phi(), max_relative = 1e-10);
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use crate::errors::{EosError, EosResult};
+use crate::phase_equilibria::SaturationTable;
 use crate::state::StateHD;
 use crate::EosUnit;
 use ndarray::prelude::*;
@@ -141,6 +142,50 @@ impl fmt::Display for DefaultIdealGasContribution {
 /// specific properties.
 pub trait MolarWeight<U: EosUnit> {
     fn molar_weight(&self) -> QuantityArray1<U>;
+
+    /// Convert mass specific values (e.g., mass densities or specific
+    /// enthalpies) to the corresponding molar values for a system with
+    /// the given mole fractions.
+    ///
+    /// `molar_unit` is any quantity with the units of the molar property,
+    /// e.g., `U::reference_density()` for densities or `U::reference_molar_energy()`
+    /// for enthalpies. Values that are already molar are returned unchanged, so that
+    /// data reported on either basis can be processed in the same way.
+    fn to_molar_values(
+        &self,
+        values: &QuantityArray1<U>,
+        molar_unit: QuantityScalar<U>,
+        molefracs: &Array1<f64>,
+    ) -> EosResult<QuantityArray1<U>>
+    where
+        QuantityArray1<U>: fmt::Display,
+        QuantityScalar<U>: fmt::Display,
+    {
+        let molar_weight = (self.molar_weight() * molefracs).sum();
+        if values.has_unit(&molar_unit) {
+            Ok(values.clone())
+        } else if (molar_unit / molar_weight).has_unit(values) {
+            Ok(values * molar_weight)
+        } else if (molar_unit * molar_weight).has_unit(values) {
+            Ok(values / molar_weight)
+        } else {
+            Err(EosError::WrongUnits(
+                format!("units of {} or mass specific equivalent", molar_unit),
+                format!("{}", values),
+            ))
+        }
+    }
+}
+
+/// Optional features of an equation of state that can be queried at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Capabilities {
+    /// The model provides molar weights (see [MolarWeight]).
+    pub molar_weight: bool,
+    /// The model provides entropy scaling correlations (see [EntropyScaling]).
+    pub entropy_scaling: bool,
+    /// The maximum number of components the model can describe, if limited.
+    pub max_components: Option<usize>,
 }
 
 /// A general equation of state.
@@ -148,6 +193,20 @@ pub trait EquationOfState {
     /// Return the number of components of the equation of state.
     fn components(&self) -> usize;
 
+    /// Return `true` if the equation of state describes a pure component.
+    fn is_pure(&self) -> bool {
+        self.components() == 1
+    }
+
+    /// Return the optional features that are supported by the equation of state.
+    ///
+    /// Models that implement [MolarWeight] or [EntropyScaling] should
+    /// overwrite this function. Per default no optional features are
+    /// reported and the number of components is not limited.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
     /// Return an equation of state consisting of the components
     /// contained in component_list.
     fn subset(&self, component_list: &[usize]) -> Self;
@@ -160,6 +219,49 @@ pub trait EquationOfState {
     /// equation of state anyways).
     fn compute_max_density(&self, moles: &Array1<f64>) -> f64;
 
+    /// Return the maximum density in Angstrom^-3 at the given temperature in K.
+    ///
+    /// Models with a strongly temperature dependent packing limit can
+    /// overwrite this function. Per default the temperature independent
+    /// [EquationOfState::compute_max_density] is returned.
+    fn compute_max_density_at_temperature(&self, _temperature: f64, moles: &Array1<f64>) -> f64 {
+        self.compute_max_density(moles)
+    }
+
+    /// Return an estimate of the liquid density in Angstrom^-3 at the
+    /// given temperature in K.
+    ///
+    /// Models with density anomalies or several liquid-like roots of
+    /// the density iteration can overwrite this function to provide
+    /// a better initial value than the maximum density. Per default
+    /// `None` is returned and the maximum density is used instead.
+    fn liquid_density_estimate(&self, _temperature: f64, _moles: &Array1<f64>) -> Option<f64> {
+        None
+    }
+
+    /// Return an estimate of the vapor density in Angstrom^-3 at the
+    /// given temperature in K and pressure in units of the reference
+    /// pressure.
+    ///
+    /// Per default `None` is returned and the ideal gas density is used.
+    fn vapor_density_estimate(
+        &self,
+        _temperature: f64,
+        _pressure: f64,
+        _moles: &Array1<f64>,
+    ) -> Option<f64> {
+        None
+    }
+
+    /// Return a table of precomputed saturation properties that is used to
+    /// initialize pure component phase equilibria.
+    ///
+    /// Per default no table is available and the phase equilibria are
+    /// initialized from ideal gas or spinodal estimates.
+    fn saturation_table(&self) -> Option<&SaturationTable> {
+        None
+    }
+
     /// Return a slice of the individual contributions (excluding the ideal gas)
     /// of the equation of state.
     fn residual(&self) -> &[Box<dyn HelmholtzEnergy>];
@@ -213,14 +315,13 @@ pub trait EquationOfState {
         &self,
         moles: Option<&QuantityArray1<U>>,
     ) -> EosResult<QuantityArray1<U>> {
-        let l = moles.map_or(1, |m| m.len());
-        if self.components() == l {
-            match moles {
-                Some(m) => Ok(m.to_owned()),
-                None => Ok(Array::ones(1) * U::reference_moles()),
-            }
-        } else {
-            Err(EosError::IncompatibleComponents(self.components(), l))
+        match moles {
+            Some(m) if m.len() == self.components() => Ok(m.to_owned()),
+            Some(m) => Err(EosError::IncompatibleComponents(self.components(), m.len())),
+            None if self.is_pure() => Ok(Array::ones(1) * U::reference_moles()),
+            None => Err(EosError::MissingComposition {
+                components: self.components(),
+            }),
         }
     }
 
@@ -240,6 +341,63 @@ pub trait EquationOfState {
         Ok(self.compute_max_density(&mr) * U::reference_density())
     }
 
+    /// Calculate the maximum density at the given temperature.
+    ///
+    /// Uses [EquationOfState::compute_max_density_at_temperature] and
+    /// is used instead of [EquationOfState::max_density] wherever the
+    /// temperature is known.
+    fn max_density_at_temperature<U: EosUnit>(
+        &self,
+        temperature: QuantityScalar<U>,
+        moles: Option<&QuantityArray1<U>>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let mr = self
+            .validate_moles(moles)?
+            .to_reduced(U::reference_moles())?;
+        let t = temperature.to_reduced(U::reference_temperature())?;
+        Ok(self.compute_max_density_at_temperature(t, &mr) * U::reference_density())
+    }
+
+    /// Calculate the initial density of a liquid phase in a density iteration.
+    ///
+    /// Uses [EquationOfState::liquid_density_estimate] if the model
+    /// provides it and the maximum density at the given temperature otherwise.
+    fn initial_liquid_density<U: EosUnit>(
+        &self,
+        temperature: QuantityScalar<U>,
+        moles: Option<&QuantityArray1<U>>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let mr = self
+            .validate_moles(moles)?
+            .to_reduced(U::reference_moles())?;
+        let t = temperature.to_reduced(U::reference_temperature())?;
+        let rho = self
+            .liquid_density_estimate(t, &mr)
+            .unwrap_or_else(|| self.compute_max_density_at_temperature(t, &mr));
+        Ok(rho * U::reference_density())
+    }
+
+    /// Calculate the initial density of a vapor phase in a density iteration.
+    ///
+    /// Uses [EquationOfState::vapor_density_estimate] if the model
+    /// provides it and the ideal gas density otherwise.
+    fn initial_vapor_density<U: EosUnit>(
+        &self,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        moles: Option<&QuantityArray1<U>>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let mr = self
+            .validate_moles(moles)?
+            .to_reduced(U::reference_moles())?;
+        let t = temperature.to_reduced(U::reference_temperature())?;
+        let p = pressure.to_reduced(U::reference_pressure())?;
+        Ok(match self.vapor_density_estimate(t, p, &mr) {
+            Some(rho) => rho * U::reference_density(),
+            None => pressure / temperature / U::gas_constant(),
+        })
+    }
+
     /// Calculate the second virial coefficient $B(T)$
     fn second_virial_coefficient<U: EosUnit>(
         &self,
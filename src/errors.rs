@@ -1,6 +1,7 @@
 use crate::parameter::ParameterError;
 use num_dual::linalg::LinAlgError;
 use quantity::QuantityError;
+use std::io;
 use thiserror::Error;
 
 /// Error type for improperly defined states and convergence problems.
@@ -8,18 +9,39 @@ use thiserror::Error;
 pub enum EosError {
     #[error("`{0}` did not converge within the maximum number of iterations.")]
     NotConverged(String),
+    #[error("`{0}` was terminated by the solver observer.")]
+    Terminated(String),
     #[error("`{0}` encountered illegal values during the iteration.")]
     IterationFailed(String),
     #[error("Iteration resulted in trivial solution.")]
     TrivialSolution,
     #[error("Equation of state is initialized for {0} components while the input specifies {1} components.")]
     IncompatibleComponents(usize, usize),
+    #[error("The equation of state is initialized for {components} components, which requires a composition. Specify `moles` or `molefracs` (or `partial_density`).")]
+    MissingComposition { components: usize },
+    #[error("Phase equilibrium with {0} phases expected, but {1} phases were given.")]
+    IncompatiblePhases(usize, usize),
     #[error("Invalid state in {0}: {1} = {2}.")]
     InvalidState(String, String, f64),
     #[error("Undetermined state: {0}.")]
     UndeterminedState(String),
-    #[error("System is supercritical.")]
-    SuperCritical,
+    #[error(
+        "{} supercritical at {given}{}.",
+        .component.map_or_else(|| String::from("System is"), |i| format!("Component {} is", i)),
+        .critical.as_ref().map_or_else(String::new, |c| format!(" ({})", c))
+    )]
+    SuperCritical {
+        /// Index of the supercritical component, if the system is a mixture
+        component: Option<usize>,
+        /// The specified temperature or pressure, e.g., `T = 500 K`
+        given: String,
+        /// The critical temperature or pressure, e.g., `T_c = 420 K`, if available
+        critical: Option<String>,
+    },
+    #[error("Unknown property: {0}.")]
+    UnknownProperty(String),
+    #[error("Property `{0}` is not supported by `{1}`.")]
+    UnsupportedProperty(String, String),
     #[error("No phase split according to stability analysis.")]
     NoPhaseSplit,
     #[error("Wrong input units. Expected {0}, got {1}")]
@@ -30,6 +52,35 @@ pub enum EosError {
     ParameterError(#[from] ParameterError),
     #[error(transparent)]
     LinAlgError(#[from] LinAlgError),
+    #[error(transparent)]
+    FileIO(#[from] io::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[cfg(feature = "arrow")]
+    #[error(transparent)]
+    Arrow(#[from] arrow::error::ArrowError),
+    #[cfg(feature = "arrow")]
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+}
+
+impl EosError {
+    /// Add the index of the component to the error of a pure
+    /// component calculation in a mixture.
+    pub(crate) fn for_component(self, index: usize) -> Self {
+        match self {
+            Self::SuperCritical {
+                component: None,
+                given,
+                critical,
+            } => Self::SuperCritical {
+                component: Some(index),
+                given,
+                critical,
+            },
+            e => e,
+        }
+    }
 }
 
 /// Convenience type for `Result<T, EosError>`.
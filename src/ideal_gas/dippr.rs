@@ -0,0 +1,435 @@
+//! Implementation of the ideal gas heat capacity correlations of the
+//! [DIPPR 801 database](https://www.aiche.org/dippr).
+use crate::constants::{A3, KB, P0, RGAS, T0};
+use crate::parameter::{
+    select_pure_records, DuplicatePolicy, IdentifierOption, ParameterError, PureRecord,
+};
+use crate::{
+    EosResult, EosUnit, EquationOfState, HelmholtzEnergy, IdealGasContribution,
+    IdealGasContributionDual,
+};
+use ndarray::Array1;
+use num_dual::DualNum;
+use quantity::QuantityScalar;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Coefficients of a DIPPR correlation for the ideal gas heat capacity
+/// of a single substance.
+///
+/// The coefficients are given in the units of the DIPPR database, i.e.,
+/// the heat capacity is obtained in J/(kmol K) for temperatures in Kelvin.
+/// In JSON, the equation form is identified by the keys `dippr100`,
+/// `dippr107` and `dippr127`, e.g.,
+/// `{"dippr107": {"a": 33363.0, "b": 26790.0, "c": 2610.5, "d": 8896.0, "e": 1169.0}}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DipprRecord {
+    /// Polynomial: $c_p^\mathrm{ig}=A+BT+CT^2+DT^3+ET^4$
+    ///
+    /// Missing coefficients `c`, `d` and `e` are set to zero.
+    Dippr100 {
+        a: f64,
+        b: f64,
+        #[serde(default)]
+        c: f64,
+        #[serde(default)]
+        d: f64,
+        #[serde(default)]
+        e: f64,
+    },
+    /// Aly-Lee equation:
+    /// $c_p^\mathrm{ig}=A+B\left(\frac{C/T}{\sinh(C/T)}\right)^2+D\left(\frac{E/T}{\cosh(E/T)}\right)^2$
+    Dippr107 {
+        a: f64,
+        b: f64,
+        c: f64,
+        d: f64,
+        e: f64,
+    },
+    /// Sum of Einstein functions:
+    /// $c_p^\mathrm{ig}=A+\sum_{(X,Y)}X\frac{(Y/T)^2\exp(Y/T)}{\left(\exp(Y/T)-1\right)^2}$
+    /// with $(X,Y)\in\\{(B,C),(D,E),(F,G)\\}$
+    Dippr127 {
+        a: f64,
+        b: f64,
+        c: f64,
+        d: f64,
+        e: f64,
+        f: f64,
+        g: f64,
+    },
+}
+
+impl DipprRecord {
+    /// Create a record for DIPPR equation 100.
+    pub fn dippr100(a: f64, b: f64, c: f64, d: f64, e: f64) -> Self {
+        Self::Dippr100 { a, b, c, d, e }
+    }
+
+    /// Create a record for DIPPR equation 107.
+    pub fn dippr107(a: f64, b: f64, c: f64, d: f64, e: f64) -> Self {
+        Self::Dippr107 { a, b, c, d, e }
+    }
+
+    /// Create a record for DIPPR equation 127.
+    #[allow(clippy::too_many_arguments)]
+    pub fn dippr127(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64, g: f64) -> Self {
+        Self::Dippr127 {
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+            g,
+        }
+    }
+
+    /// The number of the DIPPR equation.
+    pub fn equation(&self) -> usize {
+        match self {
+            Self::Dippr100 { .. } => 100,
+            Self::Dippr107 { .. } => 107,
+            Self::Dippr127 { .. } => 127,
+        }
+    }
+
+    /// Heat capacity in J/(mol K).
+    fn c_p(&self, t: f64) -> f64 {
+        let c_p = match *self {
+            Self::Dippr100 { a, b, c, d, e } => a + t * (b + t * (c + t * (d + t * e))),
+            Self::Dippr107 { a, b, c, d, e } => {
+                let sinh = |x: f64| if x == 0.0 { 1.0 } else { x / x.sinh() };
+                let cosh = |x: f64| x / x.cosh();
+                a + b * sinh(c / t).powi(2) + d * cosh(e / t).powi(2)
+            }
+            Self::Dippr127 {
+                a,
+                b,
+                c,
+                d,
+                e,
+                f,
+                g,
+            } => {
+                let einstein = |x: f64| {
+                    if x == 0.0 {
+                        1.0
+                    } else {
+                        x * x * x.exp() / x.exp_m1().powi(2)
+                    }
+                };
+                a + b * einstein(c / t) + d * einstein(e / t) + f * einstein(g / t)
+            }
+        };
+        c_p * 1e-3
+    }
+
+    /// Antiderivatives of the heat capacity ($h$) and of the heat
+    /// capacity divided by the temperature ($s$) in J/mol and J/(mol K).
+    fn enthalpy_entropy<D: DualNum<f64>>(&self, t: D) -> (D, D) {
+        let (h, s) = match *self {
+            Self::Dippr100 { a, b, c, d, e } => (
+                t * (t * (t * (t * (t * e / 5.0 + d / 4.0) + c / 3.0) + b / 2.0) + a),
+                t * (t * (t * (t * e / 4.0 + d / 3.0) + c / 2.0) + b) + t.ln() * a,
+            ),
+            Self::Dippr107 { a, b, c, d, e } => {
+                let mut h = t * a;
+                let mut s = t.ln() * a;
+                if b != 0.0 && c != 0.0 {
+                    let x = t.recip() * c;
+                    let coth = x.cosh() / x.sinh();
+                    h += coth * b * c;
+                    s += (x * coth - x.sinh().ln()) * b;
+                }
+                if d != 0.0 && e != 0.0 {
+                    let x = t.recip() * e;
+                    let tanh = x.tanh();
+                    h -= tanh * d * e;
+                    s -= (x * tanh - x.cosh().ln()) * d;
+                }
+                (h, s)
+            }
+            Self::Dippr127 {
+                a,
+                b,
+                c,
+                d,
+                e,
+                f,
+                g,
+            } => {
+                let mut h = t * a;
+                let mut s = t.ln() * a;
+                for (x, y) in [(b, c), (d, e), (f, g)] {
+                    if x != 0.0 && y != 0.0 {
+                        let z = t.recip() * y;
+                        let exp_m1 = z.exp() - 1.0;
+                        h += exp_m1.recip() * x * y;
+                        s += (z / exp_m1 - (-(-z).exp() + 1.0).ln()) * x;
+                    }
+                }
+                (h, s)
+            }
+        };
+        (h * 1e-3, s * 1e-3)
+    }
+}
+
+impl fmt::Display for DipprRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dippr100 { a, b, c, d, e } | Self::Dippr107 { a, b, c, d, e } => write!(
+                f,
+                "DipprRecord(equation={}, a={}, b={}, c={}, d={}, e={})",
+                self.equation(),
+                a,
+                b,
+                c,
+                d,
+                e
+            ),
+            Self::Dippr127 {
+                a,
+                b,
+                c,
+                d,
+                e,
+                f: f_,
+                g,
+            } => write!(
+                f,
+                "DipprRecord(equation=127, a={}, b={}, c={}, d={}, e={}, f={}, g={})",
+                a, b, c, d, e, f_, g
+            ),
+        }
+    }
+}
+
+/// The ideal gas contribution from DIPPR correlations of the
+/// ideal gas heat capacity.
+///
+/// As for [Joback](crate::joback::Joback), the enthalpy and entropy of the
+/// ideal gas are zero at the reference state $T_0=298.15~\mathrm{K}$ and
+/// $p_0$. By default, the standard pressure $p_0$ is 1 bar, it can be
+/// changed with [Dippr::with_standard_pressure].
+#[derive(Debug, Clone)]
+pub struct Dippr {
+    records: Vec<DipprRecord>,
+    /// Reduced standard pressure
+    standard_pressure: f64,
+}
+
+impl Dippr {
+    /// Creates a new ideal gas contribution from DIPPR correlations.
+    pub fn new(records: Vec<DipprRecord>) -> Self {
+        Self {
+            records,
+            standard_pressure: P0 * A3 / KB,
+        }
+    }
+
+    /// Creates the ideal gas contribution from the ideal gas records of
+    /// pure records, e.g., of the parameters of a model that uses
+    /// [DipprRecord] as its ideal gas record.
+    pub fn from_pure_records<M>(
+        pure_records: &[PureRecord<M, DipprRecord>],
+    ) -> Result<Self, ParameterError> {
+        let records = pure_records
+            .iter()
+            .map(|r| {
+                r.ideal_gas_record.clone().ok_or_else(|| {
+                    ParameterError::IncompatibleParameters(format!(
+                        "no DIPPR record for {}",
+                        r.identifier
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(records))
+    }
+
+    /// Creates the ideal gas contribution from the DIPPR records that are
+    /// stored as ideal gas records in a json file of pure records. The
+    /// model records in the file are ignored.
+    pub fn from_json<P: AsRef<Path>>(
+        substances: Vec<&str>,
+        file_pure: P,
+        search_option: IdentifierOption,
+    ) -> Result<Self, ParameterError> {
+        let reader = BufReader::new(File::open(file_pure)?);
+        let records: Vec<PureRecord<serde_json::Value, DipprRecord>> =
+            serde_json::from_reader(reader)?;
+        let records = select_pure_records(
+            vec![(substances, records)],
+            search_option,
+            DuplicatePolicy::PreferFirst,
+        )?;
+        Self::from_pure_records(&records)
+    }
+
+    /// Use the given standard pressure (e.g., 1 atm instead of 1 bar)
+    /// for the ideal gas reference state.
+    pub fn with_standard_pressure<U: EosUnit>(
+        mut self,
+        standard_pressure: QuantityScalar<U>,
+    ) -> EosResult<Self> {
+        self.standard_pressure = standard_pressure.to_reduced(U::reference_pressure())?;
+        Ok(self)
+    }
+
+    /// The records of all components.
+    pub fn records(&self) -> &[DipprRecord] {
+        &self.records
+    }
+
+    /// Directly calculates the ideal gas heat capacity from the DIPPR correlations.
+    pub fn c_p<U: EosUnit>(
+        &self,
+        temperature: QuantityScalar<U>,
+        molefracs: &Array1<f64>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let t = temperature.to_reduced(U::reference_temperature())?;
+        let c_p: f64 = self
+            .records
+            .iter()
+            .zip(molefracs.iter())
+            .map(|(r, &x)| x * r.c_p(t))
+            .sum();
+        Ok(c_p / RGAS * U::gas_constant())
+    }
+}
+
+impl fmt::Display for Dippr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ideal gas (DIPPR)")
+    }
+}
+
+impl<D: DualNum<f64>> IdealGasContributionDual<D> for Dippr {
+    fn de_broglie_wavelength(&self, temperature: D, components: usize) -> Array1<D> {
+        let t = temperature;
+        let f = (temperature / self.standard_pressure).ln();
+        Array1::from_shape_fn(components, |i| {
+            let r = &self.records[i];
+            let (h, s) = r.enthalpy_entropy(t);
+            let (h0, s0) = r.enthalpy_entropy(T0);
+            (h - h0 - t * (s - s0)) / (t * RGAS) + f
+        })
+    }
+}
+
+impl EquationOfState for Dippr {
+    fn components(&self) -> usize {
+        self.records.len()
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self {
+            records: component_list
+                .iter()
+                .map(|&i| self.records[i].clone())
+                .collect(),
+            standard_pressure: self.standard_pressure,
+        }
+    }
+
+    fn compute_max_density(&self, _moles: &Array1<f64>) -> f64 {
+        1.0
+    }
+
+    fn residual(&self) -> &[Box<dyn HelmholtzEnergy>] {
+        &[]
+    }
+
+    fn ideal_gas(&self) -> &dyn IdealGasContribution {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameter::PureRecord;
+    use crate::{Contributions, DensityInitialization, State};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+    use std::rc::Rc;
+
+    #[derive(Deserialize, Clone, Debug)]
+    struct ModelRecord;
+
+    #[test]
+    fn dippr() -> EosResult<()> {
+        // water (equation 107), and artificial records for equations 100 and 127
+        let json = r#"[
+            {
+                "identifier": {"name": "water"},
+                "model_record": null,
+                "ideal_gas_record": {
+                    "dippr107": {"a": 33363.0, "b": 26790.0, "c": 2610.5, "d": 8896.0, "e": 1169.0}
+                },
+                "molarweight": 18.015
+            },
+            {
+                "identifier": {"name": "polynomial"},
+                "model_record": null,
+                "ideal_gas_record": {"dippr100": {"a": 30000.0, "b": 20.0, "c": 0.01}},
+                "molarweight": 1.0
+            },
+            {
+                "identifier": {"name": "einstein"},
+                "model_record": null,
+                "ideal_gas_record": {
+                    "dippr127": {"a": 33000.0, "b": 20000.0, "c": 1500.0, "d": 10000.0, "e": 600.0, "f": 0.0, "g": 0.0}
+                },
+                "molarweight": 1.0
+            }
+        ]"#;
+        let mut records: Vec<PureRecord<ModelRecord, DipprRecord>> = serde_json::from_str(json)?;
+        let dippr = Rc::new(Dippr::from_pure_records(&records)?);
+        assert_eq!(
+            dippr.records()[1],
+            DipprRecord::dippr100(30000.0, 20.0, 0.01, 0.0, 0.0)
+        );
+
+        let moles = arr1(&[1.0]) * MOL;
+        let x = arr1(&[1.0]);
+        let t0 = 298.15 * KELVIN;
+        for i in 0..3 {
+            let pure = Rc::new(dippr.subset(&[i]));
+            for t in [250.0 * KELVIN, t0, 800.0 * KELVIN] {
+                let state = State::new_npt(&pure, t, BAR, &moles, DensityInitialization::None)?;
+                assert_relative_eq!(
+                    state.c_p(Contributions::IdealGas),
+                    pure.c_p(t, &x)?,
+                    max_relative = 1e-10
+                );
+            }
+
+            // enthalpy and entropy are zero at the reference state
+            let state = State::new_npt(&pure, t0, BAR, &moles, DensityInitialization::None)?;
+            let h = state.molar_enthalpy(Contributions::IdealGas);
+            let s = state.molar_entropy(Contributions::IdealGas);
+            assert!(h.to_reduced(JOULE / MOL)?.abs() < 1e-8);
+            assert!(s.to_reduced(JOULE / MOL / KELVIN)?.abs() < 1e-8);
+        }
+
+        // heat capacity of water at 298.15 K
+        assert_relative_eq!(
+            dippr.subset(&[0]).c_p(t0, &x)?,
+            33.58 * JOULE / MOL / KELVIN,
+            max_relative = 1e-3
+        );
+
+        // all pure records require a DIPPR record
+        records[1].ideal_gas_record = None;
+        assert!(Dippr::from_pure_records(&records).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+//! Ideal gas contributions that can be combined with any
+//! residual Helmholtz energy model.
+//!
+//! The heat capacity model of [Joback and Reid](crate::joback) is
+//! available in its own module.
+mod dippr;
+mod nasa;
+pub use dippr::{Dippr, DipprRecord};
+pub use nasa::{Nasa, NasaRange, NasaRecord};
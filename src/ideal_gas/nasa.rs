@@ -0,0 +1,377 @@
+//! Implementation of the ideal gas heat capacity, enthalpy and entropy
+//! from the NASA 7 and NASA 9 polynomials
+//! ([McBride et al., 2002](https://ntrs.nasa.gov/citations/20020085330)).
+use crate::constants::{A3, KB, P0};
+use crate::parameter::ParameterError;
+use crate::{
+    EosResult, EosUnit, EquationOfState, HelmholtzEnergy, IdealGasContribution,
+    IdealGasContributionDual,
+};
+use ndarray::Array1;
+use num_dual::DualNum;
+use quantity::QuantityScalar;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Coefficients of a NASA 9 polynomial in the temperature range
+/// from `t_min` to `t_max` (in Kelvin):
+///
+/// $$\frac{c_p^\mathrm{ig}}{R}=a_1T^{-2}+a_2T^{-1}+a_3+a_4T+a_5T^2+a_6T^3+a_7T^4$$
+///
+/// The integration constants `b` determine the enthalpy ($b_1$)
+/// and the entropy ($b_2$) at the standard pressure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NasaRange {
+    t_min: f64,
+    t_max: f64,
+    a: [f64; 7],
+    b: [f64; 2],
+}
+
+impl NasaRange {
+    /// Create the coefficients for a single temperature range.
+    pub fn new(t_min: f64, t_max: f64, a: [f64; 7], b: [f64; 2]) -> Self {
+        Self { t_min, t_max, a, b }
+    }
+
+    /// Coefficients of a NASA 7 polynomial $\frac{c_p^\mathrm{ig}}{R}=a_1+a_2T+a_3T^2+a_4T^3+a_5T^4$
+    /// with the integration constants $a_6$ and $a_7$.
+    fn nasa7(t_min: f64, t_max: f64, a: &[f64; 7]) -> Self {
+        Self::new(
+            t_min,
+            t_max,
+            [0.0, 0.0, a[0], a[1], a[2], a[3], a[4]],
+            [a[5], a[6]],
+        )
+    }
+
+    fn c_p(&self, t: f64) -> f64 {
+        let a = &self.a;
+        a[0] / (t * t) + a[1] / t + a[2] + t * (a[3] + t * (a[4] + t * (a[5] + t * a[6])))
+    }
+
+    /// Molar Gibbs energy $\frac{h-Ts}{RT}$ at the standard pressure.
+    fn gibbs_energy<D: DualNum<f64>>(&self, t: D) -> D {
+        let a = &self.a;
+        let (ln_t, t_inv) = (t.ln(), t.recip());
+        let h = -t_inv.powi(2) * a[0]
+            + ln_t * t_inv * a[1]
+            + a[2]
+            + t * (t * (t * (t * a[6] / 5.0 + a[5] / 4.0) + a[4] / 3.0) + a[3] / 2.0)
+            + t_inv * self.b[0];
+        let s = -t_inv.powi(2) * a[0] / 2.0 - t_inv * a[1]
+            + ln_t * a[2]
+            + t * (t * (t * (t * a[6] / 4.0 + a[5] / 3.0) + a[4] / 2.0) + a[3])
+            + self.b[1];
+        h - s
+    }
+}
+
+/// NASA polynomials of a single substance.
+///
+/// In JSON, NASA 7 polynomials are specified with the key `nasa7`,
+/// the limits of the low (`t_low` to `t_mid`) and the high (`t_mid`
+/// to `t_high`) temperature range and the seven coefficients of each
+/// range. NASA 9 polynomials are specified with the key `nasa9` and
+/// a list of [NasaRange]s in ascending order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NasaRecord {
+    Nasa7 {
+        t_low: f64,
+        t_mid: f64,
+        t_high: f64,
+        low: [f64; 7],
+        high: [f64; 7],
+    },
+    Nasa9(Vec<NasaRange>),
+}
+
+impl NasaRecord {
+    /// Create a record from the NASA 7 coefficients of the
+    /// low and the high temperature range.
+    pub fn nasa7(t_low: f64, t_mid: f64, t_high: f64, low: [f64; 7], high: [f64; 7]) -> Self {
+        Self::Nasa7 {
+            t_low,
+            t_mid,
+            t_high,
+            low,
+            high,
+        }
+    }
+
+    /// Create a record from the NASA 9 coefficients of one or more temperature ranges.
+    pub fn nasa9(ranges: Vec<NasaRange>) -> Self {
+        Self::Nasa9(ranges)
+    }
+
+    /// All temperature ranges in the form of NASA 9 polynomials.
+    fn ranges(&self) -> Result<Vec<NasaRange>, ParameterError> {
+        let ranges = match self {
+            Self::Nasa7 {
+                t_low,
+                t_mid,
+                t_high,
+                low,
+                high,
+            } => vec![
+                NasaRange::nasa7(*t_low, *t_mid, low),
+                NasaRange::nasa7(*t_mid, *t_high, high),
+            ],
+            Self::Nasa9(ranges) => ranges.clone(),
+        };
+        if ranges.is_empty() || ranges.windows(2).any(|r| r[0].t_max > r[1].t_min) {
+            return Err(ParameterError::IncompatibleParameters(String::from(
+                "NASA polynomials require at least one temperature range and ascending, non-overlapping ranges.",
+            )));
+        }
+        Ok(ranges)
+    }
+}
+
+impl fmt::Display for NasaRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nasa7 {
+                t_low,
+                t_mid,
+                t_high,
+                low,
+                high,
+            } => write!(
+                f,
+                "NasaRecord(nasa7, t_low={} K, t_mid={} K, t_high={} K, low={:?}, high={:?})",
+                t_low, t_mid, t_high, low, high
+            ),
+            Self::Nasa9(ranges) => {
+                write!(f, "NasaRecord(nasa9")?;
+                for r in ranges {
+                    write!(f, ", {}-{} K: a={:?}, b={:?}", r.t_min, r.t_max, r.a, r.b)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// The ideal gas contribution from NASA 7 or NASA 9 polynomials.
+///
+/// In contrast to [Joback](crate::joback::Joback), the enthalpies and entropies
+/// are absolute, i.e., they include the enthalpy of formation and the third
+/// law entropy at the standard pressure $p_0$. By default, $p_0$ is 1 bar, it
+/// can be changed with [Nasa::with_standard_pressure] for older data that is
+/// based on 1 atm. Outside of the temperature ranges of a record, the
+/// polynomial of the closest range is extrapolated.
+#[derive(Debug, Clone)]
+pub struct Nasa {
+    records: Vec<NasaRecord>,
+    ranges: Vec<Vec<NasaRange>>,
+    /// Reduced standard pressure
+    standard_pressure: f64,
+}
+
+impl Nasa {
+    /// Creates a new ideal gas contribution from NASA polynomials.
+    pub fn new(records: Vec<NasaRecord>) -> Result<Self, ParameterError> {
+        let ranges = records
+            .iter()
+            .map(|r| r.ranges())
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            records,
+            ranges,
+            standard_pressure: P0 * A3 / KB,
+        })
+    }
+
+    /// Use the given standard pressure (e.g., 1 atm instead of 1 bar)
+    /// for the ideal gas reference state.
+    pub fn with_standard_pressure<U: EosUnit>(
+        mut self,
+        standard_pressure: QuantityScalar<U>,
+    ) -> EosResult<Self> {
+        self.standard_pressure = standard_pressure.to_reduced(U::reference_pressure())?;
+        Ok(self)
+    }
+
+    /// The records of all components.
+    pub fn records(&self) -> &[NasaRecord] {
+        &self.records
+    }
+
+    /// Directly calculates the ideal gas heat capacity from the NASA polynomials.
+    pub fn c_p<U: EosUnit>(
+        &self,
+        temperature: QuantityScalar<U>,
+        molefracs: &Array1<f64>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let t = temperature.to_reduced(U::reference_temperature())?;
+        let c_p: f64 = self
+            .ranges
+            .iter()
+            .zip(molefracs.iter())
+            .map(|(r, &x)| x * range(r, t).c_p(t))
+            .sum();
+        Ok(c_p * U::gas_constant())
+    }
+}
+
+/// The range that contains the temperature `t` or the closest range.
+fn range(ranges: &[NasaRange], t: f64) -> &NasaRange {
+    ranges
+        .iter()
+        .find(|r| t <= r.t_max)
+        .unwrap_or_else(|| &ranges[ranges.len() - 1])
+}
+
+impl fmt::Display for Nasa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ideal gas (NASA polynomials)")
+    }
+}
+
+impl<D: DualNum<f64>> IdealGasContributionDual<D> for Nasa {
+    fn de_broglie_wavelength(&self, temperature: D, components: usize) -> Array1<D> {
+        let f = (temperature / self.standard_pressure).ln();
+        Array1::from_shape_fn(components, |i| {
+            range(&self.ranges[i], temperature.re()).gibbs_energy(temperature) + f
+        })
+    }
+}
+
+impl EquationOfState for Nasa {
+    fn components(&self) -> usize {
+        self.records.len()
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self {
+            records: component_list
+                .iter()
+                .map(|&i| self.records[i].clone())
+                .collect(),
+            ranges: component_list
+                .iter()
+                .map(|&i| self.ranges[i].clone())
+                .collect(),
+            standard_pressure: self.standard_pressure,
+        }
+    }
+
+    fn compute_max_density(&self, _moles: &Array1<f64>) -> f64 {
+        1.0
+    }
+
+    fn residual(&self) -> &[Box<dyn HelmholtzEnergy>] {
+        &[]
+    }
+
+    fn ideal_gas(&self) -> &dyn IdealGasContribution {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Contributions, DensityInitialization, State};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+    use std::rc::Rc;
+
+    /// Nitrogen from the thermodynamic data of GRI-Mech 3.0.
+    fn nitrogen() -> NasaRecord {
+        serde_json::from_str(
+            r#"{
+                "nasa7": {
+                    "t_low": 300.0,
+                    "t_mid": 1000.0,
+                    "t_high": 5000.0,
+                    "low": [3.298677, 1.4082404e-3, -3.963222e-6, 5.641515e-9, -2.444854e-12, -1020.8999, 3.950372],
+                    "high": [2.92664, 1.4879768e-3, -5.68476e-7, 1.0097038e-10, -6.753351e-15, -922.7977, 5.980528]
+                }
+            }"#,
+        )
+        .expect("Unable to parse json.")
+    }
+
+    #[test]
+    fn nasa7() -> EosResult<()> {
+        let nasa = Rc::new(Nasa::new(vec![nitrogen()])?);
+        let moles = arr1(&[1.0]) * MOL;
+        let x = arr1(&[1.0]);
+
+        // heat capacity and third law entropy at standard conditions
+        let t = 298.15 * KELVIN;
+        let state = State::new_npt(&nasa, t, BAR, &moles, DensityInitialization::None)?;
+        let c_p = state.c_p(Contributions::IdealGas);
+        assert_relative_eq!(c_p, nasa.c_p(t, &x)?, max_relative = 1e-10);
+        assert_relative_eq!(c_p, 29.12 * JOULE / MOL / KELVIN, max_relative = 2e-3);
+        assert_relative_eq!(
+            state.molar_entropy(Contributions::IdealGas),
+            191.6 * JOULE / MOL / KELVIN,
+            max_relative = 2e-3
+        );
+        assert!(
+            state
+                .molar_enthalpy(Contributions::IdealGas)
+                .to_reduced(JOULE / MOL)?
+                .abs()
+                < 10.0
+        );
+
+        // the polynomials of both temperature ranges match at t_mid
+        let (t_minus, t_plus) = (999.99 * KELVIN, 1000.01 * KELVIN);
+        assert_relative_eq!(
+            nasa.c_p(t_minus, &x)?,
+            nasa.c_p(t_plus, &x)?,
+            max_relative = 1e-3
+        );
+        let h = |t| -> EosResult<_> {
+            let state = State::new_npt(&nasa, t, BAR, &moles, DensityInitialization::None)?;
+            Ok(state.molar_enthalpy(Contributions::IdealGas))
+        };
+        assert_relative_eq!(h(t_minus)?, h(t_plus)?, max_relative = 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn nasa9() -> EosResult<()> {
+        // the NASA 7 polynomials expressed as NASA 9 polynomials
+        let ranges = match nitrogen() {
+            NasaRecord::Nasa7 {
+                t_low,
+                t_mid,
+                t_high,
+                low,
+                high,
+            } => vec![
+                NasaRange::nasa7(t_low, t_mid, &low),
+                NasaRange::nasa7(t_mid, t_high, &high),
+            ],
+            _ => unreachable!(),
+        };
+        let record = NasaRecord::nasa9(ranges);
+        let json = serde_json::to_string(&record)?;
+        assert_eq!(serde_json::from_str::<NasaRecord>(&json)?, record);
+
+        let nasa7 = Rc::new(Nasa::new(vec![nitrogen()])?);
+        let nasa9 = Rc::new(Nasa::new(vec![record])?);
+        let moles = arr1(&[1.0]) * MOL;
+        for t in [250.0 * KELVIN, 500.0 * KELVIN, 2000.0 * KELVIN] {
+            let s7 = State::new_npt(&nasa7, t, BAR, &moles, DensityInitialization::None)?;
+            let s9 = State::new_npt(&nasa9, t, BAR, &moles, DensityInitialization::None)?;
+            assert_relative_eq!(
+                s7.molar_gibbs_energy(Contributions::IdealGas),
+                s9.molar_gibbs_energy(Contributions::IdealGas),
+                max_relative = 1e-12
+            );
+        }
+
+        // overlapping ranges are rejected
+        let range = NasaRange::new(300.0, 1000.0, [0.0, 0.0, 3.5, 0.0, 0.0, 0.0, 0.0], [0.0; 2]);
+        assert!(Nasa::new(vec![NasaRecord::nasa9(vec![range.clone(), range])]).is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! Implementation of the ideal gas heat capacity (de Broglie wavelength)
 //! of [Joback and Reid, 1987](https://doi.org/10.1080/00986448708960487).
 
+use crate::constants::{A3, KB, P0, RGAS, T0};
 use crate::parameter::*;
 use crate::{
     EosResult, EosUnit, EquationOfState, HelmholtzEnergy, IdealGasContribution,
@@ -12,13 +13,14 @@ use num_dual::*;
 use quantity::QuantityScalar;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::ops::{Add, Mul};
 
 /// Coefficients used in the Joback model.
 ///
 /// Contains an additional fourth order polynomial coefficient `e`
 /// which is not used in the original publication but is used in
 /// parametrization for additional molecules in other publications.
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct JobackRecord {
     a: f64,
     b: f64,
@@ -44,6 +46,37 @@ impl fmt::Display for JobackRecord {
     }
 }
 
+/// The coefficients of a sum of records are the sums of the coefficients,
+/// i.e., the heat capacity of the sum is the sum of the heat capacities.
+impl Add for JobackRecord {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            a: self.a + rhs.a,
+            b: self.b + rhs.b,
+            c: self.c + rhs.c,
+            d: self.d + rhs.d,
+            e: self.e + rhs.e,
+        }
+    }
+}
+
+/// All coefficients are scaled by the same factor.
+impl Mul<f64> for JobackRecord {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Self {
+            a: self.a * rhs,
+            b: self.b * rhs,
+            c: self.c * rhs,
+            d: self.d * rhs,
+            e: self.e * rhs,
+        }
+    }
+}
+
 /// Implementation of the combining rules as described in
 /// [Joback and Reid, 1987](https://doi.org/10.1080/00986448708960487).
 impl<T: Copy + ValueInto<f64>> FromSegments<T> for JobackRecord {
@@ -67,15 +100,44 @@ impl<T: Copy + ValueInto<f64>> FromSegments<T> for JobackRecord {
 
 /// The ideal gas contribution according to
 /// [Joback and Reid, 1987](https://doi.org/10.1080/00986448708960487).
-#[derive(Debug, Clone)]
+///
+/// The enthalpy and entropy of the ideal gas are zero at the reference
+/// state $T_0=298.15~\mathrm{K}$ and $p_0$. By default, the standard pressure
+/// $p_0$ is 1 bar, it can be changed with [Joback::with_standard_pressure].
+#[derive(Debug, Clone, PartialEq)]
 pub struct Joback {
     pub records: Vec<JobackRecord>,
+    /// Reduced standard pressure
+    standard_pressure: f64,
 }
 
 impl Joback {
     /// Creates a new Joback contribution.
     pub fn new(records: Vec<JobackRecord>) -> Self {
-        Self { records }
+        Self {
+            records,
+            standard_pressure: P0 * A3 / KB,
+        }
+    }
+
+    /// Use the given standard pressure (e.g., 1 atm instead of 1 bar)
+    /// for the ideal gas reference state.
+    ///
+    /// The standard pressure affects all properties that depend on
+    /// the entropy of the ideal gas, e.g., entropies, Helmholtz and
+    /// Gibbs energies and chemical potentials, for every choice
+    /// of [Contributions](crate::Contributions).
+    pub fn with_standard_pressure<U: EosUnit>(
+        mut self,
+        standard_pressure: QuantityScalar<U>,
+    ) -> EosResult<Self> {
+        self.standard_pressure = standard_pressure.to_reduced(U::reference_pressure())?;
+        Ok(self)
+    }
+
+    /// The standard pressure of the ideal gas reference state.
+    pub fn standard_pressure<U: EosUnit>(&self) -> QuantityScalar<U> {
+        self.standard_pressure * U::reference_pressure()
     }
 
     /// Creates a default ($c_p^\mathrm{ig}=0$) ideal gas contribution for the
@@ -105,17 +167,11 @@ impl fmt::Display for Joback {
     }
 }
 
-const RGAS: f64 = 6.022140857 * 1.38064852;
-const T0: f64 = 298.15;
-const P0: f64 = 1.0e5;
-const A3: f64 = 1e-30;
-const KB: f64 = 1.38064852e-23;
-
 impl<D: DualNum<f64>> IdealGasContributionDual<D> for Joback {
     fn de_broglie_wavelength(&self, temperature: D, components: usize) -> Array1<D> {
         let t = temperature;
         let t2 = t * t;
-        let f = (temperature * KB / (P0 * A3)).ln();
+        let f = (temperature / self.standard_pressure).ln();
         Array1::from_shape_fn(components, |i| {
             let j = &self.records[i];
             let h = (t2 - T0 * T0) * 0.5 * j.b
@@ -143,7 +199,10 @@ impl EquationOfState for Joback {
             .iter()
             .map(|&i| self.records[i].clone())
             .collect();
-        Self::new(records)
+        Self {
+            records,
+            standard_pressure: self.standard_pressure,
+        }
     }
 
     fn compute_max_density(&self, _moles: &Array1<f64>) -> f64 {
@@ -161,7 +220,7 @@ impl EquationOfState for Joback {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Contributions, State, StateBuilder};
+    use crate::{Contributions, DensityInitialization, State, StateBuilder};
     use approx::assert_relative_eq;
     use ndarray::arr1;
     use quantity::si::*;
@@ -302,4 +361,34 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn standard_pressure() -> EosResult<()> {
+        let record = JobackRecord::new(1.0, 0.2, 0.03, 0.004, 0.005);
+        let joback = Joback::new(vec![record]);
+        assert_relative_eq!(joback.standard_pressure(), BAR, max_relative = 1e-14);
+        let atm = 101325.0 * PASCAL;
+        let joback_atm = joback.clone().with_standard_pressure(atm)?;
+        assert_relative_eq!(joback_atm.standard_pressure(), atm, max_relative = 1e-14);
+
+        // the entropy of the ideal gas vanishes at the reference state
+        let t0 = 298.15 * KELVIN;
+        let moles = arr1(&[1.0]) * MOL;
+        for (eos, p0) in [(joback, BAR), (joback_atm, atm)] {
+            let eos = Rc::new(eos);
+            let state = State::new_npt(&eos, t0, p0, &moles, DensityInitialization::None)?;
+            for contributions in [Contributions::IdealGas, Contributions::Total] {
+                let s = state
+                    .molar_entropy(contributions)
+                    .to_reduced(JOULE / MOL / KELVIN)?;
+                assert!(s.abs() < 1e-10);
+            }
+            assert_relative_eq!(
+                eos.subset(&[0]).standard_pressure(),
+                p0,
+                max_relative = 1e-14
+            );
+        }
+        Ok(())
+    }
 }
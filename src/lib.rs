@@ -6,7 +6,12 @@
 use quantity::si::*;
 use quantity::*;
 
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use log;
+
 /// Print messages with level `Verbosity::Iter` or higher.
+#[cfg(not(feature = "log"))]
 #[macro_export]
 macro_rules! log_iter {
     ($verbosity:expr, $($arg:tt)*) => {
@@ -16,7 +21,20 @@ macro_rules! log_iter {
     }
 }
 
+/// Log messages with level `Verbosity::Iter` or higher
+/// with the `debug` level of the `log` crate.
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! log_iter {
+    ($verbosity:expr, $($arg:tt)*) => {
+        if $verbosity >= Verbosity::Iter {
+            $crate::log::debug!($($arg)*);
+        }
+    }
+}
+
 /// Print messages with level `Verbosity::Result` or higher.
+#[cfg(not(feature = "log"))]
 #[macro_export]
 macro_rules! log_result {
     ($verbosity:expr, $($arg:tt)*) => {
@@ -26,27 +44,57 @@ macro_rules! log_result {
     }
 }
 
+/// Log messages with level `Verbosity::Result` or higher
+/// with the `info` level of the `log` crate.
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! log_result {
+    ($verbosity:expr, $($arg:tt)*) => {
+        if $verbosity >= Verbosity::Result {
+            $crate::log::info!($($arg)*);
+        }
+    }
+}
+
+mod constants;
 pub mod cubic;
 mod density_iteration;
 mod equation_of_state;
 mod errors;
+pub mod ideal_gas;
 pub mod joback;
+pub mod multiparameter;
 pub mod parameter;
 mod phase_equilibria;
+pub mod property_package;
 mod state;
+pub mod surface_tension;
 pub use equation_of_state::{
-    EntropyScaling, EquationOfState, HelmholtzEnergy, HelmholtzEnergyDual, IdealGasContribution,
-    IdealGasContributionDual, MolarWeight,
+    Capabilities, EntropyScaling, EquationOfState, HelmholtzEnergy, HelmholtzEnergyDual,
+    IdealGasContribution, IdealGasContributionDual, MolarWeight,
 };
 pub use errors::{EosError, EosResult};
 pub use phase_equilibria::{
-    PhaseDiagram, PhaseDiagramHetero, PhaseEquilibrium, SolverOptions, Verbosity,
+    Acceleration, BubbleDewInitialization, BulkConditions, CompositionPath,
+    EnthalpyConcentrationDiagram, EnthalpyProfile, IterationHistory, IterationRecord,
+    JacobianUpdate, PhaseDiagram, PhaseDiagramData, PhaseDiagramDifference, PhaseDiagramHetero,
+    PhaseDiagramHeteroData, PhaseEquilibrium, PhaseEquilibriumN, RetryPolicy, SaturationTable,
+    SolverObserver, SolverOptions, StabilityResult, Verbosity,
+};
+pub use state::{
+    Basis, Contributions, DensityInitialization, DerivativeCheck, MolefracsNormalization, Property,
+    ResponseFunction, State, StateBuilder, StateHD, StatePath, StateVec, StateVecProperties,
 };
-pub use state::{Contributions, DensityInitialization, State, StateBuilder, StateHD, StateVec};
 
 #[cfg(feature = "python")]
 pub mod python;
 
+#[cfg(feature = "arrow")]
+mod record_batch;
+
+#[cfg(feature = "feos-core-capi")]
+pub mod capi;
+
 /// Consistent conversions between quantities and reduced properties.
 pub trait EosUnit: Unit {
     fn reference_temperature() -> QuantityScalar<Self>;
@@ -119,3 +167,17 @@ impl EosUnit for SIUnit {
         RGAS
     }
 }
+
+/// Unique path of a temporary file, so that tests running
+/// in parallel (or in parallel processes) do not interfere.
+#[cfg(test)]
+pub(crate) fn temp_path(name: &str) -> std::path::PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    std::env::temp_dir().join(format!(
+        "feos_core_{}_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+        name
+    ))
+}
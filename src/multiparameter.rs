@@ -0,0 +1,468 @@
+//! Implementation of multiparameter Helmholtz energy equations of state for pure substances.
+//!
+//! Reference equations of state for pure fluids are formulated as an explicit expression
+//! for the reduced Helmholtz energy
+//! $$\frac{A}{nRT}=\alpha^0(\delta,\tau)+\alpha^\mathrm{r}(\delta,\tau)$$
+//! with $\delta=\rho/\rho_\mathrm{r}$ and $\tau=T_\mathrm{r}/T$. The coefficients are read
+//! from json files in the format that is used by [CoolProp](http://www.coolprop.org/) for its
+//! fluid files. Either a complete fluid file (in which case the first entry of `EOS` is used)
+//! or a single entry of the `EOS` list can be read.
+//!
+//! The following terms are supported for the residual Helmholtz energy:
+//! - `ResidualHelmholtzPower`: $n\delta^d\tau^t\exp\left(-\delta^l\right)$ (or $n\delta^d\tau^t$ for $l=0$)
+//! - `ResidualHelmholtzExponential`: $n\delta^d\tau^t\exp\left(-g\delta^l\right)$
+//! - `ResidualHelmholtzGaussian`: $n\delta^d\tau^t\exp\left(-\eta(\delta-\varepsilon)^2-\beta(\tau-\gamma)^2\right)$
+//!
+//! and for the ideal gas Helmholtz energy (the term $\ln\delta$ is always included):
+//! - `IdealGasHelmholtzLead`: $a_1+a_2\tau$
+//! - `IdealGasHelmholtzEnthalpyEntropyOffset`: $a_1+a_2\tau$
+//! - `IdealGasHelmholtzLogTau`: $a\ln\tau$
+//! - `IdealGasHelmholtzPower`: $n\tau^t$
+//! - `IdealGasHelmholtzPlanckEinstein`: $n\ln\left(1-\exp(-t\tau)\right)$
+//! - `IdealGasHelmholtzPlanckEinsteinGeneralized`: $n\ln\left(c+d\exp(t\tau)\right)$
+//!
+//! The gas constant of the individual equations is not used, i.e., all properties
+//! are calculated with the (exact) gas constant of the SI system.
+use crate::constants::NAV_A3;
+use crate::equation_of_state::{
+    Capabilities, EquationOfState, HelmholtzEnergy, HelmholtzEnergyDual, IdealGasContribution,
+    IdealGasContributionDual,
+};
+use crate::parameter::ParameterError;
+use crate::si::{GRAM, MOL};
+use crate::state::StateHD;
+use crate::MolarWeight;
+use ndarray::{arr1, Array1};
+use num_dual::DualNum;
+use quantity::si::{SIArray1, SIUnit};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Term of the residual Helmholtz energy $\alpha^\mathrm{r}$.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum ResidualTerm {
+    #[serde(rename = "ResidualHelmholtzPower")]
+    Power {
+        n: Vec<f64>,
+        d: Vec<f64>,
+        t: Vec<f64>,
+        l: Vec<f64>,
+    },
+    #[serde(rename = "ResidualHelmholtzExponential")]
+    Exponential {
+        n: Vec<f64>,
+        d: Vec<f64>,
+        t: Vec<f64>,
+        g: Vec<f64>,
+        l: Vec<f64>,
+    },
+    #[serde(rename = "ResidualHelmholtzGaussian")]
+    Gaussian {
+        n: Vec<f64>,
+        d: Vec<f64>,
+        t: Vec<f64>,
+        eta: Vec<f64>,
+        epsilon: Vec<f64>,
+        beta: Vec<f64>,
+        gamma: Vec<f64>,
+    },
+}
+
+impl ResidualTerm {
+    fn coefficients(&self) -> Vec<&Vec<f64>> {
+        match self {
+            Self::Power { n, d, t, l } => vec![n, d, t, l],
+            Self::Exponential { n, d, t, g, l } => vec![n, d, t, g, l],
+            Self::Gaussian {
+                n,
+                d,
+                t,
+                eta,
+                epsilon,
+                beta,
+                gamma,
+            } => vec![n, d, t, eta, epsilon, beta, gamma],
+        }
+    }
+
+    fn evaluate<D: DualNum<f64>>(&self, delta: D, tau: D) -> D {
+        let mut alpha = D::zero();
+        match self {
+            Self::Power { n, d, t, l } => {
+                for i in 0..n.len() {
+                    let f = delta.powf(d[i]) * tau.powf(t[i]) * n[i];
+                    alpha += if l[i] == 0.0 {
+                        f
+                    } else {
+                        f * (-delta.powf(l[i])).exp()
+                    };
+                }
+            }
+            Self::Exponential { n, d, t, g, l } => {
+                for i in 0..n.len() {
+                    alpha +=
+                        delta.powf(d[i]) * tau.powf(t[i]) * (-delta.powf(l[i]) * g[i]).exp() * n[i];
+                }
+            }
+            Self::Gaussian {
+                n,
+                d,
+                t,
+                eta,
+                epsilon,
+                beta,
+                gamma,
+            } => {
+                for i in 0..n.len() {
+                    alpha += delta.powf(d[i])
+                        * tau.powf(t[i])
+                        * (-(delta - epsilon[i]).powi(2) * eta[i]
+                            - (tau - gamma[i]).powi(2) * beta[i])
+                            .exp()
+                        * n[i];
+                }
+            }
+        }
+        alpha
+    }
+}
+
+/// Temperature dependent term of the ideal gas Helmholtz energy $\alpha^0$.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum IdealGasTerm {
+    #[serde(rename = "IdealGasHelmholtzLead")]
+    Lead { a1: f64, a2: f64 },
+    #[serde(rename = "IdealGasHelmholtzEnthalpyEntropyOffset")]
+    EnthalpyEntropyOffset { a1: f64, a2: f64 },
+    #[serde(rename = "IdealGasHelmholtzLogTau")]
+    LogTau { a: f64 },
+    #[serde(rename = "IdealGasHelmholtzPower")]
+    Power { n: Vec<f64>, t: Vec<f64> },
+    #[serde(rename = "IdealGasHelmholtzPlanckEinstein")]
+    PlanckEinstein { n: Vec<f64>, t: Vec<f64> },
+    #[serde(rename = "IdealGasHelmholtzPlanckEinsteinGeneralized")]
+    PlanckEinsteinGeneralized {
+        n: Vec<f64>,
+        t: Vec<f64>,
+        c: Vec<f64>,
+        d: Vec<f64>,
+    },
+}
+
+impl IdealGasTerm {
+    fn coefficients(&self) -> Vec<&Vec<f64>> {
+        match self {
+            Self::Lead { .. } | Self::EnthalpyEntropyOffset { .. } | Self::LogTau { .. } => {
+                vec![]
+            }
+            Self::Power { n, t } | Self::PlanckEinstein { n, t } => vec![n, t],
+            Self::PlanckEinsteinGeneralized { n, t, c, d } => vec![n, t, c, d],
+        }
+    }
+
+    fn evaluate<D: DualNum<f64>>(&self, tau: D) -> D {
+        let mut alpha = D::zero();
+        match self {
+            Self::Lead { a1, a2 } | Self::EnthalpyEntropyOffset { a1, a2 } => {
+                alpha += tau * *a2 + *a1
+            }
+            Self::LogTau { a } => alpha += tau.ln() * *a,
+            Self::Power { n, t } => {
+                for i in 0..n.len() {
+                    alpha += tau.powf(t[i]) * n[i];
+                }
+            }
+            Self::PlanckEinstein { n, t } => {
+                for i in 0..n.len() {
+                    alpha += (-(-tau * t[i]).exp() + 1.0).ln() * n[i];
+                }
+            }
+            Self::PlanckEinsteinGeneralized { n, t, c, d } => {
+                for i in 0..n.len() {
+                    alpha += ((tau * t[i]).exp() * d[i] + c[i]).ln() * n[i];
+                }
+            }
+        }
+        alpha
+    }
+}
+
+/// Reducing state of a multiparameter equation of state.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReducingState {
+    /// reducing temperature in Kelvin
+    #[serde(rename = "T")]
+    pub temperature: f64,
+    /// reducing density in mol/m³
+    #[serde(rename = "rhomolar")]
+    pub density: f64,
+}
+
+/// Characteristic states of a multiparameter equation of state.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MultiParameterStates {
+    /// state used to reduce temperature and density
+    pub reducing: ReducingState,
+}
+
+/// Coefficients of a multiparameter equation of state for a single substance.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MultiParameterRecord {
+    /// molar mass in kg/mol
+    pub molar_mass: f64,
+    /// characteristic states
+    #[serde(rename = "STATES")]
+    pub states: MultiParameterStates,
+    /// terms of the ideal gas Helmholtz energy
+    pub alpha0: Vec<IdealGasTerm>,
+    /// terms of the residual Helmholtz energy
+    pub alphar: Vec<ResidualTerm>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MultiParameterFile {
+    Fluid {
+        #[serde(rename = "EOS")]
+        eos: Vec<MultiParameterRecord>,
+    },
+    Record(MultiParameterRecord),
+}
+
+impl MultiParameterRecord {
+    /// Read the coefficients from a json file.
+    pub fn from_json<P: AsRef<Path>>(file: P) -> Result<Self, ParameterError> {
+        let reader = BufReader::new(File::open(file)?);
+        match serde_json::from_reader(reader)? {
+            MultiParameterFile::Record(record) => Ok(record),
+            MultiParameterFile::Fluid { eos } => eos
+                .into_iter()
+                .next()
+                .ok_or(ParameterError::InsufficientInformation),
+        }
+    }
+
+    fn check_coefficients(&self) -> Result<(), ParameterError> {
+        self.alpha0
+            .iter()
+            .map(IdealGasTerm::coefficients)
+            .chain(self.alphar.iter().map(ResidualTerm::coefficients))
+            .try_for_each(|c| match c.iter().all(|x| x.len() == c[0].len()) {
+                true => Ok(()),
+                false => Err(ParameterError::IncompatibleParameters(String::from(
+                    "all coefficients of a term have to have the same length.",
+                ))),
+            })
+    }
+}
+
+impl fmt::Display for MultiParameterRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MultiParameterRecord(molar_mass={} kg/mol",
+            self.molar_mass
+        )?;
+        write!(f, ", T_r={} K", self.states.reducing.temperature)?;
+        write!(f, ", rho_r={} mol/m³", self.states.reducing.density)?;
+        write!(f, ", alpha0 terms={}", self.alpha0.len())?;
+        write!(f, ", alphar terms={})", self.alphar.len())
+    }
+}
+
+struct MultiParameterResidual {
+    record: Rc<MultiParameterRecord>,
+}
+
+impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for MultiParameterResidual {
+    fn helmholtz_energy(&self, state: &StateHD<D>) -> D {
+        let reducing = &self.record.states.reducing;
+        let tau = state.temperature.recip() * reducing.temperature;
+        let delta = state.partial_density.sum() / (reducing.density * NAV_A3);
+        let alpha = self
+            .record
+            .alphar
+            .iter()
+            .fold(D::zero(), |acc, term| acc + term.evaluate(delta, tau));
+        alpha * state.moles.sum()
+    }
+}
+
+impl fmt::Display for MultiParameterResidual {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Multiparameter residual")
+    }
+}
+
+struct MultiParameterIdealGas {
+    record: Rc<MultiParameterRecord>,
+}
+
+impl<D: DualNum<f64>> IdealGasContributionDual<D> for MultiParameterIdealGas {
+    /// The de Broglie wavelength is chosen so that the ideal gas contribution
+    /// reduces to $\ln\delta+\sum\alpha^0_i(\tau)$.
+    fn de_broglie_wavelength(&self, temperature: D, components: usize) -> Array1<D> {
+        let reducing = &self.record.states.reducing;
+        let tau = temperature.recip() * reducing.temperature;
+        let alpha = self
+            .record
+            .alpha0
+            .iter()
+            .fold(D::zero(), |acc, term| acc + term.evaluate(tau));
+        Array1::from_elem(components, alpha + 1.0 - (reducing.density * NAV_A3).ln())
+    }
+}
+
+impl fmt::Display for MultiParameterIdealGas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ideal gas (multiparameter)")
+    }
+}
+
+/// A multiparameter Helmholtz energy equation of state for a pure substance.
+pub struct MultiParameterEos {
+    /// Coefficients of the equation of state
+    record: Rc<MultiParameterRecord>,
+    /// Ideal gas contribution to the Helmholtz energy
+    ideal_gas: MultiParameterIdealGas,
+    /// Residual contribution to the Helmholtz energy
+    contributions: Vec<Box<dyn HelmholtzEnergy>>,
+}
+
+impl MultiParameterEos {
+    /// Create a new equation of state from a set of coefficients.
+    pub fn new(record: MultiParameterRecord) -> Result<Self, ParameterError> {
+        record.check_coefficients()?;
+        let record = Rc::new(record);
+        let ideal_gas = MultiParameterIdealGas {
+            record: record.clone(),
+        };
+        let contributions: Vec<Box<dyn HelmholtzEnergy>> = vec![Box::new(MultiParameterResidual {
+            record: record.clone(),
+        })];
+        Ok(Self {
+            record,
+            ideal_gas,
+            contributions,
+        })
+    }
+
+    /// Create a new equation of state from a json file.
+    pub fn from_json<P: AsRef<Path>>(file: P) -> Result<Self, ParameterError> {
+        Self::new(MultiParameterRecord::from_json(file)?)
+    }
+
+    /// Return the coefficients of the equation of state.
+    pub fn record(&self) -> &MultiParameterRecord {
+        &self.record
+    }
+}
+
+impl EquationOfState for MultiParameterEos {
+    fn components(&self) -> usize {
+        1
+    }
+
+    fn subset(&self, _: &[usize]) -> Self {
+        Self::new(self.record.as_ref().clone()).unwrap()
+    }
+
+    fn compute_max_density(&self, _: &Array1<f64>) -> f64 {
+        3.5 * self.record.states.reducing.density * NAV_A3
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            molar_weight: true,
+            entropy_scaling: false,
+            max_components: Some(1),
+        }
+    }
+
+    fn residual(&self) -> &[Box<dyn HelmholtzEnergy>] {
+        &self.contributions
+    }
+
+    fn ideal_gas(&self) -> &dyn IdealGasContribution {
+        &self.ideal_gas
+    }
+}
+
+impl MolarWeight<SIUnit> for MultiParameterEos {
+    fn molar_weight(&self) -> SIArray1 {
+        arr1(&[self.record.molar_mass * 1000.0]) * GRAM / MOL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::State;
+    use crate::{Contributions, EosResult};
+    use approx::assert_relative_eq;
+    use quantity::si::*;
+
+    fn record() -> MultiParameterRecord {
+        let record = r#"{
+            "molar_mass": 0.039948,
+            "STATES": {
+                "reducing": {"T": 150.687, "rhomolar": 13407.429659}
+            },
+            "alpha0": [
+                {"type": "IdealGasHelmholtzLead", "a1": 8.31666243, "a2": -4.94651164},
+                {"type": "IdealGasHelmholtzLogTau", "a": 1.5}
+            ],
+            "alphar": [
+                {"type": "ResidualHelmholtzPower", "n": [-0.5], "d": [1], "t": [1.0], "l": [0]},
+                {"type": "ResidualHelmholtzGaussian", "n": [0.1], "d": [2], "t": [1.0],
+                 "eta": [1.0], "epsilon": [1.0], "beta": [1.0], "gamma": [1.0]}
+            ]
+        }"#;
+        serde_json::from_str(record).expect("Unable to parse json.")
+    }
+
+    #[test]
+    fn multiparameter_pressure_and_heat_capacity() -> EosResult<()> {
+        let eos = Rc::new(MultiParameterEos::new(record())?);
+        let t = 300.0 * KELVIN;
+        let rho = 1000.0 * MOL / METER.powi(3);
+        let state = State::new_nvt(&eos, t, MOL / rho, &(arr1(&[1.0]) * MOL))?;
+
+        // analytical compressibility factor Z = 1 + delta * alpha^r_delta
+        let (delta, tau) = (1000.0 / 13407.429659, 150.687 / 300.0);
+        let gauss = (-(delta - 1.0f64).powi(2) - (tau - 1.0f64).powi(2)).exp();
+        let z = 1.0 - 0.5 * delta * tau
+            + 0.1 * delta.powi(2) * tau * gauss * (2.0 - 2.0 * delta * (delta - 1.0));
+        assert_relative_eq!(
+            state.pressure(Contributions::Total),
+            z * rho * RGAS * t,
+            max_relative = 1e-10
+        );
+
+        // ideal gas heat capacity of a monatomic gas
+        assert_relative_eq!(
+            state.c_v(Contributions::IdealGas),
+            1.5 * RGAS,
+            max_relative = 1e-10
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn inconsistent_coefficients() {
+        let mut record = record();
+        record.alphar[0] = ResidualTerm::Power {
+            n: vec![1.0, 2.0],
+            d: vec![1.0],
+            t: vec![1.0],
+            l: vec![0.0],
+        };
+        assert!(MultiParameterEos::new(record).is_err());
+    }
+}
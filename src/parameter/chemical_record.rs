@@ -2,12 +2,10 @@ use super::identifier::Identifier;
 use super::segment::SegmentRecord;
 use super::ParameterError;
 use conv::ValueInto;
+use indexmap::{IndexMap, IndexSet};
 use num_traits::NumAssign;
 use serde::{Deserialize, Serialize};
-use std::{
-    borrow::Cow,
-    collections::{HashMap, HashSet},
-};
+use std::borrow::Cow;
 
 // Auxiliary structure used to deserialize chemical records without explicit bond information.
 #[derive(Serialize, Deserialize)]
@@ -68,9 +66,10 @@ impl ChemicalRecord {
     /// Count the number of occurences of each individual segment identifier in the
     /// chemical record.
     ///
-    /// The map contains the segment identifier as key and the count as value.
-    pub fn segment_count<T: NumAssign>(&self) -> HashMap<String, T> {
-        let mut counts = HashMap::with_capacity(self.segments.len());
+    /// The map contains the segment identifier as key and the count as value
+    /// in the order of the first occurence of each segment.
+    pub fn segment_count<T: NumAssign>(&self) -> IndexMap<String, T> {
+        let mut counts = IndexMap::with_capacity(self.segments.len());
         for si in &self.segments {
             let entry = counts.entry(si.clone()).or_insert_with(|| T::zero());
             *entry += T::one();
@@ -81,9 +80,10 @@ impl ChemicalRecord {
     /// Count the number of occurences of bonds between each pair of segment identifiers
     /// in the chemical record.
     ///
-    /// The map contains the segment identifiers as key and the count as value.
-    pub fn bond_count<T: NumAssign>(&self) -> HashMap<[String; 2], T> {
-        let mut bond_counts = HashMap::new();
+    /// The map contains the segment identifiers as key and the count as value
+    /// in the order of the first occurence of each bond.
+    pub fn bond_count<T: NumAssign>(&self) -> IndexMap<[String; 2], T> {
+        let mut bond_counts = IndexMap::new();
         for b in &self.bonds {
             let s1 = self.segments[b[0]].clone();
             let s2 = self.segments[b[1]].clone();
@@ -108,29 +108,33 @@ impl std::fmt::Display for ChemicalRecord {
 pub trait SegmentCount {
     type Count: Copy + ValueInto<f64>;
 
-    fn identifier(&self) -> Cow<Identifier>;
+    fn identifier(&self) -> Cow<'_, Identifier>;
 
     /// Count the number of occurences of each individual segment identifier in the
     /// molecule.
     ///
     /// The map contains the segment identifier as key and the count as value.
-    fn segment_count(&self) -> Cow<HashMap<String, Self::Count>>;
+    fn segment_count(&self) -> Cow<'_, IndexMap<String, Self::Count>>;
 
     /// Count the number of occurences of each individual segment in the
     /// molecule.
     ///
     /// The map contains the segment record as key and the count as value.
+    /// If a segment is defined multiple times in `segment_records`, the
+    /// first definition is used.
     fn segment_map<M: Clone, I: Clone>(
         &self,
         segment_records: &[SegmentRecord<M, I>],
-    ) -> Result<HashMap<SegmentRecord<M, I>, Self::Count>, ParameterError> {
+    ) -> Result<IndexMap<SegmentRecord<M, I>, Self::Count>, ParameterError> {
         let count = self.segment_count();
-        let queried: HashSet<_> = count.keys().cloned().collect();
-        let mut segments: HashMap<String, SegmentRecord<M, I>> = segment_records
-            .iter()
-            .map(|r| (r.identifier.clone(), r.clone()))
-            .collect();
-        let available = segments.keys().cloned().collect();
+        let queried: IndexSet<_> = count.keys().cloned().collect();
+        let mut segments: IndexMap<String, SegmentRecord<M, I>> = IndexMap::new();
+        for r in segment_records {
+            segments
+                .entry(r.identifier.clone())
+                .or_insert_with(|| r.clone());
+        }
+        let available: IndexSet<_> = segments.keys().cloned().collect();
         if !queried.is_subset(&available) {
             let missing: Vec<String> = queried.difference(&available).cloned().collect();
             let msg = format!("{:?}", missing);
@@ -138,7 +142,7 @@ pub trait SegmentCount {
         };
         Ok(count
             .iter()
-            .map(|(s, c)| (segments.remove(s).unwrap(), *c))
+            .map(|(s, c)| (segments.swap_remove(s).unwrap(), *c))
             .collect())
     }
 }
@@ -146,11 +150,11 @@ pub trait SegmentCount {
 impl SegmentCount for ChemicalRecord {
     type Count = usize;
 
-    fn identifier(&self) -> Cow<Identifier> {
+    fn identifier(&self) -> Cow<'_, Identifier> {
         Cow::Borrowed(&self.identifier)
     }
 
-    fn segment_count(&self) -> Cow<HashMap<String, usize>> {
+    fn segment_count(&self) -> Cow<'_, IndexMap<String, usize>> {
         Cow::Owned(self.segment_count())
     }
 }
@@ -0,0 +1,253 @@
+use super::{BinaryRecord, Identifier, IdentifierOption, ParameterError, PureRecord};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// Columns of a pure substance file that belong to the identifier.
+const IDENTIFIER_COLUMNS: [&str; 6] = ["cas", "name", "iupac_name", "smiles", "inchi", "formula"];
+/// Prefix of the columns of a pure substance file that belong to the ideal gas record.
+const IDEAL_GAS_PREFIX: &str = "ideal_gas_record.";
+
+/// A row of a CSV file as pairs of column names and (non-empty) values.
+type Row = Vec<(String, String)>;
+
+/// Split a line of a CSV file into its fields.
+///
+/// Fields that contain commas can be enclosed in double quotes. Double
+/// quotes within quoted fields are escaped by doubling them.
+fn split_line(line: &str) -> Result<Vec<String>, ParameterError> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(ParameterError::InvalidCsv(format!(
+            "Unterminated quote in line '{}'",
+            line
+        )));
+    }
+    fields.push(field);
+    Ok(fields.into_iter().map(|f| f.trim().to_owned()).collect())
+}
+
+/// Read all rows of a CSV file with a header line. Empty lines and empty cells are skipped.
+fn read_rows<P: AsRef<Path>>(path: P) -> Result<Vec<Row>, ParameterError> {
+    let content = fs::read_to_string(path)?;
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header = match lines.next() {
+        Some((_, line)) => split_line(line.trim_start_matches('\u{feff}'))?,
+        None => return Ok(Vec::new()),
+    };
+    lines
+        .map(|(i, line)| {
+            let fields = split_line(line)?;
+            if fields.len() != header.len() {
+                return Err(ParameterError::InvalidCsv(format!(
+                    "Line {} contains {} fields, but the header contains {} columns",
+                    i + 1,
+                    fields.len(),
+                    header.len()
+                )));
+            }
+            Ok(header
+                .iter()
+                .cloned()
+                .zip(fields)
+                .filter(|(_, value)| !value.is_empty())
+                .collect())
+        })
+        .collect()
+}
+
+/// Convert the content of a cell to an integer, a float, a boolean or a string.
+fn parse_value(value: &str) -> Value {
+    if let Ok(i) = value.parse::<i64>() {
+        Value::from(i)
+    } else if let Some(f) = value.parse::<f64>().ok().filter(|f| f.is_finite()) {
+        Value::from(f)
+    } else {
+        match value {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::String(value.to_owned()),
+        }
+    }
+}
+
+/// Read pure substance records from a CSV file.
+///
+/// The columns `cas`, `name`, `iupac_name`, `smiles`, `inchi` and `formula`
+/// form the identifier, the column `molarweight` contains the molar weight.
+/// Columns with the prefix `ideal_gas_record.` (e.g., `ideal_gas_record.a`)
+/// are the fields of the ideal gas record. All other columns are the fields
+/// of the model record.
+pub(super) fn pure_records_from_csv<M, I, P>(
+    path: P,
+) -> Result<Vec<PureRecord<M, I>>, ParameterError>
+where
+    M: DeserializeOwned,
+    I: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    read_rows(path)?
+        .into_iter()
+        .map(|row| {
+            let mut identifier = Map::new();
+            let mut model_record = Map::new();
+            let mut ideal_gas_record = Map::new();
+            let mut record = Map::new();
+            for (column, value) in row {
+                if IDENTIFIER_COLUMNS.contains(&column.as_str()) {
+                    identifier.insert(column, Value::String(value));
+                } else if column == "molarweight" {
+                    record.insert(column, parse_value(&value));
+                } else if let Some(field) = column.strip_prefix(IDEAL_GAS_PREFIX) {
+                    ideal_gas_record.insert(field.to_owned(), parse_value(&value));
+                } else {
+                    model_record.insert(column, parse_value(&value));
+                }
+            }
+            record.insert("identifier".to_owned(), Value::Object(identifier));
+            record.insert("model_record".to_owned(), Value::Object(model_record));
+            if !ideal_gas_record.is_empty() {
+                record.insert(
+                    "ideal_gas_record".to_owned(),
+                    Value::Object(ideal_gas_record),
+                );
+            }
+            Ok(serde_json::from_value(Value::Object(record))?)
+        })
+        .collect()
+}
+
+/// Read binary records from a CSV file.
+///
+/// The columns `id1` and `id2` contain the identifiers of the two substances
+/// of the type given by `search_option`. A single column `model_record`
+/// contains the binary record itself (e.g., a binary interaction parameter),
+/// otherwise all other columns are the fields of the binary record.
+pub(super) fn binary_records_from_csv<B, P>(
+    path: P,
+    search_option: IdentifierOption,
+) -> Result<Vec<BinaryRecord<Identifier, B>>, ParameterError>
+where
+    B: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    read_rows(path)?
+        .into_iter()
+        .map(|row| {
+            let (mut id1, mut id2) = (None, None);
+            let mut model_record = Map::new();
+            let mut value = None;
+            for (column, v) in row {
+                match column.as_str() {
+                    "id1" => id1 = Some(identifier(search_option, v)),
+                    "id2" => id2 = Some(identifier(search_option, v)),
+                    "model_record" => value = Some(parse_value(&v)),
+                    _ => {
+                        model_record.insert(column, parse_value(&v));
+                    }
+                }
+            }
+            match (id1, id2) {
+                (Some(id1), Some(id2)) => Ok(BinaryRecord::new(
+                    id1,
+                    id2,
+                    serde_json::from_value(value.unwrap_or(Value::Object(model_record)))?,
+                )),
+                _ => Err(ParameterError::InvalidCsv(String::from(
+                    "Binary records require the columns 'id1' and 'id2'",
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Create an identifier that only contains the identifier of the given type.
+fn identifier(option: IdentifierOption, value: String) -> Identifier {
+    let mut identifier = Identifier::default();
+    let field = match option {
+        IdentifierOption::Cas => &mut identifier.cas,
+        IdentifierOption::Name => &mut identifier.name,
+        IdentifierOption::IupacName => &mut identifier.iupac_name,
+        IdentifierOption::Smiles => &mut identifier.smiles,
+        IdentifierOption::Inchi => &mut identifier.inchi,
+        IdentifierOption::Formula => &mut identifier.formula,
+    };
+    *field = Some(value);
+    identifier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinsonParameters, PengRobinsonRecord};
+    use crate::joback::JobackRecord;
+    use crate::parameter::Parameter;
+
+    #[test]
+    fn test_from_csv() -> Result<(), ParameterError> {
+        let pure = crate::temp_path("pure.csv");
+        let binary = crate::temp_path("binary.csv");
+        fs::write(
+            &pure,
+            "name,cas,molarweight,tc,pc,acentric_factor,ideal_gas_record.a,ideal_gas_record.b,ideal_gas_record.c,ideal_gas_record.d,ideal_gas_record.e\n\
+             propane,74-98-6,44.0962,369.96,4250000.0,0.153,,,,,\n\
+             \"n-butane, normal\",106-97-8,58.123,425.2,3800000.0,0.199,98.5,0,0,0,0\n",
+        )?;
+        fs::write(&binary, "id1,id2,model_record\n74-98-6,106-97-8,0.0123\n")?;
+
+        let records: Vec<PureRecord<PengRobinsonRecord, JobackRecord>> =
+            pure_records_from_csv(&pure)?;
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[1].identifier.name.as_deref(),
+            Some("n-butane, normal")
+        );
+        assert!(records[0].ideal_gas_record.is_none());
+        assert_eq!(
+            serde_json::to_value(records[1].ideal_gas_record.as_ref())?["a"],
+            98.5
+        );
+
+        let parameters = PengRobinsonParameters::from_csv(
+            vec!["106-97-8", "74-98-6"],
+            &pure,
+            Some(&binary),
+            IdentifierOption::Cas,
+        )?;
+        let (pure_records, k_ij) = parameters.records();
+        assert_eq!(pure_records[0].molarweight, 58.123);
+        assert_eq!(k_ij[[0, 1]], 0.0123);
+
+        // missing components are reported
+        let missing = PengRobinsonParameters::from_csv(
+            vec!["propane", "methane"],
+            &pure,
+            None,
+            IdentifierOption::Name,
+        );
+        assert!(
+            matches!(missing, Err(ParameterError::ComponentsNotFound(m)) if m.contains("methane"))
+        );
+        fs::remove_file(pure)?;
+        fs::remove_file(binary)?;
+        Ok(())
+    }
+}
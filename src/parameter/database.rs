@@ -0,0 +1,353 @@
+use super::{
+    BinaryRecord, ChemicalRecord, FromSegments, FromSegmentsBinary, Identifier, IdentifierOption,
+    Parameter, ParameterError, PureRecord, SegmentRecord,
+};
+use ndarray::Array2;
+use serde::de::DeserializeOwned;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::hash::Hash;
+use std::io::BufReader;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Map from the identifiers of one type to the positions of the records.
+type Index<K> = Rc<HashMap<K, usize>>;
+/// Indices of a list of records for every type of identifier that was used so far.
+type IndexCache<K> = RefCell<HashMap<IdentifierOption, Index<K>>>;
+
+/// [ParameterDatabase] for the record types of a [Parameter] set.
+pub type ParameterDatabaseOf<P> =
+    ParameterDatabase<<P as Parameter>::Pure, <P as Parameter>::IdealGas, <P as Parameter>::Binary>;
+
+/// A collection of parameter records that is read once and can be used
+/// to construct parameters for arbitrary subsets of the substances.
+///
+/// In contrast to [Parameter::from_json], which reads and searches the
+/// files for every set of parameters, the records are kept in memory.
+/// For every [IdentifierOption] that is used in a query, an index of
+/// the records is created on first use and reused for all further queries.
+/// If multiple records share an identifier, the first record is used.
+pub struct ParameterDatabase<M, I, B> {
+    pure_records: Vec<PureRecord<M, I>>,
+    binary_records: Vec<BinaryRecord<Identifier, B>>,
+    chemical_records: Vec<ChemicalRecord>,
+    segment_records: Vec<SegmentRecord<M, I>>,
+    binary_segment_records: Option<Vec<BinaryRecord<String, B>>>,
+    pure_index: IndexCache<String>,
+    binary_index: IndexCache<(String, String)>,
+    chemical_index: IndexCache<String>,
+}
+
+impl<M, I, B> ParameterDatabase<M, I, B> {
+    /// Create a database from pure and binary records.
+    pub fn new(
+        pure_records: Vec<PureRecord<M, I>>,
+        binary_records: Vec<BinaryRecord<Identifier, B>>,
+    ) -> Self {
+        Self {
+            pure_records,
+            binary_records,
+            chemical_records: Vec::new(),
+            segment_records: Vec::new(),
+            binary_segment_records: None,
+            pure_index: Default::default(),
+            binary_index: Default::default(),
+            chemical_index: Default::default(),
+        }
+    }
+
+    /// Add chemical records and segment information for the
+    /// construction of parameters from group contribution methods.
+    pub fn with_segments(
+        mut self,
+        chemical_records: Vec<ChemicalRecord>,
+        segment_records: Vec<SegmentRecord<M, I>>,
+        binary_segment_records: Option<Vec<BinaryRecord<String, B>>>,
+    ) -> Self {
+        self.chemical_records = chemical_records;
+        self.segment_records = segment_records;
+        self.binary_segment_records = binary_segment_records;
+        self.chemical_index = Default::default();
+        self
+    }
+
+    /// Return the pure substance records of the database.
+    pub fn pure_records(&self) -> &[PureRecord<M, I>] {
+        &self.pure_records
+    }
+
+    /// Return the binary records of the database.
+    pub fn binary_records(&self) -> &[BinaryRecord<Identifier, B>] {
+        &self.binary_records
+    }
+
+    /// Return the pure substance record with the given identifier, if it exists.
+    pub fn pure_record(
+        &self,
+        identifier: &str,
+        search_option: IdentifierOption,
+    ) -> Option<&PureRecord<M, I>> {
+        self.pure_index(search_option)
+            .get(identifier)
+            .map(|&i| &self.pure_records[i])
+    }
+
+    /// Return the chemical record with the given identifier, if it exists.
+    pub fn chemical_record(
+        &self,
+        identifier: &str,
+        search_option: IdentifierOption,
+    ) -> Option<&ChemicalRecord> {
+        self.chemical_index(search_option)
+            .get(identifier)
+            .map(|&i| &self.chemical_records[i])
+    }
+
+    fn pure_index(&self, search_option: IdentifierOption) -> Index<String> {
+        cached_index(&self.pure_index, search_option, || {
+            self.pure_records
+                .iter()
+                .map(|r| r.identifier.as_string(search_option))
+        })
+    }
+
+    fn chemical_index(&self, search_option: IdentifierOption) -> Index<String> {
+        cached_index(&self.chemical_index, search_option, || {
+            self.chemical_records
+                .iter()
+                .map(|r| r.identifier.as_string(search_option))
+        })
+    }
+
+    /// Pairs of identifiers are only added if the reversed pair is not yet contained.
+    fn binary_index(&self, search_option: IdentifierOption) -> Index<(String, String)> {
+        cached_index(&self.binary_index, search_option, || {
+            let mut pairs = HashSet::new();
+            self.binary_records
+                .iter()
+                .map(|br| {
+                    let id1 = br.id1.as_string(search_option)?;
+                    let id2 = br.id2.as_string(search_option)?;
+                    if pairs.contains(&(id2.clone(), id1.clone())) {
+                        return None;
+                    }
+                    pairs.insert((id1.clone(), id2.clone()));
+                    Some((id1, id2))
+                })
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Positions of the queried substances in the given index.
+    fn positions(
+        index: &HashMap<String, usize>,
+        substances: &[&str],
+    ) -> Result<Vec<usize>, ParameterError> {
+        let missing: Vec<_> = substances
+            .iter()
+            .filter(|s| !index.contains_key(**s))
+            .collect();
+        if !missing.is_empty() {
+            return Err(ParameterError::ComponentsNotFound(format!("{:?}", missing)));
+        }
+        Ok(substances.iter().map(|s| index[*s]).collect())
+    }
+
+    /// Construct parameters for the given substances from the pure and binary records.
+    ///
+    /// Binary records are used for pairs of substances that are identified
+    /// with the same `search_option`, all other binary records are set to
+    /// their default value.
+    pub fn build<P>(
+        &self,
+        substances: &[&str],
+        search_option: IdentifierOption,
+    ) -> Result<P, ParameterError>
+    where
+        P: Parameter<Pure = M, IdealGas = I, Binary = B>,
+        M: Clone,
+        I: Clone,
+        B: Clone + Default,
+    {
+        let positions = Self::positions(&self.pure_index(search_option), substances)?;
+        let pure_records: Vec<_> = positions
+            .iter()
+            .map(|&i| self.pure_records[i].clone())
+            .collect();
+
+        let binary_index = self.binary_index(search_option);
+        let n = substances.len();
+        let binary_records = Array2::from_shape_fn([n, n], |(i, j)| {
+            let (id1, id2) = (substances[i].to_owned(), substances[j].to_owned());
+            binary_index
+                .get(&(id1.clone(), id2.clone()))
+                .or_else(|| binary_index.get(&(id2, id1)))
+                .map(|&k| self.binary_records[k].model_record.clone())
+                .unwrap_or_default()
+        });
+        P::from_records(pure_records, binary_records)
+    }
+
+    /// Construct parameters for the given substances from their chemical
+    /// records and the segment information of the database.
+    pub fn build_from_segments<P>(
+        &self,
+        substances: &[&str],
+        search_option: IdentifierOption,
+    ) -> Result<P, ParameterError>
+    where
+        P: Parameter<Pure = M, IdealGas = I, Binary = B>,
+        M: FromSegments<usize>,
+        I: FromSegments<usize>,
+        B: FromSegmentsBinary<usize>,
+    {
+        let positions = Self::positions(&self.chemical_index(search_option), substances)?;
+        let chemical_records: Vec<_> = positions
+            .iter()
+            .map(|&i| self.chemical_records[i].clone())
+            .collect();
+        P::from_segments(
+            chemical_records,
+            self.segment_records.clone(),
+            self.binary_segment_records.clone(),
+        )
+    }
+}
+
+impl<M: DeserializeOwned, I: DeserializeOwned, B: DeserializeOwned> ParameterDatabase<M, I, B> {
+    /// Create a database from pure substance records stored in
+    /// one or more json files and an optional file with binary records.
+    ///
+    /// The order of the files determines the priority of records
+    /// of substances that are defined in multiple files.
+    pub fn from_json<P: AsRef<Path>>(
+        files_pure: &[P],
+        file_binary: Option<P>,
+    ) -> Result<Self, ParameterError> {
+        let mut pure_records = Vec::new();
+        for file in files_pure {
+            pure_records.extend(read_json::<Vec<_>, _>(file)?);
+        }
+        let binary_records = file_binary.map_or_else(|| Ok(Vec::new()), read_json)?;
+        Ok(Self::new(pure_records, binary_records))
+    }
+
+    /// Add chemical records, segment records and (optionally)
+    /// binary segment records stored in json files.
+    pub fn with_json_segments<P: AsRef<Path>>(
+        self,
+        file_chemical: P,
+        file_segments: P,
+        file_binary: Option<P>,
+    ) -> Result<Self, ParameterError> {
+        Ok(self.with_segments(
+            read_json(file_chemical)?,
+            read_json(file_segments)?,
+            file_binary.map(read_json).transpose()?,
+        ))
+    }
+}
+
+fn read_json<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T, ParameterError> {
+    Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+}
+
+/// Return the index for `search_option` from the cache or create it from
+/// the keys of the records. Records without a key are skipped, for
+/// duplicate keys the first record is used.
+fn cached_index<K, F, T>(
+    cache: &IndexCache<K>,
+    search_option: IdentifierOption,
+    keys: F,
+) -> Index<K>
+where
+    K: Hash + Eq,
+    F: FnOnce() -> T,
+    T: IntoIterator<Item = Option<K>>,
+{
+    cache
+        .borrow_mut()
+        .entry(search_option)
+        .or_insert_with(|| {
+            let mut index = HashMap::new();
+            for (i, key) in keys().into_iter().enumerate() {
+                if let Some(key) = key {
+                    index.entry(key).or_insert(i);
+                }
+            }
+            Rc::new(index)
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinsonParameters, PengRobinsonRecord};
+    use crate::joback::JobackRecord;
+
+    fn database() -> ParameterDatabase<PengRobinsonRecord, JobackRecord, f64> {
+        let pure_records = serde_json::from_str(
+            r#"[
+                {
+                    "identifier": {"cas": "74-98-6", "name": "propane"},
+                    "molarweight": 44.0962,
+                    "model_record": {"tc": 369.96, "pc": 4250000.0, "acentric_factor": 0.153}
+                },
+                {
+                    "identifier": {"cas": "106-97-8", "name": "butane"},
+                    "molarweight": 58.123,
+                    "model_record": {"tc": 425.2, "pc": 3800000.0, "acentric_factor": 0.199}
+                },
+                {
+                    "identifier": {"cas": "74-98-6", "name": "propane (duplicate)"},
+                    "molarweight": 44.1,
+                    "model_record": {"tc": 370.0, "pc": 4250000.0, "acentric_factor": 0.153}
+                }
+            ]"#,
+        )
+        .unwrap();
+        let binary_records = serde_json::from_str(
+            r#"[
+                {"id1": {"name": "butane"}, "id2": {"name": "propane"}, "model_record": 0.01},
+                {"id1": {"name": "propane"}, "id2": {"name": "butane"}, "model_record": 0.02}
+            ]"#,
+        )
+        .unwrap();
+        ParameterDatabase::new(pure_records, binary_records)
+    }
+
+    #[test]
+    fn test_build() -> Result<(), ParameterError> {
+        let db = database();
+        let parameters: PengRobinsonParameters =
+            db.build(&["butane", "propane"], IdentifierOption::Name)?;
+        let (pure_records, k_ij) = parameters.records();
+        assert_eq!(pure_records[0].molarweight, 58.123);
+        assert_eq!(k_ij[[0, 1]], 0.01);
+        assert_eq!(k_ij[[1, 0]], 0.01);
+        assert_eq!(k_ij[[0, 0]], 0.0);
+
+        // the first record of a duplicate identifier is used,
+        // binary records are only found for the same identifier type
+        let parameters: PengRobinsonParameters =
+            db.build(&["74-98-6", "106-97-8"], IdentifierOption::Cas)?;
+        let (pure_records, k_ij) = parameters.records();
+        assert_eq!(pure_records[0].molarweight, 44.0962);
+        assert_eq!(k_ij[[0, 1]], 0.0);
+        assert_eq!(
+            db.pure_record("propane (duplicate)", IdentifierOption::Name)
+                .map(|r| r.molarweight),
+            Some(44.1)
+        );
+
+        let missing =
+            db.build::<PengRobinsonParameters>(&["propane", "methane"], IdentifierOption::Name);
+        assert!(
+            matches!(missing, Err(ParameterError::ComponentsNotFound(m)) if m.contains("methane"))
+        );
+        Ok(())
+    }
+}
@@ -3,7 +3,6 @@
 use indexmap::{IndexMap, IndexSet};
 use ndarray::Array2;
 use serde::de::DeserializeOwned;
-use std::collections::HashMap;
 use std::fs::File;
 use std::io;
 use std::io::BufReader;
@@ -11,14 +10,21 @@ use std::path::Path;
 use thiserror::Error;
 
 mod chemical_record;
+mod csv;
+mod database;
 mod identifier;
 mod model_record;
 mod segment;
+mod units;
 
 pub use chemical_record::{ChemicalRecord, SegmentCount};
+pub use database::{ParameterDatabase, ParameterDatabaseOf};
 pub use identifier::{Identifier, IdentifierOption};
 pub use model_record::{BinaryRecord, FromSegments, FromSegmentsBinary, PureRecord};
 pub use segment::SegmentRecord;
+pub use units::{
+    deserialize_with_unit, parse_unit, Angstrom, GramPerMol, Kelvin, Pascal, RecordUnit,
+};
 
 /// Constructor methods for parameters.
 ///
@@ -37,10 +43,12 @@ where
     fn from_records(
         pure_records: Vec<PureRecord<Self::Pure, Self::IdealGas>>,
         binary_records: Array2<Self::Binary>,
-    ) -> Self;
+    ) -> Result<Self, ParameterError>;
 
     /// Creates parameters for a pure component from a pure record.
-    fn new_pure(pure_record: PureRecord<Self::Pure, Self::IdealGas>) -> Self {
+    fn new_pure(
+        pure_record: PureRecord<Self::Pure, Self::IdealGas>,
+    ) -> Result<Self, ParameterError> {
         let binary_record = Array2::from_elem([1, 1], Self::Binary::default());
         Self::from_records(vec![pure_record], binary_record)
     }
@@ -50,7 +58,7 @@ where
     fn new_binary(
         pure_records: Vec<PureRecord<Self::Pure, Self::IdealGas>>,
         binary_record: Option<Self::Binary>,
-    ) -> Self {
+    ) -> Result<Self, ParameterError> {
         let binary_record = Array2::from_shape_fn([2, 2], |(i, j)| {
             if i == j {
                 Self::Binary::default()
@@ -74,31 +82,32 @@ where
     ///
     /// If the identifiers in `binary_records` are not a subset of those in
     /// `pure_records`, the `Default` implementation of Self::Binary is used.
+    /// If multiple records are given for the same pair of substances
+    /// (in any order), the first record is used. Pure records without an
+    /// identifier of the type given by `search_option` are not matched.
     fn binary_matrix_from_records(
-        pure_records: &Vec<PureRecord<Self::Pure, Self::IdealGas>>,
+        pure_records: &[PureRecord<Self::Pure, Self::IdealGas>],
         binary_records: &[BinaryRecord<Identifier, Self::Binary>],
         search_option: IdentifierOption,
     ) -> Array2<Self::Binary> {
-        // Build Hashmap (id, id) -> BinaryRecord
-        let binary_map: HashMap<(String, String), Self::Binary> = {
-            binary_records
-                .iter()
-                .filter_map(|br| {
-                    let id1 = br.id1.as_string(search_option);
-                    let id2 = br.id2.as_string(search_option);
-                    id1.and_then(|id1| id2.map(|id2| ((id1, id2), br.model_record.clone())))
-                })
-                .collect()
-        };
+        // Build map (id, id) -> BinaryRecord
+        let binary_map = binary_map(binary_records.iter().filter_map(|br| {
+            let id1 = br.id1.as_string(search_option);
+            let id2 = br.id2.as_string(search_option);
+            id1.and_then(|id1| id2.map(|id2| ((id1, id2), br.model_record.clone())))
+        }));
+        let identifiers: Vec<_> = pure_records
+            .iter()
+            .map(|pr| pr.identifier.as_string(search_option))
+            .collect();
         let n = pure_records.len();
-        Array2::from_shape_fn([n, n], |(i, j)| {
-            let id1 = pure_records[i].identifier.as_string(search_option).unwrap();
-            let id2 = pure_records[j].identifier.as_string(search_option).unwrap();
-            binary_map
+        Array2::from_shape_fn([n, n], |(i, j)| match (&identifiers[i], &identifiers[j]) {
+            (Some(id1), Some(id2)) => binary_map
                 .get(&(id1.clone(), id2.clone()))
-                .or_else(|| binary_map.get(&(id2, id1)))
+                .or_else(|| binary_map.get(&(id2.clone(), id1.clone())))
                 .cloned()
-                .unwrap_or_default()
+                .unwrap_or_default(),
+            _ => Self::Binary::default(),
         })
     }
 
@@ -116,6 +125,9 @@ where
     }
 
     /// Creates parameters from substance information stored in multiple json files.
+    ///
+    /// If a substance is found multiple times in the files, the first record
+    /// (in the order of `input`) is used.
     fn from_multiple_json<P>(
         input: &[(Vec<&str>, P)],
         file_binary: Option<P>,
@@ -124,53 +136,37 @@ where
     where
         P: AsRef<Path>,
     {
-        let mut queried: IndexSet<String> = IndexSet::new();
-        let mut record_map: HashMap<String, PureRecord<Self::Pure, Self::IdealGas>> =
-            HashMap::new();
+        Self::from_multiple_json_with_policy(
+            input,
+            file_binary,
+            search_option,
+            DuplicatePolicy::PreferFirst,
+        )
+    }
 
+    /// Creates parameters from substance information stored in multiple json files
+    /// with an explicit policy for substances that are defined in multiple files.
+    ///
+    /// Records of the same substance are conflicting if their identifiers
+    /// (all fields, not only the CAS number) or their molar weights differ.
+    /// With [DuplicatePolicy::Error], conflicting records and different requested
+    /// substances that share a CAS number result in an error.
+    fn from_multiple_json_with_policy<P>(
+        input: &[(Vec<&str>, P)],
+        file_binary: Option<P>,
+        search_option: IdentifierOption,
+        policy: DuplicatePolicy,
+    ) -> Result<Self, ParameterError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut records = Vec::with_capacity(input.len());
         for (substances, file) in input {
-            substances.iter().try_for_each(|identifier| {
-                match queried.insert(identifier.to_string()) {
-                    true => Ok(()),
-                    false => Err(ParameterError::IncompatibleParameters(format!(
-                        "tried to add substance '{}' to system but it is already present.",
-                        identifier
-                    ))),
-                }
-            })?;
             let f = File::open(file)?;
             let reader = BufReader::new(f);
-
-            let pure_records: Vec<PureRecord<Self::Pure, Self::IdealGas>> =
-                serde_json::from_reader(reader)?;
-
-            pure_records
-                .into_iter()
-                .filter_map(|record| {
-                    record
-                        .identifier
-                        .as_string(search_option)
-                        .map(|i| (i, record))
-                })
-                .for_each(|(i, r)| {
-                    let _ = record_map.insert(i, r);
-                });
+            records.push((substances.clone(), serde_json::from_reader(reader)?));
         }
-
-        // Compare queried components and available components
-        let available: IndexSet<String> = record_map
-            .keys()
-            .map(|identifier| identifier.to_string())
-            .collect();
-        if !queried.is_subset(&available) {
-            let missing: Vec<String> = queried.difference(&available).cloned().collect();
-            let msg = format!("{:?}", missing);
-            return Err(ParameterError::ComponentsNotFound(msg));
-        };
-        let p = queried
-            .iter()
-            .filter_map(|identifier| record_map.remove(&identifier.clone()))
-            .collect();
+        let p = select_pure_records(records, search_option, policy)?;
 
         let binary_records = if let Some(path) = file_binary {
             let file = File::open(path)?;
@@ -180,7 +176,42 @@ where
             Vec::new()
         };
         let record_matrix = Self::binary_matrix_from_records(&p, &binary_records, search_option);
-        Ok(Self::from_records(p, record_matrix))
+        Self::from_records(p, record_matrix)
+    }
+
+    /// Creates parameters from substance information stored in CSV files.
+    ///
+    /// The first line of the files contains the column names. Pure substance
+    /// files contain the identifier columns (`cas`, `name`, `iupac_name`, `smiles`,
+    /// `inchi`, `formula`), the column `molarweight`, the fields of the model record
+    /// and, optionally, the fields of the ideal gas record with the prefix
+    /// `ideal_gas_record.`. Binary files contain the identifiers of the
+    /// type given by `search_option` in the columns `id1` and `id2` and either the
+    /// fields of the binary record or the binary record itself in the column
+    /// `model_record`. Empty cells are treated as missing values.
+    fn from_csv<P>(
+        substances: Vec<&str>,
+        file_pure: P,
+        file_binary: Option<P>,
+        search_option: IdentifierOption,
+    ) -> Result<Self, ParameterError>
+    where
+        P: AsRef<Path>,
+    {
+        let records = csv::pure_records_from_csv(file_pure)?;
+        let p = select_pure_records(
+            vec![(substances, records)],
+            search_option,
+            DuplicatePolicy::PreferFirst,
+        )?;
+
+        let binary_records = if let Some(path) = file_binary {
+            csv::binary_records_from_csv(path, search_option)?
+        } else {
+            Vec::new()
+        };
+        let record_matrix = Self::binary_matrix_from_records(&p, &binary_records, search_option);
+        Self::from_records(p, record_matrix)
     }
 
     /// Creates parameters from the molecular structure and segment information.
@@ -210,11 +241,12 @@ where
 
         // Map: (id1, id2) -> model_record
         // empty, if no binary segment records are provided
-        let binary_map: HashMap<_, _> = binary_segment_records
-            .into_iter()
-            .flat_map(|seg| seg.into_iter())
-            .map(|br| ((br.id1, br.id2), br.model_record))
-            .collect();
+        let binary_map = binary_map(
+            binary_segment_records
+                .into_iter()
+                .flat_map(|seg| seg.into_iter())
+                .map(|br| ((br.id1, br.id2), br.model_record)),
+        );
 
         // For every component:  map: id -> count
         let segment_counts: Vec<_> = chemical_records
@@ -244,7 +276,7 @@ where
             }
         }
 
-        Ok(Self::from_records(pure_records, binary_records))
+        Self::from_records(pure_records, binary_records)
     }
 
     /// Creates parameters from segment information stored in json files.
@@ -272,7 +304,8 @@ where
         let file = File::open(file_pure)?;
         let reader = BufReader::new(file);
         let chemical_records: Vec<ChemicalRecord> = serde_json::from_reader(reader)?;
-        let mut record_map: HashMap<_, _> = chemical_records
+        let mut record_map: IndexMap<_, _> = IndexMap::new();
+        chemical_records
             .into_iter()
             .filter_map(|record| {
                 record
@@ -280,7 +313,9 @@ where
                     .as_string(search_option)
                     .map(|i| (i, record))
             })
-            .collect();
+            .for_each(|(i, r)| {
+                record_map.entry(i).or_insert(r);
+            });
 
         // Compare queried components and available components
         let available: IndexSet<String> = record_map
@@ -296,7 +331,7 @@ where
         // collect all pure records that were queried
         let chemical_records: Vec<_> = queried
             .iter()
-            .filter_map(|identifier| record_map.remove(&identifier.clone()))
+            .filter_map(|identifier| record_map.swap_remove(&identifier.clone()))
             .collect();
 
         // Read segment records
@@ -331,6 +366,14 @@ where
         });
 
         Self::from_records(pure_records, binary_records)
+            .expect("a subset of valid parameters is valid")
+    }
+
+    /// Compare the settings of two parameter sets that are not part of
+    /// the records, e.g., mixing rules. By default, parameters are fully
+    /// determined by their records.
+    fn settings_eq(&self, _other: &Self) -> bool {
+        true
     }
 }
 
@@ -377,7 +420,8 @@ pub trait ParameterHetero: Sized {
 
         let reader = BufReader::new(File::open(file_pure)?);
         let chemical_records: Vec<ChemicalRecord> = serde_json::from_reader(reader)?;
-        let mut record_map: IndexMap<_, _> = chemical_records
+        let mut record_map: IndexMap<_, _> = IndexMap::new();
+        chemical_records
             .into_iter()
             .filter_map(|record| {
                 record
@@ -385,7 +429,9 @@ pub trait ParameterHetero: Sized {
                     .as_string(search_option)
                     .map(|i| (i, record))
             })
-            .collect();
+            .for_each(|(i, r)| {
+                record_map.entry(i).or_insert(r);
+            });
 
         // Compare queried components and available components
         let available: IndexSet<String> = record_map
@@ -400,7 +446,7 @@ pub trait ParameterHetero: Sized {
         // Collect all pure records that were queried
         let chemical_records: Vec<_> = queried
             .iter()
-            .filter_map(|identifier| record_map.remove(&identifier.clone()))
+            .filter_map(|identifier| record_map.swap_remove(&identifier.clone()))
             .collect();
 
         // Read segment records
@@ -453,6 +499,137 @@ pub enum ParameterError {
     InsufficientInformation,
     #[error("Incompatible parameters: {0}")]
     IncompatibleParameters(String),
+    #[error("Invalid unit: {0}")]
+    InvalidUnit(String),
+    #[error("Invalid CSV file: {0}")]
+    InvalidCsv(String),
+}
+
+/// Treatment of substances that are defined in multiple parameter files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "python", pyo3::pyclass)]
+pub enum DuplicatePolicy {
+    /// Return an error if the records are conflicting.
+    Error,
+    /// Use the record from the first file that contains the substance.
+    #[default]
+    PreferFirst,
+    /// Use the record from the last file that contains the substance.
+    PreferLast,
+}
+
+/// The requested substances and the pure records of a single file.
+type FileRecords<'a, M, I> = (Vec<&'a str>, Vec<PureRecord<M, I>>);
+
+/// Select the pure records of the requested substances from the
+/// records of multiple files according to the duplicate policy.
+pub(crate) fn select_pure_records<M, I>(
+    input: Vec<FileRecords<M, I>>,
+    search_option: IdentifierOption,
+    policy: DuplicatePolicy,
+) -> Result<Vec<PureRecord<M, I>>, ParameterError> {
+    let mut queried: IndexSet<String> = IndexSet::new();
+    for (substances, _) in &input {
+        substances.iter().try_for_each(|identifier| {
+            match queried.insert(identifier.to_string()) {
+                true => Ok(()),
+                false => Err(ParameterError::IncompatibleParameters(format!(
+                    "tried to add substance '{}' to system but it is already present.",
+                    identifier
+                ))),
+            }
+        })?;
+    }
+
+    let mut record_map: IndexMap<String, PureRecord<M, I>> = IndexMap::new();
+    for (_, pure_records) in input {
+        for record in pure_records {
+            let i = match record.identifier.as_string(search_option) {
+                Some(i) => i,
+                None => continue,
+            };
+            if let Some(existing) = record_map.get(&i) {
+                if policy == DuplicatePolicy::Error
+                    && queried.contains(&i)
+                    && !records_match(existing, &record)
+                {
+                    return Err(ParameterError::IncompatibleParameters(format!(
+                        "conflicting records for substance '{}': {} (molar weight {}) and {} (molar weight {}).",
+                        i,
+                        existing.identifier,
+                        existing.molarweight,
+                        record.identifier,
+                        record.molarweight
+                    )));
+                }
+                if policy != DuplicatePolicy::PreferLast {
+                    continue;
+                }
+            }
+            record_map.insert(i, record);
+        }
+    }
+
+    // Compare queried components and available components
+    let available: IndexSet<String> = record_map
+        .keys()
+        .map(|identifier| identifier.to_string())
+        .collect();
+    if !queried.is_subset(&available) {
+        let missing: Vec<String> = queried.difference(&available).cloned().collect();
+        let msg = format!("{:?}", missing);
+        return Err(ParameterError::ComponentsNotFound(msg));
+    };
+    let p: Vec<_> = queried
+        .iter()
+        .filter_map(|identifier| record_map.swap_remove(&identifier.clone()))
+        .collect();
+
+    // Different requested substances must not share a CAS number
+    if policy == DuplicatePolicy::Error {
+        for (i, r1) in p.iter().enumerate() {
+            for r2 in &p[i + 1..] {
+                if r1.identifier.cas.is_some() && r1.identifier.cas == r2.identifier.cas {
+                    return Err(ParameterError::IncompatibleParameters(format!(
+                        "{} and {} describe the same substance.",
+                        r1.identifier, r2.identifier
+                    )));
+                }
+            }
+        }
+    }
+    Ok(p)
+}
+
+/// Whether two records describe the same substance with
+/// identical identifiers and molar weights.
+fn records_match<M, I>(record1: &PureRecord<M, I>, record2: &PureRecord<M, I>) -> bool {
+    let (id1, id2) = (&record1.identifier, &record2.identifier);
+    id1.cas == id2.cas
+        && id1.name == id2.name
+        && id1.iupac_name == id2.iupac_name
+        && id1.smiles == id2.smiles
+        && id1.inchi == id2.inchi
+        && id1.formula == id2.formula
+        && record1.molarweight == record2.molarweight
+}
+
+/// Collect binary records into a map (id1, id2) -> record.
+///
+/// If multiple records are given for the same pair (in any order),
+/// the first record is kept, so that the result does not depend on
+/// the order of the identifiers within the records.
+fn binary_map<I, B>(records: I) -> IndexMap<(String, String), B>
+where
+    I: IntoIterator<Item = ((String, String), B)>,
+{
+    let mut map = IndexMap::new();
+    for ((id1, id2), record) in records {
+        if !map.contains_key(&(id2.clone(), id1.clone())) {
+            map.entry((id1, id2)).or_insert(record);
+        }
+    }
+    map
 }
 
 #[cfg(test)]
@@ -491,11 +668,11 @@ mod test {
         fn from_records(
             pure_records: Vec<PureRecord<MyPureModel, JobackRecord>>,
             binary_records: Array2<MyBinaryModel>,
-        ) -> Self {
-            Self {
+        ) -> Result<Self, ParameterError> {
+            Ok(Self {
                 pure_records,
                 binary_records,
-            }
+            })
         }
 
         fn records(
@@ -547,14 +724,14 @@ mod test {
             }
         ]
         "#;
-        let pure_records = serde_json::from_str(pr_json).expect("Unable to parse json.");
+        let pure_records: Vec<_> = serde_json::from_str(pr_json).expect("Unable to parse json.");
         let binary_records: Vec<_> = serde_json::from_str(br_json).expect("Unable to parse json.");
         let binary_matrix = MyParameter::binary_matrix_from_records(
             &pure_records,
             &binary_records,
             IdentifierOption::Cas,
         );
-        let p = MyParameter::from_records(pure_records, binary_matrix);
+        let p = MyParameter::from_records(pure_records, binary_matrix).unwrap();
 
         assert_eq!(p.pure_records[0].identifier.cas, Some("123-4-5".into()));
         assert_eq!(p.pure_records[1].identifier.cas, Some("678-9-1".into()));
@@ -600,14 +777,14 @@ mod test {
             }
         ]
         "#;
-        let pure_records = serde_json::from_str(pr_json).expect("Unable to parse json.");
+        let pure_records: Vec<_> = serde_json::from_str(pr_json).expect("Unable to parse json.");
         let binary_records: Vec<_> = serde_json::from_str(br_json).expect("Unable to parse json.");
         let binary_matrix = MyParameter::binary_matrix_from_records(
             &pure_records,
             &binary_records,
             IdentifierOption::Cas,
         );
-        let p = MyParameter::from_records(pure_records, binary_matrix);
+        let p = MyParameter::from_records(pure_records, binary_matrix).unwrap();
 
         assert_eq!(p.pure_records[0].identifier.cas, Some("123-4-5".into()));
         assert_eq!(p.pure_records[1].identifier.cas, Some("678-9-1".into()));
@@ -615,6 +792,56 @@ mod test {
         assert_eq!(p.binary_records[[0, 1]].b, 0.0)
     }
 
+    #[test]
+    fn from_records_missing_identifier() {
+        let pr_json = r#"
+        [
+            {
+                "identifier": {
+                    "cas": "123-4-5"
+                },
+                "molarweight": 16.0426,
+                "model_record": {
+                    "a": 0.1
+                }
+            },
+            {
+                "identifier": {
+                    "name": "my substance"
+                },
+                "molarweight": 32.08412,
+                "model_record": {
+                    "a": 0.2
+                }
+            }
+        ]
+        "#;
+        let br_json = r#"
+        [
+            {
+                "id1": {
+                    "cas": "123-4-5"
+                },
+                "id2": {
+                    "name": "my substance"
+                },
+                "model_record": {
+                    "b": 12.0
+                }
+            }
+        ]
+        "#;
+        let pure_records: Vec<_> = serde_json::from_str(pr_json).expect("Unable to parse json.");
+        let binary_records: Vec<_> = serde_json::from_str(br_json).expect("Unable to parse json.");
+        let binary_matrix = MyParameter::binary_matrix_from_records(
+            &pure_records,
+            &binary_records,
+            IdentifierOption::Cas,
+        );
+        assert_eq!(binary_matrix[[0, 1]], MyBinaryModel::default());
+        assert_eq!(binary_matrix[[1, 0]], MyBinaryModel::default());
+    }
+
     #[test]
     fn from_records_correct_binary_order() {
         let pr_json = r#"
@@ -663,14 +890,14 @@ mod test {
             }
         ]
         "#;
-        let pure_records = serde_json::from_str(pr_json).expect("Unable to parse json.");
+        let pure_records: Vec<_> = serde_json::from_str(pr_json).expect("Unable to parse json.");
         let binary_records: Vec<_> = serde_json::from_str(br_json).expect("Unable to parse json.");
         let binary_matrix = MyParameter::binary_matrix_from_records(
             &pure_records,
             &binary_records,
             IdentifierOption::Cas,
         );
-        let p = MyParameter::from_records(pure_records, binary_matrix);
+        let p = MyParameter::from_records(pure_records, binary_matrix).unwrap();
 
         assert_eq!(p.pure_records[0].identifier.cas, Some("000-0-0".into()));
         assert_eq!(p.pure_records[1].identifier.cas, Some("123-4-5".into()));
@@ -682,4 +909,113 @@ mod test {
         assert_eq!(p.binary_records[[2, 1]].b, 12.0);
         assert_eq!(p.binary_records[[1, 2]].b, 12.0);
     }
+
+    #[test]
+    fn from_records_duplicate_binary() {
+        let pr_json = r#"
+        [
+            {
+                "identifier": {
+                    "cas": "123-4-5"
+                },
+                "molarweight": 16.0426,
+                "model_record": {
+                    "a": 0.1
+                }
+            },
+            {
+                "identifier": {
+                    "cas": "678-9-1"
+                },
+                "molarweight": 32.08412,
+                "model_record": {
+                    "a": 0.2
+                }
+            }
+        ]
+        "#;
+        let br_json = r#"
+        [
+            {
+                "id1": {
+                    "cas": "123-4-5"
+                },
+                "id2": {
+                    "cas": "678-9-1"
+                },
+                "model_record": {
+                    "b": 12.0
+                }
+            },
+            {
+                "id1": {
+                    "cas": "678-9-1"
+                },
+                "id2": {
+                    "cas": "123-4-5"
+                },
+                "model_record": {
+                    "b": 13.0
+                }
+            }
+        ]
+        "#;
+        let pure_records: Vec<_> = serde_json::from_str(pr_json).expect("Unable to parse json.");
+        let binary_records: Vec<_> = serde_json::from_str(br_json).expect("Unable to parse json.");
+        let binary_matrix = MyParameter::binary_matrix_from_records(
+            &pure_records,
+            &binary_records,
+            IdentifierOption::Cas,
+        );
+        assert_eq!(binary_matrix[[0, 1]].b, 12.0);
+        assert_eq!(binary_matrix[[1, 0]].b, 12.0);
+    }
+
+    #[test]
+    fn select_duplicate_records() {
+        fn record(
+            cas: &str,
+            name: &str,
+            molarweight: f64,
+        ) -> PureRecord<MyPureModel, JobackRecord> {
+            let json = format!(
+                r#"{{"identifier": {{"cas": "{}", "name": "{}"}}, "molarweight": {}, "model_record": {{"a": {}}}}}"#,
+                cas, name, molarweight, molarweight
+            );
+            serde_json::from_str(&json).expect("Unable to parse json.")
+        }
+        let input = || {
+            vec![
+                (vec!["methane"], vec![record("74-82-8", "methane", 16.0)]),
+                (
+                    vec!["ethane"],
+                    vec![
+                        record("74-84-0", "ethane", 30.0),
+                        record("74-82-8", "methane", 16.1),
+                    ],
+                ),
+            ]
+        };
+        let select = |input, policy| select_pure_records(input, IdentifierOption::Name, policy);
+
+        let first = select(input(), DuplicatePolicy::PreferFirst).unwrap();
+        assert_eq!(first[0].molarweight, 16.0);
+        let last = select(input(), DuplicatePolicy::PreferLast).unwrap();
+        assert_eq!(last[0].molarweight, 16.1);
+        assert_eq!(last[1].molarweight, 30.0);
+        assert!(select(input(), DuplicatePolicy::Error).is_err());
+
+        // identical records are not conflicting
+        let mut identical = input();
+        identical[1].1[1] = record("74-82-8", "methane", 16.0);
+        assert!(select(identical, DuplicatePolicy::Error).is_ok());
+
+        // the same substance requested under different names
+        let input = vec![
+            (vec!["methane"], vec![record("74-82-8", "methane", 16.0)]),
+            (vec!["CH4"], vec![record("74-82-8", "CH4", 16.0)]),
+        ];
+        assert!(select(input.clone(), DuplicatePolicy::PreferFirst).is_ok());
+        assert!(select(input, DuplicatePolicy::Error).is_err());
+    }
 }
@@ -1,13 +1,16 @@
 use super::identifier::Identifier;
 use super::segment::SegmentRecord;
+use super::units::{deserialize_with_unit, GramPerMol};
 use super::ParameterError;
 use conv::ValueInto;
 use serde::{Deserialize, Serialize};
+use std::ops::{Add, Mul};
 
 /// A collection of parameters of a pure substance.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PureRecord<M, I> {
     pub identifier: Identifier,
+    #[serde(deserialize_with = "deserialize_with_unit::<_, GramPerMol>")]
     pub molarweight: f64,
     pub model_record: M,
     #[serde(default = "Default::default")]
@@ -67,6 +70,46 @@ impl<M, I> PureRecord<M, I> {
     }
 }
 
+impl<M, I> PureRecord<M, I>
+where
+    M: Clone + Add<Output = M> + Mul<f64, Output = M>,
+    I: Clone + Add<Output = I> + Mul<f64, Output = I>,
+{
+    /// Create a pseudo-component as a weighted blend of pure records,
+    /// e.g., to lump isomers into a single component.
+    ///
+    /// The weights (typically mole fractions) are normalized. The molar weight
+    /// and the model and ideal gas records are the weighted sums of those of
+    /// the individual records. The ideal gas record is only blended if it is
+    /// given for all records.
+    pub fn blend(identifier: Identifier, records: &[(Self, f64)]) -> Result<Self, ParameterError> {
+        let total: f64 = records.iter().map(|(_, w)| w).sum();
+        if records.is_empty() || records.iter().any(|&(_, w)| w < 0.0) || total <= 0.0 {
+            return Err(ParameterError::IncompatibleParameters(String::from(
+                "Blending requires at least one record and non-negative weights with a positive sum",
+            )));
+        }
+        let mut records = records.iter().map(|(r, w)| (r, w / total));
+        let (first, w) = records.next().unwrap();
+        let mut molarweight = first.molarweight * w;
+        let mut model_record = first.model_record.clone() * w;
+        let mut ideal_gas_record = first.ideal_gas_record.clone().map(|i| i * w);
+        for (r, w) in records {
+            molarweight += r.molarweight * w;
+            model_record = model_record + r.model_record.clone() * w;
+            ideal_gas_record = ideal_gas_record
+                .zip(r.ideal_gas_record.clone())
+                .map(|(i1, i2)| i1 + i2 * w);
+        }
+        Ok(Self::new(
+            identifier,
+            molarweight,
+            model_record,
+            ideal_gas_record,
+        ))
+    }
+}
+
 impl<M, I> std::fmt::Display for PureRecord<M, I>
 where
     M: std::fmt::Display,
@@ -193,4 +236,57 @@ mod test {
         assert_eq!(records[0].identifier.cas, Some("1".into()));
         assert_eq!(records[1].identifier.cas, Some("2".into()))
     }
+
+    #[test]
+    fn blend() -> crate::EosResult<()> {
+        use crate::cubic::PengRobinsonRecord;
+        use crate::joback::Joback;
+        use ndarray::arr1;
+        use quantity::si::KELVIN;
+
+        let record = |mw, tc, a| {
+            PureRecord::new(
+                Identifier::default(),
+                mw,
+                PengRobinsonRecord::new(tc, 4e6, 0.2),
+                Some(JobackRecord::new(a, 0.1, 0.0, 0.0, 0.0)),
+            )
+        };
+        let records = [
+            (record(50.0, 400.0, 10.0), 1.0),
+            (record(60.0, 500.0, 20.0), 3.0),
+        ];
+        let blend = PureRecord::blend(Identifier::default(), &records)?;
+        assert_eq!(blend.molarweight, 57.5);
+        assert_eq!(
+            blend.model_record.to_string(),
+            PengRobinsonRecord::new(475.0, 4e6, 0.2).to_string()
+        );
+
+        // the heat capacity of the blend is that of the ideal gas mixture
+        let t = 300.0 * KELVIN;
+        let mixture = Joback::new(
+            records
+                .iter()
+                .map(|(r, _)| r.ideal_gas_record.clone().unwrap())
+                .collect(),
+        );
+        let pseudo = Joback::new(vec![blend.ideal_gas_record.unwrap()]);
+        approx::assert_relative_eq!(
+            pseudo.c_p(t, &arr1(&[1.0]))?,
+            mixture.c_p(t, &arr1(&[0.25, 0.75]))?,
+            max_relative = 1e-14
+        );
+
+        // ideal gas records are only blended if they are available for all records
+        let mut records = records;
+        records[0].0.ideal_gas_record = None;
+        let blend = PureRecord::blend(Identifier::default(), &records)?;
+        assert!(blend.ideal_gas_record.is_none());
+        assert!(
+            PureRecord::<PengRobinsonRecord, JobackRecord>::blend(Identifier::default(), &[])
+                .is_err()
+        );
+        Ok(())
+    }
 }
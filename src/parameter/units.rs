@@ -0,0 +1,192 @@
+use super::ParameterError;
+use quantity::si::*;
+use serde::{Deserialize, Deserializer};
+
+/// Base units that can be used in unit strings.
+const BASE_UNITS: [(&str, SINumber); 17] = [
+    ("m", METER),
+    ("g", GRAM),
+    ("s", SECOND),
+    ("min", MINUTE),
+    ("h", HOUR),
+    ("A", AMPERE),
+    ("mol", MOL),
+    ("K", KELVIN),
+    ("N", NEWTON),
+    ("J", JOULE),
+    ("cal", CALORIE),
+    ("W", WATT),
+    ("Pa", PASCAL),
+    ("bar", BAR),
+    ("L", LITER),
+    ("l", LITER),
+    ("Å", ANGSTROM),
+];
+
+/// SI prefixes that can be combined with the base units.
+const PREFIXES: [(&str, f64); 12] = [
+    ("T", TERA),
+    ("G", GIGA),
+    ("M", MEGA),
+    ("k", KILO),
+    ("h", HECTO),
+    ("d", DECI),
+    ("c", CENTI),
+    ("m", MILLI),
+    ("µ", MICRO),
+    ("u", MICRO),
+    ("n", NANO),
+    ("p", PICO),
+];
+
+/// Parse a single unit with an optional prefix and exponent, e.g., `"dm^3"`.
+fn parse_factor(factor: &str) -> Result<SINumber, ParameterError> {
+    let (symbol, exponent) = match factor.split_once('^') {
+        Some((symbol, exponent)) => (
+            symbol,
+            exponent.parse::<i32>().map_err(|_| {
+                ParameterError::InvalidUnit(format!("Invalid exponent in unit '{}'", factor))
+            })?,
+        ),
+        None => (factor, 1),
+    };
+    let base_unit = |s: &str| BASE_UNITS.iter().find(|(b, _)| *b == s).map(|&(_, u)| u);
+    let unit = base_unit(symbol)
+        .or_else(|| {
+            PREFIXES
+                .iter()
+                .find_map(|(p, f)| Some(*f * base_unit(symbol.strip_prefix(p)?)?))
+        })
+        .ok_or_else(|| ParameterError::InvalidUnit(format!("Unknown unit '{}'", symbol)))?;
+    Ok(unit.powi(exponent))
+}
+
+/// Parse a unit string like `"K"`, `"kJ/mol"` or `"mol/m^3"`.
+///
+/// Units are separated by `*` or spaces. All units after the first
+/// `/` are in the denominator. Exponents are given with `^` or `**`.
+pub fn parse_unit(unit: &str) -> Result<SINumber, ParameterError> {
+    let unit = unit.replace("**", "^").replace(['(', ')'], "");
+    let (numerator, denominator) = unit.split_once('/').unwrap_or((&unit, ""));
+    let product = |s: &str| {
+        s.split(['*', ' ', '/'])
+            .filter(|f| !f.is_empty() && *f != "1")
+            .try_fold(SINumber::from(1.0), |acc, f| {
+                Ok::<_, ParameterError>(acc * parse_factor(f)?)
+            })
+    };
+    Ok(product(numerator)? / product(denominator)?)
+}
+
+/// The unit in which a field of a parameter record is stored.
+pub trait RecordUnit {
+    /// The unit of the stored value.
+    fn unit() -> SINumber;
+}
+
+macro_rules! record_unit {
+    ($name:ident, $unit:expr, $doc:literal) => {
+        #[doc = $doc]
+        pub struct $name;
+
+        impl RecordUnit for $name {
+            fn unit() -> SINumber {
+                $unit
+            }
+        }
+    };
+}
+
+record_unit!(Kelvin, KELVIN, "Temperatures in K.");
+record_unit!(Pascal, PASCAL, "Pressures in Pa.");
+record_unit!(Angstrom, ANGSTROM, "Lengths in Å.");
+record_unit!(GramPerMol, GRAM / MOL, "Molar weights in g/mol.");
+
+/// A value in a parameter file, either as plain number in the unit
+/// of the record or annotated with a unit.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnnotatedValue {
+    Plain(f64),
+    Annotated { value: f64, unit: String },
+}
+
+/// Deserialize a field of a parameter record that is given either as plain
+/// number (in the unit of the record) or as value with a unit annotation,
+/// e.g., `{"value": 42.5, "unit": "bar"}`, which is converted to the unit `T`.
+///
+/// Use it with `#[serde(deserialize_with = "deserialize_with_unit::<_, Pascal>")]`.
+/// The values are serialized as plain numbers in the unit of the record, so that
+/// serialized records can be read again.
+pub fn deserialize_with_unit<'de, D, T>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+    T: RecordUnit,
+{
+    match AnnotatedValue::deserialize(deserializer)? {
+        AnnotatedValue::Plain(value) => Ok(value),
+        AnnotatedValue::Annotated { value, unit } => {
+            let quantity = value * parse_unit(&unit).map_err(serde::de::Error::custom)?;
+            (quantity / T::unit()).into_value().map_err(|_| {
+                serde::de::Error::custom(format!(
+                    "Unit '{}' is not compatible with the unit of the parameter ({})",
+                    unit,
+                    T::unit()
+                ))
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::PengRobinsonRecord;
+    use crate::joback::JobackRecord;
+    use crate::parameter::PureRecord;
+    use approx::assert_relative_eq;
+
+    type Record = PureRecord<PengRobinsonRecord, JobackRecord>;
+
+    #[test]
+    fn test_unit_annotations() -> Result<(), ParameterError> {
+        let plain = r#"{
+            "identifier": {"name": "propane"},
+            "molarweight": 44.0962,
+            "model_record": {"tc": 369.96, "pc": 4250000.0, "acentric_factor": 0.153}
+        }"#;
+        let annotated = r#"{
+            "identifier": {"name": "propane"},
+            "molarweight": {"value": 0.0440962, "unit": "kg/mol"},
+            "model_record": {
+                "tc": 369.96,
+                "pc": {"value": 42.5, "unit": "bar"},
+                "acentric_factor": 0.153
+            }
+        }"#;
+        let plain: Record = serde_json::from_str(plain)?;
+        let annotated: Record = serde_json::from_str(annotated)?;
+        assert_relative_eq!(
+            annotated.molarweight,
+            plain.molarweight,
+            max_relative = 1e-14
+        );
+        assert_eq!(
+            serde_json::to_value(&annotated.model_record)?,
+            serde_json::to_value(&plain.model_record)?
+        );
+
+        // round trip
+        let json = serde_json::to_string(&annotated)?;
+        let reread: Record = serde_json::from_str(&json)?;
+        assert_eq!(json, serde_json::to_string(&reread)?);
+
+        // incompatible and unknown units
+        let wrong = r#"{"tc": {"value": 369.96, "unit": "bar"}, "pc": 4250000.0, "acentric_factor": 0.153}"#;
+        assert!(serde_json::from_str::<PengRobinsonRecord>(wrong).is_err());
+        let unknown =
+            r#"{"tc": {"value": 369.96, "unit": "R"}, "pc": 4250000.0, "acentric_factor": 0.153}"#;
+        assert!(serde_json::from_str::<PengRobinsonRecord>(unknown).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,573 @@
+use super::{PhaseDiagram, PhaseEquilibrium, RetryPolicy, SolverOptions, TerminatedExt, Verbosity};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::{Contributions, DensityInitialization, State, TPSpec};
+use crate::EosUnit;
+use ndarray::{arr1, arr2};
+use num_dual::linalg::{norm, LU};
+use quantity::{QuantityArray1, QuantityScalar};
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+const MAX_ITER_AZEOTROPE: usize = 50;
+const TOL_AZEOTROPE: f64 = 1e-10;
+const MAX_XSTEP: f64 = 0.1;
+const MAX_TSTEP: f64 = 20.0;
+const MAX_LNPSTEP: f64 = 0.1;
+
+/// # Azeotropes
+impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
+    /// Calculate a homogeneous azeotrope of a binary mixture for a given
+    /// temperature or pressure.
+    ///
+    /// The azeotrope has to be bracketed by `x_bracket`, i.e., $y_1-x_1$ of the
+    /// bubble points at the two liquid mole fractions of the first component has
+    /// to change its sign. Starting from the linear interpolation of the root of
+    /// $y_1-x_1$, the azeotrope is converged with the Newton scheme of
+    /// [PhaseEquilibrium::azeotrope_t] and [PhaseEquilibrium::azeotrope_p].
+    pub fn azeotrope(
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+        x_bracket: (f64, f64),
+        options: SolverOptions,
+        bubble_dew_options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let tp = TPSpec::try_from(temperature_or_pressure)?;
+        let residual = |vle: &Self| vle.vapor().molefracs[0] - vle.liquid().molefracs[0];
+
+        let (x0, x1) = x_bracket;
+        let vle0 = PhaseEquilibrium::bubble_point(
+            eos,
+            temperature_or_pressure,
+            &arr1(&[x0, 1.0 - x0]),
+            None,
+            None,
+            bubble_dew_options.clone(),
+        )?;
+        let vle1 = PhaseEquilibrium::bubble_point(
+            eos,
+            temperature_or_pressure,
+            &arr1(&[x1, 1.0 - x1]),
+            Some(vle0.vapor().tp(tp)),
+            Some(&vle0.vapor().molefracs),
+            bubble_dew_options.clone(),
+        )?;
+        let (f0, f1) = (residual(&vle0), residual(&vle1));
+        if f0 * f1 > 0.0 {
+            return Err(EosError::UndeterminedState(format!(
+                "no azeotrope between x = {} and x = {}",
+                x0, x1
+            )));
+        }
+
+        // start at the closer end of the bracket and fall back to the default initialization
+        let x = x0 - f0 * (x1 - x0) / (f1 - f0);
+        let init = if (x - x0).abs() < (x - x1).abs() {
+            &vle0
+        } else {
+            &vle1
+        };
+        let (options_inner, options_outer) = bubble_dew_options;
+        let policy = RetryPolicy::new()
+            .attempt(
+                options_outer.clone(),
+                (
+                    Some(init.vapor().tp(tp)),
+                    Some(init.vapor().molefracs.clone()),
+                ),
+            )
+            .attempt(options_outer, (None, None));
+        let vle = PhaseEquilibrium::bubble_point_with_retry(
+            eos,
+            temperature_or_pressure,
+            &arr1(&[x, 1.0 - x]),
+            options_inner,
+            &policy,
+        )?;
+
+        let vle = Self::azeotrope_newton(tp, &vle, options)?
+            .filter(|vle| {
+                let x = vle.liquid().molefracs[0];
+                (x - x0) * (x - x1) <= 0.0
+            })
+            .ok_or_else(|| {
+                EosError::UndeterminedState(format!(
+                    "the azeotrope left the bracket between x = {} and x = {}",
+                    x0, x1
+                ))
+            })?;
+        Ok(vle)
+    }
+
+    /// Calculate a homogeneous azeotrope of a binary mixture for a given
+    /// temperature.
+    ///
+    /// Starting from the bubble point at the liquid mole fraction `x_init` of the
+    /// first component, the composition $x_1=y_1$ of both phases and the pressure
+    /// are iterated with a Newton scheme until the fugacity coefficients of both
+    /// phases are equal. In contrast to [PhaseEquilibrium::azeotrope], no bracket
+    /// is required, but the iteration can fail for poor initial compositions.
+    pub fn azeotrope_t(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        x_init: f64,
+        options: SolverOptions,
+        bubble_dew_options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        match TPSpec::try_from(temperature)? {
+            TPSpec::Temperature(_) => {
+                Self::azeotrope_init(eos, temperature, x_init, None, options, bubble_dew_options)?
+                    .ok_or_else(|| {
+                        EosError::UndeterminedState(format!(
+                            "no azeotrope found starting from x = {}",
+                            x_init
+                        ))
+                    })
+            }
+            TPSpec::Pressure(_) => Err(EosError::WrongUnits(
+                String::from("temperature"),
+                temperature.to_string(),
+            )),
+        }
+    }
+
+    /// Calculate a homogeneous azeotrope of a binary mixture for a given
+    /// pressure.
+    ///
+    /// Analogous to [PhaseEquilibrium::azeotrope_t] with the temperature
+    /// as iteration variable. `t_init` is the initial temperature of the
+    /// bubble point at `x_init`.
+    pub fn azeotrope_p(
+        eos: &Rc<E>,
+        pressure: QuantityScalar<U>,
+        x_init: f64,
+        t_init: Option<QuantityScalar<U>>,
+        options: SolverOptions,
+        bubble_dew_options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        match TPSpec::try_from(pressure)? {
+            TPSpec::Pressure(_) => {
+                Self::azeotrope_init(eos, pressure, x_init, t_init, options, bubble_dew_options)?
+                    .ok_or_else(|| {
+                        EosError::UndeterminedState(format!(
+                            "no azeotrope found starting from x = {}",
+                            x_init
+                        ))
+                    })
+            }
+            TPSpec::Temperature(_) => Err(EosError::WrongUnits(
+                String::from("pressure"),
+                pressure.to_string(),
+            )),
+        }
+    }
+
+    /// Newton iteration of the azeotrope starting from the bubble point at `x_init`.
+    fn azeotrope_init(
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+        x_init: f64,
+        tp_init: Option<QuantityScalar<U>>,
+        options: SolverOptions,
+        bubble_dew_options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Option<Self>>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let vle = PhaseEquilibrium::bubble_point(
+            eos,
+            temperature_or_pressure,
+            &arr1(&[x_init, 1.0 - x_init]),
+            tp_init,
+            None,
+            bubble_dew_options,
+        )?;
+        Self::azeotrope_newton(TPSpec::try_from(temperature_or_pressure)?, &vle, options)
+    }
+
+    /// Newton iteration of the azeotropic composition and the pressure (or
+    /// temperature) that is not specified, starting from `vle`.
+    ///
+    /// Returns `None` if the composition leaves the range between the pure
+    /// components, i.e., if there is no azeotrope close to the initial value.
+    fn azeotrope_newton(
+        tp: TPSpec<U>,
+        vle: &Self,
+        options: SolverOptions,
+    ) -> EosResult<Option<Self>>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_AZEOTROPE, TOL_AZEOTROPE);
+        let eos = &vle.liquid().eos;
+        let (mut temperature, mut pressure) = match tp {
+            TPSpec::Temperature(t) => (t, vle.liquid().pressure(Contributions::Total)),
+            TPSpec::Pressure(p) => (vle.liquid().temperature, p),
+        };
+        let mut x = vle.liquid().molefracs[0];
+        let (mut rho_l, mut rho_v) = (vle.liquid().density, vle.vapor().density);
+
+        log_iter!(verbosity, " iter |    residual    |  mole fraction  ");
+        log_iter!(verbosity, "{:-<38}", "");
+
+        for i in 1..=max_iter {
+            // both phases have the same composition
+            let moles = arr1(&[x, 1.0 - x]) * U::reference_moles();
+            let liquid = State::new_npt(
+                eos,
+                temperature,
+                pressure,
+                &moles,
+                DensityInitialization::InitialDensity(rho_l),
+            )?;
+            let vapor = State::new_npt(
+                eos,
+                temperature,
+                pressure,
+                &moles,
+                DensityInitialization::InitialDensity(rho_v),
+            )?;
+            if ((liquid.density - vapor.density) / liquid.density)
+                .into_value()?
+                .abs()
+                < 1e-3
+            {
+                return Err(EosError::TrivialSolution);
+            }
+
+            // equal fugacity coefficients of both components
+            let res = liquid.ln_phi() - vapor.ln_phi();
+            let error = norm(&res);
+            log_iter!(verbosity, " {:4} | {:14.8e} | {:.10}", i, error, x);
+            if error < tol {
+                options.notify("azeotrope", i, error, None)?;
+                log_result!(
+                    verbosity,
+                    "Azeotrope: calculation converged in {} step(s)\n",
+                    i
+                );
+                // the azeotrope merged with a pure component
+                if x < TOL_AZEOTROPE.sqrt() || x > 1.0 - TOL_AZEOTROPE.sqrt() {
+                    return Ok(None);
+                }
+                return Ok(Some(PhaseEquilibrium::from_states(vapor, liquid)));
+            }
+
+            // derivatives w.r.t. x_1 (at constant total moles) and ln(p) or T
+            let dln_phi_dn = (liquid.dln_phi_dnj() - vapor.dln_phi_dnj())
+                .to_reduced(U::reference_moles().powi(-1))?;
+            let dln_phi_dtp = match tp {
+                TPSpec::Temperature(_) => {
+                    ((liquid.dln_phi_dp() - vapor.dln_phi_dp()) * pressure).into_value()?
+                }
+                TPSpec::Pressure(_) => (liquid.dln_phi_dt() - vapor.dln_phi_dt())
+                    .to_reduced(U::reference_temperature().powi(-1))?,
+            };
+            let jacobian = arr2(&[
+                [dln_phi_dn[(0, 0)] - dln_phi_dn[(0, 1)], dln_phi_dtp[0]],
+                [dln_phi_dn[(1, 0)] - dln_phi_dn[(1, 1)], dln_phi_dtp[1]],
+            ]);
+            let dx = LU::new(jacobian)?.solve(&res) * options.damping.unwrap_or(1.0);
+            options.notify("azeotrope", i, error, Some(norm(&dx)))?;
+
+            // apply the (limited) Newton step
+            x -= dx[0].clamp(-MAX_XSTEP, MAX_XSTEP);
+            if !(x > 0.0 && x < 1.0) {
+                log_result!(
+                    verbosity,
+                    "Azeotrope: composition left the range of mole fractions\n"
+                );
+                return Ok(None);
+            }
+            match tp {
+                TPSpec::Temperature(_) => {
+                    pressure = pressure * (-dx[1]).clamp(-MAX_LNPSTEP, MAX_LNPSTEP).exp()
+                }
+                TPSpec::Pressure(_) => {
+                    temperature -= dx[1].clamp(-MAX_TSTEP, MAX_TSTEP) * U::reference_temperature()
+                }
+            }
+            rho_l = liquid.density;
+            rho_v = vapor.density;
+        }
+        Err(EosError::NotConverged(String::from("Azeotrope")))
+    }
+}
+
+impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
+    /// Trace the homogeneous azeotrope of a binary mixture over the given
+    /// temperatures or pressures.
+    ///
+    /// The first azeotrope is calculated starting from the bubble point at the
+    /// liquid mole fraction `x_init`, every subsequent point is initialized with
+    /// the previous azeotrope. The locus ends at the first point for which the
+    /// composition leaves the range between the pure components, i.e., the
+    /// azeotrope merged with a pure component. The indices of the temperatures
+    /// or pressures for which no azeotrope exists are returned together with the
+    /// locus. All other errors of the iterations are returned.
+    pub fn azeotropic_locus(
+        eos: &Rc<E>,
+        temperatures_or_pressures: &QuantityArray1<U>,
+        x_init: f64,
+        options: SolverOptions,
+        bubble_dew_options: (SolverOptions, SolverOptions),
+    ) -> EosResult<(Self, Vec<usize>)>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let n = temperatures_or_pressures.len();
+        let mut states: Vec<PhaseEquilibrium<U, E, 2>> = Vec::with_capacity(n);
+        for i in 0..n {
+            let tp = temperatures_or_pressures.get(i);
+            let vle = match states.last() {
+                None => PhaseEquilibrium::azeotrope_init(
+                    eos,
+                    tp,
+                    x_init,
+                    None,
+                    options.clone(),
+                    bubble_dew_options.clone(),
+                )?,
+                Some(init) => PhaseEquilibrium::azeotrope_newton(
+                    TPSpec::try_from(tp)?,
+                    init,
+                    options.clone(),
+                )?,
+            };
+            match vle {
+                Some(vle) => states.push(vle),
+                None => {
+                    log_result!(
+                        options.verbosity,
+                        "Azeotropic locus: no azeotrope at {}, the locus ends after {} of {} points\n",
+                        tp,
+                        i,
+                        n
+                    );
+                    return Ok((Self { states }, (i..n).collect()));
+                }
+            }
+        }
+        Ok((Self { states }, Vec::new()))
+    }
+
+    /// Return the homogeneous azeotrope of a binary phase diagram
+    /// if the diagram contains one.
+    pub fn azeotrope(&self) -> Option<&PhaseEquilibrium<U, E, 2>> {
+        self.states.iter().find(|vle| {
+            let (x, y) = (vle.liquid().molefracs[0], vle.vapor().molefracs[0]);
+            vle.liquid().eos.components() == 2
+                && x > TOL_AZEOTROPE.sqrt()
+                && x < 1.0 - TOL_AZEOTROPE.sqrt()
+                && (y - x).abs() < TOL_AZEOTROPE.sqrt()
+        })
+    }
+
+    /// Refine azeotropes that are crossed between two consecutive points
+    /// of a binary diagram and insert them into the diagram.
+    pub(super) fn insert_azeotropes(
+        mut self,
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+        bubble_dew_options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let residual = |vle: &PhaseEquilibrium<U, E, 2>| {
+            (
+                vle.liquid().molefracs[0],
+                vle.vapor().molefracs[0] - vle.liquid().molefracs[0],
+            )
+        };
+        let mut i = 1;
+        while i < self.states.len() {
+            let (x0, f0) = residual(&self.states[i - 1]);
+            let (x1, f1) = residual(&self.states[i]);
+            if f0 * f1 < 0.0 {
+                if let Some(vle) = PhaseEquilibrium::azeotrope(
+                    eos,
+                    temperature_or_pressure,
+                    (x0, x1),
+                    bubble_dew_options.1.clone(),
+                    bubble_dew_options.clone(),
+                )
+                .ok_or_terminated()?
+                {
+                    self.states.insert(i, vle);
+                    i += 1;
+                }
+            }
+            i += 1;
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters, PengRobinsonRecord};
+    use crate::parameter::{Identifier, Parameter, PureRecord};
+    use crate::phase_equilibria::IterationRecord;
+    use crate::state::Contributions;
+    use approx::assert_relative_eq;
+    use ndarray::arr2;
+    use quantity::si::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_azeotrope() -> EosResult<()> {
+        // propane and propylene with a binary interaction parameter
+        let propane_propylene = |k_ij: f64| {
+            let records = vec![
+                PureRecord::new(
+                    Identifier::default(),
+                    44.0962,
+                    PengRobinsonRecord::new(369.96, 4250000.0, 0.153),
+                    None,
+                ),
+                PureRecord::new(
+                    Identifier::default(),
+                    42.081,
+                    PengRobinsonRecord::new(364.9, 4600000.0, 0.142),
+                    None,
+                ),
+            ];
+            let k_ij = arr2(&[[0.0, k_ij], [k_ij, 0.0]]);
+            let parameters = PengRobinsonParameters::from_records(records, k_ij).unwrap();
+            Rc::new(PengRobinson::new(Rc::new(parameters)))
+        };
+        let eos = propane_propylene(0.1);
+        let t = 300.0 * KELVIN;
+
+        let dia = PhaseDiagram::binary_vle(&eos, t, Some(21), None, Default::default())?;
+        let azeotrope = dia.azeotrope().expect("no azeotrope found");
+        assert_eq!(dia.states.len(), 22);
+        let (x, y) = (
+            azeotrope.liquid().molefracs[0],
+            azeotrope.vapor().molefracs[0],
+        );
+        assert_relative_eq!(x, y, epsilon = 1e-8);
+
+        // maximum pressure azeotrope
+        let p_max = dia
+            .states
+            .iter()
+            .map(|vle| {
+                vle.vapor()
+                    .pressure(Contributions::Total)
+                    .to_reduced(BAR)
+                    .unwrap()
+            })
+            .fold(0.0, f64::max);
+        assert_relative_eq!(
+            azeotrope.vapor().pressure(Contributions::Total),
+            p_max * BAR,
+            max_relative = 1e-12
+        );
+
+        let vle = PhaseEquilibrium::azeotrope(
+            &eos,
+            t,
+            (0.1, 0.9),
+            Default::default(),
+            Default::default(),
+        )?;
+        assert_relative_eq!(vle.liquid().molefracs[0], x, epsilon = 1e-8);
+        assert!(PhaseEquilibrium::azeotrope(
+            &eos,
+            t,
+            (0.01, 0.02),
+            Default::default(),
+            Default::default()
+        )
+        .is_err());
+
+        // the options of the outer bubble point iteration reach the azeotrope
+        let observer = Rc::new(RefCell::new(|info: &IterationRecord| {
+            info.solver != "azeotrope"
+        }));
+        let options = (
+            SolverOptions::new(),
+            SolverOptions::new().observer(observer),
+        );
+        let res = PhaseDiagram::binary_vle(&eos, t, Some(21), None, options);
+        assert!(matches!(res, Err(EosError::Terminated(_))));
+
+        // Newton scheme without bracket
+        let vle =
+            PhaseEquilibrium::azeotrope_t(&eos, t, 0.3, Default::default(), Default::default())?;
+        assert_relative_eq!(vle.liquid().molefracs[0], x, epsilon = 1e-8);
+        assert_relative_eq!(vle.vapor().molefracs[0], x, epsilon = 1e-8);
+        let p = vle.vapor().pressure(Contributions::Total);
+        let vle_p = PhaseEquilibrium::azeotrope_p(
+            &eos,
+            p,
+            0.3,
+            Some(290.0 * KELVIN),
+            Default::default(),
+            Default::default(),
+        )?;
+        assert_relative_eq!(vle_p.liquid().temperature, t, max_relative = 1e-8);
+        assert!(matches!(
+            PhaseEquilibrium::azeotrope_t(&eos, p, 0.3, Default::default(), Default::default()),
+            Err(EosError::WrongUnits(_, _))
+        ));
+
+        // azeotropic locus
+        let temperatures = arr1(&[280.0, 290.0, 300.0]) * KELVIN;
+        let (locus, missing) = PhaseDiagram::azeotropic_locus(
+            &eos,
+            &temperatures,
+            0.3,
+            Default::default(),
+            Default::default(),
+        )?;
+        assert_eq!(locus.states.len(), 3);
+        assert!(missing.is_empty());
+        assert_relative_eq!(locus.states[2].liquid().molefracs[0], x, epsilon = 1e-8);
+
+        // the azeotrope merges with pure propylene and the locus ends
+        let eos = propane_propylene(0.02);
+        let temperatures = arr1(&[200.0, 220.0, 240.0, 250.0, 260.0, 270.0]) * KELVIN;
+        let (locus, missing) = PhaseDiagram::azeotropic_locus(
+            &eos,
+            &temperatures,
+            0.1,
+            Default::default(),
+            Default::default(),
+        )?;
+        assert_eq!(locus.states.len(), 4);
+        assert_eq!(missing, vec![4, 5]);
+        assert!(PhaseEquilibrium::azeotrope_t(
+            &eos,
+            270.0 * KELVIN,
+            0.1,
+            Default::default(),
+            Default::default()
+        )
+        .is_err());
+
+        // the termination by an observer is not swallowed
+        let mut calls = 0;
+        let observer = Rc::new(RefCell::new(move |_: &IterationRecord| {
+            calls += 1;
+            calls < 10
+        }));
+        let options = SolverOptions::new().observer(observer);
+        let res =
+            PhaseDiagram::azeotropic_locus(&eos, &temperatures, 0.1, options, Default::default());
+        assert!(matches!(res, Err(EosError::Terminated(_))));
+        Ok(())
+    }
+}
@@ -1,9 +1,10 @@
-use super::{PhaseEquilibrium, SolverOptions, Verbosity};
+use super::{JacobianUpdate, PhaseEquilibrium, RetryPolicy, SolverOptions, Verbosity};
+use crate::density_iteration::density_ln_phi;
 use crate::errors::{EosError, EosResult};
 use crate::state::{
-    Contributions,
+    validate_molefracs, Contributions,
     DensityInitialization::{InitialDensity, Liquid, Vapor},
-    State, StateBuilder, TPSpec,
+    MolefracsNormalization, State, StateBuilder, TPSpec,
 };
 use crate::{equation_of_state::EquationOfState, EosUnit};
 use ndarray::*;
@@ -15,6 +16,7 @@ use std::rc::Rc;
 const MAX_ITER_INNER: usize = 5;
 const TOL_INNER: f64 = 1e-9;
 const MAX_ITER_OUTER: usize = 400;
+const MAX_ITER_SUCCESSIVE_SUBSTITUTION: usize = 100;
 const TOL_OUTER: f64 = 1e-10;
 
 const MAX_TSTEP: f64 = 20.0;
@@ -23,6 +25,7 @@ const PROMISING_F: f64 = 1.0;
 const P_START: f64 = 1.0 / 138.0649; // equivalent to 1 bar in SI units
 const T_START: f64 = 400.0;
 const NEWTON_TOL: f64 = 1e-3;
+const MAX_RESIDUAL_RATIO: f64 = 0.5;
 
 impl<U: EosUnit> TPSpec<U> {
     fn starting_value(&self) -> QuantityScalar<U> {
@@ -66,6 +69,10 @@ where
     }
 }
 
+/// Initial values of bubble and dew point calculations: the temperature
+/// or pressure and the mole fractions of the incipient phase.
+pub type BubbleDewInitialization<U> = (Option<QuantityScalar<U>>, Option<Array1<f64>>);
+
 /// # Bubble and dew point calculations
 impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
     /// Calculate a phase equilibrium for a given temperature
@@ -116,6 +123,310 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
         )
     }
 
+    /// Calculate a bubble point with the options and initial values of a
+    /// [RetryPolicy].
+    ///
+    /// The initial values of every attempt consist of the initial temperature
+    /// or pressure and the initial composition of the vapor phase. The options
+    /// of the policy are used for the outer loop, `inner_options` are used for
+    /// the inner loop of every attempt.
+    pub fn bubble_point_with_retry(
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+        liquid_molefracs: &Array1<f64>,
+        inner_options: SolverOptions,
+        policy: &RetryPolicy<BubbleDewInitialization<U>>,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        policy.run(|options, (tp_init, vapor_molefracs)| {
+            Self::bubble_point(
+                eos,
+                temperature_or_pressure,
+                liquid_molefracs,
+                *tp_init,
+                vapor_molefracs.as_ref(),
+                (inner_options.clone(), options),
+            )
+        })
+    }
+
+    /// Calculate a dew point with the options and initial values of a
+    /// [RetryPolicy].
+    ///
+    /// The initial values of every attempt consist of the initial temperature
+    /// or pressure and the initial composition of the liquid phase. The options
+    /// of the policy are used for the outer loop, `inner_options` are used for
+    /// the inner loop of every attempt.
+    pub fn dew_point_with_retry(
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+        vapor_molefracs: &Array1<f64>,
+        inner_options: SolverOptions,
+        policy: &RetryPolicy<BubbleDewInitialization<U>>,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        policy.run(|options, (tp_init, liquid_molefracs)| {
+            Self::dew_point(
+                eos,
+                temperature_or_pressure,
+                vapor_molefracs,
+                *tp_init,
+                liquid_molefracs.as_ref(),
+                (inner_options.clone(), options),
+            )
+        })
+    }
+
+    /// Calculate phase equilibria for arrays of temperatures
+    /// or pressures and compositions of the liquid phase.
+    ///
+    /// Each row of `liquid_molefracs` corresponds to one entry of
+    /// `temperature_or_pressure`. A single row is used for all points.
+    /// The result of every converged point is used as initial value for
+    /// the next point. Points that do not converge are returned as errors
+    /// without aborting the calculation of the remaining points.
+    pub fn bubble_points(
+        eos: &Rc<E>,
+        temperature_or_pressure: &QuantityArray1<U>,
+        liquid_molefracs: &Array2<f64>,
+        options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Vec<EosResult<Self>>>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        Self::bubble_dew_points(
+            eos,
+            temperature_or_pressure,
+            liquid_molefracs,
+            true,
+            options,
+        )
+    }
+
+    /// Calculate phase equilibria for arrays of temperatures
+    /// or pressures and compositions of the vapor phase.
+    ///
+    /// Each row of `vapor_molefracs` corresponds to one entry of
+    /// `temperature_or_pressure`. A single row is used for all points.
+    /// The result of every converged point is used as initial value for
+    /// the next point. Points that do not converge are returned as errors
+    /// without aborting the calculation of the remaining points.
+    pub fn dew_points(
+        eos: &Rc<E>,
+        temperature_or_pressure: &QuantityArray1<U>,
+        vapor_molefracs: &Array2<f64>,
+        options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Vec<EosResult<Self>>>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        Self::bubble_dew_points(
+            eos,
+            temperature_or_pressure,
+            vapor_molefracs,
+            false,
+            options,
+        )
+    }
+
+    fn bubble_dew_points(
+        eos: &Rc<E>,
+        temperature_or_pressure: &QuantityArray1<U>,
+        molefracs: &Array2<f64>,
+        bubble: bool,
+        options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Vec<EosResult<Self>>>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let n = temperature_or_pressure.len();
+        if molefracs.nrows() != n && molefracs.nrows() != 1 {
+            return Err(EosError::UndeterminedState(format!(
+                "{} compositions given for {} points",
+                molefracs.nrows(),
+                n
+            )));
+        }
+
+        let mut tp_init = None;
+        let mut molefracs_init: Option<Array1<f64>> = None;
+        let mut vle = Vec::with_capacity(n);
+        for i in 0..n {
+            let tp_spec = TPSpec::try_from(temperature_or_pressure.get(i))?;
+            let molefracs_spec = molefracs.row(if molefracs.nrows() == 1 { 0 } else { i });
+            let res = Self::bubble_dew_point_with_options(
+                eos,
+                tp_spec,
+                tp_init,
+                &molefracs_spec.to_owned(),
+                molefracs_init.as_ref(),
+                bubble,
+                options.clone(),
+            );
+            if let Ok(v) = &res {
+                let (state, other) = if bubble {
+                    (v.vapor(), v.liquid())
+                } else {
+                    (v.liquid(), v.vapor())
+                };
+                tp_init = Some(match tp_spec {
+                    TPSpec::Temperature(_) => other.pressure(Contributions::Total),
+                    TPSpec::Pressure(_) => other.temperature,
+                });
+                molefracs_init = Some(state.molefracs.clone());
+            }
+            vle.push(res);
+        }
+        Ok(vle)
+    }
+
+    /// Calculate the bubble point pressure and the composition of the vapor
+    /// phase for a given temperature and composition of the liquid phase.
+    ///
+    /// In contrast to [PhaseEquilibrium::bubble_point], the pressure and the vapor
+    /// composition are determined by successive substitution, which only requires
+    /// fugacity coefficients and no higher order derivatives, and no phase
+    /// equilibrium is constructed. If the iteration fails or converges to the trivial
+    /// solution, the result of [PhaseEquilibrium::bubble_point] is returned instead,
+    /// for which the options are used for both the inner and the outer loop.
+    pub fn bubble_point_py(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        liquid_molefracs: &Array1<f64>,
+        p_init: Option<QuantityScalar<U>>,
+        options: SolverOptions,
+    ) -> EosResult<(QuantityScalar<U>, Array1<f64>)>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        Self::successive_substitution_p(eos, temperature, liquid_molefracs, p_init, true, options)
+    }
+
+    /// Calculate the dew point pressure and the composition of the liquid
+    /// phase for a given temperature and composition of the vapor phase.
+    ///
+    /// The dew point analog of [PhaseEquilibrium::bubble_point_py].
+    pub fn dew_point_px(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        vapor_molefracs: &Array1<f64>,
+        p_init: Option<QuantityScalar<U>>,
+        options: SolverOptions,
+    ) -> EosResult<(QuantityScalar<U>, Array1<f64>)>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        Self::successive_substitution_p(eos, temperature, vapor_molefracs, p_init, false, options)
+    }
+
+    /// Calculate the phase equilibrium of a binary mixture for a given
+    /// temperature and pressure, starting from the composition of the liquid phase.
+    ///
+    /// The compositions of both phases are determined by successive substitution.
+    /// If multiple phase equilibria exist at the given temperature and pressure
+    /// (e.g., on both sides of an azeotrope), the initial liquid composition
+    /// determines which one is found. Defaults to an equimolar liquid.
+    pub fn bubble_point_tp(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        liquid_molefracs_init: Option<&Array1<f64>>,
+        options: SolverOptions,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        successive_substitution_tp(
+            eos,
+            temperature,
+            pressure,
+            liquid_molefracs_init,
+            true,
+            options,
+        )
+    }
+
+    /// Calculate the phase equilibrium of a binary mixture for a given
+    /// temperature and pressure, starting from the composition of the vapor phase.
+    ///
+    /// The dew point analog of [PhaseEquilibrium::bubble_point_tp].
+    pub fn dew_point_tp(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        vapor_molefracs_init: Option<&Array1<f64>>,
+        options: SolverOptions,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        successive_substitution_tp(
+            eos,
+            temperature,
+            pressure,
+            vapor_molefracs_init,
+            false,
+            options,
+        )
+    }
+
+    fn successive_substitution_p(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        molefracs_spec: &Array1<f64>,
+        p_init: Option<QuantityScalar<U>>,
+        bubble: bool,
+        options: SolverOptions,
+    ) -> EosResult<(QuantityScalar<U>, Array1<f64>)>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        if molefracs_spec.len() != eos.components() {
+            return Err(EosError::IncompatibleComponents(
+                eos.components(),
+                molefracs_spec.len(),
+            ));
+        }
+        let molefracs_spec = validate_molefracs(molefracs_spec, MolefracsNormalization::Normalize)?;
+        let p_start = p_init.unwrap_or_else(|| P_START * U::reference_pressure());
+
+        // successive substitution first, then the full bubble/dew point calculation
+        let policy = RetryPolicy::new()
+            .attempt(options.clone(), true)
+            .attempt(options.clone(), false);
+        policy.run(|options, &substitution| {
+            if substitution {
+                successive_substitution_p(
+                    eos,
+                    temperature,
+                    &molefracs_spec,
+                    p_start,
+                    bubble,
+                    options,
+                )
+            } else {
+                let vle = Self::bubble_dew_point_with_options(
+                    eos,
+                    TPSpec::Temperature(temperature),
+                    p_init,
+                    &molefracs_spec,
+                    None,
+                    bubble,
+                    (options.clone(), options),
+                )?;
+                let other = if bubble { vle.vapor() } else { vle.liquid() };
+                Ok((
+                    other.pressure(Contributions::Total),
+                    other.molefracs.clone(),
+                ))
+            }
+        })
+    }
+
     pub(super) fn bubble_dew_point_with_options(
         eos: &Rc<E>,
         tp_spec: TPSpec<U>,
@@ -128,17 +439,194 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
     where
         QuantityScalar<U>: std::fmt::Display,
     {
+        if molefracs_spec.len() != eos.components() {
+            return Err(EosError::IncompatibleComponents(
+                eos.components(),
+                molefracs_spec.len(),
+            ));
+        }
+        let molefracs_spec =
+            &validate_molefracs(molefracs_spec, MolefracsNormalization::Normalize)?;
+        let molefracs_init = molefracs_init
+            .map(|x| validate_molefracs(x, MolefracsNormalization::Normalize))
+            .transpose()?;
+
         let tp_init = tp_init.unwrap_or_else(|| tp_spec.starting_value());
         let (var, t, p) = tp_spec.temperature_pressure(tp_init);
         let (state1, state2) = if bubble {
-            starting_x2_bubble(eos, t, p, molefracs_spec, molefracs_init)
+            starting_x2_bubble(eos, t, p, molefracs_spec, molefracs_init.as_ref())
         } else {
-            starting_x2_dew(eos, t, p, molefracs_spec, molefracs_init)
+            starting_x2_dew(eos, t, p, molefracs_spec, molefracs_init.as_ref())
         }?;
         bubble_dew(tp_spec, var, state1, state2, options)
     }
 }
 
+/// Successive substitution of the pressure and the composition of the second
+/// phase at constant temperature.
+fn successive_substitution_p<U: EosUnit, E: EquationOfState>(
+    eos: &Rc<E>,
+    temperature: QuantityScalar<U>,
+    molefracs_spec: &Array1<f64>,
+    p_init: QuantityScalar<U>,
+    bubble: bool,
+    options: SolverOptions,
+) -> EosResult<(QuantityScalar<U>, Array1<f64>)>
+where
+    QuantityScalar<U>: std::fmt::Display,
+{
+    let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_SUCCESSIVE_SUBSTITUTION, TOL_OUTER);
+    let mut pressure = p_init;
+    let (state1, state2) = if bubble {
+        starting_x2_bubble(eos, temperature, pressure, molefracs_spec, None)
+    } else {
+        starting_x2_dew(eos, temperature, pressure, molefracs_spec, None)
+    }?;
+
+    // only the densities and fugacity coefficients are updated in the iteration
+    let (mut density1, mut ln_phi1) = (state1.density, state1.ln_phi());
+    let (mut density2, mut ln_phi2) = (state2.density, state2.ln_phi());
+    let mut molefracs2 = state2.molefracs;
+
+    log_iter!(
+        verbosity,
+        " iter |    residual    |      pressure      | molefracs second phase"
+    );
+    log_iter!(verbosity, "{:-<80}", "");
+    for i in 1..=max_iter {
+        // bubble point: y = K x, dew point: x = y / K with K = phi_l / phi_v
+        let k = (&ln_phi1 - &ln_phi2).mapv(f64::exp);
+        let sum = (molefracs_spec * &k).sum();
+        let x2 = molefracs_spec * &k / sum;
+        let res = (sum - 1.0).abs() + (&x2 - &molefracs2).mapv(f64::abs).sum();
+        pressure = if bubble {
+            pressure * sum
+        } else {
+            pressure / sum
+        };
+        log_iter!(
+            verbosity,
+            " {:4} | {:14.8e} | {:12.8} | {:.8}",
+            i,
+            res,
+            pressure,
+            x2
+        );
+        options.notify("successive_substitution", i, res, None)?;
+
+        if res < tol {
+            let trivial = ((density1 - density2) / density1).into_value()?.abs() < 1e-3;
+            if trivial {
+                return Err(EosError::TrivialSolution);
+            }
+            log_result!(
+                verbosity,
+                "Successive substitution converged in {} step(s)\n",
+                i
+            );
+            return Ok((pressure, x2));
+        }
+
+        let (rho1, phi1) = density_ln_phi(eos, temperature, pressure, molefracs_spec, density1)?;
+        let (rho2, phi2) = density_ln_phi(eos, temperature, pressure, &x2, density2)?;
+        density1 = rho1;
+        ln_phi1 = phi1;
+        density2 = rho2;
+        ln_phi2 = phi2;
+        molefracs2 = x2;
+    }
+    Err(EosError::NotConverged(String::from(
+        "successive substitution",
+    )))
+}
+
+/// Successive substitution of the compositions of both phases of a binary
+/// mixture at constant temperature and pressure.
+fn successive_substitution_tp<U: EosUnit, E: EquationOfState>(
+    eos: &Rc<E>,
+    temperature: QuantityScalar<U>,
+    pressure: QuantityScalar<U>,
+    molefracs_init: Option<&Array1<f64>>,
+    bubble: bool,
+    options: SolverOptions,
+) -> EosResult<PhaseEquilibrium<U, E, 2>>
+where
+    QuantityScalar<U>: std::fmt::Display,
+{
+    if eos.components() != 2 {
+        return Err(EosError::IncompatibleComponents(eos.components(), 2));
+    }
+    let molefracs_init = match molefracs_init {
+        Some(x) => validate_molefracs(x, MolefracsNormalization::Normalize)?,
+        None => arr1(&[0.5, 0.5]),
+    };
+    let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_SUCCESSIVE_SUBSTITUTION, TOL_OUTER);
+    let (mut liquid, mut vapor) = if bubble {
+        starting_x2_bubble(eos, temperature, pressure, &molefracs_init, None)?
+    } else {
+        let (vapor, liquid) = starting_x2_dew(eos, temperature, pressure, &molefracs_init, None)?;
+        (liquid, vapor)
+    };
+
+    log_iter!(
+        verbosity,
+        " iter |    residual    | molefracs liquid | molefracs vapor"
+    );
+    log_iter!(verbosity, "{:-<80}", "");
+    for i in 1..=max_iter {
+        // for a binary mixture, x1 + x2 = 1 and K1 x1 + K2 x2 = 1 determine
+        // the liquid composition for given K-values
+        let k = (liquid.ln_phi() - vapor.ln_phi()).mapv(f64::exp);
+        let x1 = (1.0 - k[1]) / (k[0] - k[1]);
+        let y1 = k[0] * x1;
+        if !(x1 > 0.0 && x1 < 1.0 && y1 > 0.0 && y1 < 1.0) {
+            return Err(EosError::IterationFailed(String::from(
+                "successive substitution (T, p)",
+            )));
+        }
+        let x = arr1(&[x1, 1.0 - x1]);
+        let y = arr1(&[y1, 1.0 - y1]);
+        let res = (&x - &liquid.molefracs).mapv(f64::abs).sum()
+            + (&y - &vapor.molefracs).mapv(f64::abs).sum();
+        log_iter!(verbosity, " {:4} | {:14.8e} | {:.8} | {:.8}", i, res, x, y);
+        options.notify("successive_substitution_tp", i, res, None)?;
+
+        liquid = State::new_npt(
+            eos,
+            temperature,
+            pressure,
+            &(x * U::reference_moles()),
+            InitialDensity(liquid.density),
+        )?;
+        vapor = State::new_npt(
+            eos,
+            temperature,
+            pressure,
+            &(y * U::reference_moles()),
+            InitialDensity(vapor.density),
+        )?;
+
+        if res < tol {
+            let trivial = ((liquid.density - vapor.density) / liquid.density)
+                .into_value()?
+                .abs()
+                < 1e-3;
+            if trivial {
+                return Err(EosError::TrivialSolution);
+            }
+            log_result!(
+                verbosity,
+                "Successive substitution (T, p) converged in {} step(s)\n",
+                i
+            );
+            return Ok(PhaseEquilibrium::from_states(vapor, liquid));
+        }
+    }
+    Err(EosError::NotConverged(String::from(
+        "successive substitution (T, p)",
+    )))
+}
+
 #[allow(clippy::type_complexity)]
 fn starting_x2_bubble<U: EosUnit, E: EquationOfState>(
     eos: &Rc<E>,
@@ -207,7 +695,7 @@ fn starting_x2_dew<U: EosUnit, E: EquationOfState>(
     Ok((vapor_state, liquid_state))
 }
 
-fn bubble_dew<U: EosUnit, E: EquationOfState>(
+pub(super) fn bubble_dew<U: EosUnit, E: EquationOfState>(
     tp_spec: TPSpec<U>,
     mut var_tp: TPSpec<U>,
     mut state1: State<U, E>,
@@ -222,6 +710,8 @@ where
     // initialize variables
     let mut err_out = 1.0;
     let mut k_out = 0;
+    let mut jacobian_inner = NewtonJacobian::new(&options_inner);
+    let mut jacobian_outer = NewtonJacobian::new(&options_outer);
 
     // If the starting values are insufficient find better ones
     if !promising_values(&state1, &state2) {
@@ -239,36 +729,49 @@ where
     // Outer loop for finding x2
     for ko in 0..options_outer.max_iter.unwrap_or(MAX_ITER_OUTER) {
         // Iso-Fugacity equation
-        err_out = if err_out > NEWTON_TOL {
+        let step_size = if err_out > NEWTON_TOL {
             // Inner loop for finding T or p
-            for _ in 0..options_inner.max_iter.unwrap_or(MAX_ITER_INNER) {
+            jacobian_inner.reset();
+            jacobian_outer.reset();
+            for ki in 0..options_inner.max_iter.unwrap_or(MAX_ITER_INNER) {
                 // Newton step
-                if adjust_t_p(
+                let err_in = adjust_t_p(
                     &mut var_tp,
                     &mut state1,
                     &mut state2,
+                    &mut jacobian_inner,
                     options_inner.verbosity,
-                )? < options_inner.tol.unwrap_or(TOL_INNER)
-                {
+                )?;
+                options_inner.notify(
+                    "bubble_dew_inner",
+                    ki + 1,
+                    err_in,
+                    Some(jacobian_inner.step_size),
+                )?;
+                if err_in < options_inner.tol.unwrap_or(TOL_INNER) {
                     break;
                 }
             }
-            adjust_x2(&state1, &mut state2, options_outer.verbosity)
+            err_out = adjust_x2(&state1, &mut state2, options_outer.verbosity)?;
+            None
         } else {
-            newton_step(
+            err_out = newton_step(
                 tp_spec,
                 &mut var_tp,
                 &mut state1,
                 &mut state2,
+                &mut jacobian_outer,
                 options_outer.verbosity,
-            )
-        }?;
+            )?;
+            Some(jacobian_outer.step_size)
+        };
 
         // if a trivial solution is encountered, reinitialize T or p
         if PhaseEquilibrium::is_trivial_solution(&state1, &state2) {
             log_iter!(options_outer.verbosity, "Trivial solution encountered!");
             // find_starting_values(iterate_t, bubble, &mut itervars)?;
         }
+        options_outer.notify("bubble_dew", ko + 1, err_out, step_size)?;
 
         if err_out < options_outer.tol.unwrap_or(TOL_OUTER) {
             k_out = ko + 1;
@@ -279,8 +782,9 @@ where
     if err_out < options_outer.tol.unwrap_or(TOL_OUTER) {
         log_result!(
             options_outer.verbosity,
-            "Bubble/dew point: calculation converged in {} step(s)\n",
-            k_out
+            "Bubble/dew point: calculation converged in {} step(s) with {} Jacobian evaluation(s)\n",
+            k_out,
+            jacobian_inner.evaluations + jacobian_outer.evaluations
         );
         Ok(PhaseEquilibrium::from_states(state1, state2))
     } else {
@@ -293,11 +797,14 @@ fn adjust_t_p<U: EosUnit, E: EquationOfState>(
     var: &mut TPSpec<U>,
     state1: &mut State<U, E>,
     state2: &mut State<U, E>,
+    jacobian: &mut NewtonJacobian,
     verbosity: Verbosity,
 ) -> EosResult<f64>
 where
     QuantityScalar<U>: std::fmt::Display,
 {
+    check_pressures(state1, state2)?;
+
     // calculate K = phi_1/phi_2 = x_2/x_1
     let ln_phi_1 = state1.ln_phi();
     let ln_phi_2 = state2.ln_phi();
@@ -309,10 +816,16 @@ where
     match var {
         TPSpec::Temperature(t) => {
             // Derivative w.r.t. temperature
-            let ln_phi_1_dt = state1.dln_phi_dt();
-            let ln_phi_2_dt = state2.dln_phi_dt();
-            let df = ((ln_phi_1_dt - ln_phi_2_dt) * &state1.molefracs * &k).sum();
-            let mut tstep = -f / df;
+            let x = arr1(&[t.to_reduced(U::reference_temperature())?]);
+            let dx = jacobian.step(&x, &arr1(&[f]), || {
+                let ln_phi_1_dt = state1.dln_phi_dt();
+                let ln_phi_2_dt = state2.dln_phi_dt();
+                let df = ((ln_phi_1_dt - ln_phi_2_dt) * &state1.molefracs * &k)
+                    .sum()
+                    .to_reduced(U::reference_temperature().powi(-1))?;
+                Ok(arr2(&[[df]]))
+            })?;
+            let mut tstep = -dx[0] * U::reference_temperature();
 
             // catch too big t-steps
             if tstep < -MAX_TSTEP * U::reference_temperature() {
@@ -326,25 +839,34 @@ where
         }
         TPSpec::Pressure(p) => {
             // Derivative w.r.t. ln(pressure)
-            let ln_phi_1_dp = state1.dln_phi_dp();
-            let ln_phi_2_dp = state2.dln_phi_dp();
-            let df = ((ln_phi_1_dp - ln_phi_2_dp) * *p * &state1.molefracs * &k)
-                .sum()
-                .into_value()?;
-            let mut lnpstep = -f / df;
-
+            let x = arr1(&[p.to_reduced(U::reference_pressure())?.ln()]);
+            let dx = jacobian.step(&x, &arr1(&[f]), || {
+                let ln_phi_1_dp = state1.dln_phi_dp();
+                let ln_phi_2_dp = state2.dln_phi_dp();
+                let df = ((ln_phi_1_dp - ln_phi_2_dp) * *p * &state1.molefracs * &k)
+                    .sum()
+                    .into_value()?;
+                Ok(arr2(&[[df]]))
+            })?;
             // catch too big p-steps
-            if lnpstep < -MAX_LNPSTEP {
-                lnpstep = -MAX_LNPSTEP;
-            } else if lnpstep > MAX_LNPSTEP {
-                lnpstep = MAX_LNPSTEP;
-            }
+            let lnpstep = (-dx[0]).clamp(-MAX_LNPSTEP, MAX_LNPSTEP);
 
             // Update p
             *p = *p * lnpstep.exp();
         }
     };
 
+    // stop the iteration for invalid temperatures or pressures
+    let valid = match var {
+        TPSpec::Temperature(t) => *t > 0.0 * U::reference_temperature(),
+        TPSpec::Pressure(p) => *p > 0.0 * U::reference_pressure(),
+    };
+    if !valid {
+        return Err(EosError::IterationFailed(String::from(
+            "bubble-dew-iteration",
+        )));
+    }
+
     // update states with new temperature/pressure
     adjust_states(&*var, state1, state2, None)?;
 
@@ -393,6 +915,7 @@ fn adjust_x2<U: EosUnit, E: EquationOfState>(
     state2: &mut State<U, E>,
     verbosity: Verbosity,
 ) -> EosResult<f64> {
+    check_pressures(state1, state2)?;
     let x1 = &state1.molefracs;
     let ln_phi_1 = state1.ln_phi();
     let ln_phi_2 = state2.ln_phi();
@@ -415,14 +938,15 @@ fn newton_step<U: EosUnit, E: EquationOfState>(
     var: &mut TPSpec<U>,
     state1: &mut State<U, E>,
     state2: &mut State<U, E>,
+    jacobian: &mut NewtonJacobian,
     verbosity: Verbosity,
 ) -> EosResult<f64>
 where
     QuantityScalar<U>: std::fmt::Display,
 {
     match tp_spec {
-        TPSpec::Temperature(_) => newton_step_t(var, state1, state2, verbosity),
-        TPSpec::Pressure(p) => newton_step_p(p, var, state1, state2, verbosity),
+        TPSpec::Temperature(_) => newton_step_t(var, state1, state2, jacobian, verbosity),
+        TPSpec::Pressure(p) => newton_step_p(p, var, state1, state2, jacobian, verbosity),
     }
 }
 
@@ -430,21 +954,12 @@ fn newton_step_t<U: EosUnit, E: EquationOfState>(
     pressure: &mut TPSpec<U>,
     state1: &mut State<U, E>,
     state2: &mut State<U, E>,
+    jacobian: &mut NewtonJacobian,
     verbosity: Verbosity,
 ) -> EosResult<f64>
 where
     QuantityScalar<U>: std::fmt::Display,
 {
-    let dmu_drho_1 = (state1.dmu_dni(Contributions::Total) * state1.volume)
-        .to_reduced(U::reference_molar_energy() / U::reference_density())?
-        .dot(&state1.molefracs);
-    let dmu_drho_2 = (state2.dmu_dni(Contributions::Total) * state2.volume)
-        .to_reduced(U::reference_molar_energy() / U::reference_density())?;
-    let dp_drho_1 = (state1.dp_dni(Contributions::Total) * state1.volume)
-        .to_reduced(U::reference_pressure() / U::reference_density())?
-        .dot(&state1.molefracs);
-    let dp_drho_2 = (state2.dp_dni(Contributions::Total) * state2.volume)
-        .to_reduced(U::reference_pressure() / U::reference_density())?;
     let mu_1 = state1
         .chemical_potential(Contributions::Total)
         .to_reduced(U::reference_molar_energy())?;
@@ -462,19 +977,33 @@ where
     let res = concatenate![Axis(0), mu_1 - &mu_2, arr1(&[p_1 - p_2])];
     let error = norm(&res);
 
-    // calculate Jacobian
-    let jacobian = concatenate![
-        Axis(1),
-        concatenate![Axis(0), -dmu_drho_2, -dp_drho_2.insert_axis(Axis(0))],
-        concatenate![
-            Axis(0),
-            dmu_drho_1.insert_axis(Axis(1)),
-            arr2(&[[dp_drho_1]])
-        ]
-    ];
-
     // calculate Newton step
-    let dx = LU::new(jacobian)?.solve(&res);
+    let x = concatenate![
+        Axis(0),
+        state2.partial_density.to_reduced(U::reference_density())?,
+        arr1(&[state1.density.to_reduced(U::reference_density())?])
+    ];
+    let dx = jacobian.step(&x, &res, || {
+        let dmu_drho_1 = (state1.dmu_dni(Contributions::Total) * state1.volume)
+            .to_reduced(U::reference_molar_energy() / U::reference_density())?
+            .dot(&state1.molefracs);
+        let dmu_drho_2 = (state2.dmu_dni(Contributions::Total) * state2.volume)
+            .to_reduced(U::reference_molar_energy() / U::reference_density())?;
+        let dp_drho_1 = (state1.dp_dni(Contributions::Total) * state1.volume)
+            .to_reduced(U::reference_pressure() / U::reference_density())?
+            .dot(&state1.molefracs);
+        let dp_drho_2 = (state2.dp_dni(Contributions::Total) * state2.volume)
+            .to_reduced(U::reference_pressure() / U::reference_density())?;
+        Ok(concatenate![
+            Axis(1),
+            concatenate![Axis(0), -dmu_drho_2, -dp_drho_2.insert_axis(Axis(0))],
+            concatenate![
+                Axis(0),
+                dmu_drho_1.insert_axis(Axis(1)),
+                arr2(&[[dp_drho_1]])
+            ]
+        ])
+    })?;
 
     // apply Newton step
     let rho_l1 = state1.density - dx[dx.len() - 1] * U::reference_density();
@@ -491,6 +1020,7 @@ where
         .temperature(state2.temperature)
         .partial_density(&rho_l2)
         .build()?;
+    check_pressures(state1, state2)?;
     *pressure = TPSpec::Pressure(state1.pressure(Contributions::Total));
     log_iter!(
         verbosity,
@@ -508,33 +1038,12 @@ fn newton_step_p<U: EosUnit, E: EquationOfState>(
     temperature: &mut TPSpec<U>,
     state1: &mut State<U, E>,
     state2: &mut State<U, E>,
+    jacobian: &mut NewtonJacobian,
     verbosity: Verbosity,
 ) -> EosResult<f64>
 where
     QuantityScalar<U>: std::fmt::Display,
 {
-    let dmu_drho_1 = (state1.dmu_dni(Contributions::Total) * state1.volume)
-        .to_reduced(U::reference_molar_energy() / U::reference_density())?
-        .dot(&state1.molefracs);
-    let dmu_drho_2 = (state2.dmu_dni(Contributions::Total) * state2.volume)
-        .to_reduced(U::reference_molar_energy() / U::reference_density())?;
-    let dmu_dt_1 = state1
-        .dmu_dt(Contributions::Total)
-        .to_reduced(U::reference_molar_energy() / U::reference_temperature())?;
-    let dmu_dt_2 = state2
-        .dmu_dt(Contributions::Total)
-        .to_reduced(U::reference_molar_energy() / U::reference_temperature())?;
-    let dp_drho_1 = (state1.dp_dni(Contributions::Total) * state1.volume)
-        .to_reduced(U::reference_pressure() / U::reference_density())?
-        .dot(&state1.molefracs);
-    let dp_dt_1 = state1
-        .dp_dt(Contributions::Total)
-        .to_reduced(U::reference_pressure() / U::reference_temperature())?;
-    let dp_dt_2 = state2
-        .dp_dt(Contributions::Total)
-        .to_reduced(U::reference_pressure() / U::reference_temperature())?;
-    let dp_drho_2 = (state2.dp_dni(Contributions::Total) * state2.volume)
-        .to_reduced(U::reference_pressure() / U::reference_density())?;
     let mu_1 = state1
         .chemical_potential(Contributions::Total)
         .to_reduced(U::reference_molar_energy())?;
@@ -553,29 +1062,58 @@ where
     let res = concatenate![Axis(0), mu_1 - &mu_2, arr1(&[p_1 - p]), arr1(&[p_2 - p])];
     let error = norm(&res);
 
-    // calculate Jacobian
-    let jacobian = concatenate![
-        Axis(1),
-        concatenate![
-            Axis(0),
-            -dmu_drho_2,
-            Array2::zeros((1, res.len() - 2)),
-            dp_drho_2.insert_axis(Axis(0))
-        ],
-        concatenate![
-            Axis(0),
-            dmu_drho_1.insert_axis(Axis(1)),
-            arr2(&[[dp_drho_1], [0.0]])
-        ],
-        concatenate![
-            Axis(0),
-            (dmu_dt_1 - dmu_dt_2).insert_axis(Axis(1)),
-            arr2(&[[dp_dt_1], [dp_dt_2]])
-        ]
-    ];
-
     // calculate Newton step
-    let dx = LU::new(jacobian)?.solve(&res);
+    let x = concatenate![
+        Axis(0),
+        state2.partial_density.to_reduced(U::reference_density())?,
+        arr1(&[
+            state1.density.to_reduced(U::reference_density())?,
+            state1.temperature.to_reduced(U::reference_temperature())?
+        ])
+    ];
+    let dx = jacobian.step(&x, &res, || {
+        let dmu_drho_1 = (state1.dmu_dni(Contributions::Total) * state1.volume)
+            .to_reduced(U::reference_molar_energy() / U::reference_density())?
+            .dot(&state1.molefracs);
+        let dmu_drho_2 = (state2.dmu_dni(Contributions::Total) * state2.volume)
+            .to_reduced(U::reference_molar_energy() / U::reference_density())?;
+        let dmu_dt_1 = state1
+            .dmu_dt(Contributions::Total)
+            .to_reduced(U::reference_molar_energy() / U::reference_temperature())?;
+        let dmu_dt_2 = state2
+            .dmu_dt(Contributions::Total)
+            .to_reduced(U::reference_molar_energy() / U::reference_temperature())?;
+        let dp_drho_1 = (state1.dp_dni(Contributions::Total) * state1.volume)
+            .to_reduced(U::reference_pressure() / U::reference_density())?
+            .dot(&state1.molefracs);
+        let dp_dt_1 = state1
+            .dp_dt(Contributions::Total)
+            .to_reduced(U::reference_pressure() / U::reference_temperature())?;
+        let dp_dt_2 = state2
+            .dp_dt(Contributions::Total)
+            .to_reduced(U::reference_pressure() / U::reference_temperature())?;
+        let dp_drho_2 = (state2.dp_dni(Contributions::Total) * state2.volume)
+            .to_reduced(U::reference_pressure() / U::reference_density())?;
+        Ok(concatenate![
+            Axis(1),
+            concatenate![
+                Axis(0),
+                -dmu_drho_2,
+                Array2::zeros((1, res.len() - 2)),
+                dp_drho_2.insert_axis(Axis(0))
+            ],
+            concatenate![
+                Axis(0),
+                dmu_drho_1.insert_axis(Axis(1)),
+                arr2(&[[dp_drho_1], [0.0]])
+            ],
+            concatenate![
+                Axis(0),
+                (dmu_dt_1 - dmu_dt_2).insert_axis(Axis(1)),
+                arr2(&[[dp_dt_1], [dp_dt_2]])
+            ]
+        ])
+    })?;
 
     // apply Newton step
     let rho_l1 = state1.density - dx[dx.len() - 2] * U::reference_density();
@@ -605,11 +1143,115 @@ where
     Ok(error)
 }
 
+/// Jacobian that is kept between the Newton steps of the bubble and
+/// dew point iteration according to the [JacobianUpdate] option.
+struct NewtonJacobian {
+    update: JacobianUpdate,
+    damping: f64,
+    inverse: Option<Array2<f64>>,
+    previous: Option<(Array1<f64>, Array1<f64>)>,
+    evaluations: usize,
+    step_size: f64,
+}
+
+impl NewtonJacobian {
+    fn new(options: &SolverOptions) -> Self {
+        Self {
+            update: options.jacobian,
+            damping: options.damping.unwrap_or(1.0),
+            inverse: None,
+            previous: None,
+            evaluations: 0,
+            step_size: 0.0,
+        }
+    }
+
+    /// Forget the last iterate, e.g., if the system of equations changed.
+    fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// Calculate the (damped) Newton step for the variables `x` and the
+    /// residual `res`. The Jacobian is only evaluated if required.
+    fn step<F>(&mut self, x: &Array1<f64>, res: &Array1<f64>, jacobian: F) -> EosResult<Array1<f64>>
+    where
+        F: FnOnce() -> EosResult<Array2<f64>>,
+    {
+        let dx = self.solve(x, res, jacobian)? * self.damping;
+        self.step_size = norm(&dx);
+        Ok(dx)
+    }
+
+    /// Solve J dx = res for the variables `x` and the residual `res`.
+    fn solve<F>(
+        &mut self,
+        x: &Array1<f64>,
+        res: &Array1<f64>,
+        jacobian: F,
+    ) -> EosResult<Array1<f64>>
+    where
+        F: FnOnce() -> EosResult<Array2<f64>>,
+    {
+        if self.update == JacobianUpdate::Exact {
+            self.evaluations += 1;
+            return Ok(LU::new(jacobian()?)?.solve(res));
+        }
+
+        let mut inverse = self.inverse.take();
+        if let (Some(h), Some((x_old, res_old))) = (inverse.as_mut(), self.previous.take()) {
+            if norm(res) > MAX_RESIDUAL_RATIO * norm(&res_old) {
+                // insufficient convergence: evaluate the Jacobian again
+                inverse = None;
+            } else if self.update == JacobianUpdate::Broyden {
+                // rank-one update of the inverse Jacobian
+                let s = x - &x_old;
+                let hy = h.dot(&(res - &res_old));
+                let denominator = s.dot(&hy);
+                if denominator.abs() > f64::EPSILON * norm(&s) * norm(&hy) {
+                    let sh = s.dot(&*h);
+                    *h += &((s - hy).insert_axis(Axis(1)) * sh.insert_axis(Axis(0)) / denominator);
+                }
+            }
+        }
+        let inverse = match inverse {
+            Some(h) => h,
+            None => {
+                self.evaluations += 1;
+                LU::new(jacobian()?)?.inverse()
+            }
+        };
+        let dx = inverse.dot(res);
+        self.inverse = Some(inverse);
+        self.previous = Some((x.clone(), res.clone()));
+        Ok(dx)
+    }
+}
+
+/// Fugacity coefficients are only defined for states with positive pressures,
+/// which is not guaranteed during the iteration for asymmetric mixtures.
+fn check_pressures<U: EosUnit, E: EquationOfState>(
+    state1: &State<U, E>,
+    state2: &State<U, E>,
+) -> EosResult<()> {
+    let p_min = 0.0 * U::reference_pressure();
+    if state1.pressure(Contributions::Total) > p_min
+        && state2.pressure(Contributions::Total) > p_min
+    {
+        Ok(())
+    } else {
+        Err(EosError::IterationFailed(String::from(
+            "bubble-dew-iteration",
+        )))
+    }
+}
+
 fn promising_values<U: EosUnit, E: EquationOfState>(
     state1: &State<U, E>,
     state2: &State<U, E>,
 ) -> bool {
-    if PhaseEquilibrium::is_trivial_solution(state1, state2) {
+    if PhaseEquilibrium::is_trivial_solution(state1, state2)
+        || check_pressures(state1, state2).is_err()
+    {
         return false;
     }
 
@@ -617,3 +1259,166 @@ fn promising_values<U: EosUnit, E: EquationOfState>(
     let ln_phi_2 = state2.ln_phi();
     ((&state1.molefracs * &(ln_phi_1 - ln_phi_2).mapv(f64::exp)).sum() - 1.0).abs() < PROMISING_F
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use crate::cubic::PengRobinson;
+    use approx::assert_relative_eq;
+    use quantity::si::*;
+
+    #[test]
+    fn test_bubble_point_py() -> EosResult<()> {
+        let eos = propane_butane();
+        let t = 300.0 * KELVIN;
+        let z = arr1(&[0.3, 0.7]);
+
+        let (p, y) = PhaseEquilibrium::bubble_point_py(&eos, t, &z, None, Default::default())?;
+        let vle = PhaseEquilibrium::bubble_point(&eos, t, &z, None, None, Default::default())?;
+        assert_relative_eq!(
+            p,
+            vle.vapor().pressure(Contributions::Total),
+            max_relative = 1e-8
+        );
+        assert_relative_eq!(y, vle.vapor().molefracs, max_relative = 1e-8);
+
+        let (p, x) = PhaseEquilibrium::dew_point_px(&eos, t, &z, None, Default::default())?;
+        let vle = PhaseEquilibrium::dew_point(&eos, t, &z, None, None, Default::default())?;
+        assert_relative_eq!(
+            p,
+            vle.liquid().pressure(Contributions::Total),
+            max_relative = 1e-8
+        );
+        assert_relative_eq!(x, vle.liquid().molefracs, max_relative = 1e-8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bubble_dew_points() -> EosResult<()> {
+        let eos = propane_butane();
+        let z = arr1(&[0.3, 0.7]);
+
+        // the point above the critical temperature of both components fails
+        let t = arr1(&[280.0, 300.0, 500.0, 320.0]) * KELVIN;
+        let vle = PhaseEquilibrium::bubble_points(
+            &eos,
+            &t,
+            &z.clone().insert_axis(Axis(0)),
+            Default::default(),
+        )?;
+        assert_eq!(vle.len(), 4);
+        assert!(vle[2].is_err());
+        for i in [0, 1, 3] {
+            let reference =
+                PhaseEquilibrium::bubble_point(&eos, t.get(i), &z, None, None, Default::default())?;
+            let vle = vle[i].as_ref().unwrap();
+            assert_relative_eq!(
+                vle.vapor().pressure(Contributions::Total),
+                reference.vapor().pressure(Contributions::Total),
+                max_relative = 1e-8
+            );
+            assert_relative_eq!(vle.liquid().molefracs, z, max_relative = 1e-10);
+        }
+
+        // one composition per point
+        let t = arr1(&[280.0, 320.0]) * KELVIN;
+        let y = arr2(&[[0.3, 0.7], [0.6, 0.4]]);
+        let vle = PhaseEquilibrium::dew_points(&eos, &t, &y, Default::default())?;
+        for (i, vle) in vle.into_iter().enumerate() {
+            let vle = vle?;
+            let reference = PhaseEquilibrium::dew_point(
+                &eos,
+                t.get(i),
+                &y.row(i).to_owned(),
+                None,
+                None,
+                Default::default(),
+            )?;
+            assert_relative_eq!(
+                vle.vapor().pressure(Contributions::Total),
+                reference.vapor().pressure(Contributions::Total),
+                max_relative = 1e-8
+            );
+            assert_relative_eq!(vle.vapor().molefracs, y.row(i), max_relative = 1e-10);
+        }
+
+        // the number of compositions has to match the number of points
+        let t = arr1(&[280.0, 300.0, 320.0]) * KELVIN;
+        assert!(PhaseEquilibrium::dew_points(&eos, &t, &y, Default::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bubble_dew_point_tp() -> EosResult<()> {
+        let eos = propane_butane();
+        let t = 300.0 * KELVIN;
+        let z = arr1(&[0.3, 0.7]);
+        let vle = PhaseEquilibrium::bubble_point(&eos, t, &z, None, None, Default::default())?;
+        let p = vle.vapor().pressure(Contributions::Total);
+
+        let vle_tp = PhaseEquilibrium::bubble_point_tp(&eos, t, p, None, Default::default())?;
+        assert_relative_eq!(vle_tp.liquid().molefracs, z, max_relative = 1e-6);
+        assert_relative_eq!(
+            vle_tp.vapor().molefracs,
+            vle.vapor().molefracs,
+            max_relative = 1e-6
+        );
+        let vle_tp = PhaseEquilibrium::dew_point_tp(&eos, t, p, None, Default::default())?;
+        assert_relative_eq!(vle_tp.liquid().molefracs, z, max_relative = 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_jacobian_update() -> EosResult<()> {
+        let eos = PengRobinson::from_critical_constants(
+            &[369.83, 425.12, 469.7, 507.6],
+            &[4248000.0, 3796000.0, 3370000.0, 3025000.0],
+            &[0.152, 0.2, 0.252, 0.301],
+            &[44.097, 58.123, 72.15, 86.177],
+        )?;
+        let t = 350.0 * KELVIN;
+        let x = arr1(&[0.4, 0.3, 0.2, 0.1]);
+        let p = 5.0 * BAR;
+        let t_init = Some(360.0 * KELVIN);
+        let reference =
+            PhaseEquilibrium::bubble_point(&eos, t, &x, None, None, Default::default())?;
+        let reference_dew =
+            PhaseEquilibrium::dew_point(&eos, p, &x, t_init, None, Default::default())?;
+        for jacobian in [JacobianUpdate::Reuse, JacobianUpdate::Broyden] {
+            let options = SolverOptions::new().jacobian(jacobian);
+            let dew = PhaseEquilibrium::dew_point(
+                &eos,
+                p,
+                &x,
+                t_init,
+                None,
+                (options.clone(), options.clone()),
+            )?;
+            assert_relative_eq!(
+                dew.vapor().temperature,
+                reference_dew.vapor().temperature,
+                max_relative = 1e-8
+            );
+            let vle = PhaseEquilibrium::bubble_point(
+                &eos,
+                t,
+                &x,
+                None,
+                None,
+                (options.clone(), options),
+            )?;
+            assert_relative_eq!(
+                vle.vapor().pressure(Contributions::Total),
+                reference.vapor().pressure(Contributions::Total),
+                max_relative = 1e-8
+            );
+            assert_relative_eq!(
+                vle.vapor().molefracs,
+                reference.vapor().molefracs,
+                max_relative = 1e-8
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use super::PhaseEquilibrium;
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::Contributions;
+use crate::EosUnit;
+use ndarray::Array1;
+use quantity::{QuantityArray1, QuantityScalar};
+
+const TOL_BULK_CONDITIONS: f64 = 1e-6;
+
+/// Temperature, pressure and chemical potentials that are shared by all
+/// phases of a phase equilibrium, together with the partial densities of
+/// the coexisting phases.
+///
+/// The struct contains everything that is required as boundary conditions
+/// of interfacial models, e.g., density functional theory or square gradient
+/// theory. All properties are provided in units of `U` and in reduced units.
+#[derive(Clone, Debug)]
+pub struct BulkConditions<U> {
+    /// Temperature of the phase equilibrium.
+    pub temperature: QuantityScalar<U>,
+    /// Pressure of the phase equilibrium.
+    pub pressure: QuantityScalar<U>,
+    /// Chemical potentials of all components.
+    pub chemical_potential: QuantityArray1<U>,
+    /// Partial densities of every phase.
+    pub partial_densities: Vec<QuantityArray1<U>>,
+    /// Temperature in reduced units.
+    pub reduced_temperature: f64,
+    /// Pressure in reduced units.
+    pub reduced_pressure: f64,
+    /// Chemical potentials in reduced units.
+    pub reduced_chemical_potential: Array1<f64>,
+    /// Partial densities of every phase in reduced units.
+    pub reduced_partial_densities: Vec<Array1<f64>>,
+}
+
+/// # Boundary conditions of interfacial models
+impl<U: EosUnit, E: EquationOfState, const N: usize> PhaseEquilibrium<U, E, N> {
+    /// Return the temperature, pressure and chemical potentials shared by all
+    /// phases together with the partial densities of the phases.
+    ///
+    /// Before the values are returned, the phases are checked for thermal,
+    /// mechanical and chemical equilibrium with a relative tolerance of 1e-6
+    /// (chemical potentials relative to $RT$). Components that are absent in
+    /// one of the phases are excluded from the check of the chemical
+    /// potentials. The values of temperature, pressure and chemical potentials
+    /// are those of the first phase.
+    pub fn bulk_conditions(&self) -> EosResult<BulkConditions<U>> {
+        let states = &self.0;
+        let temperature = states[0].temperature;
+        let pressure = states[0].pressure(Contributions::Total);
+        let chemical_potential = states[0].chemical_potential(Contributions::Total);
+        let rt = U::gas_constant() * temperature;
+
+        for s in states.iter().skip(1) {
+            let dt = ((s.temperature - temperature) / temperature).into_value()?;
+            if dt.abs() > TOL_BULK_CONDITIONS {
+                return Err(EosError::InvalidState(
+                    String::from("bulk_conditions"),
+                    String::from("relative temperature deviation"),
+                    dt,
+                ));
+            }
+            let dp = ((s.pressure(Contributions::Total) - pressure) / pressure).into_value()?;
+            if dp.abs() > TOL_BULK_CONDITIONS {
+                return Err(EosError::InvalidState(
+                    String::from("bulk_conditions"),
+                    String::from("relative pressure deviation"),
+                    dp,
+                ));
+            }
+            let dmu = ((s.chemical_potential(Contributions::Total) - &chemical_potential) / rt)
+                .into_value()?;
+            for (i, &dmu) in dmu.iter().enumerate() {
+                let present = s.molefracs[i] > 0.0 && states[0].molefracs[i] > 0.0;
+                if present && dmu.abs() > TOL_BULK_CONDITIONS {
+                    return Err(EosError::InvalidState(
+                        String::from("bulk_conditions"),
+                        format!("chemical potential deviation of component {} / RT", i),
+                        dmu,
+                    ));
+                }
+            }
+        }
+
+        let partial_densities: Vec<_> = states.iter().map(|s| s.partial_density.clone()).collect();
+        Ok(BulkConditions {
+            reduced_temperature: temperature.to_reduced(U::reference_temperature())?,
+            reduced_pressure: pressure.to_reduced(U::reference_pressure())?,
+            reduced_chemical_potential: chemical_potential
+                .to_reduced(U::reference_molar_energy())?,
+            reduced_partial_densities: partial_densities
+                .iter()
+                .map(|rho| rho.to_reduced(U::reference_density()))
+                .collect::<Result<_, _>>()?,
+            temperature,
+            pressure,
+            chemical_potential,
+            partial_densities,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use crate::phase_equilibria::SolverOptions;
+    use crate::state::{DensityInitialization, State};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    #[test]
+    fn test_bulk_conditions() -> EosResult<()> {
+        let eos = propane_butane();
+        let t = 300.0 * KELVIN;
+        let options = SolverOptions::default();
+        let vle = PhaseEquilibrium::bubble_point(
+            &eos,
+            t,
+            &arr1(&[0.3, 0.7]),
+            None,
+            None,
+            (options.clone(), options),
+        )?;
+        let bulk = vle.bulk_conditions()?;
+        assert_relative_eq!(bulk.temperature, t);
+        assert_relative_eq!(
+            bulk.chemical_potential,
+            vle.liquid().chemical_potential(Contributions::Total),
+            max_relative = 1e-6
+        );
+        assert_relative_eq!(
+            bulk.reduced_pressure * SIUnit::reference_pressure(),
+            bulk.pressure
+        );
+        assert_relative_eq!(
+            bulk.reduced_partial_densities[1].clone() * SIUnit::reference_density(),
+            vle.liquid().partial_density,
+            max_relative = 1e-12
+        );
+
+        // states that are not in equilibrium are rejected
+        let moles = arr1(&[0.3, 0.7]) * MOL;
+        let liquid = State::new_npt(
+            &eos,
+            t,
+            2.0 * vle.vapor().pressure(Contributions::Total),
+            &moles,
+            DensityInitialization::Liquid,
+        )?;
+        let invalid = PhaseEquilibrium::new([vle.vapor().clone(), liquid]);
+        assert!(matches!(
+            invalid.bulk_conditions(),
+            Err(EosError::InvalidState(_, p, _)) if p.contains("pressure")
+        ));
+        Ok(())
+    }
+}
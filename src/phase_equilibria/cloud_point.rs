@@ -0,0 +1,180 @@
+use super::bubble_dew::bubble_dew;
+use super::{PhaseEquilibrium, SolverOptions};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::{
+    validate_molefracs, Contributions, DensityInitialization, MolefracsNormalization, State, TPSpec,
+};
+use crate::EosUnit;
+use ndarray::{Array1, Array2};
+use quantity::QuantityScalar;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+/// # Cloud points
+impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
+    /// Calculate the cloud point of a feed for a given temperature or pressure.
+    ///
+    /// At the cloud point, an incipient (shadow) phase of negligible amount
+    /// is in equilibrium with the feed. If no composition of the shadow phase
+    /// is given, it is initialized from a stability analysis of the feed at `tp_init`,
+    /// which therefore has to lie inside the two-phase region. The temperature or pressure
+    /// and the composition of the shadow phase are then determined from the equality
+    /// of fugacities, while the composition of the feed is fixed. In contrast to bubble
+    /// and dew points, neither phase has to be a vapor, so that cloud points of
+    /// liquid-liquid equilibria can be calculated as well.
+    ///
+    /// In the resulting phase equilibrium, the phase with the composition
+    /// of the feed is the cloud phase, and the other phase is the shadow phase.
+    pub fn cloud_point(
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+        feed_molefracs: &Array1<f64>,
+        tp_init: QuantityScalar<U>,
+        shadow_molefracs: Option<&Array1<f64>>,
+        options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let (feed, shadow) = Self::cloud_point_states(
+            eos,
+            TPSpec::try_from(temperature_or_pressure)?,
+            feed_molefracs,
+            tp_init,
+            shadow_molefracs,
+            options,
+        )?;
+        Ok(Self::from_states(feed, shadow))
+    }
+
+    /// Calculate the cloud points for a given temperature or pressure
+    /// and every feed composition (row) in `feed_molefracs`.
+    ///
+    /// The compositions of the shadow phases form the shadow curve.
+    /// The result of every converged point is used as initial value for
+    /// the next point. Points that do not converge are returned as errors
+    /// without aborting the calculation of the remaining points.
+    pub fn cloud_points(
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+        feed_molefracs: &Array2<f64>,
+        tp_init: QuantityScalar<U>,
+        options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Vec<EosResult<Self>>>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let tp_spec = TPSpec::try_from(temperature_or_pressure)?;
+        let mut tp_init = tp_init;
+        let mut shadow_init: Option<Array1<f64>> = None;
+        let mut vle = Vec::with_capacity(feed_molefracs.nrows());
+        for feed in feed_molefracs.rows() {
+            let res = Self::cloud_point_states(
+                eos,
+                tp_spec,
+                &feed.to_owned(),
+                tp_init,
+                shadow_init.as_ref(),
+                options.clone(),
+            );
+            vle.push(res.map(|(feed, shadow)| {
+                tp_init = match tp_spec {
+                    TPSpec::Temperature(_) => shadow.pressure(Contributions::Total),
+                    TPSpec::Pressure(_) => shadow.temperature,
+                };
+                shadow_init = Some(shadow.molefracs.clone());
+                Self::from_states(feed, shadow)
+            }));
+        }
+        Ok(vle)
+    }
+
+    fn cloud_point_states(
+        eos: &Rc<E>,
+        tp_spec: TPSpec<U>,
+        feed_molefracs: &Array1<f64>,
+        tp_init: QuantityScalar<U>,
+        shadow_molefracs: Option<&Array1<f64>>,
+        options: (SolverOptions, SolverOptions),
+    ) -> EosResult<(State<U, E>, State<U, E>)>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        if feed_molefracs.len() != eos.components() {
+            return Err(EosError::IncompatibleComponents(
+                eos.components(),
+                feed_molefracs.len(),
+            ));
+        }
+        let feed_molefracs = validate_molefracs(feed_molefracs, MolefracsNormalization::Normalize)?;
+        let (var, t, p) = tp_spec.temperature_pressure(tp_init);
+        let feed = State::new_npt(
+            eos,
+            t,
+            p,
+            &(feed_molefracs.clone() * U::reference_moles()),
+            DensityInitialization::None,
+        )?;
+        let shadow = match shadow_molefracs {
+            Some(x) => State::new_npt(
+                eos,
+                t,
+                p,
+                &(validate_molefracs(x, MolefracsNormalization::Normalize)? * U::reference_moles()),
+                DensityInitialization::None,
+            )?,
+            None => feed
+                .stability_analysis(options.0.clone())?
+                .into_iter()
+                .next()
+                .ok_or(EosError::NoPhaseSplit)?,
+        };
+        let vle = bubble_dew(tp_spec, var, feed, shadow, options)?;
+
+        // identify the phases by the composition of the feed
+        let [vapor, liquid] = vle.0;
+        let deviation = |s: &State<U, E>| (&s.molefracs - &feed_molefracs).mapv(f64::abs).sum();
+        Ok(if deviation(&vapor) < deviation(&liquid) {
+            (vapor, liquid)
+        } else {
+            (liquid, vapor)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use approx::assert_relative_eq;
+    use ndarray::{arr1, arr2};
+    use quantity::si::*;
+
+    #[test]
+    fn test_cloud_point() -> EosResult<()> {
+        let eos = propane_butane();
+        let t = 300.0 * KELVIN;
+        let z = arr1(&[0.5, 0.5]);
+        let cp = PhaseEquilibrium::cloud_point(&eos, t, &z, 5.0 * BAR, None, Default::default())?;
+        let p = cp.vapor().pressure(Contributions::Total);
+
+        // for a vapor-liquid equilibrium, the cloud point is a bubble or a dew point
+        let bubble = PhaseEquilibrium::bubble_point(&eos, t, &z, None, None, Default::default())?;
+        let dew = PhaseEquilibrium::dew_point(&eos, t, &z, None, None, Default::default())?;
+        let p_bubble = bubble.vapor().pressure(Contributions::Total);
+        let p_dew = dew.vapor().pressure(Contributions::Total);
+        let p_ref = if (p - p_bubble).abs() < (p - p_dew).abs() {
+            p_bubble
+        } else {
+            p_dew
+        };
+        assert_relative_eq!(p, p_ref, max_relative = 1e-8);
+
+        // shadow curve
+        let feeds = arr2(&[[0.5, 0.5], [0.45, 0.55], [0.4, 0.6]]);
+        let cps = PhaseEquilibrium::cloud_points(&eos, t, &feeds, 5.0 * BAR, Default::default())?;
+        assert!(cps.iter().all(|cp| cp.is_ok()));
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use super::{PhaseEquilibrium, SolverOptions, Verbosity};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::{DensityInitialization, State};
+use crate::EosUnit;
+use ndarray::Array1;
+use quantity::{QuantityArray1, QuantityScalar};
+use std::rc::Rc;
+
+const MAX_ITER_PHASE_BOUNDARY: usize = 50;
+const TOL_PHASE_BOUNDARY: f64 = 1e-8;
+
+/// Homogeneous states and phase equilibria along a composition path
+/// at constant temperature and pressure.
+pub struct CompositionPath<U, E> {
+    /// Fractions of stream A along the path
+    pub fractions: Array1<f64>,
+    /// Homogeneous states of the mixture
+    pub states: Vec<State<U, E>>,
+    /// Phase equilibria for mixtures that split into two phases
+    pub phase_equilibria: Vec<Option<PhaseEquilibrium<U, E, 2>>>,
+    /// Fractions of stream A at which a phase split appears or disappears
+    pub phase_boundaries: Vec<f64>,
+}
+
+impl<U: EosUnit, E: EquationOfState> CompositionPath<U, E> {
+    /// Trace the states obtained by mixing stream A into stream B
+    /// at constant temperature and pressure.
+    ///
+    /// The moles along the path are $N_i=\lambda N_i^A+(1-\lambda)N_i^B$ for every
+    /// fraction $\lambda$. Flash calculations are initialized with the phase
+    /// equilibrium of the previous point, if available. If the stability of the
+    /// mixture changes between two points, the fraction at which the phase split
+    /// appears or disappears (i.e. the cloud point along the path) is located
+    /// by bisection.
+    pub fn new(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        stream_a: &QuantityArray1<U>,
+        stream_b: &QuantityArray1<U>,
+        fractions: &Array1<f64>,
+        options: SolverOptions,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        if fractions.iter().any(|&l| !(0.0..=1.0).contains(&l)) {
+            return Err(EosError::UndeterminedState(String::from(
+                "The fractions of stream A have to be between 0 and 1",
+            )));
+        }
+        let moles = |l: f64| stream_a * l + stream_b * (1.0 - l);
+        let state = |l: f64| {
+            State::new_npt(
+                eos,
+                temperature,
+                pressure,
+                &moles(l),
+                DensityInitialization::None,
+            )
+        };
+
+        let mut states: Vec<State<U, E>> = Vec::with_capacity(fractions.len());
+        let mut phase_equilibria: Vec<Option<PhaseEquilibrium<U, E, 2>>> =
+            Vec::with_capacity(fractions.len());
+        let mut phase_boundaries = Vec::new();
+        for (i, &l) in fractions.iter().enumerate() {
+            let s = state(l)?;
+            let stable = s.is_stable(SolverOptions::default())?;
+
+            // locate phase boundaries between the previous and the current point
+            if i > 0 && stable != phase_equilibria[i - 1].is_none() {
+                phase_boundaries.push(Self::phase_boundary(
+                    (fractions[i - 1], l),
+                    stable,
+                    |l| state(l)?.is_stable(SolverOptions::default()),
+                    options.clone(),
+                )?);
+            }
+
+            let equilibrium = if stable {
+                None
+            } else {
+                // use the previous phase equilibrium as initial value if possible
+                let vle = phase_equilibria
+                    .last()
+                    .and_then(Option::as_ref)
+                    .and_then(|init| s.tp_flash(Some(init), SolverOptions::default(), None).ok());
+                Some(match vle {
+                    Some(vle) => vle,
+                    None => s.tp_flash(None, SolverOptions::default(), None)?,
+                })
+            };
+            states.push(s);
+            phase_equilibria.push(equilibrium);
+        }
+        Ok(Self {
+            fractions: fractions.clone(),
+            states,
+            phase_equilibria,
+            phase_boundaries,
+        })
+    }
+
+    /// Locate the fraction at which the stability changes by bisection.
+    fn phase_boundary<F>(
+        interval: (f64, f64),
+        stable: bool,
+        is_stable: F,
+        options: SolverOptions,
+    ) -> EosResult<f64>
+    where
+        F: Fn(f64) -> EosResult<bool>,
+    {
+        let (max_iter, tol, verbosity) =
+            options.unwrap_or(MAX_ITER_PHASE_BOUNDARY, TOL_PHASE_BOUNDARY);
+        let (mut a, mut b) = interval;
+        log_iter!(verbosity, " iter |    residual    |    fraction    ");
+        log_iter!(verbosity, "{:-<38}", "");
+        for i in 1..=max_iter {
+            let c = 0.5 * (a + b);
+            if is_stable(c)? == stable {
+                b = c;
+            } else {
+                a = c;
+            }
+            log_iter!(verbosity, " {:4} | {:14.8e} | {:14.8}", i, (b - a).abs(), c);
+            if (b - a).abs() < tol {
+                log_result!(
+                    verbosity,
+                    "Phase boundary calculation converged in {} step(s)\n",
+                    i
+                );
+                return Ok(0.5 * (a + b));
+            }
+        }
+        Err(EosError::NotConverged(String::from("phase_boundary")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use crate::Contributions;
+    use approx::assert_relative_eq;
+    use ndarray::{arr1, Array1};
+    use quantity::si::*;
+
+    #[test]
+    fn test_composition_path() -> EosResult<()> {
+        let eos = propane_butane();
+        let (t, p) = (300.0 * KELVIN, 5.0 * BAR);
+        let path = CompositionPath::new(
+            &eos,
+            t,
+            p,
+            &(arr1(&[1.0, 0.0]) * MOL),
+            &(arr1(&[0.0, 1.0]) * MOL),
+            &Array1::linspace(0.01, 0.99, 21),
+            SolverOptions::default(),
+        )?;
+
+        // the path crosses the bubble point and the dew point
+        assert_eq!(path.phase_boundaries.len(), 2);
+        let bubble = PhaseEquilibrium::bubble_point(
+            &eos,
+            t,
+            &arr1(&[path.phase_boundaries[0], 1.0 - path.phase_boundaries[0]]),
+            None,
+            None,
+            Default::default(),
+        )?;
+        assert_relative_eq!(
+            bubble.vapor().pressure(Contributions::Total),
+            p,
+            max_relative = 1e-6
+        );
+        assert!(path.phase_equilibria.first().unwrap().is_none());
+        assert!(path.phase_equilibria[10].is_some());
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use super::SolverOptions;
+use crate::errors::{EosError, EosResult};
+
+/// Residual of a single iteration of a solver.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IterationRecord {
+    /// Name of the solver, e.g., `"pure_t"` or `"tp_flash"`.
+    pub solver: &'static str,
+    /// Iteration of the solver (starting at 1).
+    pub iteration: usize,
+    /// The residual that is compared to the tolerance of the solver.
+    pub residual: f64,
+    /// Norm of the (reduced) step of the iteration variables in this
+    /// iteration. [None] if the solver does not provide it, e.g., for
+    /// successive substitution steps, for which the residual already is
+    /// the change of the iteration variables.
+    pub step_size: Option<f64>,
+}
+
+/// Callback that is notified about every iteration of a solver.
+///
+/// An observer is registered on the [SolverOptions] of a solver and is
+/// called in addition to the iteration output (see [Verbosity](super::Verbosity)).
+/// Returning `false` from [SolverObserver::on_iteration] terminates the
+/// solver with [EosError::Terminated], which is not followed by further
+/// attempts with other initial values.
+///
+/// # Example
+/// ```
+/// # use feos_core::{EosError, IterationRecord, PhaseEquilibrium, SolverOptions};
+/// # use feos_core::cubic::PengRobinson;
+/// # use quantity::si::*;
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// # fn main() -> Result<(), EosError> {
+/// let eos = PengRobinson::from_critical_constants(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+/// let mut calls = 0;
+/// let observer = Rc::new(RefCell::new(move |_: &IterationRecord| {
+///     calls += 1;
+///     calls < 2
+/// }));
+/// let options = SolverOptions::new().observer(observer);
+/// let vle = PhaseEquilibrium::pure(&eos, 300.0 * KELVIN, None, options);
+/// assert!(matches!(vle, Err(EosError::Terminated(_))));
+/// # Ok(())
+/// # }
+/// ```
+pub trait SolverObserver {
+    /// Called after every iteration. Return `false` to terminate the solver.
+    fn on_iteration(&mut self, info: &IterationRecord) -> bool;
+}
+
+impl<F: FnMut(&IterationRecord) -> bool> SolverObserver for F {
+    fn on_iteration(&mut self, info: &IterationRecord) -> bool {
+        self(info)
+    }
+}
+
+/// Observer that records the iterations of all solvers that are
+/// called with the corresponding [SolverOptions].
+///
+/// The history is a structured alternative to the iteration output
+/// (see [Verbosity](super::Verbosity)) that can be used to debug the
+/// convergence of phase equilibrium calculations. Registering separate
+/// histories on the options of different stages, e.g., the inner and
+/// outer loop of bubble and dew point calculations, records the stages
+/// separately.
+///
+/// # Example
+/// ```
+/// # use feos_core::{EosResult, IterationHistory, PhaseEquilibrium, SolverOptions};
+/// # use feos_core::cubic::PengRobinson;
+/// # use quantity::si::*;
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// # fn main() -> EosResult<()> {
+/// let eos = PengRobinson::from_critical_constants(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+/// let history = Rc::new(RefCell::new(IterationHistory::default()));
+/// let options = SolverOptions::new().observer(history.clone());
+/// PhaseEquilibrium::pure(&eos, 300.0 * KELVIN, None, options)?;
+/// let history = history.borrow();
+/// assert!(!history.0.is_empty());
+/// assert!(history.0.iter().all(|r| r.solver == "pure_t"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IterationHistory(pub Vec<IterationRecord>);
+
+impl SolverObserver for IterationHistory {
+    fn on_iteration(&mut self, info: &IterationRecord) -> bool {
+        self.0.push(info.clone());
+        true
+    }
+}
+
+impl SolverOptions {
+    /// Notify the observer of the options (if any) about an iteration.
+    ///
+    /// Returns [EosError::Terminated] if the observer terminates the solver.
+    pub(crate) fn notify(
+        &self,
+        solver: &'static str,
+        iteration: usize,
+        residual: f64,
+        step_size: Option<f64>,
+    ) -> EosResult<()> {
+        let info = IterationRecord {
+            solver,
+            iteration,
+            residual,
+            step_size,
+        };
+        if let Some(observer) = &self.observer {
+            // observers that call solvers with the same options themselves
+            // are not notified about the nested iterations
+            if let Ok(mut observer) = observer.try_borrow_mut() {
+                if !observer.on_iteration(&info) {
+                    return Err(EosError::Terminated(solver.into()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Extension of solver results that distinguishes failed attempts, which
+/// can be followed by another attempt, from terminated solvers.
+pub(crate) trait TerminatedExt<T> {
+    /// Convert a failed attempt into [None], but propagate
+    /// [EosError::Terminated].
+    fn ok_or_terminated(self) -> EosResult<Option<T>>;
+}
+
+impl<T> TerminatedExt<T> for EosResult<T> {
+    fn ok_or_terminated(self) -> EosResult<Option<T>> {
+        match self {
+            Ok(res) => Ok(Some(res)),
+            Err(e @ EosError::Terminated(_)) => Err(e),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::{propane, propane_butane};
+    use crate::phase_equilibria::PhaseEquilibrium;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_iteration_history_stages() -> EosResult<()> {
+        let eos = propane_butane();
+        let inner = Rc::new(RefCell::new(IterationHistory::default()));
+        let outer = Rc::new(RefCell::new(IterationHistory::default()));
+        let options = (
+            SolverOptions::new().observer(inner.clone()),
+            SolverOptions::new().observer(outer.clone()),
+        );
+        PhaseEquilibrium::bubble_point(
+            &eos,
+            300.0 * KELVIN,
+            &arr1(&[0.3, 0.7]),
+            None,
+            None,
+            options,
+        )?;
+
+        // the inner and outer loop are recorded separately
+        let (inner, outer) = (inner.borrow(), outer.borrow());
+        assert!(!inner.0.is_empty());
+        assert!(!outer.0.is_empty());
+        assert!(inner
+            .0
+            .iter()
+            .all(|r| r.solver == "bubble_dew_inner" && r.step_size.is_some()));
+        assert!(outer.0.iter().all(|r| r.solver == "bubble_dew"));
+        let iterations: Vec<_> = outer.0.iter().map(|r| r.iteration).collect();
+        assert_eq!(iterations, (1..=outer.0.len()).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_damping() -> EosResult<()> {
+        let eos = propane();
+        let pure = |options: SolverOptions| {
+            let history = Rc::new(RefCell::new(IterationHistory::default()));
+            let vle = PhaseEquilibrium::pure(
+                &eos,
+                300.0 * KELVIN,
+                None,
+                options.observer(history.clone()),
+            )?;
+            let history = history.borrow().clone();
+            Ok::<_, EosError>((vle, history))
+        };
+        let (vle, history) = pure(SolverOptions::new())?;
+        let (vle_damped, history_damped) = pure(SolverOptions::new().damping(0.5))?;
+
+        // damped Newton steps converge to the same solution in more iterations
+        assert_relative_eq!(
+            vle.vapor().pressure(crate::Contributions::Total),
+            vle_damped.vapor().pressure(crate::Contributions::Total),
+            max_relative = 1e-6
+        );
+        assert!(history_damped.0.len() > history.0.len());
+        assert!(history.0.iter().all(|r| r.step_size.is_some()));
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+use super::{SolverOptions, Verbosity};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::{Contributions, DensityInitialization, State, TPSpec};
+use crate::EosUnit;
+use ndarray::arr1;
+use quantity::QuantityScalar;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+const MAX_ITER_COEXISTENCE: usize = 50;
+const TOL_COEXISTENCE: f64 = 1e-10;
+const MAX_PRESSURE_FACTOR: f64 = 5.0;
+const MAX_TEMPERATURE_STEP: f64 = 0.2;
+
+/// # Coexistence of two models
+impl<U: EosUnit, E: EquationOfState> State<U, E> {
+    /// Calculate the coexistence of a pure substance described by two
+    /// different models, e.g., a solid model and a fluid model.
+    ///
+    /// For the given temperature (pressure), the pressure (temperature) at which
+    /// the molar Gibbs energies of both models are equal is determined with a
+    /// Newton iteration, starting at `initial_value`. The densities of the
+    /// first states are obtained with the respective density initializations,
+    /// all further density iterations start at the previous densities.
+    ///
+    /// The Gibbs energies are only comparable if both models use the same
+    /// ideal gas contribution or, more generally, the same reference state.
+    pub fn model_coexistence<E2: EquationOfState>(
+        eos: &Rc<E>,
+        density_initialization: DensityInitialization<U>,
+        other: &Rc<E2>,
+        other_density_initialization: DensityInitialization<U>,
+        temperature_or_pressure: QuantityScalar<U>,
+        initial_value: QuantityScalar<U>,
+        options: SolverOptions,
+    ) -> EosResult<(Self, State<U, E2>)>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        if eos.components() != 1 || other.components() != 1 {
+            return Err(EosError::IncompatibleComponents(
+                1,
+                eos.components().max(other.components()),
+            ));
+        }
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_COEXISTENCE, TOL_COEXISTENCE);
+        let spec = TPSpec::try_from(temperature_or_pressure)?;
+        let (mut t, mut p) = match spec {
+            TPSpec::Temperature(t) => (t, initial_value),
+            TPSpec::Pressure(p) => (initial_value, p),
+        };
+        let moles = arr1(&[1.0]) * U::reference_moles();
+        let (mut init1, mut init2) = (density_initialization, other_density_initialization);
+
+        log_iter!(
+            verbosity,
+            " iter |    residual    |  temperature  |    pressure    "
+        );
+        log_iter!(verbosity, "{:-<60}", "");
+
+        for i in 1..=max_iter {
+            let state1 = State::new_npt(eos, t, p, &moles, init1)?;
+            let state2 = State::new_npt(other, t, p, &moles, init2)?;
+            let rt = U::gas_constant() * t;
+            let res = ((state1.molar_gibbs_energy(Contributions::Total)
+                - state2.molar_gibbs_energy(Contributions::Total))
+                / rt)
+                .into_value()?;
+            log_iter!(
+                verbosity,
+                " {:4} | {:14.8e} | {:13.5} | {:14.5}",
+                i,
+                res,
+                t,
+                p
+            );
+            if res.abs() < tol {
+                log_result!(
+                    verbosity,
+                    "Model coexistence: calculation converged in {} step(s)\n",
+                    i
+                );
+                return Ok((state1, state2));
+            }
+
+            // Newton step with dg/dp = v and dg/dT = -s
+            match spec {
+                TPSpec::Temperature(_) => {
+                    let dv = 1.0 / state1.density - 1.0 / state2.density;
+                    let p_new = p - res * rt / dv;
+                    p = if p_new > p * MAX_PRESSURE_FACTOR {
+                        p * MAX_PRESSURE_FACTOR
+                    } else if p_new < p / MAX_PRESSURE_FACTOR {
+                        p / MAX_PRESSURE_FACTOR
+                    } else {
+                        p_new
+                    };
+                }
+                TPSpec::Pressure(_) => {
+                    let ds = state1.molar_entropy(Contributions::Total)
+                        - state2.molar_entropy(Contributions::Total);
+                    let dt = (res * rt / ds).to_reduced(t)?;
+                    t = t * (1.0 + dt.clamp(-MAX_TEMPERATURE_STEP, MAX_TEMPERATURE_STEP));
+                }
+            }
+            init1 = DensityInitialization::InitialDensity(state1.density);
+            init2 = DensityInitialization::InitialDensity(state2.density);
+        }
+        Err(EosError::NotConverged(String::from("Model coexistence")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane;
+    use crate::joback::Joback;
+    use crate::phase_equilibria::PhaseEquilibrium;
+    use approx::assert_relative_eq;
+    use quantity::si::*;
+
+    #[test]
+    fn test_model_coexistence() -> EosResult<()> {
+        let eos = propane();
+        let options = SolverOptions::default();
+
+        // the vapor and liquid branch of the same model coexist at the vapor pressure
+        let t = 300.0 * KELVIN;
+        let vle = PhaseEquilibrium::pure(&eos, t, None, options.clone())?;
+        let p_sat = vle.vapor().pressure(Contributions::Total);
+        let (vapor, liquid) = State::model_coexistence(
+            &eos,
+            DensityInitialization::Vapor,
+            &eos,
+            DensityInitialization::Liquid,
+            t,
+            5.0 * BAR,
+            options.clone(),
+        )?;
+        assert_relative_eq!(
+            vapor.pressure(Contributions::Total),
+            p_sat,
+            max_relative = 1e-8
+        );
+        assert_relative_eq!(liquid.density, vle.liquid().density, max_relative = 1e-8);
+
+        let (vapor, _) = State::model_coexistence(
+            &eos,
+            DensityInitialization::Vapor,
+            &eos,
+            DensityInitialization::Liquid,
+            p_sat,
+            280.0 * KELVIN,
+            options.clone(),
+        )?;
+        assert_relative_eq!(vapor.temperature, t, max_relative = 1e-8);
+
+        // the liquid is in equilibrium with an ideal gas below the vapor pressure
+        let ideal_gas = Rc::new(Joback::default(1));
+        let (_, gas) = State::model_coexistence(
+            &eos,
+            DensityInitialization::Liquid,
+            &ideal_gas,
+            DensityInitialization::None,
+            t,
+            5.0 * BAR,
+            options,
+        )?;
+        assert!(gas.pressure(Contributions::Total) < p_sat);
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use super::PhaseEquilibrium;
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::State;
+use crate::EosUnit;
+use ndarray::Array1;
+use quantity::{QuantityArray1, QuantityScalar};
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::ops::Index;
+use std::slice::Iter;
+
+/// A thermodynamic equilibrium state with an arbitrary number of phases.
+///
+/// In contrast to [PhaseEquilibrium], the number of phases is only known
+/// at runtime, which makes the struct suitable for the results of
+/// multiphase flash calculations and for post-processing code that does
+/// not depend on the number of phases. Phase equilibria with a fixed number
+/// of phases can be converted with [From] and, if the number of phases
+/// matches, converted back with [TryFrom].
+#[derive(Debug)]
+pub struct PhaseEquilibriumN<U, E> {
+    states: Vec<State<U, E>>,
+    phase_fractions: Array1<f64>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl<U: Clone, E> Clone for PhaseEquilibriumN<U, E> {
+    fn clone(&self) -> Self {
+        Self {
+            states: self.states.clone(),
+            phase_fractions: self.phase_fractions.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+impl<U, E> fmt::Display for PhaseEquilibriumN<U, E>
+where
+    QuantityScalar<U>: fmt::Display,
+    QuantityArray1<U>: fmt::Display,
+    E: EquationOfState,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (s, beta)) in self.states.iter().zip(&self.phase_fractions).enumerate() {
+            writeln!(f, "phase {} ({:.5}): {}", i, beta, s)?;
+        }
+        Ok(())
+    }
+}
+
+impl<U: EosUnit, E> PhaseEquilibriumN<U, E> {
+    /// Create a phase equilibrium from its phases. The phase
+    /// fractions are determined from the amounts of substance.
+    pub fn new(states: Vec<State<U, E>>) -> Self {
+        let moles: Array1<f64> = states
+            .iter()
+            .map(|s| s.total_moles.to_reduced(U::reference_moles()).unwrap())
+            .collect();
+        let phase_fractions = &moles / moles.sum();
+        Self {
+            states,
+            phase_fractions,
+            metadata: BTreeMap::new(),
+        }
+    }
+}
+
+impl<U, E> PhaseEquilibriumN<U, E> {
+    /// The phases of the phase equilibrium.
+    pub fn states(&self) -> &[State<U, E>] {
+        &self.states
+    }
+
+    /// The molar phase fractions of all phases.
+    pub fn phase_fractions(&self) -> &Array1<f64> {
+        &self.phase_fractions
+    }
+
+    /// The number of phases.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Return `true` if the phase equilibrium does not contain any phases.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Iterate over all phases.
+    pub fn iter(&self) -> Iter<'_, State<U, E>> {
+        self.states.iter()
+    }
+
+    /// User defined metadata of the phase equilibrium, e.g.,
+    /// an experiment ID or a branch label.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Mutable access to the user defined metadata.
+    pub fn metadata_mut(&mut self) -> &mut BTreeMap<String, String> {
+        &mut self.metadata
+    }
+
+    /// Add an entry to the user defined metadata.
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.insert(key.to_owned(), value.to_owned());
+        self
+    }
+}
+
+impl<U, E> Index<usize> for PhaseEquilibriumN<U, E> {
+    type Output = State<U, E>;
+
+    fn index(&self, index: usize) -> &State<U, E> {
+        &self.states[index]
+    }
+}
+
+impl<'a, U, E> IntoIterator for &'a PhaseEquilibriumN<U, E> {
+    type Item = &'a State<U, E>;
+    type IntoIter = Iter<'a, State<U, E>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.states.iter()
+    }
+}
+
+impl<U, E> IntoIterator for PhaseEquilibriumN<U, E> {
+    type Item = State<U, E>;
+    type IntoIter = std::vec::IntoIter<State<U, E>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.states.into_iter()
+    }
+}
+
+impl<U: EosUnit, E, const N: usize> From<PhaseEquilibrium<U, E, N>> for PhaseEquilibriumN<U, E> {
+    fn from(phase_equilibrium: PhaseEquilibrium<U, E, N>) -> Self {
+        let PhaseEquilibrium(states, metadata) = phase_equilibrium;
+        Self {
+            metadata,
+            ..Self::new(Vec::from(states))
+        }
+    }
+}
+
+impl<U, E, const N: usize> TryFrom<PhaseEquilibriumN<U, E>> for PhaseEquilibrium<U, E, N> {
+    type Error = EosError;
+
+    fn try_from(phase_equilibrium: PhaseEquilibriumN<U, E>) -> EosResult<Self> {
+        let n = phase_equilibrium.len();
+        let states = phase_equilibrium
+            .states
+            .try_into()
+            .map_err(|_| EosError::IncompatiblePhases(N, n))?;
+        Ok(Self(states, phase_equilibrium.metadata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters, PengRobinsonRecord};
+    use crate::parameter::{Identifier, Parameter, PureRecord};
+    use crate::phase_equilibria::SolverOptions;
+    use approx::assert_relative_eq;
+    use ndarray::{arr1, arr2};
+    use quantity::si::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_tp_flash_multiphase() -> EosResult<()> {
+        // water, n-hexane and nitrogen
+        let records = [
+            (647.1, 22064000.0, 0.345, 18.015),
+            (507.6, 3025000.0, 0.301, 86.178),
+            (126.2, 3400000.0, 0.037, 28.014),
+        ]
+        .iter()
+        .map(|&(tc, pc, w, mw)| {
+            let record = PengRobinsonRecord::new(tc, pc, w);
+            PureRecord::new(Identifier::default(), mw, record, None)
+        })
+        .collect();
+        let k_ij = arr2(&[[0.0, 0.5, 0.4], [0.5, 0.0, 0.1], [0.4, 0.1, 0.0]]);
+        let parameters = PengRobinsonParameters::from_records(records, k_ij)?;
+        let eos = Rc::new(PengRobinson::new(Rc::new(parameters)));
+        let options = SolverOptions::default();
+        let (t, p) = (320.0 * KELVIN, BAR);
+
+        // three phases agree with the VLLE flash
+        let feed = arr1(&[0.45, 0.45, 0.1]) * MOL;
+        let vlle = PhaseEquilibrium::tp_flash_vlle(&eos, t, p, &feed, options.clone())?;
+        let multiphase = PhaseEquilibriumN::tp_flash(&eos, t, p, &feed, None, options.clone())?;
+        assert_eq!(multiphase.len(), 3);
+        assert_relative_eq!(multiphase.phase_fractions().sum(), 1.0, epsilon = 1e-12);
+        for (s1, s2) in multiphase
+            .iter()
+            .zip(PhaseEquilibriumN::from(vlle.clone()).iter())
+        {
+            assert_relative_eq!(s1.molefracs, s2.molefracs, epsilon = 1e-6);
+        }
+
+        // conversions between the phase equilibria
+        let vlle = vlle.with_metadata("source", "vlle");
+        let converted: PhaseEquilibrium<_, _, 3> = PhaseEquilibriumN::from(vlle).try_into()?;
+        assert_eq!(converted.metadata()["source"], "vlle");
+        let two_phases: EosResult<PhaseEquilibrium<_, _, 2>> = multiphase.try_into();
+        assert!(matches!(
+            two_phases,
+            Err(EosError::IncompatiblePhases(2, 3))
+        ));
+
+        // the number of phases can be limited
+        let limited = PhaseEquilibriumN::tp_flash(&eos, t, p, &feed, Some(2), options.clone())?;
+        assert_eq!(limited.len(), 2);
+
+        // a stable feed results in a single phase
+        let feed = arr1(&[0.01, 0.01, 0.98]) * MOL;
+        let gas = PhaseEquilibriumN::tp_flash(&eos, t, p, &feed, None, options)?;
+        assert_eq!(gas.len(), 1);
+        assert_eq!(gas.phase_fractions()[0], 1.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use super::{PhaseEquilibrium, SolverOptions, TerminatedExt, Verbosity};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::{check_moles, Contributions, DensityInitialization, State};
+use crate::EosUnit;
+use quantity::{QuantityArray1, QuantityScalar};
+use std::rc::Rc;
+
+const MAX_ITER_PH: usize = 50;
+const TOL_PH: f64 = 1e-6;
+const MAX_TEMPERATURE_STEP: f64 = 0.2;
+
+/// # Flash calculations
+impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2>
+where
+    QuantityScalar<U>: std::fmt::Display,
+{
+    /// Perform a flash calculation for given pressure and molar enthalpy
+    /// of the feed.
+    ///
+    /// The temperature is iterated with a safeguarded secant method on
+    /// top of a Tp-flash. The equilibrium temperature is the temperature
+    /// of the resulting states. If the specification is in a single
+    /// phase region, [EosError::NoPhaseSplit] is returned.
+    pub fn ph_flash(
+        eos: &Rc<E>,
+        pressure: QuantityScalar<U>,
+        molar_enthalpy: QuantityScalar<U>,
+        feed: &QuantityArray1<U>,
+        initial_temperature: Option<QuantityScalar<U>>,
+        options: SolverOptions,
+    ) -> EosResult<Self> {
+        let t0 = initial_temperature.unwrap_or(298.15 * U::reference_temperature());
+        Self::flash_temperature_iteration(
+            eos,
+            pressure,
+            feed,
+            t0,
+            options,
+            "Ph flash",
+            |s| s.molar_enthalpy(Contributions::Total),
+            |s| s.c_p(Contributions::Total),
+            molar_enthalpy,
+            U::gas_constant() * t0,
+        )
+    }
+
+    /// Perform a flash calculation for given pressure and molar entropy
+    /// of the feed.
+    ///
+    /// The temperature is iterated with a safeguarded secant method on
+    /// top of a Tp-flash. The equilibrium temperature is the temperature
+    /// of the resulting states. If the specification is in a single
+    /// phase region, [EosError::NoPhaseSplit] is returned.
+    pub fn ps_flash(
+        eos: &Rc<E>,
+        pressure: QuantityScalar<U>,
+        molar_entropy: QuantityScalar<U>,
+        feed: &QuantityArray1<U>,
+        initial_temperature: Option<QuantityScalar<U>>,
+        options: SolverOptions,
+    ) -> EosResult<Self> {
+        let t0 = initial_temperature.unwrap_or(298.15 * U::reference_temperature());
+        Self::flash_temperature_iteration(
+            eos,
+            pressure,
+            feed,
+            t0,
+            options,
+            "Ps flash",
+            |s| s.molar_entropy(Contributions::Total),
+            |s| s.c_p(Contributions::Total) / s.temperature,
+            molar_entropy,
+            U::gas_constant(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn flash_temperature_iteration<F, G>(
+        eos: &Rc<E>,
+        pressure: QuantityScalar<U>,
+        feed: &QuantityArray1<U>,
+        initial_temperature: QuantityScalar<U>,
+        options: SolverOptions,
+        name: &str,
+        property: F,
+        temperature_derivative: G,
+        specification: QuantityScalar<U>,
+        scale: QuantityScalar<U>,
+    ) -> EosResult<Self>
+    where
+        F: Fn(&State<U, E>) -> QuantityScalar<U>,
+        G: Fn(&State<U, E>) -> QuantityScalar<U>,
+    {
+        check_moles(feed)?;
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_PH, TOL_PH);
+        let flash_options = SolverOptions {
+            max_iter: None,
+            tol: None,
+            verbosity: Verbosity::None,
+            ..options
+        };
+        let total_moles = feed.sum();
+
+        log_iter!(verbosity, " iter |    residual    |  temperature  | phases");
+        log_iter!(verbosity, "{:-<50}", "");
+
+        let mut t = initial_temperature;
+        let mut vle: Option<Self> = None;
+        let mut previous: Option<(QuantityScalar<U>, f64)> = None;
+        let (mut t_lower, mut t_upper) = (None, None);
+        for i in 1..=max_iter {
+            // flash at the current temperature, reusing the last phase split
+            let feed_state = State::new_npt(eos, t, pressure, feed, DensityInitialization::None)?;
+            let mut next = match &vle {
+                Some(init) => feed_state
+                    .tp_flash(Some(init), flash_options.clone(), None)
+                    .ok_or_terminated()?,
+                None => None,
+            };
+            if next.is_none() {
+                next = feed_state
+                    .tp_flash(None, flash_options.clone(), None)
+                    .ok_or_terminated()?;
+            }
+            vle = next.filter(|vle| !Self::is_trivial_solution(vle.vapor(), vle.liquid()));
+
+            // total property of the feed and its deviation from the specification
+            let value = match &vle {
+                Some(vle) => {
+                    (property(vle.vapor()) * vle.vapor().total_moles
+                        + property(vle.liquid()) * vle.liquid().total_moles)
+                        / total_moles
+                }
+                None => property(&feed_state),
+            };
+            let res = ((value - specification) / scale).into_value()?;
+            log_iter!(
+                verbosity,
+                " {:4} | {:14.8e} | {:13.5} | {}",
+                i,
+                res,
+                t,
+                if vle.is_some() { 2 } else { 1 }
+            );
+            if res.abs() < tol {
+                log_result!(
+                    verbosity,
+                    "{}: calculation converged in {} step(s)\n",
+                    name,
+                    i
+                );
+                return vle.ok_or(EosError::NoPhaseSplit);
+            }
+
+            // the property increases monotonically with the temperature
+            if res < 0.0 {
+                t_lower = Some(t);
+            } else {
+                t_upper = Some(t);
+            }
+
+            // secant step, or a Newton step with the derivative of the feed
+            let t_new = match previous {
+                Some((t_old, res_old)) if res != res_old => t - (t - t_old) * res / (res - res_old),
+                _ => t - res * scale / temperature_derivative(&feed_state),
+            };
+            previous = Some((t, res));
+
+            // stay within the bracket and limit the step size
+            t = match (t_lower, t_upper) {
+                (Some(lo), Some(hi)) if t_new <= lo || t_new >= hi => 0.5 * (lo + hi),
+                (Some(_), Some(_)) => t_new,
+                _ => {
+                    let dt_max = MAX_TEMPERATURE_STEP * t;
+                    if t_new > t + dt_max {
+                        t + dt_max
+                    } else if t_new < t - dt_max {
+                        t - dt_max
+                    } else {
+                        t_new
+                    }
+                }
+            };
+        }
+        Err(EosError::NotConverged(name.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use crate::cubic::PengRobinson;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    #[test]
+    fn test_ph_ps_flash() -> EosResult<()> {
+        let eos = propane_butane();
+        let feed = arr1(&[0.4, 0.6]) * MOL;
+        let (t, p) = (300.0 * KELVIN, 5.0 * BAR);
+        let vle = PhaseEquilibrium::tp_flash(&eos, t, p, &feed, None, Default::default(), None)?;
+        let total = |f: &dyn Fn(&State<SIUnit, PengRobinson>) -> SINumber| {
+            (f(vle.vapor()) * vle.vapor().total_moles + f(vle.liquid()) * vle.liquid().total_moles)
+                / feed.sum()
+        };
+        let h = total(&|s| s.molar_enthalpy(Contributions::Total));
+        let s = total(&|s| s.molar_entropy(Contributions::Total));
+
+        let options = SolverOptions::default();
+        let ph =
+            PhaseEquilibrium::ph_flash(&eos, p, h, &feed, Some(250.0 * KELVIN), options.clone())?;
+        assert_relative_eq!(ph.vapor().temperature, t, max_relative = 1e-8);
+        assert_relative_eq!(
+            ph.vapor_phase_fraction(),
+            vle.vapor_phase_fraction(),
+            max_relative = 1e-6
+        );
+
+        let ps =
+            PhaseEquilibrium::ps_flash(&eos, p, s, &feed, Some(350.0 * KELVIN), options.clone())?;
+        assert_relative_eq!(ps.vapor().temperature, t, max_relative = 1e-8);
+        assert_relative_eq!(
+            ps.liquid().molefracs,
+            vle.liquid().molefracs,
+            max_relative = 1e-6
+        );
+
+        // specification in the single phase (vapor) region
+        let h_vapor = State::new_npt(&eos, t, p, &feed, DensityInitialization::Vapor)?
+            .molar_enthalpy(Contributions::Total);
+        assert!(matches!(
+            PhaseEquilibrium::ph_flash(
+                &eos,
+                p,
+                h_vapor + 1000.0 * JOULE / MOL,
+                &feed,
+                None,
+                options
+            ),
+            Err(EosError::NoPhaseSplit)
+        ));
+        Ok(())
+    }
+}
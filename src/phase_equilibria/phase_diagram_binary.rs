@@ -1,15 +1,23 @@
-use super::{PhaseDiagram, PhaseEquilibrium, SolverOptions};
+use super::{PhaseDiagram, PhaseEquilibrium, RetryPolicy, SolverOptions, Verbosity};
 use crate::equation_of_state::EquationOfState;
 use crate::errors::{EosError, EosResult};
-use crate::state::{Contributions, DensityInitialization, State, StateBuilder, TPSpec};
+use crate::state::{
+    check_moles, Contributions, DensityInitialization, State, StateBuilder, TPSpec,
+};
 use crate::EosUnit;
 use ndarray::{arr1, arr2, concatenate, s, Array1, Array2, Axis};
 use num_dual::linalg::{norm, LU};
 use quantity::{QuantityArray1, QuantityScalar};
 use std::convert::{TryFrom, TryInto};
+use std::f64::consts::PI;
 use std::rc::Rc;
 
 const DEFAULT_POINTS: usize = 51;
+const MAX_STEP_REFINEMENTS: usize = 4;
+const TRIVIAL_DENSITY_DEVIATION: f64 = 1e-3;
+const PURE_COMPONENT_TOLERANCE: f64 = 1e-10;
+const SPECIFICATION_TOLERANCE: f64 = 1e-10;
+const COMPOSITION_TOLERANCE: f64 = 1e-12;
 
 impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
     /// Create a new binary phase diagram exhibiting a
@@ -18,6 +26,10 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
     /// If a heteroazeotrope occurs and the composition of the liquid
     /// phases are known, they can be passed as `x_lle` to avoid
     /// the calculation of unstable branches.
+    ///
+    /// Homogeneous azeotropes that are crossed between two points are
+    /// inserted into the diagram, which then contains one additional
+    /// state per azeotrope, i.e., more than `npoints` states.
     pub fn binary_vle(
         eos: &Rc<E>,
         temperature_or_pressure: QuantityScalar<U>,
@@ -32,8 +44,8 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
         let tp = temperature_or_pressure.try_into()?;
 
         // calculate boiling temperature/vapor pressure of pure components
-        let vle_sat = PhaseEquilibrium::vle_pure_comps(eos, temperature_or_pressure);
-        let vle_sat = [vle_sat[1].clone(), vle_sat[0].clone()];
+        let mut vle_sat = PhaseEquilibrium::try_vle_pure_comps(eos, temperature_or_pressure);
+        let vle_sat = [vle_sat.remove(1), vle_sat.remove(0)];
 
         // Only calculate up to specified compositions
         if let Some(x_lle) = x_lle {
@@ -55,8 +67,10 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
 
         // look for supercritical components
         let (x_lim, vle_lim, bubble) = match vle_sat {
-            [None, None] => return Err(EosError::SuperCritical),
-            [Some(vle2), None] => {
+            [Err(e @ EosError::Terminated(_)), _]
+            | [_, Err(e @ EosError::Terminated(_))]
+            | [Err(e), Err(_)] => return Err(e),
+            [Ok(vle2), Err(_)] => {
                 let cp = State::critical_point_binary(
                     eos,
                     temperature_or_pressure,
@@ -67,7 +81,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
                 let cp_vle = PhaseEquilibrium::from_states(cp.clone(), cp.clone());
                 ([0.0, cp.molefracs[0]], (vle2, cp_vle), bubble)
             }
-            [None, Some(vle1)] => {
+            [Err(_), Ok(vle1)] => {
                 let cp = State::critical_point_binary(
                     eos,
                     temperature_or_pressure,
@@ -78,7 +92,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
                 let cp_vle = PhaseEquilibrium::from_states(cp.clone(), cp.clone());
                 ([1.0, cp.molefracs[0]], (vle1, cp_vle), bubble)
             }
-            [Some(vle2), Some(vle1)] => ([0.0, 1.0], (vle2, vle1), true),
+            [Ok(vle2), Ok(vle1)] => ([0.0, 1.0], (vle2, vle1), true),
         };
 
         let mut states = iterate_vle(
@@ -89,12 +103,97 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
             Some(vle_lim.1),
             npoints,
             bubble,
-            bubble_dew_options,
-        );
+            bubble_dew_options.clone(),
+        )?;
         if !bubble {
             states = states.into_iter().rev().collect();
         }
-        Ok(Self { states })
+        Self { states }.insert_azeotropes(eos, temperature_or_pressure, bubble_dew_options)
+    }
+
+    /// Recalculate a binary vapor/liquid phase diagram for a different
+    /// (typically slightly perturbed) equation of state.
+    ///
+    /// Every point is converged as bubble point at the liquid composition
+    /// of the old point, using its pressure (or temperature) and vapor
+    /// composition as initial values, which avoids the continuation in
+    /// composition of [PhaseDiagram::binary_vle] (run the example
+    /// `recalculate_phase_diagram` for a comparison of the run times).
+    /// Points of pure components are recalculated as pure component phase
+    /// equilibria and critical points as critical points of the mixture.
+    ///
+    /// Whether the diagram is isothermal or isobaric is determined from
+    /// the stored states. Points that cannot be recalculated, e.g., because
+    /// the critical composition moved past them, are omitted. Their indices
+    /// in the old diagram are returned together with the new diagram.
+    pub fn recalculate(
+        &self,
+        eos: &Rc<E>,
+        bubble_dew_options: (SolverOptions, SolverOptions),
+    ) -> EosResult<(Self, Vec<usize>)>
+    where
+        QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
+    {
+        let first = self.states.first().ok_or_else(|| {
+            EosError::UndeterminedState(String::from("The phase diagram contains no states"))
+        })?;
+        let t = first.vapor().temperature;
+        let isothermal = self.states.iter().all(|vle| {
+            ((vle.vapor().temperature - t) / t)
+                .into_value()
+                .is_ok_and(|dt| dt.abs() < SPECIFICATION_TOLERANCE)
+        });
+        let (tp, temperature_or_pressure) = if isothermal {
+            (TPSpec::Temperature(t), t)
+        } else {
+            let p = first.vapor().pressure(Contributions::Total);
+            (TPSpec::Pressure(p), p)
+        };
+
+        let recalculate = |vle: &PhaseEquilibrium<U, E, 2>| {
+            let x = &vle.liquid().molefracs;
+            let (rho_v, rho_l) = (vle.vapor().density, vle.liquid().density);
+            if let Some(i) = x
+                .iter()
+                .position(|&x| (1.0 - x).abs() < PURE_COMPONENT_TOLERANCE)
+            {
+                return PhaseEquilibrium::vle_pure_comp(eos, temperature_or_pressure, i);
+            }
+            if ((rho_l - rho_v) / rho_l).into_value()?.abs() < TRIVIAL_DENSITY_DEVIATION {
+                let cp = State::critical_point_binary(
+                    eos,
+                    temperature_or_pressure,
+                    Some(vle.vapor().temperature),
+                    Some([x[0], x[1]]),
+                    bubble_dew_options.0.clone(),
+                )?;
+                return Ok(PhaseEquilibrium::from_states(cp.clone(), cp));
+            }
+            let vle = PhaseEquilibrium::bubble_point(
+                eos,
+                temperature_or_pressure,
+                x,
+                Some(vle.vapor().tp(tp)),
+                Some(&vle.vapor().molefracs),
+                bubble_dew_options.clone(),
+            )?;
+            let (rho_v, rho_l) = (vle.vapor().density, vle.liquid().density);
+            if ((rho_l - rho_v) / rho_l).into_value()?.abs() < TRIVIAL_DENSITY_DEVIATION {
+                return Err(EosError::TrivialSolution);
+            }
+            Ok(vle)
+        };
+
+        let mut states = Vec::with_capacity(self.states.len());
+        let mut dropped = Vec::new();
+        for (i, vle) in self.states.iter().enumerate() {
+            match recalculate(vle) {
+                Ok(vle) => states.push(vle),
+                Err(e @ EosError::Terminated(_)) => return Err(e),
+                Err(_) => dropped.push(i),
+            }
+        }
+        Ok((Self { states }, dropped))
     }
 
     #[allow(clippy::type_complexity)]
@@ -103,7 +202,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
         tp: TPSpec<U>,
         npoints: usize,
         x_lle: (f64, f64),
-        vle_sat: [Option<PhaseEquilibrium<U, E, 2>>; 2],
+        vle_sat: [EosResult<PhaseEquilibrium<U, E, 2>>; 2],
         bubble_dew_options: (SolverOptions, SolverOptions),
     ) -> EosResult<(
         Vec<PhaseEquilibrium<U, E, 2>>,
@@ -113,7 +212,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
         QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
     {
         match vle_sat {
-            [Some(vle2), Some(vle1)] => {
+            [Ok(vle2), Ok(vle1)] => {
                 let states1 = iterate_vle(
                     eos,
                     tp,
@@ -122,8 +221,8 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
                     None,
                     npoints / 2,
                     true,
-                    bubble_dew_options,
-                );
+                    bubble_dew_options.clone(),
+                )?;
                 let states2 = iterate_vle(
                     eos,
                     tp,
@@ -133,10 +232,10 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
                     npoints - npoints / 2,
                     true,
                     bubble_dew_options,
-                );
+                )?;
                 Ok((states1, states2))
             }
-            _ => Err(EosError::SuperCritical),
+            [Err(e), _] | [_, Err(e)] => Err(e),
         }
     }
 
@@ -157,6 +256,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
     where
         QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
     {
+        check_moles(feed)?;
         let npoints = npoints.unwrap_or(DEFAULT_POINTS);
         let mut states = Vec::with_capacity(npoints);
         let tp: TPSpec<U> = temperature_or_pressure.try_into()?;
@@ -183,6 +283,18 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
     }
 }
 
+/// Calculate bubble (or dew) points along a line of compositions.
+///
+/// If a calculation fails, the step in composition is reduced (up to
+/// `MAX_STEP_REFINEMENTS` times) and the intermediate points are added
+/// to the diagram. If the step can not be completed with the given
+/// formulation, the opposite formulation (dew instead of bubble point
+/// or vice versa) is tried. Regions that could not be calculated at
+/// all are reported with [Verbosity::Result].
+///
+/// If the diagram ends in a critical point (`vle_1`), the compositions
+/// are clustered towards that point.
+#[allow(clippy::too_many_arguments)]
 fn iterate_vle<U: EosUnit, E: EquationOfState>(
     eos: &Rc<E>,
     tp: TPSpec<U>,
@@ -192,58 +304,153 @@ fn iterate_vle<U: EosUnit, E: EquationOfState>(
     npoints: usize,
     bubble: bool,
     bubble_dew_options: (SolverOptions, SolverOptions),
-) -> Vec<PhaseEquilibrium<U, E, 2>>
+) -> EosResult<Vec<PhaseEquilibrium<U, E, 2>>>
 where
     QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
 {
     let mut vle_vec = Vec::with_capacity(npoints);
 
-    let x = Array1::linspace(x_lim[0], x_lim[1], npoints);
+    // cluster the points towards a critical end point
+    let critical = vle_1.as_ref().is_some_and(|vle| {
+        let (rho_v, rho_l) = (vle.vapor().density, vle.liquid().density);
+        ((rho_l - rho_v) / rho_l)
+            .into_value()
+            .is_ok_and(|d| d.abs() < TRIVIAL_DENSITY_DEVIATION)
+    });
+    let s = Array1::linspace(0.0, 1.0, npoints);
+    let s = if critical {
+        s.mapv(|s| (0.5 * PI * s).sin())
+    } else {
+        s
+    };
+    let x = s * (x_lim[1] - x_lim[0]) + x_lim[0];
     let x = if vle_1.is_some() {
         x.slice(s![1..-1])
     } else {
         x.slice(s![1..])
     };
 
+    // compositions of the specified and the second phase at the last two points
+    let composition = |vle: &PhaseEquilibrium<U, E, 2>| {
+        let (x, y) = (vle.liquid().molefracs[0], vle.vapor().molefracs[0]);
+        if bubble {
+            (x, y)
+        } else {
+            (y, x)
+        }
+    };
+    let mut history = vec![composition(&vle_0)];
+
+    // reject trivial solutions and solutions that do not lie on the
+    // current branch of the diagram; in contrast to the other checks of
+    // the density difference, the deviation is signed so that solutions
+    // with a vapor phase that is denser than the liquid are rejected too
+    let accept = |vle: PhaseEquilibrium<U, E, 2>, x_new: f64, x_old: f64| {
+        let (rho_v, rho_l) = (vle.vapor().density, vle.liquid().density);
+        let (x_vle, _) = composition(&vle);
+        if ((rho_l - rho_v) / rho_l).into_value()? < TRIVIAL_DENSITY_DEVIATION
+            || (x_vle - x_new).abs() > (x_new - x_old).abs()
+        {
+            Err(EosError::TrivialSolution)
+        } else {
+            Ok(vle)
+        }
+    };
+
     let mut tp_old = Some(vle_0.vapor().tp(tp));
     let mut y_old = None;
+    let mut x_old = x_lim[0];
+    let mut truncated: Vec<(f64, f64)> = Vec::new();
     vle_vec.push(vle_0);
-    for xi in x {
-        let vle = PhaseEquilibrium::bubble_dew_point_with_options(
-            eos,
-            tp,
-            tp_old,
-            &arr1(&[*xi, 1.0 - xi]),
-            y_old.as_ref(),
-            bubble,
-            bubble_dew_options,
-        );
-
-        if let Ok(vle) = vle {
-            y_old = Some(if bubble {
-                vle.vapor().molefracs.clone()
+    for &xi in x {
+        let mut refinement: usize = 0;
+        while (xi - x_old).abs() > COMPOSITION_TOLERANCE {
+            let x_new = if refinement == 0 {
+                xi
             } else {
-                vle.liquid().molefracs.clone()
-            });
-            tp_old = Some(match tp {
-                TPSpec::Temperature(_) => vle.vapor().pressure(Contributions::Total),
-                TPSpec::Pressure(_) => vle.vapor().temperature,
+                x_old + (xi - x_old) * 0.5f64.powi(refinement as i32)
+            };
+            // if the calculation fails, switch to the opposite formulation using
+            // the composition of the second phase extrapolated from the last two points
+            let (options_inner, options_outer) = bubble_dew_options.clone();
+            let mut policy = RetryPolicy::new().attempt(
+                options_outer.clone(),
+                (bubble, arr1(&[x_new, 1.0 - x_new]), y_old.clone()),
+            );
+            let (x0, y0) = history[history.len().max(2) - 2];
+            let (x1, y1) = history[history.len() - 1];
+            if (x1 - x0).abs() > COMPOSITION_TOLERANCE {
+                let y_new = y1 + (y1 - y0) * (x_new - x1) / (x1 - x0);
+                if (0.0..=1.0).contains(&y_new) {
+                    policy = policy.attempt(
+                        options_outer,
+                        (
+                            !bubble,
+                            arr1(&[y_new, 1.0 - y_new]),
+                            Some(arr1(&[x_new, 1.0 - x_new])),
+                        ),
+                    );
+                }
+            }
+            let vle = policy.run(|options, (bubble, molefracs_spec, molefracs_init)| {
+                PhaseEquilibrium::bubble_dew_point_with_options(
+                    eos,
+                    tp,
+                    tp_old,
+                    molefracs_spec,
+                    molefracs_init.as_ref(),
+                    *bubble,
+                    (options_inner.clone(), options),
+                )
+                .and_then(|vle| accept(vle, x_new, x_old))
             });
-            vle_vec.push(vle.clone());
-        } else {
-            y_old = None;
-            tp_old = None;
+
+            match vle {
+                Ok(vle) => {
+                    y_old = Some(if bubble {
+                        vle.vapor().molefracs.clone()
+                    } else {
+                        vle.liquid().molefracs.clone()
+                    });
+                    tp_old = Some(vle.vapor().tp(tp));
+                    history.push(composition(&vle));
+                    vle_vec.push(vle);
+                    x_old = x_new;
+                    refinement = refinement.saturating_sub(1);
+                }
+                Err(e @ EosError::Terminated(_)) => return Err(e),
+                Err(_) if refinement < MAX_STEP_REFINEMENTS => refinement += 1,
+                Err(_) => {
+                    // skip the step and continue without initial values
+                    match truncated.last_mut() {
+                        Some(last) if (last.1 - x_old).abs() < COMPOSITION_TOLERANCE => last.1 = xi,
+                        _ => truncated.push((x_old, xi)),
+                    }
+                    y_old = None;
+                    tp_old = None;
+                    x_old = xi;
+                }
+            }
         }
     }
     if let Some(vle_1) = vle_1 {
         vle_vec.push(vle_1);
     }
 
-    vle_vec
+    for (x0, x1) in truncated {
+        log_result!(
+            bubble_dew_options.1.verbosity,
+            "Phase diagram: no phase equilibria found for x = {:.6} to {:.6}",
+            x0,
+            x1
+        );
+    }
+
+    Ok(vle_vec)
 }
 
 impl<U: EosUnit, E: EquationOfState> State<U, E> {
-    fn tp(&self, tp: TPSpec<U>) -> QuantityScalar<U> {
+    pub(super) fn tp(&self, tp: TPSpec<U>) -> QuantityScalar<U> {
         match tp {
             TPSpec::Temperature(_) => self.pressure(Contributions::Total),
             TPSpec::Pressure(_) => self.temperature,
@@ -280,8 +487,8 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
         let tp = temperature_or_pressure.try_into()?;
 
         // calculate pure components
-        let vle_sat = PhaseEquilibrium::vle_pure_comps(eos, temperature_or_pressure);
-        let vle_sat = [vle_sat[1].clone(), vle_sat[0].clone()];
+        let mut vle_sat = PhaseEquilibrium::try_vle_pure_comps(eos, temperature_or_pressure);
+        let vle_sat = [vle_sat.remove(1), vle_sat.remove(0)];
 
         // calculate heteroazeotrope
         let vlle = match tp {
@@ -290,14 +497,14 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
                 t,
                 x_lle,
                 SolverOptions::default(),
-                bubble_dew_options,
+                bubble_dew_options.clone(),
             ),
             TPSpec::Pressure(p) => PhaseEquilibrium::heteroazeotrope_p(
                 eos,
                 p,
                 x_lle,
                 SolverOptions::default(),
-                bubble_dew_options,
+                bubble_dew_options.clone(),
             ),
         }?;
         let x_hetero = (vlle.liquid1().molefracs[0], vlle.liquid2().molefracs[0]);
@@ -393,8 +600,14 @@ where
         // calculate initial values using bubble point
         let x1 = arr1(&[x_init.0, 1.0 - x_init.0]);
         let x2 = arr1(&[x_init.1, 1.0 - x_init.1]);
-        let vle1 =
-            PhaseEquilibrium::bubble_point(eos, temperature, &x1, None, None, bubble_dew_options)?;
+        let vle1 = PhaseEquilibrium::bubble_point(
+            eos,
+            temperature,
+            &x1,
+            None,
+            None,
+            bubble_dew_options.clone(),
+        )?;
         let vle2 =
             PhaseEquilibrium::bubble_point(eos, temperature, &x2, None, None, bubble_dew_options)?;
         let mut l1 = vle1.liquid().clone();
@@ -449,7 +662,7 @@ where
 
             // check for convergence
             if norm(&res) < options.tol.unwrap_or(TOL_HETERO) {
-                return Ok(Self([v, l1, l2]));
+                return Ok(Self::new([v, l1, l2]));
             }
 
             // calculate Jacobian
@@ -534,8 +747,14 @@ where
         // calculate initial values using bubble point
         let x1 = arr1(&[x_init.0, 1.0 - x_init.0]);
         let x2 = arr1(&[x_init.1, 1.0 - x_init.1]);
-        let vle1 =
-            PhaseEquilibrium::bubble_point(eos, pressure, &x1, None, None, bubble_dew_options)?;
+        let vle1 = PhaseEquilibrium::bubble_point(
+            eos,
+            pressure,
+            &x1,
+            None,
+            None,
+            bubble_dew_options.clone(),
+        )?;
         let vle2 =
             PhaseEquilibrium::bubble_point(eos, pressure, &x2, None, None, bubble_dew_options)?;
         let mut l1 = vle1.liquid().clone();
@@ -601,7 +820,7 @@ where
 
             // check for convergence
             if norm(&res) < options.tol.unwrap_or(TOL_HETERO) {
-                return Ok(Self([v, l1, l2]));
+                return Ok(Self::new([v, l1, l2]));
             }
 
             // calculate Jacobian
@@ -685,3 +904,120 @@ where
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::PengRobinson;
+    use approx::assert_relative_eq;
+    use quantity::si::*;
+
+    #[test]
+    fn test_wide_boiling_binary() -> EosResult<()> {
+        // methane + n-decane
+        let eos = PengRobinson::from_critical_constants(
+            &[190.56, 617.7],
+            &[4599000.0, 2110000.0],
+            &[0.011, 0.49],
+            &[16.04, 142.28],
+        )?;
+        let dia =
+            PhaseDiagram::binary_vle(&eos, 400.0 * KELVIN, Some(51), None, Default::default())?;
+        let (first, states) = dia.states.split_first().unwrap();
+        let (critical, states) = states.split_last().unwrap();
+        assert_eq!(first.liquid().molefracs[0], 0.0);
+        assert_eq!(critical.vapor().density, critical.liquid().density);
+
+        let mut p_old = first.vapor().pressure(Contributions::Total);
+        for vle in states {
+            let (x, y) = (vle.liquid().molefracs[0], vle.vapor().molefracs[0]);
+            let p = vle.vapor().pressure(Contributions::Total);
+            assert!(y > x + 1e-2);
+            assert!(p > p_old);
+            p_old = p;
+        }
+
+        let (liquid, vapor) = (dia.liquid(), dia.vapor());
+        assert_eq!(liquid.len(), dia.states.len());
+        assert_eq!(liquid.molefracs().column(0)[0], 0.0);
+        assert_eq!(vapor.temperature(), liquid.temperature());
+        let h_vap = vapor.molar_enthalpy() - liquid.molar_enthalpy();
+        for i in 0..dia.states.len() - 1 {
+            assert!(h_vap.get(i) > 0.0 * JOULE / MOL);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_supercritical() -> EosResult<()> {
+        // methane + n-decane above the critical temperature of both components
+        let eos = PengRobinson::from_critical_constants(
+            &[190.56, 617.7],
+            &[4599000.0, 2110000.0],
+            &[0.011, 0.49],
+            &[16.04, 142.28],
+        )?;
+        let result =
+            PhaseDiagram::binary_vle(&eos, 650.0 * KELVIN, Some(11), None, Default::default());
+        match result {
+            Err(EosError::SuperCritical {
+                component: Some(1),
+                critical: Some(critical),
+                ..
+            }) => assert!(critical.starts_with("T_c")),
+            _ => panic!("expected a supercritical error for component 1"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_recalculate() -> EosResult<()> {
+        // propane + n-butane
+        let eos = |tc: f64| {
+            PengRobinson::from_critical_constants(
+                &[tc, 425.2],
+                &[4250000.0, 3800000.0],
+                &[0.153, 0.199],
+                &[44.0962, 58.123],
+            )
+        };
+        let t = 300.0 * KELVIN;
+        let dia = PhaseDiagram::binary_vle(&eos(369.96)?, t, Some(11), None, Default::default())?;
+        let perturbed = eos(371.0)?;
+        let (new, dropped) = dia.recalculate(&perturbed, Default::default())?;
+        assert!(dropped.is_empty());
+        let reference =
+            PhaseDiagram::binary_vle(&perturbed, t, Some(11), None, Default::default())?;
+        assert_eq!(new.states.len(), reference.states.len());
+        for (vle, vle_ref) in new.states.iter().zip(reference.states.iter()) {
+            assert_relative_eq!(
+                vle.liquid().molefracs[0],
+                vle_ref.liquid().molefracs[0],
+                epsilon = 1e-12
+            );
+            assert_relative_eq!(
+                vle.vapor().pressure(Contributions::Total),
+                vle_ref.vapor().pressure(Contributions::Total),
+                max_relative = 1e-6
+            );
+        }
+
+        // methane + n-decane, the diagram ends at a critical point
+        let eos = |tc: f64| {
+            PengRobinson::from_critical_constants(
+                &[190.56, tc],
+                &[4599000.0, 2110000.0],
+                &[0.011, 0.49],
+                &[16.04, 142.28],
+            )
+        };
+        let t = 400.0 * KELVIN;
+        let dia = PhaseDiagram::binary_vle(&eos(617.7)?, t, Some(11), None, Default::default())?;
+        let (new, dropped) = dia.recalculate(&eos(620.0)?, Default::default())?;
+        assert_eq!(new.states.len() + dropped.len(), dia.states.len());
+        let critical = new.states.last().unwrap();
+        assert_eq!(critical.vapor().density, critical.liquid().density);
+        assert_eq!(critical.vapor().temperature, t);
+        Ok(())
+    }
+}
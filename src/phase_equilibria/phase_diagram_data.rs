@@ -0,0 +1,251 @@
+use super::{PhaseDiagram, PhaseDiagramHetero};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::EosResult;
+use crate::state::StateVec;
+use quantity::si::*;
+use serde::{Deserialize, Serialize, Serializer};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Serializable data of a phase diagram in SI units.
+///
+/// The data can be written to and read from JSON files without
+/// the equation of state that was used to calculate the diagram.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PhaseDiagramData {
+    /// Temperature in K
+    pub temperature: Vec<f64>,
+    /// Pressure in Pa
+    pub pressure: Vec<f64>,
+    /// Density of the vapor phase in mol/m³
+    pub vapor_density: Vec<f64>,
+    /// Density of the liquid phase in mol/m³
+    pub liquid_density: Vec<f64>,
+    /// Mole fractions of the vapor phase for every point
+    pub vapor_molefracs: Vec<Vec<f64>>,
+    /// Mole fractions of the liquid phase for every point
+    pub liquid_molefracs: Vec<Vec<f64>>,
+    /// Molar enthalpy of the vapor phase in J/mol
+    pub vapor_molar_enthalpy: Vec<f64>,
+    /// Molar enthalpy of the liquid phase in J/mol
+    pub liquid_molar_enthalpy: Vec<f64>,
+}
+
+impl PhaseDiagramData {
+    fn new<E: EquationOfState>(
+        vapor: StateVec<'_, SIUnit, E>,
+        liquid: StateVec<'_, SIUnit, E>,
+    ) -> EosResult<Self> {
+        let rows = |x: ndarray::Array2<f64>| x.outer_iter().map(|x| x.to_vec()).collect();
+        Ok(Self {
+            temperature: (vapor.temperature() / KELVIN).into_value()?.to_vec(),
+            pressure: (vapor.pressure() / PASCAL).into_value()?.to_vec(),
+            vapor_density: (vapor.density() / (MOL / METER.powi(3)))
+                .into_value()?
+                .to_vec(),
+            liquid_density: (liquid.density() / (MOL / METER.powi(3)))
+                .into_value()?
+                .to_vec(),
+            vapor_molefracs: rows(vapor.molefracs()),
+            liquid_molefracs: rows(liquid.molefracs()),
+            vapor_molar_enthalpy: (vapor.molar_enthalpy() / (JOULE / MOL))
+                .into_value()?
+                .to_vec(),
+            liquid_molar_enthalpy: (liquid.molar_enthalpy() / (JOULE / MOL))
+                .into_value()?
+                .to_vec(),
+        })
+    }
+
+    /// Read the data of a phase diagram from a JSON file.
+    pub fn from_json<P: AsRef<Path>>(path: P) -> EosResult<Self> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+
+    /// Write the data to a JSON file.
+    pub fn to_json<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        Ok(serde_json::to_writer_pretty(
+            BufWriter::new(File::create(path)?),
+            self,
+        )?)
+    }
+
+    /// Write the data to a CSV file with one line per point of the diagram.
+    pub fn to_csv<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", self.csv_header().join(","))?;
+        for line in self.csv_lines() {
+            writeln!(writer, "{}", line.join(","))?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn csv_header(&self) -> Vec<String> {
+        let n = self.liquid_molefracs.first().map_or(0, |x| x.len());
+        let mut header = vec![
+            "temperature [K]".to_owned(),
+            "pressure [Pa]".to_owned(),
+            "vapor density [mol/m^3]".to_owned(),
+            "liquid density [mol/m^3]".to_owned(),
+        ];
+        header.extend((0..n).map(|i| format!("y{}", i)));
+        header.extend((0..n).map(|i| format!("x{}", i)));
+        header.push("vapor molar enthalpy [J/mol]".to_owned());
+        header.push("liquid molar enthalpy [J/mol]".to_owned());
+        header
+    }
+
+    fn csv_lines(&self) -> Vec<Vec<String>> {
+        (0..self.temperature.len())
+            .map(|i| {
+                let mut line = vec![
+                    self.temperature[i],
+                    self.pressure[i],
+                    self.vapor_density[i],
+                    self.liquid_density[i],
+                ];
+                line.extend(&self.vapor_molefracs[i]);
+                line.extend(&self.liquid_molefracs[i]);
+                line.push(self.vapor_molar_enthalpy[i]);
+                line.push(self.liquid_molar_enthalpy[i]);
+                line.iter().map(|x| x.to_string()).collect()
+            })
+            .collect()
+    }
+}
+
+/// Serializable data of a heterogeneous phase diagram in SI units.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PhaseDiagramHeteroData {
+    /// Vapor-liquid equilibria of the first liquid phase
+    pub vle1: PhaseDiagramData,
+    /// Vapor-liquid equilibria of the second liquid phase
+    pub vle2: PhaseDiagramData,
+    /// Liquid-liquid equilibria
+    pub lle: Option<PhaseDiagramData>,
+}
+
+impl PhaseDiagramHeteroData {
+    /// Read the data of a phase diagram from a JSON file.
+    pub fn from_json<P: AsRef<Path>>(path: P) -> EosResult<Self> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+
+    /// Write the data to a JSON file.
+    pub fn to_json<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        Ok(serde_json::to_writer_pretty(
+            BufWriter::new(File::create(path)?),
+            self,
+        )?)
+    }
+
+    /// Write the data to a CSV file with one line per point.
+    ///
+    /// The first column contains the part of the diagram
+    /// (`vle1`, `vle2` or `lle`) to which the point belongs.
+    pub fn to_csv<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "diagram,{}", self.vle1.csv_header().join(","))?;
+        let parts = [("vle1", Some(&self.vle1)), ("vle2", Some(&self.vle2))];
+        for (name, data) in parts.iter().copied().chain([("lle", self.lle.as_ref())]) {
+            for line in data.iter().flat_map(|d| d.csv_lines()) {
+                writeln!(writer, "{},{}", name, line.join(","))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<E: EquationOfState> PhaseDiagram<SIUnit, E> {
+    /// Return the temperatures, pressures, densities, compositions
+    /// and molar enthalpies of the diagram in SI units.
+    pub fn data(&self) -> EosResult<PhaseDiagramData> {
+        PhaseDiagramData::new(self.vapor(), self.liquid())
+    }
+
+    /// Write the data of the diagram to a JSON file.
+    pub fn to_json<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        self.data()?.to_json(path)
+    }
+
+    /// Write the data of the diagram to a CSV file.
+    pub fn to_csv<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        self.data()?.to_csv(path)
+    }
+}
+
+impl<E: EquationOfState> Serialize for PhaseDiagram<SIUnit, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<E: EquationOfState> PhaseDiagramHetero<SIUnit, E> {
+    /// Return the data of the individual parts of the diagram in SI units.
+    pub fn data(&self) -> EosResult<PhaseDiagramHeteroData> {
+        Ok(PhaseDiagramHeteroData {
+            vle1: self.vle1.data()?,
+            vle2: self.vle2.data()?,
+            lle: self.lle.as_ref().map(|lle| lle.data()).transpose()?,
+        })
+    }
+
+    /// Write the data of the diagram to a JSON file.
+    pub fn to_json<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        self.data()?.to_json(path)
+    }
+
+    /// Write the data of the diagram to a CSV file.
+    pub fn to_csv<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        self.data()?.to_csv(path)
+    }
+}
+
+impl<E: EquationOfState> Serialize for PhaseDiagramHetero<SIUnit, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use crate::errors::EosResult;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_phase_diagram_data() -> EosResult<()> {
+        let eos = propane_butane();
+        let dia =
+            PhaseDiagram::binary_vle(&eos, 300.0 * KELVIN, Some(11), None, Default::default())?;
+        let data = dia.data()?;
+        assert_eq!(data.temperature.len(), dia.states.len());
+        assert_eq!(
+            data.liquid_molefracs[3],
+            dia.states[3].liquid().molefracs.to_vec()
+        );
+        assert_eq!(serde_json::to_value(&dia)?, serde_json::to_value(&data)?);
+
+        let json = crate::temp_path("phase_diagram_data.json");
+        let csv = crate::temp_path("phase_diagram_data.csv");
+        dia.to_json(&json)?;
+        let reread = PhaseDiagramData::from_json(&json)?;
+        assert_eq!(reread.liquid_molefracs.len(), data.liquid_molefracs.len());
+        for (p1, p2) in reread.pressure.iter().zip(data.pressure.iter()) {
+            assert_relative_eq!(p1, p2, max_relative = 1e-14);
+        }
+        dia.to_csv(&csv)?;
+        let lines = std::fs::read_to_string(&csv)?;
+        assert_eq!(lines.lines().count(), dia.states.len() + 1);
+        assert!(lines.starts_with("temperature [K],pressure [Pa]"));
+        std::fs::remove_file(json)?;
+        std::fs::remove_file(csv)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use super::PhaseDiagram;
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::EosUnit;
+use ndarray::{concatenate, s, Array1, Array2, Axis};
+use quantity::QuantityArray1;
+
+/// Differences between two phase diagrams.
+///
+/// The differences are evaluated at the points of a reference diagram
+/// and are calculated as (other - reference).
+pub struct PhaseDiagramDifference<U> {
+    /// Indices of the points of the reference diagram at which the diagrams are compared
+    pub indices: Vec<usize>,
+    /// Differences in temperature
+    pub temperature: QuantityArray1<U>,
+    /// Differences in pressure
+    pub pressure: QuantityArray1<U>,
+    /// Differences in the density of the vapor phase
+    pub vapor_density: QuantityArray1<U>,
+    /// Differences in the density of the liquid phase
+    pub liquid_density: QuantityArray1<U>,
+    /// Differences in the mole fractions of the vapor phase
+    pub vapor_molefracs: Array2<f64>,
+    /// Differences in the mole fractions of the liquid phase
+    pub liquid_molefracs: Array2<f64>,
+}
+
+impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
+    /// Compare the phase diagram to another phase diagram that
+    /// was calculated for the same specifications, e.g., with
+    /// a different model or different parameters.
+    ///
+    /// For pure components, the diagrams are aligned at the temperatures of
+    /// the reference diagram (`self`); for mixtures, they are aligned at the
+    /// liquid mole fractions of the first component. The properties of `other`
+    /// are interpolated linearly between its points. Points of the reference
+    /// diagram outside of the range of `other` are skipped.
+    ///
+    /// For an isothermal diagram of a binary mixture, the result thus contains
+    /// the differences in the bubble point pressures and vapor compositions
+    /// for the same liquid compositions.
+    pub fn compare<E2: EquationOfState>(
+        &self,
+        other: &PhaseDiagram<U, E2>,
+    ) -> EosResult<PhaseDiagramDifference<U>> {
+        let (x_ref, y_ref) = self.reduced_properties()?;
+        let (x_other, y_other) = other.reduced_properties()?;
+        if y_ref.ncols() != y_other.ncols() {
+            return Err(EosError::IncompatibleComponents(
+                (y_ref.ncols() - 4) / 2,
+                (y_other.ncols() - 4) / 2,
+            ));
+        }
+
+        let mut indices = Vec::new();
+        let mut rows = Vec::new();
+        for (i, (&x, y)) in x_ref.iter().zip(y_ref.rows()).enumerate() {
+            if let Some(y_interp) = interpolate(&x_other, &y_other, x) {
+                indices.push(i);
+                rows.push(y_interp - y);
+            }
+        }
+        let mut delta = Array2::zeros((rows.len(), y_ref.ncols()));
+        for (mut d, r) in delta.rows_mut().into_iter().zip(rows) {
+            d.assign(&r);
+        }
+
+        let n = (y_ref.ncols() - 4) / 2;
+        let density = U::reference_density();
+        Ok(PhaseDiagramDifference {
+            indices,
+            temperature: delta.column(0).to_owned() * U::reference_temperature(),
+            pressure: delta.column(1).to_owned() * U::reference_pressure(),
+            vapor_density: delta.column(2).to_owned() * density,
+            liquid_density: delta.column(3).to_owned() * density,
+            vapor_molefracs: delta.slice(s![.., 4..4 + n]).to_owned(),
+            liquid_molefracs: delta.slice(s![.., 4 + n..]).to_owned(),
+        })
+    }
+
+    /// The coordinate along the diagram and the reduced properties
+    /// (temperature, pressure, densities and mole fractions) at every point.
+    fn reduced_properties(&self) -> EosResult<(Array1<f64>, Array2<f64>)> {
+        let (vapor, liquid) = (self.vapor(), self.liquid());
+        let column = |x: Array1<f64>| x.insert_axis(Axis(1));
+        let t = vapor.temperature().to_reduced(U::reference_temperature())?;
+        let p = vapor.pressure().to_reduced(U::reference_pressure())?;
+        let rho_v = vapor.density().to_reduced(U::reference_density())?;
+        let rho_l = liquid.density().to_reduced(U::reference_density())?;
+        let (y, x) = (vapor.molefracs(), liquid.molefracs());
+        let coordinate = if x.ncols() == 1 {
+            t.clone()
+        } else {
+            x.column(0).to_owned()
+        };
+        let properties = concatenate(
+            Axis(1),
+            &[
+                column(t).view(),
+                column(p).view(),
+                column(rho_v).view(),
+                column(rho_l).view(),
+                y.view(),
+                x.view(),
+            ],
+        )
+        .map_err(|e| EosError::UndeterminedState(e.to_string()))?;
+        Ok((coordinate, properties))
+    }
+}
+
+/// Interpolate the rows of `y` linearly at `x0` using the first
+/// interval of `x` that contains `x0`.
+fn interpolate(x: &Array1<f64>, y: &Array2<f64>, x0: f64) -> Option<Array1<f64>> {
+    (1..x.len()).find_map(|j| {
+        let (a, b) = (x[j - 1], x[j]);
+        if a == b || (a - x0) * (b - x0) > 0.0 {
+            return None;
+        }
+        let w = (x0 - a) / (b - a);
+        Some(&y.row(j - 1) * (1.0 - w) + &y.row(j) * w)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane;
+    use crate::cubic::PengRobinson;
+    use crate::phase_equilibria::{PhaseEquilibrium, SolverOptions};
+    use crate::state::Contributions;
+    use approx::assert_relative_eq;
+    use quantity::si::*;
+
+    #[test]
+    fn test_compare_phase_diagrams() -> EosResult<()> {
+        let eos1 = propane();
+        let eos2 =
+            PengRobinson::from_critical_constants(&[369.96], &[4250000.0], &[0.16], &[44.0962])?;
+        let options = SolverOptions::default();
+        let dia1 = PhaseDiagram::pure(&eos1, 250.0 * KELVIN, 101, None, options.clone())?;
+        let dia2 = PhaseDiagram::pure(&eos2, 250.0 * KELVIN, 101, None, options.clone())?;
+
+        // a diagram does not differ from itself
+        let diff = dia1.compare(&dia1)?;
+        assert_eq!(diff.indices.len(), dia1.states.len());
+        assert!(diff.pressure.to_reduced(PASCAL)?.iter().all(|&d| d == 0.0));
+
+        let diff = dia1.compare(&dia2)?;
+        let i = diff.indices[50];
+        let t = dia1.states[i].vapor().temperature;
+        let p1 = dia1.states[i].vapor().pressure(Contributions::Total);
+        let p2 = PhaseEquilibrium::pure(&eos2, t, None, options)?
+            .vapor()
+            .pressure(Contributions::Total);
+        assert_relative_eq!(diff.temperature.get(50), 0.0 * KELVIN);
+        assert_relative_eq!(diff.pressure.get(50), p2 - p1, max_relative = 1e-3);
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+use super::{PhaseDiagram, PhaseEquilibrium, PhaseEquilibriumN, SolverOptions};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::{check_moles, Contributions, DensityInitialization, State, TPSpec};
+use crate::EosUnit;
+use ndarray::Array1;
+use quantity::{QuantityArray1, QuantityScalar};
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+const DEFAULT_POINTS: usize = 51;
+
+/// Enthalpy-concentration (H-x) diagram of a binary mixture at constant pressure.
+///
+/// The mole fractions and molar enthalpies of the saturated liquid and
+/// vapor phases are stored for every point of the diagram. Entries with
+/// the same index are in equilibrium and are connected by a tie line.
+pub struct EnthalpyConcentrationDiagram<U> {
+    /// The constant pressure
+    pub pressure: QuantityScalar<U>,
+    /// Equilibrium temperatures
+    pub temperature: QuantityArray1<U>,
+    /// Mole fractions of the first component in the saturated liquid
+    pub liquid_molefracs: Array1<f64>,
+    /// Mole fractions of the first component in the saturated vapor
+    pub vapor_molefracs: Array1<f64>,
+    /// Molar enthalpies of the saturated liquid
+    pub liquid_enthalpy: QuantityArray1<U>,
+    /// Molar enthalpies of the saturated vapor
+    pub vapor_enthalpy: QuantityArray1<U>,
+}
+
+impl<U: EosUnit> EnthalpyConcentrationDiagram<U> {
+    /// Calculate the enthalpy-concentration diagram of a binary
+    /// mixture at the given pressure.
+    ///
+    /// The phase equilibria are calculated with [PhaseDiagram::binary_vle].
+    pub fn new<E: EquationOfState>(
+        eos: &Rc<E>,
+        pressure: QuantityScalar<U>,
+        npoints: Option<usize>,
+        bubble_dew_options: (SolverOptions, SolverOptions),
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
+    {
+        if let TPSpec::Temperature(_) = TPSpec::try_from(pressure)? {
+            return Err(EosError::UndeterminedState(String::from(
+                "Enthalpy-concentration diagrams are calculated at constant pressure",
+            )));
+        }
+        let dia = PhaseDiagram::binary_vle(eos, pressure, npoints, None, bubble_dew_options)?;
+        Self::from_phase_diagram(&dia)
+    }
+
+    /// Extract the enthalpy-concentration diagram from an isobaric
+    /// phase diagram of a binary mixture.
+    pub fn from_phase_diagram<E: EquationOfState>(dia: &PhaseDiagram<U, E>) -> EosResult<Self> {
+        let (vapor, liquid) = (dia.vapor(), dia.liquid());
+        let (x, y) = (liquid.molefracs(), vapor.molefracs());
+        if x.ncols() != 2 {
+            return Err(EosError::IncompatibleComponents(x.ncols(), 2));
+        }
+        let pressure = dia.states[0].vapor().pressure(Contributions::Total);
+        Ok(Self {
+            pressure,
+            temperature: vapor.temperature(),
+            liquid_molefracs: x.column(0).to_owned(),
+            vapor_molefracs: y.column(0).to_owned(),
+            liquid_enthalpy: liquid.molar_enthalpy(),
+            vapor_enthalpy: vapor.molar_enthalpy(),
+        })
+    }
+
+    /// The end points (mole fraction of the first component and molar
+    /// enthalpy) of the tie lines between the liquid and the vapor phase.
+    pub fn tie_lines(&self) -> Vec<[(f64, QuantityScalar<U>); 2]> {
+        (0..self.liquid_molefracs.len())
+            .map(|i| {
+                [
+                    (self.liquid_molefracs[i], self.liquid_enthalpy.get(i)),
+                    (self.vapor_molefracs[i], self.vapor_enthalpy.get(i)),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Isobaric enthalpy profile of a feed with constant composition.
+///
+/// Instead of the temperature, the molar enthalpy is used as marching
+/// variable, so the profile passes continuously through the two-phase
+/// region, e.g., along a heat exchanger or for a flash drum.
+pub struct EnthalpyProfile<U, E> {
+    /// The constant pressure
+    pub pressure: QuantityScalar<U>,
+    /// The specified molar enthalpies
+    pub molar_enthalpy: QuantityArray1<U>,
+    /// The phases in equilibrium at every point of the profile
+    pub states: Vec<PhaseEquilibriumN<U, E>>,
+}
+
+impl<U: EosUnit, E: EquationOfState> EnthalpyProfile<U, E>
+where
+    QuantityScalar<U>: std::fmt::Display,
+{
+    /// Calculate the profile of a feed at the given pressure for
+    /// `npoints` (default 51) equidistant molar enthalpies between
+    /// `min_enthalpy` and `max_enthalpy`.
+    ///
+    /// Every point is calculated with a Ph flash that is initialized with
+    /// the temperature of the previous point. If the flash fails, the point
+    /// is calculated with [State::new_nph] and accepted if the resulting
+    /// state is stable.
+    pub fn new(
+        eos: &Rc<E>,
+        pressure: QuantityScalar<U>,
+        feed: &QuantityArray1<U>,
+        min_enthalpy: QuantityScalar<U>,
+        max_enthalpy: QuantityScalar<U>,
+        npoints: Option<usize>,
+        options: SolverOptions,
+    ) -> EosResult<Self> {
+        check_moles(feed)?;
+        let npoints = npoints.unwrap_or(DEFAULT_POINTS);
+        let molar_enthalpy = QuantityArray1::linspace(min_enthalpy, max_enthalpy, npoints)?;
+        let mut states: Vec<PhaseEquilibriumN<U, E>> = Vec::with_capacity(npoints);
+        for h in molar_enthalpy.into_iter() {
+            let t0 = states.last().map(|s| s[0].temperature);
+            let vle = match PhaseEquilibrium::ph_flash(eos, pressure, h, feed, t0, options.clone())
+            {
+                Ok(vle) => vle.into(),
+                Err(e) => {
+                    // the flash does not necessarily converge in a single phase region
+                    let state =
+                        State::new_nph(eos, pressure, h, feed, DensityInitialization::None, t0)?;
+                    if !state.is_stable(options.clone())? {
+                        return Err(e);
+                    }
+                    PhaseEquilibriumN::new(vec![state])
+                }
+            };
+            states.push(vle);
+        }
+        Ok(Self {
+            pressure,
+            molar_enthalpy,
+            states,
+        })
+    }
+
+    /// The temperatures of all points of the profile.
+    pub fn temperature(&self) -> QuantityArray1<U> {
+        QuantityArray1::from_shape_fn(self.states.len(), |i| self.states[i][0].temperature)
+    }
+
+    /// The molar fraction of the vapor (the phase with the lower density)
+    /// at every point of the profile or `None` for points in a single
+    /// phase region.
+    pub fn vapor_fraction(&self) -> Vec<Option<f64>> {
+        self.states
+            .iter()
+            .map(|vle| {
+                (vle.len() == 2).then(|| {
+                    let vapor = if vle[0].density < vle[1].density {
+                        0
+                    } else {
+                        1
+                    };
+                    vle.phase_fractions()[vapor]
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use crate::phase_equilibria::PhaseEquilibrium;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    #[test]
+    fn test_enthalpy_concentration_diagram() -> EosResult<()> {
+        let eos = propane_butane();
+        let p = 5.0 * BAR;
+        let dia = EnthalpyConcentrationDiagram::new(&eos, p, Some(21), Default::default())?;
+        assert_eq!(dia.tie_lines().len(), 21);
+        assert_relative_eq!(dia.pressure, p, max_relative = 1e-8);
+
+        // the enthalpy of vaporization is positive
+        for [(_, h_l), (_, h_v)] in dia.tie_lines() {
+            assert!(h_v > h_l);
+        }
+
+        let x = dia.liquid_molefracs[10];
+        let vle = PhaseEquilibrium::bubble_point(
+            &eos,
+            p,
+            &arr1(&[x, 1.0 - x]),
+            Some(dia.temperature.get(10)),
+            None,
+            Default::default(),
+        )?;
+        assert_relative_eq!(
+            dia.vapor_enthalpy.get(10),
+            vle.vapor().molar_enthalpy(Contributions::Total),
+            max_relative = 1e-6
+        );
+
+        assert!(
+            EnthalpyConcentrationDiagram::new(&eos, 300.0 * KELVIN, None, Default::default())
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_enthalpy_profile() -> EosResult<()> {
+        let eos = propane_butane();
+        let (p, x) = (5.0 * BAR, arr1(&[0.5, 0.5]));
+        let feed = &x * MOL;
+        let bubble = PhaseEquilibrium::bubble_point(
+            &eos,
+            p,
+            &x,
+            Some(300.0 * KELVIN),
+            None,
+            Default::default(),
+        )?;
+        let dew = PhaseEquilibrium::dew_point(
+            &eos,
+            p,
+            &x,
+            Some(300.0 * KELVIN),
+            None,
+            Default::default(),
+        )?;
+        let h_bubble = bubble.liquid().molar_enthalpy(Contributions::Total);
+        let h_dew = dew.vapor().molar_enthalpy(Contributions::Total);
+        let dh = 0.02 * (h_dew - h_bubble);
+
+        // subcooled liquid, two phases and superheated vapor
+        let profile = EnthalpyProfile::new(
+            &eos,
+            p,
+            &feed,
+            h_bubble - dh,
+            h_dew + dh,
+            Some(13),
+            SolverOptions::default(),
+        )?;
+        let vapor_fraction = profile.vapor_fraction();
+        assert_eq!(vapor_fraction.len(), 13);
+        assert!(vapor_fraction[0].is_none() && vapor_fraction[12].is_none());
+        assert!(vapor_fraction[2..11].iter().all(|v| v.is_some()));
+        assert_relative_eq!(vapor_fraction[6].unwrap(), 0.5, epsilon = 0.1);
+
+        // the temperature increases monotonically between bubble and dew point
+        let t = profile.temperature();
+        assert!(t.get(1) > bubble.liquid().temperature - 0.01 * KELVIN);
+        assert!(t.get(11) < dew.vapor().temperature + 0.01 * KELVIN);
+        for i in 1..13 {
+            assert!(t.get(i) > t.get(i - 1));
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use super::{PhaseEquilibrium, SolverOptions};
+use super::{PhaseEquilibrium, SolverOptions, TerminatedExt};
 use crate::equation_of_state::EquationOfState;
 use crate::errors::EosResult;
 use crate::state::{State, StateVec};
@@ -43,7 +43,8 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
 
         let mut vle = None;
         for &ti in temperatures.iter() {
-            vle = PhaseEquilibrium::pure(eos, ti, vle.as_ref(), options).ok();
+            vle = PhaseEquilibrium::pure(eos, ti, vle.as_ref(), options.clone())
+                .ok_or_terminated()?;
             if let Some(vle) = vle.as_ref() {
                 states.push(vle.clone());
             }
@@ -55,11 +56,11 @@ impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
 
     /// Return the vapor states of the diagram.
     pub fn vapor(&self) -> StateVec<'_, U, E> {
-        self.states.iter().map(|s| s.vapor()).collect()
+        StateVec::vapor(&self.states)
     }
 
     /// Return the liquid states of the diagram.
     pub fn liquid(&self) -> StateVec<'_, U, E> {
-        self.states.iter().map(|s| s.liquid()).collect()
+        StateVec::liquid(&self.states)
     }
 }
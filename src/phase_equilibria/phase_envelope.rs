@@ -0,0 +1,346 @@
+use super::{PhaseDiagram, PhaseEquilibrium, SolverOptions, Verbosity};
+use crate::constants::{A3, KB, P0};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::{check_moles, Contributions, DensityInitialization, State};
+use crate::EosUnit;
+use ndarray::{s, Array1, Array2};
+use num_dual::linalg::{norm, LU};
+use quantity::{QuantityArray1, QuantityScalar};
+use std::rc::Rc;
+
+const MAX_ITER_ENVELOPE: usize = 20;
+const TOL_ENVELOPE: f64 = 1e-10;
+const MAX_POINTS_ENVELOPE: usize = 1000;
+const INITIAL_STEP: f64 = 0.05;
+const MAX_STEP: f64 = 0.1;
+const MIN_STEP: f64 = 1e-6;
+const CRITICAL_JUMP: f64 = 0.05;
+
+/// A converged point of the phase envelope.
+///
+/// The variables are $\ln K_i$, $\ln T$ and $\ln p$, where $K_i=w_i/z_i$
+/// relates the composition $w$ of the incipient phase to the feed composition $z$.
+struct EnvelopePoint<U, E> {
+    variables: Array1<f64>,
+    feed: State<U, E>,
+    incipient: State<U, E>,
+}
+
+impl<U: EosUnit, E: EquationOfState> PhaseDiagram<U, E> {
+    /// Trace the pressure-temperature phase envelope of a mixture with fixed composition.
+    ///
+    /// The envelope is traced with a continuation method starting from the
+    /// dew point at the standard pressure of 1 bar. In every step, the variable that changes
+    /// most along the envelope ($\ln T$, $\ln p$ or one of the $\ln K_i$) is
+    /// specified and the solution is extrapolated from the previous point.
+    /// The calculation passes the cricondentherm, the critical point (which is
+    /// calculated explicitly and included in the diagram), the cricondenbar
+    /// and continues along the bubble point branch until the pressure falls
+    /// below 1 bar again.
+    pub fn phase_envelope(
+        eos: &Rc<E>,
+        moles: &QuantityArray1<U>,
+        options: SolverOptions,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
+    {
+        check_moles(moles)?;
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_ENVELOPE, TOL_ENVELOPE);
+        let n = eos.components();
+        let z = (moles / moles.sum()).into_value()?;
+        // the envelope starts and ends at the standard pressure
+        let min_pressure = P0 * A3 / KB * U::reference_pressure();
+        let ln_p_min = min_pressure.to_reduced(U::reference_pressure())?.ln();
+
+        // initialize with the dew point at the minimum pressure using the
+        // mean boiling temperature of the pure components as initial value
+        let mut t_init = 0.0 * U::reference_temperature();
+        for (vle, &z) in PhaseEquilibrium::try_vle_pure_comps(eos, min_pressure)
+            .into_iter()
+            .zip(z.iter())
+        {
+            t_init += vle?.vapor().temperature * z;
+        }
+        let dew = PhaseEquilibrium::dew_point(
+            eos,
+            min_pressure,
+            &z,
+            Some(t_init),
+            None,
+            (SolverOptions::default(), SolverOptions::default()),
+        )?;
+        let mut variables = Array1::zeros(n + 2);
+        for i in 0..n {
+            variables[i] = (dew.liquid().molefracs[i] / z[i]).ln();
+        }
+        variables[n] = dew
+            .vapor()
+            .temperature
+            .to_reduced(U::reference_temperature())?
+            .ln();
+        variables[n + 1] = ln_p_min;
+        let densities = (dew.vapor().density, dew.liquid().density);
+        let mut point = EnvelopePoint::new(eos, &z, variables, densities)?;
+
+        let mut states = vec![PhaseEquilibrium::from_states(
+            point.feed.clone(),
+            point.incipient.clone(),
+        )];
+        let mut spec = n + 1;
+        let mut step = INITIAL_STEP;
+        let mut sensitivity = point.sensitivity(&z, spec)?;
+
+        log_iter!(
+            verbosity,
+            " point | spec |  iter  |    temperature    |      pressure      "
+        );
+        log_iter!(verbosity, "{:-<66}", "");
+        while states.len() < MAX_POINTS_ENVELOPE {
+            // predictor and corrector
+            let initial = &point.variables + &(&sensitivity * step);
+            let next = point.newton(eos, &z, initial, spec, max_iter, tol);
+            let (next, iter) = match next {
+                Ok(next) => next,
+                Err(_) if step.abs() > MIN_STEP => {
+                    step *= 0.5;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            log_iter!(
+                verbosity,
+                " {:5} | {:4} | {:6} | {:17.8} | {:18.8}",
+                states.len(),
+                spec,
+                iter,
+                next.feed.temperature,
+                next.feed.pressure(Contributions::Total),
+            );
+
+            // add the critical point if the K factors crossed unity
+            if point.variables[0] * next.variables[0] < 0.0 {
+                let w = point.variables[0] / (point.variables[0] - next.variables[0]);
+                let t = ((1.0 - w) * point.variables[n] + w * next.variables[n]).exp()
+                    * U::reference_temperature();
+                if let Ok(cp) = State::critical_point(eos, Some(moles), Some(t), options.clone()) {
+                    states.push(PhaseEquilibrium::from_states(cp.clone(), cp));
+                }
+            }
+
+            if next.variables[n + 1] < ln_p_min {
+                break;
+            }
+            states.push(PhaseEquilibrium::from_states(
+                next.feed.clone(),
+                next.incipient.clone(),
+            ));
+
+            // select the new specification and adjust the step size
+            let delta = &next.variables - &point.variables;
+            point = next;
+            let new_spec = (0..n + 2)
+                .max_by(|&i, &j| delta[i].abs().total_cmp(&delta[j].abs()))
+                .unwrap();
+            step = delta[new_spec];
+            spec = new_spec;
+            if iter <= 3 {
+                step *= 1.5;
+            } else if iter > 5 {
+                step *= 0.5;
+            }
+            step = step.signum() * step.abs().clamp(MIN_STEP, MAX_STEP);
+
+            // jump across the critical point
+            let x_spec = point.variables[spec];
+            if spec < n && x_spec.abs() < CRITICAL_JUMP && x_spec * step < 0.0 {
+                step = -2.0 * x_spec;
+            }
+            sensitivity = point.sensitivity(&z, spec)?;
+        }
+
+        log_result!(
+            verbosity,
+            "Phase envelope calculation finished with {} point(s)\n",
+            states.len()
+        );
+        Ok(Self { states })
+    }
+}
+
+impl<U: EosUnit, E: EquationOfState> EnvelopePoint<U, E> {
+    /// Residuals of the envelope equations and their Jacobian w.r.t.
+    /// $\ln K_i$, $\ln T$ and $\ln p$ (without the specification equation).
+    fn residuals(&self, z: &Array1<f64>) -> EosResult<(Array1<f64>, Array2<f64>)> {
+        let n = z.len();
+        let (feed, incipient) = (&self.feed, &self.incipient);
+        let w = &incipient.moles.to_reduced(U::reference_moles())?;
+        let t = feed.temperature.to_reduced(U::reference_temperature())?;
+        let p = self.variables[n + 1].exp();
+
+        let ln_phi_w = incipient.ln_phi();
+        let ln_phi_z = feed.ln_phi();
+        let dln_phi_dn = incipient
+            .dln_phi_dnj()
+            .to_reduced(U::reference_moles().powi(-1))?;
+        let dln_phi_dt = (incipient.dln_phi_dt() - feed.dln_phi_dt())
+            .to_reduced(U::reference_temperature().powi(-1))?;
+        let dln_phi_dp = (incipient.dln_phi_dp() - feed.dln_phi_dp())
+            .to_reduced(U::reference_pressure().powi(-1))?;
+
+        let mut residuals = Array1::zeros(n + 1);
+        let mut jacobian = Array2::zeros((n + 1, n + 2));
+        for i in 0..n {
+            residuals[i] = self.variables[i] + ln_phi_w[i] - ln_phi_z[i];
+            for j in 0..n {
+                jacobian[(i, j)] = dln_phi_dn[(i, j)] * w[j];
+            }
+            jacobian[(i, i)] += 1.0;
+            jacobian[(i, n)] = dln_phi_dt[i] * t;
+            jacobian[(i, n + 1)] = dln_phi_dp[i] * p;
+            jacobian[(n, i)] = w[i];
+        }
+        residuals[n] = w.sum() - z.sum();
+        Ok((residuals, jacobian))
+    }
+
+    /// Full Jacobian including the specification of variable `spec`.
+    fn jacobian(jacobian: Array2<f64>, spec: usize) -> Array2<f64> {
+        let n = jacobian.nrows();
+        let mut full = Array2::zeros((n + 1, n + 1));
+        full.slice_mut(s![..n, ..]).assign(&jacobian);
+        full[(n, spec)] = 1.0;
+        full
+    }
+
+    /// Derivatives of all variables w.r.t. the specified variable.
+    fn sensitivity(&self, z: &Array1<f64>, spec: usize) -> EosResult<Array1<f64>> {
+        let (_, jacobian) = self.residuals(z)?;
+        let jacobian = Self::jacobian(jacobian, spec);
+        let mut rhs = Array1::zeros(jacobian.nrows());
+        rhs[jacobian.nrows() - 1] = 1.0;
+        Ok(LU::new(jacobian)?.solve(&rhs))
+    }
+
+    /// Evaluate the feed and the incipient phase for given variables.
+    fn new(
+        eos: &Rc<E>,
+        z: &Array1<f64>,
+        variables: Array1<f64>,
+        densities: (QuantityScalar<U>, QuantityScalar<U>),
+    ) -> EosResult<Self> {
+        let n = z.len();
+        let t = variables[n].exp() * U::reference_temperature();
+        let p = variables[n + 1].exp() * U::reference_pressure();
+        let w = variables.slice(s![..n]).mapv(f64::exp) * z;
+        let init_feed = DensityInitialization::InitialDensity(densities.0);
+        let init_incipient = DensityInitialization::InitialDensity(densities.1);
+        let feed = State::new_npt(eos, t, p, &(z * U::reference_moles()), init_feed)?;
+        let incipient = State::new_npt(eos, t, p, &(w * U::reference_moles()), init_incipient)?;
+        Ok(Self {
+            variables,
+            feed,
+            incipient,
+        })
+    }
+
+    /// Solve the envelope equations with a Newton method for fixed `variables[spec]`.
+    fn newton(
+        &self,
+        eos: &Rc<E>,
+        z: &Array1<f64>,
+        variables: Array1<f64>,
+        spec: usize,
+        max_iter: usize,
+        tol: f64,
+    ) -> EosResult<(Self, usize)> {
+        let densities = (self.feed.density, self.incipient.density);
+        let mut point = Self::new(eos, z, variables, densities)?;
+        for i in 1..=max_iter {
+            let (residuals, jacobian) = point.residuals(z)?;
+            let mut rhs = Array1::zeros(residuals.len() + 1);
+            rhs.slice_mut(s![..residuals.len()]).assign(&-&residuals);
+            let delta = LU::new(Self::jacobian(jacobian, spec))?.solve(&rhs);
+            if delta.iter().any(|d| !d.is_finite()) {
+                break;
+            }
+            let densities = (point.feed.density, point.incipient.density);
+            point = Self::new(eos, z, &point.variables + &delta, densities)?;
+            if norm(&residuals) < tol && norm(&delta) < tol.sqrt() {
+                if PhaseEquilibrium::is_trivial_solution(&point.feed, &point.incipient) {
+                    return Err(EosError::TrivialSolution);
+                }
+                return Ok((point, i));
+            }
+        }
+        Err(EosError::NotConverged(String::from("phase envelope")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::PengRobinson;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    #[test]
+    fn test_phase_envelope() -> EosResult<()> {
+        let eos = PengRobinson::from_critical_constants(
+            &[190.56, 369.96, 425.2],
+            &[4599000.0, 4250000.0, 3800000.0],
+            &[0.011, 0.153, 0.199],
+            &[16.04, 44.0962, 58.123],
+        )?;
+        let moles = arr1(&[0.3, 0.4, 0.3]) * MOL;
+        let dia = PhaseDiagram::phase_envelope(&eos, &moles, SolverOptions::default())?;
+        assert!(dia.states.len() > 10);
+
+        // the envelope contains the critical point of the mixture
+        let cp = State::critical_point(&eos, Some(&moles), None, SolverOptions::default())?;
+        assert!(dia.states.iter().any(|vle| {
+            vle.vapor().density == vle.liquid().density
+                && (vle.vapor().temperature - cp.temperature)
+                    .to_reduced(KELVIN)
+                    .unwrap()
+                    .abs()
+                    < 1e-6
+        }));
+
+        // all points are phase equilibria
+        for vle in &dia.states {
+            let (vapor, liquid) = (vle.vapor(), vle.liquid());
+            assert_relative_eq!(
+                vapor.pressure(Contributions::Total),
+                liquid.pressure(Contributions::Total),
+                max_relative = 1e-8
+            );
+            let ln_f_v = vapor.ln_phi() + vapor.molefracs.mapv(f64::ln);
+            let ln_f_l = liquid.ln_phi() + liquid.molefracs.mapv(f64::ln);
+            assert_relative_eq!(ln_f_v, ln_f_l, epsilon = 1e-6);
+        }
+
+        // the envelope passes the critical point on the way to the cricondentherm
+        // and the cricondenbar
+        let t_max = dia
+            .vapor()
+            .temperature()
+            .to_reduced(KELVIN)?
+            .fold(0.0, |a: f64, &b| a.max(b));
+        let p_max = dia
+            .vapor()
+            .pressure()
+            .to_reduced(BAR)?
+            .fold(0.0, |a: f64, &b| a.max(b));
+        assert!(t_max * KELVIN > cp.temperature);
+        assert!(p_max * BAR > cp.pressure(Contributions::Total));
+        assert_relative_eq!(
+            dia.states[0].vapor().pressure(Contributions::Total),
+            BAR,
+            max_relative = 1e-10
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use super::{SolverOptions, Verbosity};
+use crate::errors::{EosError, EosResult};
+
+/// A deterministic sequence of solver options and initial values that are
+/// tried in order until a calculation converges.
+///
+/// The type of the initial values `I` depends on the solver, e.g., initial
+/// phase equilibria for flash and saturation calculations or initial
+/// temperatures for critical points. Every attempt that fails is reported
+/// with the verbosity of its options. If all attempts fail, the error of the
+/// last attempt is returned. Attempts that are terminated by an observer
+/// are not retried.
+///
+/// # Example
+/// ```
+/// # use feos_core::{EosResult, RetryPolicy, SolverOptions, State};
+/// # use feos_core::cubic::PengRobinson;
+/// # use quantity::si::*;
+/// # fn main() -> EosResult<()> {
+/// let eos = PengRobinson::from_critical_constants(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+/// let policy = RetryPolicy::new()
+///     .attempt(SolverOptions::new().max_iter(5), 100.0 * KELVIN)
+///     .attempt(SolverOptions::new(), 400.0 * KELVIN);
+/// let cp = State::critical_point_with_retry(&eos, None, &policy)?;
+/// assert!((cp.temperature.to_reduced(KELVIN)? - 369.96).abs() < 1e-2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy<I> {
+    attempts: Vec<(SolverOptions, I)>,
+}
+
+impl<I> Default for RetryPolicy<I> {
+    fn default() -> Self {
+        Self {
+            attempts: Vec::new(),
+        }
+    }
+}
+
+impl<I> RetryPolicy<I> {
+    /// Create a policy without any attempts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an attempt with the given options and initial values.
+    pub fn attempt(mut self, options: SolverOptions, initialization: I) -> Self {
+        self.attempts.push((options, initialization));
+        self
+    }
+
+    /// All attempts in the order in which they are tried.
+    pub fn attempts(&self) -> &[(SolverOptions, I)] {
+        &self.attempts
+    }
+
+    /// Return the result of the first converged attempt.
+    pub fn run<T, F>(&self, mut solver: F) -> EosResult<T>
+    where
+        F: FnMut(SolverOptions, &I) -> EosResult<T>,
+    {
+        let mut error = EosError::NotConverged(String::from("RetryPolicy without attempts"));
+        for (k, (options, initialization)) in self.attempts.iter().enumerate() {
+            match solver(options.clone(), initialization) {
+                Ok(res) => return Ok(res),
+                Err(e @ EosError::Terminated(_)) => return Err(e),
+                Err(e) => {
+                    log_result!(options.verbosity, "Attempt {} failed: {}", k + 1, e);
+                    error = e;
+                }
+            }
+        }
+        Err(error)
+    }
+}
+
+impl<I> From<Vec<(SolverOptions, I)>> for RetryPolicy<I> {
+    fn from(attempts: Vec<(SolverOptions, I)>) -> Self {
+        Self { attempts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::{propane, propane_butane};
+    use crate::phase_equilibria::{IterationRecord, PhaseEquilibrium};
+    use crate::state::{Contributions, State};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy::new()
+            .attempt(SolverOptions::new().max_iter(1), 1)
+            .attempt(SolverOptions::new().max_iter(2), 2)
+            .attempt(SolverOptions::new().max_iter(3), 3);
+
+        // attempts are evaluated in order until the first success
+        let mut tried = Vec::new();
+        let res = policy.run(|options, &i| {
+            tried.push(i);
+            if options.max_iter == Some(2) {
+                Ok(i)
+            } else {
+                Err(EosError::NotConverged(format!("{}", i)))
+            }
+        });
+        assert_eq!(res.unwrap(), 2);
+        assert_eq!(tried, [1, 2]);
+
+        // the error of the last attempt is returned
+        let res: EosResult<()> = policy.run(|_, &i| Err(EosError::NotConverged(format!("{}", i))));
+        assert!(matches!(res, Err(EosError::NotConverged(s)) if s == "3"));
+        assert!(RetryPolicy::<()>::new().run(|_, _| Ok(())).is_err());
+    }
+
+    #[test]
+    fn test_with_retry() -> EosResult<()> {
+        let eos = propane_butane();
+        let (t, p) = (300.0 * KELVIN, 4.5 * BAR);
+        let z = arr1(&[0.3, 0.7]);
+        let failing = SolverOptions::new().max_iter(0);
+
+        // bubble and dew points: the first attempt cannot converge
+        let policy = RetryPolicy::new()
+            .attempt(failing.clone(), (None, None))
+            .attempt(SolverOptions::new(), (None, None));
+        let vle = PhaseEquilibrium::bubble_point(&eos, t, &z, None, None, Default::default())?;
+        let bubble =
+            PhaseEquilibrium::bubble_point_with_retry(&eos, t, &z, Default::default(), &policy)?;
+        assert_relative_eq!(
+            bubble.vapor().pressure(Contributions::Total),
+            vle.vapor().pressure(Contributions::Total),
+            max_relative = 1e-8
+        );
+        let vle = PhaseEquilibrium::dew_point(&eos, t, &z, None, None, Default::default())?;
+        let dew = PhaseEquilibrium::dew_point_with_retry(&eos, t, &z, Default::default(), &policy)?;
+        assert_relative_eq!(
+            dew.liquid().pressure(Contributions::Total),
+            vle.liquid().pressure(Contributions::Total),
+            max_relative = 1e-8
+        );
+
+        // the inner options are used in every attempt
+        let terminate =
+            SolverOptions::new().observer(Rc::new(RefCell::new(|record: &IterationRecord| {
+                record.solver != "bubble_dew_inner"
+            })));
+        let res = PhaseEquilibrium::bubble_point_with_retry(&eos, t, &z, terminate, &policy);
+        assert!(matches!(res, Err(EosError::Terminated(_))));
+
+        // Tp flash, the first attempt starts at the bubble point
+        let policy = RetryPolicy::new()
+            .attempt(failing.clone(), Some(bubble))
+            .attempt(SolverOptions::new(), None);
+        let vle = PhaseEquilibrium::tp_flash_with_retry(&eos, t, p, &(&z * MOL), &policy, None)?;
+        assert_relative_eq!(
+            vle.vapor().pressure(Contributions::Total),
+            p,
+            max_relative = 1e-8
+        );
+
+        // pure components
+        let eos = propane();
+        let policy = RetryPolicy::new()
+            .attempt(failing.clone(), None)
+            .attempt(SolverOptions::new(), None);
+        let vle = PhaseEquilibrium::pure_with_retry(&eos, t, &policy)?;
+        let reference = PhaseEquilibrium::pure(&eos, t, None, Default::default())?;
+        assert_relative_eq!(
+            vle.vapor().pressure(Contributions::Total),
+            reference.vapor().pressure(Contributions::Total),
+            max_relative = 1e-8
+        );
+
+        // critical points
+        let policy = RetryPolicy::new()
+            .attempt(failing, 300.0 * KELVIN)
+            .attempt(SolverOptions::new(), 300.0 * KELVIN);
+        let cp = State::critical_point_with_retry(&eos, None, &policy)?;
+        assert_relative_eq!(cp.temperature, 369.96 * KELVIN, max_relative = 1e-4);
+        Ok(())
+    }
+    #[test]
+    fn test_retry_policy_terminated() -> EosResult<()> {
+        let eos = propane();
+        let calls = Rc::new(RefCell::new(0));
+        let counter = calls.clone();
+        let observer = Rc::new(RefCell::new(move |_: &IterationRecord| {
+            *counter.borrow_mut() += 1;
+            false
+        }));
+        let options = SolverOptions::new().observer(observer);
+        let policy = RetryPolicy::new()
+            .attempt(options.clone(), None)
+            .attempt(options, None);
+
+        // the second attempt is not started after the observer terminated the first one
+        let res = PhaseEquilibrium::pure_with_retry(&eos, 300.0 * KELVIN, &policy);
+        assert!(matches!(res, Err(EosError::Terminated(_))));
+        assert_eq!(*calls.borrow(), 1);
+        Ok(())
+    }
+}
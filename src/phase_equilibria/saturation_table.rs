@@ -0,0 +1,215 @@
+use super::{PhaseEquilibrium, SolverOptions};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::EosUnit;
+use ndarray::Array1;
+use quantity::{QuantityArray1, QuantityScalar};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+/// Coarse table of saturation properties of a pure component that is used
+/// to initialize pure component phase equilibria.
+///
+/// All values are stored in reduced units. Between the tabulated
+/// temperatures, the logarithms of the vapor pressure and the vapor density
+/// are interpolated linearly in the inverse temperature and the liquid
+/// density is interpolated linearly in the temperature. The table is only
+/// used within the range of the tabulated temperatures.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SaturationTable {
+    temperature: Array1<f64>,
+    pressure: Array1<f64>,
+    liquid_density: Array1<f64>,
+    vapor_density: Array1<f64>,
+}
+
+impl SaturationTable {
+    /// Create a table from reduced temperatures and vapor pressures (both
+    /// in increasing order), liquid densities and vapor densities.
+    pub fn new(
+        temperature: Array1<f64>,
+        pressure: Array1<f64>,
+        liquid_density: Array1<f64>,
+        vapor_density: Array1<f64>,
+    ) -> EosResult<Self> {
+        let n = temperature.len();
+        if n < 2
+            || [&pressure, &liquid_density, &vapor_density]
+                .iter()
+                .any(|x| x.len() != n)
+        {
+            return Err(EosError::UndeterminedState(String::from(
+                "a saturation table requires at least two points and arrays of equal length",
+            )));
+        }
+        if [&temperature, &pressure, &liquid_density, &vapor_density]
+            .iter()
+            .any(|x| x.iter().any(|v| !v.is_finite()))
+        {
+            return Err(EosError::UndeterminedState(String::from(
+                "a saturation table requires finite values",
+            )));
+        }
+        if temperature.windows(2).into_iter().any(|t| t[1] <= t[0]) {
+            return Err(EosError::UndeterminedState(String::from(
+                "the temperatures of a saturation table have to be increasing",
+            )));
+        }
+        if pressure.windows(2).into_iter().any(|p| p[1] <= p[0]) {
+            return Err(EosError::UndeterminedState(String::from(
+                "the pressures of a saturation table have to be increasing",
+            )));
+        }
+        Ok(Self {
+            temperature,
+            pressure,
+            liquid_density,
+            vapor_density,
+        })
+    }
+
+    /// Calculate the table for a pure component equation of state at the
+    /// given temperatures.
+    ///
+    /// The phase equilibria are calculated in order of increasing
+    /// temperature, each initialized with the previous result.
+    pub fn from_eos<U: EosUnit, E: EquationOfState>(
+        eos: &Rc<E>,
+        temperatures: &QuantityArray1<U>,
+        options: SolverOptions,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
+    {
+        eos.validate_moles::<U>(None)?;
+        let mut temperature = temperatures
+            .to_reduced(U::reference_temperature())?
+            .to_vec();
+        if temperature.iter().any(|t| !t.is_finite()) {
+            return Err(EosError::UndeterminedState(String::from(
+                "a saturation table requires finite temperatures",
+            )));
+        }
+        temperature.sort_by(f64::total_cmp);
+        let mut points = Vec::with_capacity(temperature.len());
+        let mut vle: Option<PhaseEquilibrium<U, E, 2>> = None;
+        for &t in &temperature {
+            let t = t * U::reference_temperature();
+            let v = PhaseEquilibrium::pure(eos, t, vle.as_ref(), options.clone())?;
+            points.push([
+                v.vapor().pressure(crate::Contributions::Total),
+                v.liquid().density,
+                v.vapor().density,
+            ]);
+            vle = Some(v);
+        }
+        let column = |i: usize, unit: QuantityScalar<U>| -> EosResult<Array1<f64>> {
+            points
+                .iter()
+                .map(|p| p[i].to_reduced(unit).map_err(EosError::from))
+                .collect()
+        };
+        Self::new(
+            Array1::from(temperature),
+            column(0, U::reference_pressure())?,
+            column(1, U::reference_density())?,
+            column(2, U::reference_density())?,
+        )
+    }
+
+    /// Interpolate the vapor pressure, the liquid density and the vapor
+    /// density (in reduced units) at the given reduced temperature.
+    ///
+    /// Returns `None` if the temperature is outside of the table.
+    pub fn interpolate(&self, temperature: f64) -> Option<(f64, f64, f64)> {
+        let t = &self.temperature;
+        if !(temperature >= t[0] && temperature <= t[t.len() - 1]) {
+            return None;
+        }
+        let i = (1..t.len() - 1)
+            .find(|&i| temperature < t[i])
+            .unwrap_or(t.len() - 1);
+        let x = (1.0 / temperature - 1.0 / t[i - 1]) / (1.0 / t[i] - 1.0 / t[i - 1]);
+        let log_interpolate = |y: &Array1<f64>| (y[i - 1].ln() * (1.0 - x) + y[i].ln() * x).exp();
+        let pressure = log_interpolate(&self.pressure);
+        let vapor_density = log_interpolate(&self.vapor_density);
+        let x = (temperature - t[i - 1]) / (t[i] - t[i - 1]);
+        let liquid_density = self.liquid_density[i - 1] * (1.0 - x) + self.liquid_density[i] * x;
+        Some((pressure, liquid_density, vapor_density))
+    }
+
+    /// Interpolate the saturation temperature, the liquid density and the
+    /// vapor density (in reduced units) at the given reduced pressure.
+    ///
+    /// Returns `None` if the pressure is outside of the table.
+    pub fn interpolate_pressure(&self, pressure: f64) -> Option<(f64, f64, f64)> {
+        let p = &self.pressure;
+        if !(pressure >= p[0] && pressure <= p[p.len() - 1]) {
+            return None;
+        }
+        let i = (1..p.len() - 1)
+            .find(|&i| pressure < p[i])
+            .unwrap_or(p.len() - 1);
+        let x = (pressure.ln() - p[i - 1].ln()) / (p[i].ln() - p[i - 1].ln());
+        let t = &self.temperature;
+        let temperature = 1.0 / (1.0 / t[i - 1] * (1.0 - x) + 1.0 / t[i] * x);
+        self.interpolate(temperature.clamp(t[i - 1], t[i]))
+            .map(|(_, rho_l, rho_v)| (temperature, rho_l, rho_v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::Contributions;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    #[test]
+    fn test_saturation_table() -> EosResult<()> {
+        let eos = propane();
+        let temperatures = arr1(&[200.0, 250.0, 300.0, 350.0]) * KELVIN;
+        let table = SaturationTable::from_eos(&eos, &temperatures, SolverOptions::default())?;
+        assert!(table.interpolate(150.0).is_none());
+        let nan = arr1(&[200.0, f64::NAN]) * KELVIN;
+        assert!(SaturationTable::from_eos(&eos, &nan, SolverOptions::default()).is_err());
+        let t = arr1(&[1.0, 2.0]);
+        let p = arr1(&[2.0, 1.0]);
+        assert!(SaturationTable::new(t.clone(), p.clone(), t.clone(), t).is_err());
+
+        // interpolated values are close to the actual saturation properties
+        let vle = PhaseEquilibrium::pure(&eos, 280.0 * KELVIN, None, SolverOptions::default())?;
+        let (p, rho_l, rho_v) = table.interpolate(280.0).unwrap();
+        let p_sat = vle.vapor().pressure(Contributions::Total);
+        let density = SIUnit::reference_density();
+        assert_relative_eq!(p * SIUnit::reference_pressure(), p_sat, max_relative = 2e-2);
+        assert_relative_eq!(rho_l * density, vle.liquid().density, max_relative = 2e-2);
+        assert_relative_eq!(rho_v * density, vle.vapor().density, max_relative = 1e-1);
+        let p_sat_reduced = p_sat.to_reduced(SIUnit::reference_pressure())?;
+        let (t, _, _) = table.interpolate_pressure(p_sat_reduced).unwrap();
+        assert_relative_eq!(t, 280.0, max_relative = 1e-2);
+
+        // the equation of state uses the table to initialize phase equilibria
+        let parameters =
+            PengRobinsonParameters::new_simple(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+        let eos = Rc::new(PengRobinson::new(Rc::new(parameters)).with_saturation_table(table)?);
+        assert!(eos.saturation_table().is_some());
+        let options = SolverOptions::new().max_iter(5);
+        let vle_table = PhaseEquilibrium::pure(&eos, 280.0 * KELVIN, None, options.clone())?;
+        assert_relative_eq!(
+            vle_table.vapor().pressure(Contributions::Total),
+            vle.vapor().pressure(Contributions::Total),
+            max_relative = 1e-8
+        );
+        let vle_table = PhaseEquilibrium::pure(&eos, p_sat, None, options)?;
+        assert_relative_eq!(
+            vle_table.vapor().temperature,
+            280.0 * KELVIN,
+            max_relative = 1e-8
+        );
+        Ok(())
+    }
+}
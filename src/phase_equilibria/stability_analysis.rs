@@ -6,7 +6,6 @@ use crate::EosUnit;
 use ndarray::*;
 use num_dual::linalg::smallest_ev;
 use num_dual::linalg::LU;
-use std::f64::EPSILON;
 use std::ops::MulAssign;
 
 const X_DOMINANT: f64 = 0.99;
@@ -16,6 +15,33 @@ const ETA_STEP: f64 = 0.25;
 const MINIMIZE_KMAX: usize = 100;
 const ZERO_TPD: f64 = -1E-08;
 
+/// Result of the tangent plane distance minimization for a single trial phase.
+#[derive(Clone)]
+pub struct StabilityResult<U, E> {
+    /// The component that dominates the initial trial phase or `None` for the
+    /// trial phase that is initialized as an ideal vapor.
+    pub dominant_component: Option<usize>,
+    /// The trial state at the end of the minimization.
+    pub state: State<U, E>,
+    /// The tangent plane distance of the trial state.
+    pub tpd: f64,
+    /// Whether the minimization converged.
+    pub converged: bool,
+    /// Whether the minimization converged to the trivial solution, i.e.,
+    /// to the composition of the analyzed state.
+    pub trivial: bool,
+    /// The number of iterations.
+    pub iterations: usize,
+}
+
+impl<U, E> StabilityResult<U, E> {
+    /// Returns `true` if the trial state has a negative tangent plane distance
+    /// and therefore indicates an instability of the analyzed state.
+    pub fn is_candidate(&self) -> bool {
+        self.converged && !self.trivial && self.tpd < ZERO_TPD
+    }
+}
+
 /// # Stability analysis
 impl<U: EosUnit, E: EquationOfState> State<U, E> {
     /// Determine if the state is stable, i.e. if a phase split should
@@ -28,33 +54,74 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
     /// negative tangent plane distance (i.e. lower Gibbs energy) that can be
     /// used as initial estimates for a phase equilibrium calculation.
     pub fn stability_analysis(&self, options: SolverOptions) -> EosResult<Vec<State<U, E>>> {
+        let mut result: Vec<State<U, E>> = Vec::new();
+        for trial in self.stability_analysis_detailed(options.clone())? {
+            let phase = match trial.dominant_component {
+                None => "Vapor phase".to_string(),
+                Some(i) => format!("Liquid phase {}", i + 1),
+            };
+            let msg = if !trial.converged {
+                return Err(EosError::NotConverged(String::from("stability analysis")));
+            } else if trial.trivial {
+                "Found trivial solution"
+            } else if !trial.is_candidate() {
+                "Found minimum > 0"
+            } else if result
+                .iter()
+                .any(|s| PhaseEquilibrium::is_trivial_solution(s, &trial.state))
+            {
+                "Found already identified minimum"
+            } else {
+                result.push(trial.state);
+                "Found candidate"
+            };
+            log_result!(
+                options.verbosity,
+                "{}: {} in {} step(s)\n",
+                phase,
+                msg,
+                trial.iterations
+            );
+        }
+        Ok(result)
+    }
+
+    /// Perform a stability analysis and return the results of the
+    /// minimizations for all trial phases, including trivial and not
+    /// converged solutions.
+    ///
+    /// [State::stability_analysis] is based on this function. In contrast
+    /// to it, trial phases that do not converge are reported with
+    /// `converged` set to `false` instead of resulting in an error.
+    /// Candidates with negative tangent plane distance are not checked
+    /// for duplicates.
+    pub fn stability_analysis_detailed(
+        &self,
+        options: SolverOptions,
+    ) -> EosResult<Vec<StabilityResult<U, E>>> {
+        let (max_iter, _, _) = options.unwrap_or(MINIMIZE_KMAX, MINIMIZE_TOL);
         let mut result = Vec::new();
         for i_trial in 0..self.eos.components() + 1 {
-            let phase = if i_trial == self.eos.components() {
-                "Vapor phase".to_string()
+            let dominant_component = if i_trial == self.eos.components() {
+                None
             } else {
-                format!("Liquid phase {}", i_trial + 1)
+                Some(i_trial)
             };
             if let Ok(mut trial_state) = self.define_trial_state(i_trial) {
-                let (tpd, i) = self.minimize_tpd(&mut trial_state, options)?;
-                let msg = if let Some(tpd) = tpd {
-                    if tpd < ZERO_TPD {
-                        if result
-                            .iter()
-                            .any(|s| PhaseEquilibrium::is_trivial_solution(s, &trial_state))
-                        {
-                            "Found already identified minimum"
-                        } else {
-                            result.push(trial_state);
-                            "Found candidate"
-                        }
-                    } else {
-                        "Found minimum > 0"
-                    }
-                } else {
-                    "Found trivial solution"
-                };
-                log_result!(options.verbosity, "{}: {} in {} step(s)\n", phase, msg, i);
+                let (converged, trivial, iterations) =
+                    match self.minimize_tpd(&mut trial_state, options.clone()) {
+                        Ok((tpd, i)) => (true, tpd.is_none(), i),
+                        Err(EosError::NotConverged(_)) => (false, false, max_iter),
+                        Err(e) => return Err(e),
+                    };
+                result.push(StabilityResult {
+                    dominant_component,
+                    tpd: self.tangent_plane_distance(&trial_state),
+                    state: trial_state,
+                    converged,
+                    trivial,
+                    iterations,
+                });
             }
         }
         Ok(result)
@@ -128,6 +195,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                 // case: newton step
                 trial.stability_newton_step(&di, &mut tpd)?
             };
+            options.notify("stability_analysis", i, error, None)?;
             log_iter!(
                 verbosity,
                 " {:4} | {:14.8e} | {:11.8} | {}",
@@ -163,7 +231,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         let mut hesse = (self.dln_phi_dnj() * U::reference_moles()).into_value()?;
         let lnphi = self.ln_phi();
         let y = self.moles.to_reduced(U::reference_moles())?;
-        let ln_y = Zip::from(&y).map_collect(|&y| if y > EPSILON { y.ln() } else { 0.0 });
+        let ln_y = Zip::from(&y).map_collect(|&y| if y > f64::EPSILON { y.ln() } else { 0.0 });
         let sq_y = y.mapv(f64::sqrt);
         let gradient = (&ln_y + &lnphi - di) * &sq_y;
 
@@ -172,7 +240,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
             hesse
                 .index_axis_mut(Axis(0), i)
                 .mul_assign(&(sq_y[i] * &sq_y));
-            if y[i] > EPSILON {
+            if y[i] > f64::EPSILON {
                 hesse[[i, i]] += ln_y[i] + lnphi[i] - di[i];
             }
         }
@@ -211,7 +279,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
             }
 
             let y = (&sq_y - &(delta_y / 2.0)).mapv(|v| v.powi(2));
-            let ln_y = Zip::from(&y).map_collect(|&y| if y > EPSILON { y.ln() } else { 0.0 });
+            let ln_y = Zip::from(&y).map_collect(|&y| if y > f64::EPSILON { y.ln() } else { 0.0 });
             *tpd = 1.0 + (&y * &(&ln_y + &lnphi - di - 1.0)).sum();
             if *tpd > tpd_old + 0.0 * 1E-03 && eta_h < 30.0 {
                 eta_h += ETA_STEP;
@@ -231,3 +299,41 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         Ok(gradient.mapv(f64::abs).sum())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    #[test]
+    fn test_stability_analysis_detailed() -> EosResult<()> {
+        let eos = propane_butane();
+        let moles = arr1(&[0.5, 0.5]) * MOL;
+        let state =
+            |p| State::new_npt(&eos, 300.0 * KELVIN, p, &moles, DensityInitialization::None);
+
+        // unstable feed inside the two-phase region
+        let unstable = state(5.0 * BAR)?;
+        let results = unstable.stability_analysis_detailed(SolverOptions::default())?;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[2].dominant_component, None);
+        assert!(results.iter().all(|r| r.converged));
+        let candidates: Vec<_> = results.iter().filter(|r| r.is_candidate()).collect();
+        assert!(!candidates.is_empty());
+        for c in candidates {
+            assert!(c.tpd < 0.0);
+            assert!(unstable
+                .stability_analysis(SolverOptions::default())?
+                .iter()
+                .any(|s| PhaseEquilibrium::is_trivial_solution(s, &c.state)));
+        }
+
+        // stable vapor
+        let stable = state(BAR)?;
+        let results = stable.stability_analysis_detailed(SolverOptions::default())?;
+        assert!(results.iter().all(|r| !r.is_candidate()));
+        Ok(())
+    }
+}
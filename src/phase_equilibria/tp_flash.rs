@@ -1,11 +1,15 @@
-use super::{PhaseEquilibrium, SolverOptions, Verbosity};
+use super::{
+    Acceleration, PhaseEquilibrium, PhaseEquilibriumN, RetryPolicy, SolverOptions, Verbosity,
+};
 use crate::equation_of_state::EquationOfState;
 use crate::errors::{EosError, EosResult};
-use crate::state::{Contributions, DensityInitialization, State};
+use crate::state::{check_moles, Contributions, DensityInitialization, State};
 use crate::EosUnit;
 use ndarray::*;
-use num_dual::linalg::norm;
+use num_dual::linalg::{norm, LU};
 use quantity::{QuantityArray1, QuantityScalar};
+use std::cmp::Ordering;
+use std::convert::TryInto;
 use std::rc::Rc;
 
 const MAX_ITER_TP: usize = 400;
@@ -18,6 +22,9 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
     ///
     /// The algorithm can be use to calculate phase equilibria of systems
     /// containing non-volatile components (e.g. ions).
+    ///
+    /// The successive substitution is accelerated according to
+    /// [SolverOptions::acceleration].
     pub fn tp_flash(
         eos: &Rc<E>,
         temperature: QuantityScalar<U>,
@@ -27,6 +34,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
         options: SolverOptions,
         non_volatile_components: Option<Vec<usize>>,
     ) -> EosResult<Self> {
+        check_moles(feed)?;
         State::new_npt(
             eos,
             temperature,
@@ -36,6 +44,33 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
         )?
         .tp_flash(initial_state, options, non_volatile_components)
     }
+
+    /// Perform a Tp-flash calculation with the options and initial
+    /// values of a [RetryPolicy]. Attempts without initial values are
+    /// initialized using a stability analysis.
+    pub fn tp_flash_with_retry(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        feed: &QuantityArray1<U>,
+        policy: &RetryPolicy<Option<PhaseEquilibrium<U, E, 2>>>,
+        non_volatile_components: Option<Vec<usize>>,
+    ) -> EosResult<Self> {
+        let feed = State::new_npt(
+            eos,
+            temperature,
+            pressure,
+            feed,
+            DensityInitialization::None,
+        )?;
+        policy.run(|options, initial_state| {
+            feed.tp_flash(
+                initial_state.as_ref(),
+                options,
+                non_volatile_components.clone(),
+            )
+        })
+    }
 }
 
 /// # Flash calculations
@@ -46,6 +81,9 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
     ///
     /// The algorithm can be use to calculate phase equilibria of systems
     /// containing non-volatile components (e.g. ions).
+    ///
+    /// The successive substitution is accelerated according to
+    /// [SolverOptions::acceleration].
     pub fn tp_flash(
         &self,
         initial_state: Option<&PhaseEquilibrium<U, E, 2>>,
@@ -85,7 +123,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                 &mut iter,
                 &mut None,
                 tol,
-                verbosity,
+                &options,
                 &non_volatile_components,
             )?;
 
@@ -111,7 +149,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                     &mut iter,
                     &mut None,
                     tol,
-                    verbosity,
+                    &options,
                     &non_volatile_components,
                 )?;
             }
@@ -130,7 +168,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                     &mut iter,
                     &mut None,
                     tol,
-                    verbosity,
+                    &options,
                     &non_volatile_components,
                 )?;
             }
@@ -142,14 +180,14 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
             &mut iter,
             max_iter,
             tol,
-            verbosity,
+            &options,
             &non_volatile_components,
         )?;
 
         Ok(new_vle_state)
     }
 
-    fn tangent_plane_distance(&self, trial_state: &State<U, E>) -> f64 {
+    pub(super) fn tangent_plane_distance(&self, trial_state: &State<U, E>) -> f64 {
         let ln_phi_z = self.ln_phi();
         let ln_phi_w = trial_state.ln_phi();
         let z = &self.molefracs;
@@ -165,9 +203,10 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
         iter: &mut usize,
         max_iter: usize,
         tol: f64,
-        verbosity: Verbosity,
+        options: &SolverOptions,
         non_volatile_components: &Option<Vec<usize>>,
     ) -> EosResult<()> {
+        let verbosity = options.verbosity;
         for _ in 0..max_iter {
             // do 5 successive substitution steps and check for convergence
             let mut k_vec = Array::zeros((4, self.vapor().eos.components()));
@@ -177,7 +216,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
                 iter,
                 &mut Some(&mut k_vec),
                 tol,
-                verbosity,
+                options,
                 non_volatile_components,
             )? {
                 log_result!(
@@ -196,15 +235,25 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
             let delta = Array::from_shape_fn((3, 3), |(i, j)| {
                 (&delta_vec.index_axis(Axis(0), i) * &delta_vec.index_axis(Axis(0), j)).sum()
             });
-            let d = delta[(0, 1)] * delta[(0, 1)] - delta[(0, 0)] * delta[(1, 1)];
-            let a = (delta[(0, 2)] * delta[(0, 1)] - delta[(1, 2)] * delta[(0, 0)]) / d;
-            let b = (delta[(1, 2)] * delta[(0, 1)] - delta[(0, 2)] * delta[(1, 1)]) / d;
-
-            let mut k = (&k_vec.index_axis(Axis(0), 3)
-                + &((b * &delta_vec.index_axis(Axis(0), 1)
-                    + (a + b) * &delta_vec.index_axis(Axis(0), 2))
-                    / (1.0 - a - b)))
-                .mapv(f64::exp);
+            let ln_k = &k_vec.index_axis(Axis(0), 3);
+            let mut k = match options.acceleration {
+                Acceleration::None => continue,
+                Acceleration::Dem => {
+                    let lambda = delta[(1, 2)] / delta[(1, 1)];
+                    (ln_k + &(&delta_vec.index_axis(Axis(0), 2) * lambda / (1.0 - lambda)))
+                        .mapv(f64::exp)
+                }
+                Acceleration::Gdem => {
+                    let d = delta[(0, 1)] * delta[(0, 1)] - delta[(0, 0)] * delta[(1, 1)];
+                    let a = (delta[(0, 2)] * delta[(0, 1)] - delta[(1, 2)] * delta[(0, 0)]) / d;
+                    let b = (delta[(1, 2)] * delta[(0, 1)] - delta[(0, 2)] * delta[(1, 1)]) / d;
+                    (ln_k
+                        + &((b * &delta_vec.index_axis(Axis(0), 1)
+                            + (a + b) * &delta_vec.index_axis(Axis(0), 2))
+                            / (1.0 - a - b)))
+                        .mapv(f64::exp)
+                }
+            };
 
             // Set k = 0 for non-volatile components
             if let Some(nvc) = non_volatile_components.as_ref() {
@@ -231,9 +280,10 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
         iter: &mut usize,
         k_vec: &mut Option<&mut Array2<f64>>,
         abs_tol: f64,
-        verbosity: Verbosity,
+        options: &SolverOptions,
         non_volatile_components: &Option<Vec<usize>>,
     ) -> EosResult<bool> {
+        let verbosity = options.verbosity;
         for i in 0..iterations {
             let ln_phi_v = self.vapor().ln_phi();
             let ln_phi_l = self.liquid().ln_phi();
@@ -260,6 +310,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
                 nvc.iter().for_each(|&c| res_vec[c] = 0.0);
             }
             let res = norm(&res_vec);
+            options.notify("tp_flash", *iter, res, None)?;
             log_iter!(
                 verbosity,
                 " {:4} | {:14.8e} | {:.8} | {:.8}",
@@ -308,6 +359,363 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
     }
 }
 
+const MAX_ITER_BETA: usize = 50;
+const TOL_BETA: f64 = 1e-12;
+const MIN_PHASE_FRACTION: f64 = 1e-8;
+
+/// # Flash calculations
+impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 3> {
+    /// Perform a three-phase (vapor-liquid-liquid) Tp-flash calculation.
+    ///
+    /// The calculation is initialized with a two-phase Tp-flash.
+    /// A stability analysis of the resulting phases provides the
+    /// initial estimate for the third phase. The phase fractions and
+    /// compositions are then determined simultaneously by successive
+    /// substitution (Michelsen, Fluid Phase Equilib. 9 (1982) 21-40).
+    ///
+    /// Returns [EosError::NoPhaseSplit] if the feed does not split
+    /// into three phases at the given conditions.
+    pub fn tp_flash_vlle(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        feed: &QuantityArray1<U>,
+        options: SolverOptions,
+    ) -> EosResult<Self> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_TP, TOL_TP);
+        let feed_state = State::new_npt(
+            eos,
+            temperature,
+            pressure,
+            feed,
+            DensityInitialization::None,
+        )?;
+
+        // two-phase flash and stability analysis of both phases
+        let vle = feed_state.tp_flash(None, SolverOptions::default(), None)?;
+        let third_phase = vle
+            .0
+            .iter()
+            .map(|s| s.stability_analysis(SolverOptions::default()))
+            .collect::<EosResult<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .find(|s| {
+                vle.0
+                    .iter()
+                    .all(|p| !PhaseEquilibrium::is_trivial_solution(s, p))
+            })
+            .ok_or(EosError::NoPhaseSplit)?;
+        let beta_v = vle.vapor_phase_fraction();
+        let mut states = vec![vle.0[0].clone(), vle.0[1].clone(), third_phase];
+        let beta = multiphase_successive_substitution(
+            &feed_state,
+            pressure,
+            &mut states,
+            arr1(&[beta_v, 1.0 - beta_v, 0.0]),
+            (max_iter, tol, verbosity),
+            "Tp flash (VLLE)",
+        )?;
+        if beta.iter().any(|&b| b < MIN_PHASE_FRACTION) {
+            log_result!(verbosity, "Tp flash (VLLE): phase vanished\n");
+            return Err(EosError::NoPhaseSplit);
+        }
+        let states = split_feed(&feed_state, pressure, &states, &beta)?;
+        let mut states: [State<U, E>; 3] = states.try_into().ok().unwrap();
+        sort_by_density(&mut states, "Tp flash (VLLE)")?;
+        if PhaseEquilibrium::is_trivial_solution(&states[0], &states[1])
+            || PhaseEquilibrium::is_trivial_solution(&states[1], &states[2])
+        {
+            return Err(EosError::TrivialSolution);
+        }
+        Ok(Self::new(states))
+    }
+}
+
+/// # Flash calculations
+impl<U: EosUnit, E: EquationOfState> PhaseEquilibriumN<U, E> {
+    /// Perform a Tp-flash calculation for an arbitrary number of phases.
+    ///
+    /// Starting from the feed, a stability analysis of all current
+    /// phases is used to add one phase at a time. After every addition,
+    /// the phase fractions and compositions are determined simultaneously
+    /// by successive substitution and phases with vanishing phase fractions
+    /// are removed. The calculation stops if all phases are stable or
+    /// `max_phases` phases are present. By default, the maximum number of
+    /// phases is the number of components (Gibbs phase rule).
+    ///
+    /// If the feed is stable, the result contains only the feed.
+    pub fn tp_flash(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        feed: &QuantityArray1<U>,
+        max_phases: Option<usize>,
+        options: SolverOptions,
+    ) -> EosResult<Self> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_TP, TOL_TP);
+        let max_phases = max_phases.unwrap_or_else(|| eos.components());
+        let feed_state = State::new_npt(
+            eos,
+            temperature,
+            pressure,
+            feed,
+            DensityInitialization::None,
+        )?;
+
+        let mut states = vec![feed_state.clone()];
+        let mut beta = arr1(&[1.0]);
+        while states.len() < max_phases {
+            // trial phase from the stability analysis of the current phases
+            let mut trial_phase = None;
+            for s in states.iter() {
+                trial_phase = s
+                    .stability_analysis(SolverOptions::default())?
+                    .into_iter()
+                    .find(|t| {
+                        states
+                            .iter()
+                            .all(|p| !PhaseEquilibrium::is_trivial_solution(t, p))
+                    });
+                if trial_phase.is_some() {
+                    break;
+                }
+            }
+            let trial_phase = match trial_phase {
+                Some(t) => t,
+                None => break,
+            };
+            let n_phases = states.len();
+            states.push(trial_phase);
+            let beta_trial =
+                Array1::from_shape_fn(n_phases + 1, |k| beta.get(k).map_or(0.0, |&b| b));
+            beta = multiphase_successive_substitution(
+                &feed_state,
+                pressure,
+                &mut states,
+                beta_trial,
+                (max_iter, tol, verbosity),
+                "Tp flash (multiphase)",
+            )?;
+
+            // remove vanished phases and phases that coincide with other phases
+            let mut kept: Vec<usize> = Vec::new();
+            for k in 0..states.len() {
+                if beta[k] < MIN_PHASE_FRACTION {
+                    continue;
+                }
+                match kept
+                    .iter()
+                    .find(|&&l| PhaseEquilibrium::is_trivial_solution(&states[k], &states[l]))
+                {
+                    Some(&l) => beta[l] += beta[k],
+                    None => kept.push(k),
+                }
+            }
+            let n_kept = kept.len();
+            beta = kept.iter().map(|&k| beta[k]).collect();
+            states = kept.into_iter().map(|k| states[k].clone()).collect();
+            if n_kept <= n_phases {
+                break;
+            }
+        }
+
+        if states.len() == 1 {
+            return Ok(Self::new(vec![feed_state]));
+        }
+        let mut states = split_feed(&feed_state, pressure, &states, &beta)?;
+        sort_by_density(&mut states, "Tp flash (multiphase)")?;
+        Ok(Self::new(states))
+    }
+}
+
+/// Determine the phase fractions and compositions of the phases in `states`
+/// by successive substitution (Michelsen, Fluid Phase Equilib. 9 (1982) 21-40),
+/// starting from the phase fractions `beta`.
+///
+/// On return, `states` contains the (normalized) phases and the
+/// returned array their phase fractions. Phases can vanish during
+/// the iteration, in which case their phase fraction is 0.
+fn multiphase_successive_substitution<U: EosUnit, E: EquationOfState>(
+    feed_state: &State<U, E>,
+    pressure: QuantityScalar<U>,
+    states: &mut [State<U, E>],
+    mut beta: Array1<f64>,
+    (max_iter, tol, verbosity): (usize, f64, Verbosity),
+    name: &str,
+) -> EosResult<Array1<f64>> {
+    log_iter!(verbosity, " iter |    residual    |   phase fractions   ");
+    log_iter!(verbosity, "{:-<46}", "");
+
+    let n = states.len();
+    let z = &feed_state.molefracs;
+    let total_moles = feed_state.total_moles;
+    for iter in 1..=max_iter {
+        // fugacity coefficients shifted for numerical stability
+        let mut ln_phi = Array2::zeros((n, z.len()));
+        for (mut row, s) in ln_phi.outer_iter_mut().zip(states.iter()) {
+            row.assign(&s.ln_phi());
+        }
+        let ln_phi_min = ln_phi.fold_axis(Axis(0), f64::INFINITY, |&a, &b| a.min(b));
+        let a = (&ln_phi_min - &ln_phi).mapv(f64::exp);
+
+        // phase fractions and new compositions
+        beta = multiphase_rachford_rice(z, &a, beta)?;
+        if beta.iter().any(|b| !b.is_finite()) {
+            return Err(EosError::NotConverged(name.to_owned()));
+        }
+        let e = a.t().dot(&beta);
+        let x = &a * &(z / &e);
+
+        // check for convergence
+        let reference = (0..n).max_by(|&i, &j| beta[i].total_cmp(&beta[j])).unwrap();
+        let ln_f = |k: usize| {
+            Zip::from(&states[k].molefracs)
+                .and(ln_phi.row(k))
+                .map_collect(|&x, &l| if x > 0.0 { x.ln() + l } else { 0.0 })
+        };
+        let ln_f_ref = ln_f(reference);
+        let res = (0..n)
+            .filter(|&k| beta[k] > 0.0)
+            .map(|k| norm(&(ln_f(k) - &ln_f_ref)))
+            .sum::<f64>();
+        log_iter!(verbosity, " {:4} | {:14.8e} | {:.8}", iter, res, beta);
+        if res < tol && iter > 1 {
+            log_result!(
+                verbosity,
+                "{}: calculation converged in {} step(s)\n",
+                name,
+                iter
+            );
+            return Ok(beta);
+        }
+
+        // update states
+        for (k, s) in states.iter_mut().enumerate() {
+            let x_k = x.row(k).to_owned();
+            *s = State::new_npt(
+                &s.eos,
+                feed_state.temperature,
+                pressure,
+                &(&x_k / x_k.sum() * total_moles),
+                DensityInitialization::InitialDensity(s.density),
+            )?;
+        }
+    }
+    Err(EosError::NotConverged(name.to_owned()))
+}
+
+/// Distribute the feed to the given phases according to the phase fractions.
+fn split_feed<U: EosUnit, E: EquationOfState>(
+    feed_state: &State<U, E>,
+    pressure: QuantityScalar<U>,
+    states: &[State<U, E>],
+    beta: &Array1<f64>,
+) -> EosResult<Vec<State<U, E>>> {
+    states
+        .iter()
+        .zip(beta.iter())
+        .map(|(s, &b)| {
+            State::new_npt(
+                &s.eos,
+                feed_state.temperature,
+                pressure,
+                &(&s.molefracs * b * feed_state.total_moles),
+                DensityInitialization::InitialDensity(s.density),
+            )
+        })
+        .collect()
+}
+
+/// Sort the phases by increasing density.
+///
+/// Returns [EosError::NotConverged] if the density of a phase is not
+/// finite, e.g., after a diverging iteration.
+fn sort_by_density<U: EosUnit, E>(states: &mut [State<U, E>], name: &str) -> EosResult<()> {
+    for s in states.iter() {
+        if !s.density.to_reduced(U::reference_density())?.is_finite() {
+            return Err(EosError::NotConverged(name.to_owned()));
+        }
+    }
+    states.sort_by(|s1, s2| {
+        s1.density
+            .partial_cmp(&s2.density)
+            .unwrap_or(Ordering::Equal)
+    });
+    Ok(())
+}
+
+/// Determine the phase fractions of a multiphase system for given
+/// (scaled) inverse fugacity coefficients `a` by minimizing
+/// Q = sum(beta) - sum(z ln(a^T beta)) subject to beta >= 0.
+fn multiphase_rachford_rice(
+    feed: &Array1<f64>,
+    a: &Array2<f64>,
+    beta_in: Array1<f64>,
+) -> EosResult<Array1<f64>> {
+    let q = |beta: &Array1<f64>| beta.sum() - (feed * &a.t().dot(beta).mapv(f64::ln)).sum();
+    let mut beta = beta_in;
+    for _ in 0..MAX_ITER_BETA {
+        let e = a.t().dot(&beta);
+        let x = a * &(feed / &e);
+        let g = 1.0 - x.sum_axis(Axis(1));
+        let h = Array2::from_shape_fn((beta.len(), beta.len()), |(k, l)| {
+            Zip::from(x.row(k))
+                .and(x.row(l))
+                .and(feed)
+                .fold(
+                    0.0,
+                    |acc, &xk, &xl, &z| {
+                        if z > 0.0 {
+                            acc + xk * xl / z
+                        } else {
+                            acc
+                        }
+                    },
+                )
+        });
+
+        // phases with vanishing fractions are only considered
+        // if they lower the objective function
+        let free: Vec<_> = (0..beta.len())
+            .filter(|&k| beta[k] > 0.0 || g[k] < 0.0)
+            .collect();
+        let g_free = Array1::from_shape_fn(free.len(), |i| g[free[i]]);
+        if norm(&g_free) < TOL_BETA {
+            return Ok(beta);
+        }
+        let h_free =
+            Array2::from_shape_fn((free.len(), free.len()), |(i, j)| h[(free[i], free[j])]);
+        let delta = LU::new(h_free)?.solve(&g_free);
+
+        // limit the step to the feasible region and ensure descent
+        let mut step = free
+            .iter()
+            .zip(delta.iter())
+            .filter(|&(&k, &d)| beta[k] - d < 0.0)
+            .map(|(&k, &d)| beta[k] / d)
+            .fold(1.0, f64::min);
+        let q0 = q(&beta);
+        let mut beta_new = beta.clone();
+        loop {
+            for (&k, &d) in free.iter().zip(delta.iter()) {
+                beta_new[k] = (beta[k] - step * d).max(0.0);
+            }
+            if q(&beta_new) <= q0 || step < 1e-10 {
+                break;
+            }
+            step *= 0.5;
+        }
+        if !beta_new.iter().all(|b| b.is_finite()) || beta_new.sum() <= 0.0 {
+            return Err(EosError::IterationFailed(String::from(
+                "multiphase_rachford_rice",
+            )));
+        }
+        beta = beta_new;
+    }
+    Ok(beta)
+}
+
 fn rachford_rice(feed: &Array1<f64>, k: &Array1<f64>, beta_in: Option<f64>) -> EosResult<f64> {
     const MAX_ITER: usize = 10;
     const ABS_TOL: f64 = 1e-6;
@@ -374,3 +782,89 @@ fn rachford_rice(feed: &Array1<f64>, k: &Array1<f64>, beta_in: Option<f64>) -> E
 
     Ok(beta)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters, PengRobinsonRecord};
+    use crate::parameter::{Identifier, Parameter, PureRecord};
+    use approx::assert_relative_eq;
+    use quantity::si::*;
+
+    #[test]
+    fn test_tp_flash_acceleration() -> EosResult<()> {
+        let eos = propane_butane();
+        let feed = arr1(&[0.5, 0.5]) * MOL;
+        // close to the critical point of the mixture (401.7 K, 42.2 bar)
+        let t = 400.0 * KELVIN;
+        let p = 41.0 * BAR;
+        let [reference, dem, none] = [Acceleration::Gdem, Acceleration::Dem, Acceleration::None]
+            .map(|acceleration| {
+                let options = SolverOptions::new().acceleration(acceleration);
+                PhaseEquilibrium::tp_flash(&eos, t, p, &feed, None, options, None)
+            });
+        let reference = reference?;
+        for vle in [dem?, none?] {
+            assert_relative_eq!(
+                vle.vapor().molefracs,
+                reference.vapor().molefracs,
+                max_relative = 1e-6
+            );
+            assert_relative_eq!(
+                vle.liquid().molefracs,
+                reference.liquid().molefracs,
+                max_relative = 1e-6
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_tp_flash_vlle() -> EosResult<()> {
+        // water, n-hexane and nitrogen
+        let records = [
+            (647.1, 22064000.0, 0.345, 18.015),
+            (507.6, 3025000.0, 0.301, 86.178),
+            (126.2, 3400000.0, 0.037, 28.014),
+        ]
+        .iter()
+        .map(|&(tc, pc, w, mw)| {
+            let record = PengRobinsonRecord::new(tc, pc, w);
+            PureRecord::new(Identifier::default(), mw, record, None)
+        })
+        .collect();
+        let k_ij = arr2(&[[0.0, 0.5, 0.4], [0.5, 0.0, 0.1], [0.4, 0.1, 0.0]]);
+        let parameters = PengRobinsonParameters::from_records(records, k_ij)?;
+        let eos = Rc::new(PengRobinson::new(Rc::new(parameters)));
+
+        let feed = arr1(&[0.45, 0.45, 0.1]) * MOL;
+        let t = 320.0 * KELVIN;
+        let p = BAR;
+        let vlle = PhaseEquilibrium::tp_flash_vlle(&eos, t, p, &feed, Default::default())?;
+        let states = [vlle.vapor(), vlle.liquid1(), vlle.liquid2()];
+
+        // mass balance
+        let moles = states
+            .iter()
+            .fold(&feed * 0.0, |acc, s| acc + s.moles.clone());
+        for i in 0..3 {
+            assert_relative_eq!(moles.get(i), feed.get(i), max_relative = 1e-8);
+        }
+
+        // equal fugacities in all phases
+        let ln_f = states.map(|s| s.ln_phi() + s.molefracs.mapv(f64::ln));
+        assert_relative_eq!(ln_f[0], ln_f[1], epsilon = 1e-6);
+        assert_relative_eq!(ln_f[0], ln_f[2], epsilon = 1e-6);
+
+        // the vapor is mostly nitrogen, the liquids are water and hexane rich
+        assert!(states[0].molefracs[2] > 0.2);
+        assert!(states[1].molefracs[1] > 0.9);
+        assert!(states[2].molefracs[0] > 0.9);
+
+        // without nitrogen only two liquid phases are present
+        let feed = arr1(&[0.5, 0.5, 0.0]) * MOL;
+        assert!(PhaseEquilibrium::tp_flash_vlle(&eos, t, p, &feed, Default::default()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use super::{PhaseEquilibrium, SolverOptions, Verbosity};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::{check_moles, Contributions, DensityInitialization, State};
+use crate::EosUnit;
+use ndarray::*;
+use num_dual::linalg::{norm, LU};
+use quantity::{QuantityArray1, QuantityScalar};
+use std::rc::Rc;
+
+const MAX_ITER_TV: usize = 50;
+const TOL_TV: f64 = 1e-10;
+const MAX_ITER_INIT: usize = 50;
+const TOL_INIT: f64 = 1e-3;
+const MAX_LN_P_STEP: f64 = 2.0;
+const MAX_STEP_FRACTION: f64 = 0.9;
+
+/// # Flash calculations
+impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
+    /// Perform a flash calculation for given temperature, total volume
+    /// and amount of substance, e.g., of a closed vessel.
+    ///
+    /// The total Helmholtz energy is minimized with respect to the amount
+    /// of substance and the volume of the vapor phase using a Newton
+    /// method. The phases are initialized with Tp-flash calculations,
+    /// iterating the pressure until the phases approximately fill the
+    /// given volume. If no phase split is found,
+    /// [EosError::NoPhaseSplit] is returned.
+    pub fn tv_flash(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        volume: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+        options: SolverOptions,
+    ) -> EosResult<Self> {
+        check_moles(moles)?;
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_TV, TOL_TV);
+
+        let n = moles.to_reduced(U::reference_moles())?;
+        let v = volume.to_reduced(U::reference_volume())?;
+        let mut vle = Self::tv_flash_init(eos, temperature, volume, moles)?;
+
+        log_iter!(
+            verbosity,
+            " iter |    residual    |  phase I mole fractions  |  phase II mole fractions  "
+        );
+        log_iter!(verbosity, "{:-<77}", "");
+
+        for i in 1..=max_iter {
+            let (vapor, liquid) = (vle.vapor(), vle.liquid());
+
+            // gradient of the total Helmholtz energy
+            let mu_v = vapor
+                .chemical_potential(Contributions::Total)
+                .to_reduced(U::reference_molar_energy())?;
+            let mu_l = liquid
+                .chemical_potential(Contributions::Total)
+                .to_reduced(U::reference_molar_energy())?;
+            let p_v = vapor
+                .pressure(Contributions::Total)
+                .to_reduced(U::reference_pressure())?;
+            let p_l = liquid
+                .pressure(Contributions::Total)
+                .to_reduced(U::reference_pressure())?;
+            let gradient = concatenate![Axis(0), mu_v - &mu_l, arr1(&[p_l - p_v])];
+            let res = norm(&gradient);
+            log_iter!(
+                verbosity,
+                " {:4} | {:14.8e} | {:.8} | {:.8}",
+                i,
+                res,
+                vapor.molefracs,
+                liquid.molefracs,
+            );
+            if res < tol {
+                log_result!(
+                    verbosity,
+                    "TV flash: calculation converged in {} step(s)\n",
+                    i
+                );
+                return vle.check_trivial_solution();
+            }
+
+            // Newton step
+            let hessian = helmholtz_energy_hessian(vapor)? + helmholtz_energy_hessian(liquid)?;
+            let dx = LU::new(hessian)?.solve(&gradient);
+            let n_v = vapor.moles.to_reduced(U::reference_moles())?;
+            let v_v = vapor.volume.to_reduced(U::reference_volume())?;
+            let x = concatenate![Axis(0), n_v, arr1(&[v_v])];
+            let x_total = concatenate![Axis(0), n, arr1(&[v])];
+
+            // keep the amounts and volumes of both phases positive
+            let alpha = x.iter().zip(x_total.iter()).zip(dx.iter()).fold(
+                1.0,
+                |alpha: f64, ((&x, &x_total), &dx)| {
+                    if dx > 0.0 {
+                        alpha.min(MAX_STEP_FRACTION * x / dx)
+                    } else if dx < 0.0 {
+                        alpha.min(MAX_STEP_FRACTION * (x - x_total) / dx)
+                    } else {
+                        alpha
+                    }
+                },
+            );
+            let x = x - alpha * &dx;
+            let nc = n.len();
+            let (n_v, v_v) = (x.slice(s![..nc]).to_owned(), x[nc]);
+            vle = Self::from_states(
+                State::new_nvt(
+                    eos,
+                    temperature,
+                    v_v * U::reference_volume(),
+                    &(&n_v * U::reference_moles()),
+                )?,
+                State::new_nvt(
+                    eos,
+                    temperature,
+                    (v - v_v) * U::reference_volume(),
+                    &((&n - &n_v) * U::reference_moles()),
+                )?,
+            );
+        }
+        Err(EosError::NotConverged("TV flash".to_owned()))
+    }
+
+    fn tv_flash_init(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        volume: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+    ) -> EosResult<Self> {
+        // start at the pressure of the homogeneous state or the ideal gas
+        let p_hom = State::new_nvt(eos, temperature, volume, moles)?.pressure(Contributions::Total);
+        let mut ln_p = if p_hom > 0.0 * U::reference_pressure() {
+            p_hom
+        } else {
+            moles.sum() * U::gas_constant() * temperature / volume
+        }
+        .to_reduced(U::reference_pressure())?
+        .ln();
+
+        // secant method (with bisection) for the pressure at which the
+        // phases of a Tp-flash fill the given volume
+        let mut vle: Option<Self> = None;
+        let mut previous: Option<(f64, f64)> = None;
+        let (mut ln_p_lower, mut ln_p_upper) = (None, None);
+        for _ in 0..MAX_ITER_INIT {
+            let pressure = ln_p.exp() * U::reference_pressure();
+            let state = State::new_npt(
+                eos,
+                temperature,
+                pressure,
+                moles,
+                DensityInitialization::None,
+            )?;
+            vle = vle
+                .as_ref()
+                .and_then(|init| {
+                    state
+                        .tp_flash(Some(init), SolverOptions::default(), None)
+                        .ok()
+                })
+                .or_else(|| state.tp_flash(None, SolverOptions::default(), None).ok())
+                .filter(|vle| !Self::is_trivial_solution(vle.vapor(), vle.liquid()));
+            let v = match &vle {
+                Some(vle) => vle.vapor().volume + vle.liquid().volume,
+                None => state.volume,
+            };
+            let f = (v / volume).into_value()?.ln();
+            if f.abs() < TOL_INIT && vle.is_some() {
+                break;
+            }
+
+            // the volume decreases with increasing pressure
+            if f > 0.0 {
+                ln_p_lower = Some(ln_p);
+            } else {
+                ln_p_upper = Some(ln_p);
+            }
+            let ln_p_new = match previous {
+                Some((ln_p_old, f_old)) if f != f_old => ln_p - f * (ln_p - ln_p_old) / (f - f_old),
+                _ => ln_p + f,
+            };
+            previous = Some((ln_p, f));
+            ln_p = match (ln_p_lower, ln_p_upper) {
+                (Some(lo), Some(hi)) if ln_p_new <= lo || ln_p_new >= hi => 0.5 * (lo + hi),
+                _ => ln_p_new.clamp(ln_p - MAX_LN_P_STEP, ln_p + MAX_LN_P_STEP),
+            };
+        }
+        let vle = vle.ok_or(EosError::NoPhaseSplit)?;
+
+        // scale the phase volumes to the given total volume
+        let scale = (volume / (vle.vapor().volume + vle.liquid().volume)).into_value()?;
+        Ok(Self::from_states(
+            State::new_nvt(
+                eos,
+                temperature,
+                scale * vle.vapor().volume,
+                &vle.vapor().moles,
+            )?,
+            State::new_nvt(
+                eos,
+                temperature,
+                scale * vle.liquid().volume,
+                &vle.liquid().moles,
+            )?,
+        ))
+    }
+}
+
+/// Hessian of the Helmholtz energy with respect to the amount
+/// of substance of each component and the volume.
+fn helmholtz_energy_hessian<U: EosUnit, E: EquationOfState>(
+    state: &State<U, E>,
+) -> EosResult<Array2<f64>> {
+    let dmu_dn = state
+        .dmu_dni(Contributions::Total)
+        .to_reduced(U::reference_molar_energy() / U::reference_moles())?;
+    let dp_dn = state
+        .dp_dni(Contributions::Total)
+        .to_reduced(U::reference_pressure() / U::reference_moles())?;
+    let dp_dv = state
+        .dp_dv(Contributions::Total)
+        .to_reduced(U::reference_pressure() / U::reference_volume())?;
+    Ok(concatenate![
+        Axis(0),
+        concatenate![Axis(1), dmu_dn, -&dp_dn.view().insert_axis(Axis(1))],
+        concatenate![
+            Axis(1),
+            -&dp_dn.view().insert_axis(Axis(0)),
+            arr2(&[[-dp_dv]])
+        ]
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use approx::assert_relative_eq;
+    use quantity::si::*;
+
+    #[test]
+    fn test_tv_flash() -> EosResult<()> {
+        let eos = propane_butane();
+        let t = 300.0 * KELVIN;
+        let moles = arr1(&[0.4, 0.6]) * MOL;
+        for &p in [4.0 * BAR, 5.0 * BAR].iter() {
+            let tp =
+                PhaseEquilibrium::tp_flash(&eos, t, p, &moles, None, Default::default(), None)?;
+            let volume = tp.vapor().volume + tp.liquid().volume;
+            let tv = PhaseEquilibrium::tv_flash(&eos, t, volume, &moles, Default::default())?;
+            assert_relative_eq!(
+                tv.vapor().pressure(Contributions::Total),
+                p,
+                max_relative = 1e-7
+            );
+            assert_relative_eq!(tv.liquid().moles, tp.liquid().moles, max_relative = 1e-6);
+        }
+
+        // closed vessel that is almost filled with liquid
+        let liquid = State::new_npt(&eos, t, 10.0 * BAR, &moles, DensityInitialization::Liquid)?;
+        let volume = 1.05 * liquid.volume;
+        let tv = PhaseEquilibrium::tv_flash(&eos, t, volume, &moles, Default::default())?;
+        assert_relative_eq!(
+            tv.vapor().volume + tv.liquid().volume,
+            volume,
+            max_relative = 1e-10
+        );
+        assert_relative_eq!(
+            tv.vapor().pressure(Contributions::Total),
+            tv.liquid().pressure(Contributions::Total),
+            max_relative = 1e-7
+        );
+        assert!(tv.liquid().volume > 0.9 * volume);
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use super::{PhaseEquilibrium, SolverOptions, Verbosity};
+use super::{PhaseEquilibrium, RetryPolicy, SolverOptions, TerminatedExt, Verbosity};
 use crate::density_iteration::pressure_spinodal;
 use crate::equation_of_state::EquationOfState;
 use crate::errors::{EosError, EosResult};
@@ -32,6 +32,26 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
         }
     }
 
+    /// Calculate a phase equilibrium for a pure component with
+    /// the options and initial values of a [RetryPolicy].
+    pub fn pure_with_retry(
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+        policy: &RetryPolicy<Option<PhaseEquilibrium<U, E, 2>>>,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
+    {
+        policy.run(|options, initial_state| {
+            Self::pure(
+                eos,
+                temperature_or_pressure,
+                initial_state.as_ref(),
+                options,
+            )
+        })
+    }
+
     /// Calculate a phase equilibrium for a pure component
     /// and given temperature.
     fn pure_t(
@@ -43,36 +63,67 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
     where
         QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
     {
-        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_PURE, TOL_PURE);
-
         // First use given initial state if applicable
-        let mut vle = initial_state.and_then(|init| {
-            Self::init_pure_state(init, temperature)
-                .and_then(|vle| vle.iterate_pure_t(max_iter, tol, verbosity))
-                .ok()
-        });
+        let mut vle = match initial_state {
+            Some(init) => Self::init_pure_state(init, temperature)
+                .and_then(|vle| vle.iterate_pure_t(&options))
+                .ok_or_terminated()?,
+            None => None,
+        };
+
+        // Next use the saturation table of the equation of state if available
+        if vle.is_none() {
+            vle = Self::init_pure_table(eos, temperature)
+                .and_then(|vle| vle.iterate_pure_t(&options))
+                .ok_or_terminated()?;
+        }
 
         // Next try to initialize with an ideal gas assumption
-        vle = vle.or_else(|| {
-            Self::init_pure_ideal_gas(eos, temperature)
-                .and_then(|vle| vle.iterate_pure_t(max_iter, tol, verbosity))
-                .ok()
-        });
+        if vle.is_none() {
+            vle = Self::init_pure_ideal_gas(eos, temperature)
+                .and_then(|vle| vle.iterate_pure_t(&options))
+                .ok_or_terminated()?;
+        }
 
         // Finally use the spinodal to initialize the calculation
-        vle.map_or_else(
-            || {
-                Self::init_pure_spinodal(eos, temperature)
-                    .and_then(|vle| vle.iterate_pure_t(max_iter, tol, verbosity))
+        match vle {
+            Some(vle) => Ok(vle),
+            None => Self::init_pure_spinodal(eos, temperature)
+                .and_then(|vle| vle.iterate_pure_t(&options))
+                .map_err(|e| Self::check_critical_temperature(eos, temperature, e)),
+        }
+    }
+
+    /// Replace the error of a failed calculation by an
+    /// [EosError::SuperCritical] if the temperature is above
+    /// the critical temperature.
+    fn check_critical_temperature(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        error: EosError,
+    ) -> EosError
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        if let EosError::Terminated(_) = error {
+            return error;
+        }
+        match State::critical_point(eos, None, None, SolverOptions::default()) {
+            Ok(cp) if temperature >= cp.temperature => EosError::SuperCritical {
+                component: None,
+                given: format!("T = {}", temperature),
+                critical: Some(format!("T_c = {}", cp.temperature)),
             },
-            Ok,
-        )
+            _ => error,
+        }
     }
 
-    fn iterate_pure_t(self, max_iter: usize, tol: f64, verbosity: Verbosity) -> EosResult<Self>
+    fn iterate_pure_t(self, options: &SolverOptions) -> EosResult<Self>
     where
         QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
     {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_PURE, TOL_PURE);
+        let damping = options.damping.unwrap_or(1.0);
         let mut p_old = self.vapor().pressure(Contributions::Total);
         let [mut vapor, mut liquid] = self.0;
 
@@ -133,8 +184,16 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
             }
 
             // Calculate Newton steps for the densities and update state.
-            let rho_l = liquid.density + (p_new - p_l) / p_rho_l;
-            let rho_v = vapor.density + (p_new - p_v) / p_rho_v;
+            let rho_l = liquid.density + damping * (p_new - p_l) / p_rho_l;
+            let mut rho_v = vapor.density + damping * (p_new - p_v) / p_rho_v;
+            // For very low vapor pressures, the Newton step can lead to negative
+            // vapor densities. In that case, assume the gas phase is ideal.
+            if rho_v.is_sign_negative() {
+                rho_v = p_new / kt;
+            }
+            let step_size = ((rho_l - liquid.density) / liquid.density)
+                .into_value()?
+                .hypot(((rho_v - vapor.density) / vapor.density).into_value()?);
             liquid = State::new_pure(&liquid.eos, liquid.temperature, rho_l)?;
             vapor = State::new_pure(&vapor.eos, vapor.temperature, rho_v)?;
             if Self::is_trivial_solution(&vapor, &liquid) {
@@ -143,6 +202,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
 
             // Check for convergence
             let res = (p_new - p_old).abs();
+            options.notify("pure_t", i, res.to_reduced(p_old)?, Some(step_size))?;
             log_iter!(
                 verbosity,
                 " {:4} | {:14.8e} | {:12.8} | {:12.8} | {:12.8} | {}",
@@ -159,7 +219,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
                     "PhaseEquilibrium::pure_t: calculation converged in {} step(s)\n",
                     i
                 );
-                return Ok(Self([vapor, liquid]));
+                return Ok(Self::new([vapor, liquid]));
             }
             p_old = p_new;
         }
@@ -178,13 +238,17 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
         QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
     {
         let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_PURE, TOL_PURE);
+        let damping = options.damping.unwrap_or(1.0);
 
         // Initialize the phase equilibrium
         let mut vle = match initial_state {
             Some(init) => init
                 .clone()
                 .update_pressure(init.vapor().temperature, pressure)?,
-            None => PhaseEquilibrium::init_pure_p(eos, pressure)?,
+            None => match Self::init_pure_table_p(eos, pressure) {
+                Ok(vle) => vle,
+                Err(_) => PhaseEquilibrium::init_pure_p(eos, pressure)?,
+            },
         };
 
         log_iter!(
@@ -220,12 +284,15 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
             let v_v = 1.0 / vle.vapor().density;
 
             // estimate the temperature steps
-            let delta_t = (pressure * (v_v - v_l) + (a_v - a_l)) / (s_v - s_l);
+            let delta_t = damping * (pressure * (v_v - v_l) + (a_v - a_l)) / (s_v - s_l);
             let t_new = vle.vapor().temperature + delta_t;
 
             // calculate Newton steps for the densities and update state.
-            let rho_l = vle.liquid().density + (pressure - p_l - p_t_l * delta_t) / p_rho_l;
-            let rho_v = vle.vapor().density + (pressure - p_v - p_t_v * delta_t) / p_rho_v;
+            let rho_l =
+                vle.liquid().density + damping * (pressure - p_l - p_t_l * delta_t) / p_rho_l;
+            let rho_v =
+                vle.vapor().density + damping * (pressure - p_v - p_t_v * delta_t) / p_rho_v;
+            let (rho_l_old, rho_v_old) = (vle.liquid().density, vle.vapor().density);
 
             if rho_l.is_sign_negative()
                 || rho_v.is_sign_negative()
@@ -237,7 +304,7 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
                     .check_trivial_solution()?;
             } else {
                 // update state
-                vle = Self([
+                vle = Self::new([
                     State::new_pure(eos, t_new, rho_v)?,
                     State::new_pure(eos, t_new, rho_l)?,
                 ]);
@@ -245,6 +312,17 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
 
             // check for convergence
             let res = delta_t.abs();
+            let step_size = res.to_reduced(vle.vapor().temperature)?.hypot(
+                ((vle.liquid().density - rho_l_old) / rho_l_old)
+                    .into_value()?
+                    .hypot(((vle.vapor().density - rho_v_old) / rho_v_old).into_value()?),
+            );
+            options.notify(
+                "pure_p",
+                i,
+                res.to_reduced(vle.vapor().temperature)?,
+                Some(step_size),
+            )?;
             log_iter!(
                 verbosity,
                 " {:4} | {:14.8e} | {:13.8} | {:12.8} | {:12.8}",
@@ -269,12 +347,44 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
     fn init_pure_state(initial_state: &Self, temperature: QuantityScalar<U>) -> EosResult<Self> {
         let vapor = initial_state.vapor().update_temperature(temperature)?;
         let liquid = initial_state.liquid().update_temperature(temperature)?;
-        Ok(Self([vapor, liquid]))
+        Ok(Self::new([vapor, liquid]))
+    }
+
+    fn init_pure_table(eos: &Rc<E>, temperature: QuantityScalar<U>) -> EosResult<Self> {
+        let (_, rho_l, rho_v) = eos
+            .saturation_table()
+            .and_then(|table| {
+                table.interpolate(temperature.to_reduced(U::reference_temperature()).ok()?)
+            })
+            .ok_or_else(|| EosError::UndeterminedState(String::from("no saturation table")))?;
+        Self::from_table(eos, temperature, rho_l, rho_v)
+    }
+
+    fn init_pure_table_p(eos: &Rc<E>, pressure: QuantityScalar<U>) -> EosResult<Self> {
+        let (t, rho_l, rho_v) = eos
+            .saturation_table()
+            .and_then(|table| {
+                table.interpolate_pressure(pressure.to_reduced(U::reference_pressure()).ok()?)
+            })
+            .ok_or_else(|| EosError::UndeterminedState(String::from("no saturation table")))?;
+        Self::from_table(eos, t * U::reference_temperature(), rho_l, rho_v)
+    }
+
+    fn from_table(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        liquid_density: f64,
+        vapor_density: f64,
+    ) -> EosResult<Self> {
+        Ok(Self::new([
+            State::new_pure(eos, temperature, vapor_density * U::reference_density())?,
+            State::new_pure(eos, temperature, liquid_density * U::reference_density())?,
+        ]))
     }
 
     fn init_pure_ideal_gas(eos: &Rc<E>, temperature: QuantityScalar<U>) -> EosResult<Self> {
         let m = arr1(&[1.0]) * U::reference_moles();
-        let density = 0.75 * eos.max_density(None)?;
+        let density = 0.75 * eos.max_density_at_temperature(temperature, None)?;
         let liquid = State::new_nvt(eos, temperature, U::reference_moles() / density, &m)?;
         let z = liquid.compressibility(Contributions::Total);
         let mu = liquid.chemical_potential(Contributions::ResidualNvt);
@@ -299,12 +409,12 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
         temperature: QuantityScalar<U>,
         moles: &QuantityArray1<U>,
     ) -> EosResult<Self> {
-        let max_density = eos.max_density(Some(moles))?;
+        let max_density = eos.max_density_at_temperature(temperature, Some(moles))?;
         let sp = pressure_spinodal(eos, temperature, max_density * 1e-5, moles)?;
         let vapor = State::new_nvt(eos, temperature, moles.get(0) / sp.rho, moles)?;
         let sp = pressure_spinodal(eos, temperature, max_density, moles)?;
         let liquid = State::new_nvt(eos, temperature, moles.get(0) / sp.rho, moles)?;
-        Ok(PhaseEquilibrium([vapor, liquid]))
+        Ok(PhaseEquilibrium::new([vapor, liquid]))
     }
 
     /// Initialize a new VLE for a pure substance for a given pressure.
@@ -330,8 +440,13 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
         }
 
         let cp = State::critical_point(eos, None, None, SolverOptions::default())?;
-        if pressure > cp.pressure(Contributions::Total) {
-            return Err(EosError::SuperCritical);
+        let pc = cp.pressure(Contributions::Total);
+        if pressure > pc {
+            return Err(EosError::SuperCritical {
+                component: None,
+                given: format!("p = {}", pressure),
+                critical: Some(format!("p_c = {}", pc)),
+            });
         };
         if let Some(mut e) = vle {
             if e.vapor().density < cp.density {
@@ -419,51 +534,135 @@ impl<U: EosUnit, E: EquationOfState> PhaseEquilibrium<U, E, 2> {
             .collect()
     }
 
+    /// Calculate the boiling temperatures of a pure component
+    /// for all given pressures.
+    ///
+    /// Every converged phase equilibrium is used as initial value for the
+    /// next pressure (with a fallback to the default initialization), so that
+    /// tabulated saturation temperatures $T^\mathrm{sat}(p)$ can be evaluated
+    /// directly without inverting vapor pressures. Pressures for which no phase
+    /// equilibrium is found (e.g. above the critical point) result in `None`.
+    pub fn boiling_temperatures(
+        eos: &Rc<E>,
+        pressure: &QuantityArray1<U>,
+    ) -> EosResult<Vec<Option<QuantityScalar<U>>>>
+    where
+        QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
+    {
+        if eos.components() != 1 {
+            return Err(EosError::IncompatibleComponents(eos.components(), 1));
+        }
+        let mut vle: Option<Self> = None;
+        let mut temperatures = Vec::with_capacity(pressure.len());
+        for i in 0..pressure.len() {
+            let p = pressure.get(i);
+            let policy = match &vle {
+                Some(init) => {
+                    RetryPolicy::new().attempt(SolverOptions::default(), Some(init.clone()))
+                }
+                None => RetryPolicy::new(),
+            }
+            .attempt(SolverOptions::default(), None);
+            let res = Self::pure_with_retry(eos, p, &policy).ok_or_terminated()?;
+            temperatures.push(res.as_ref().map(|vle| vle.vapor().temperature));
+            if res.is_some() {
+                vle = res;
+            }
+        }
+        Ok(temperatures)
+    }
+
     /// Calculate the pure component phase equilibria of all
     /// components in the system.
     pub fn vle_pure_comps(
         eos: &Rc<E>,
         temperature_or_pressure: QuantityScalar<U>,
     ) -> Vec<Option<PhaseEquilibrium<U, E, 2>>>
+    where
+        QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
+    {
+        Self::try_vle_pure_comps(eos, temperature_or_pressure)
+            .into_iter()
+            .map(Result::ok)
+            .collect()
+    }
+
+    /// Calculate the pure component phase equilibria of all
+    /// components in the system and keep the errors of the
+    /// failed calculations, e.g., of supercritical components.
+    pub(crate) fn try_vle_pure_comps(
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+    ) -> Vec<EosResult<PhaseEquilibrium<U, E, 2>>>
     where
         QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
     {
         (0..eos.components())
-            .map(|i| {
-                let pure_eos = Rc::new(eos.subset(&[i]));
-                PhaseEquilibrium::pure(
-                    &pure_eos,
-                    temperature_or_pressure,
-                    None,
-                    SolverOptions::default(),
-                )
-                .ok()
-                .map(|vle_pure| {
-                    let mut moles_vapor = Array1::zeros(eos.components()) * U::reference_moles();
-                    let mut moles_liquid = moles_vapor.clone();
-                    moles_vapor
-                        .try_set(i, vle_pure.vapor().total_moles)
-                        .unwrap();
-                    moles_liquid
-                        .try_set(i, vle_pure.liquid().total_moles)
-                        .unwrap();
-                    let vapor = State::new_nvt(
-                        eos,
-                        vle_pure.vapor().temperature,
-                        vle_pure.vapor().volume,
-                        &moles_vapor,
-                    )
-                    .unwrap();
-                    let liquid = State::new_nvt(
-                        eos,
-                        vle_pure.liquid().temperature,
-                        vle_pure.liquid().volume,
-                        &moles_liquid,
-                    )
-                    .unwrap();
-                    PhaseEquilibrium::from_states(vapor, liquid)
-                })
-            })
+            .map(|i| Self::vle_pure_comp(eos, temperature_or_pressure, i))
             .collect()
     }
+
+    /// Calculate the phase equilibrium of the pure component `i`
+    /// in terms of states of the mixture.
+    pub(crate) fn vle_pure_comp(
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+        i: usize,
+    ) -> EosResult<PhaseEquilibrium<U, E, 2>>
+    where
+        QuantityScalar<U>: std::fmt::Display + std::fmt::LowerExp,
+    {
+        let pure_eos = Rc::new(eos.subset(&[i]));
+        let vle_pure = PhaseEquilibrium::pure(
+            &pure_eos,
+            temperature_or_pressure,
+            None,
+            SolverOptions::default(),
+        )
+        .map_err(|e| e.for_component(i))?;
+        let mut moles_vapor = Array1::zeros(eos.components()) * U::reference_moles();
+        let mut moles_liquid = moles_vapor.clone();
+        moles_vapor.try_set(i, vle_pure.vapor().total_moles)?;
+        moles_liquid.try_set(i, vle_pure.liquid().total_moles)?;
+        let vapor = State::new_nvt(
+            eos,
+            vle_pure.vapor().temperature,
+            vle_pure.vapor().volume,
+            &moles_vapor,
+        )?;
+        let liquid = State::new_nvt(
+            eos,
+            vle_pure.liquid().temperature,
+            vle_pure.liquid().volume,
+            &moles_liquid,
+        )?;
+        Ok(PhaseEquilibrium::from_states(vapor, liquid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::{propane, propane_butane};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    #[test]
+    fn test_boiling_temperatures() -> EosResult<()> {
+        let eos = propane();
+        // the third pressure is above the critical pressure
+        let p = arr1(&[1.0, 5.0, 100.0, 10.0]) * BAR;
+        let t = PhaseEquilibrium::boiling_temperatures(&eos, &p)?;
+        assert_eq!(t.len(), 4);
+        assert!(t[2].is_none());
+        for i in [0, 1, 3] {
+            let vle = PhaseEquilibrium::pure_p(&eos, p.get(i), None, Default::default())?;
+            assert_relative_eq!(t[i].unwrap(), vle.vapor().temperature, max_relative = 1e-8);
+        }
+
+        let eos = propane_butane();
+        assert!(PhaseEquilibrium::boiling_temperatures(&eos, &p).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,228 @@
+//! A property package interface for process simulators.
+//!
+//! The [PropertyPackage] trait mirrors the two central calls of the
+//! property package interfaces of process simulators (e.g., CAPE-OPEN):
+//! the calculation of the equilibrium state for a given flash specification
+//! (`CalcEquilibrium`) and the calculation of properties of a single phase
+//! (`CalcSinglePhaseProps`). The trait is implemented for every equation of
+//! state, so any model can be used by an adapter to an external simulator
+//! without depending on the details of the individual solvers.
+use crate::equation_of_state::{EquationOfState, MolarWeight};
+use crate::errors::{EosError, EosResult};
+use crate::phase_equilibria::{PhaseEquilibrium, PhaseEquilibriumN, SolverOptions};
+use crate::state::{Contributions, DensityInitialization, Property, State};
+use crate::EosUnit;
+use quantity::{QuantityArray1, QuantityScalar};
+use std::fmt;
+use std::rc::Rc;
+
+/// The two state variables that specify an equilibrium calculation.
+#[derive(Clone, Copy)]
+pub enum FlashSpecification<U> {
+    /// Temperature and pressure.
+    TP(QuantityScalar<U>, QuantityScalar<U>),
+    /// Pressure and molar enthalpy.
+    PH(QuantityScalar<U>, QuantityScalar<U>),
+    /// Pressure and molar entropy.
+    PS(QuantityScalar<U>, QuantityScalar<U>),
+    /// Temperature and total volume.
+    TV(QuantityScalar<U>, QuantityScalar<U>),
+}
+
+impl<U: EosUnit> fmt::Display for FlashSpecification<U>
+where
+    QuantityScalar<U>: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TP(t, p) => write!(f, "T = {}, p = {}", t, p),
+            Self::PH(p, h) => write!(f, "p = {}, h = {}", p, h),
+            Self::PS(p, s) => write!(f, "p = {}, s = {}", p, s),
+            Self::TV(t, v) => write!(f, "T = {}, V = {}", t, v),
+        }
+    }
+}
+
+/// The phase for which single phase properties are calculated.
+///
+/// Also used to select the phase in [State::new_npt_forced_phase].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhaseLabel {
+    /// The vapor phase.
+    Vapor,
+    /// The liquid phase.
+    Liquid,
+    /// The stable phase, or, in the two phase region, the phase
+    /// with the lower Gibbs energy.
+    Stable,
+}
+
+impl<U: EosUnit> From<PhaseLabel> for DensityInitialization<U> {
+    fn from(phase: PhaseLabel) -> Self {
+        match phase {
+            PhaseLabel::Vapor => Self::Vapor,
+            PhaseLabel::Liquid => Self::Liquid,
+            PhaseLabel::Stable => Self::None,
+        }
+    }
+}
+
+/// The calls of a property package of a process simulator.
+pub trait PropertyPackage<U: EosUnit> {
+    /// The equation of state of the phases.
+    type Eos: EquationOfState;
+
+    /// The number of components.
+    fn components(&self) -> usize;
+
+    /// Calculate the equilibrium state of a feed for the given specification.
+    ///
+    /// The result contains a single phase if the feed is stable.
+    fn calc_equilibrium(
+        &self,
+        specification: FlashSpecification<U>,
+        moles: &QuantityArray1<U>,
+        options: SolverOptions,
+    ) -> EosResult<PhaseEquilibriumN<U, Self::Eos>>;
+
+    /// Calculate properties of a single phase at given temperature,
+    /// pressure and amount of substance.
+    fn calc_single_phase_props(
+        &self,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+        phase: PhaseLabel,
+        properties: &[Property],
+    ) -> EosResult<Vec<QuantityScalar<U>>>;
+}
+
+impl<U: EosUnit, E: EquationOfState + MolarWeight<U>> PropertyPackage<U> for Rc<E>
+where
+    QuantityScalar<U>: fmt::Display,
+{
+    type Eos = E;
+
+    fn components(&self) -> usize {
+        EquationOfState::components(self.as_ref())
+    }
+
+    fn calc_equilibrium(
+        &self,
+        specification: FlashSpecification<U>,
+        moles: &QuantityArray1<U>,
+        options: SolverOptions,
+    ) -> EosResult<PhaseEquilibriumN<U, E>> {
+        let single_phase = |state: EosResult<State<U, E>>| -> EosResult<_> {
+            Ok(PhaseEquilibriumN::new(vec![state?]))
+        };
+        let res = match specification {
+            FlashSpecification::TP(t, p) => {
+                return PhaseEquilibriumN::tp_flash(self, t, p, moles, None, options)
+            }
+            FlashSpecification::PH(p, h) => {
+                PhaseEquilibrium::ph_flash(self, p, h, moles, None, options).map(Into::into)
+            }
+            FlashSpecification::PS(p, s) => {
+                PhaseEquilibrium::ps_flash(self, p, s, moles, None, options).map(Into::into)
+            }
+            FlashSpecification::TV(t, v) => {
+                PhaseEquilibrium::tv_flash(self, t, v, moles, options).map(Into::into)
+            }
+        };
+        match res {
+            Err(EosError::NoPhaseSplit) => match specification {
+                FlashSpecification::PH(p, h) => single_phase(State::new_nph(
+                    self,
+                    p,
+                    h,
+                    moles,
+                    DensityInitialization::None,
+                    None,
+                )),
+                FlashSpecification::PS(p, s) => single_phase(State::new_nps(
+                    self,
+                    p,
+                    s,
+                    moles,
+                    DensityInitialization::None,
+                    None,
+                )),
+                FlashSpecification::TV(t, v) => single_phase(State::new_nvt(self, t, v, moles)),
+                FlashSpecification::TP(_, _) => unreachable!(),
+            },
+            res => res,
+        }
+    }
+
+    fn calc_single_phase_props(
+        &self,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+        phase: PhaseLabel,
+        properties: &[Property],
+    ) -> EosResult<Vec<QuantityScalar<U>>> {
+        let state = State::new_npt(self, temperature, pressure, moles, phase.into())?;
+        Ok(properties
+            .iter()
+            .map(|&p| state.get(p, Contributions::Total))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    #[test]
+    fn test_property_package() -> EosResult<()> {
+        let eos = propane_butane();
+        let moles = arr1(&[0.5, 0.5]) * MOL;
+        let options = SolverOptions::default();
+        assert_eq!(PropertyPackage::<SIUnit>::components(&eos), 2);
+
+        // two phases at the specified temperature and pressure
+        let (t, p) = (300.0 * KELVIN, 5.0 * BAR);
+        let tp = eos.calc_equilibrium(FlashSpecification::TP(t, p), &moles, options.clone())?;
+        assert_eq!(tp.len(), 2);
+
+        // the Ph flash reproduces the result of the Tp flash
+        let h = tp
+            .iter()
+            .map(|s| s.enthalpy(Contributions::Total))
+            .fold(0.0 * JOULE, |acc, h| acc + h)
+            / moles.sum();
+        let ph = eos.calc_equilibrium(FlashSpecification::PH(p, h), &moles, options.clone())?;
+        assert_eq!(ph.len(), 2);
+        assert_relative_eq!(ph[0].temperature, t, max_relative = 1e-6);
+
+        // a single phase for a stable feed
+        let v = eos.calc_equilibrium(FlashSpecification::TP(t, BAR), &moles, options.clone())?;
+        assert_eq!(v.len(), 1);
+        let ps = FlashSpecification::PS(BAR, v[0].molar_entropy(Contributions::Total));
+        let ps = eos.calc_equilibrium(ps, &moles, options)?;
+        assert_eq!(ps.len(), 1);
+        assert_relative_eq!(ps[0].temperature, t, max_relative = 1e-6);
+
+        // single phase properties
+        let props = eos.calc_single_phase_props(
+            t,
+            BAR,
+            &moles,
+            PhaseLabel::Vapor,
+            &[Property::Density, Property::MolarEnthalpy],
+        )?;
+        assert_relative_eq!(props[0], v[0].density, max_relative = 1e-10);
+        assert_relative_eq!(
+            props[1],
+            v[0].molar_enthalpy(Contributions::Total),
+            max_relative = 1e-10
+        );
+        Ok(())
+    }
+}
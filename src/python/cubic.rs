@@ -1,4 +1,4 @@
-use crate::cubic::{PengRobinsonParameters, PengRobinsonRecord};
+use crate::cubic::{AlphaFunction, MixingRule, Nrtl, PengRobinsonParameters, PengRobinsonRecord};
 use crate::joback::JobackRecord;
 use crate::parameter::{
     BinaryRecord, Identifier, IdentifierOption, Parameter, ParameterError, PureRecord,
@@ -13,15 +13,122 @@ use std::convert::{TryFrom, TryInto};
 use std::rc::Rc;
 
 /// A pure substance parameter for the Peng-Robinson equation of state.
+///
+/// Parameters
+/// ----------
+/// tc : float
+///     The critical temperature in Kelvin.
+/// pc : float
+///     The critical pressure in Pascal.
+/// acentric_factor : float
+///     The acentric factor.
+/// c : float, optional
+///     The volume translation in m³/mol.
+/// influence_parameter : float, optional
+///     The influence parameter of the square gradient theory in J m⁵/mol².
+///
+/// Returns
+/// -------
+/// PengRobinsonRecord
 #[pyclass(name = "PengRobinsonRecord", unsendable)]
+#[pyo3(text_signature = "(tc, pc, acentric_factor, c=None, influence_parameter=None)")]
 #[derive(Clone)]
-pub struct PyPengRobinsonRecord(PengRobinsonRecord);
+pub struct PyPengRobinsonRecord(pub PengRobinsonRecord);
 
 #[pymethods]
 impl PyPengRobinsonRecord {
     #[new]
-    fn new(tc: f64, pc: f64, acentric_factor: f64) -> Self {
-        Self(PengRobinsonRecord::new(tc, pc, acentric_factor))
+    fn new(
+        tc: f64,
+        pc: f64,
+        acentric_factor: f64,
+        c: Option<f64>,
+        influence_parameter: Option<f64>,
+    ) -> Self {
+        let mut record = PengRobinsonRecord::new(tc, pc, acentric_factor);
+        if let Some(c) = c {
+            record = record.with_volume_translation(c);
+        }
+        if let Some(influence_parameter) = influence_parameter {
+            record = record.with_influence_parameter(influence_parameter);
+        }
+        Self(record)
+    }
+
+    /// Return a copy of the record with a volume translation
+    /// estimated from the Rackett compressibility factor.
+    ///
+    /// Parameters
+    /// ----------
+    /// z_ra : float, optional
+    ///     The Rackett compressibility factor. If not given,
+    ///     it is estimated from the acentric factor.
+    ///
+    /// Returns
+    /// -------
+    /// PengRobinsonRecord
+    #[pyo3(text_signature = "($self, z_ra=None)")]
+    fn with_rackett_volume_translation(&self, z_ra: Option<f64>) -> Self {
+        Self(self.0.clone().with_rackett_volume_translation(z_ra))
+    }
+
+    /// Return a copy of the record with the alpha function of Twu et al. (1991).
+    ///
+    /// Parameters
+    /// ----------
+    /// l : float
+    ///     The parameter L of the alpha function.
+    /// m : float
+    ///     The parameter M of the alpha function.
+    /// n : float
+    ///     The parameter N of the alpha function.
+    ///
+    /// Returns
+    /// -------
+    /// PengRobinsonRecord
+    #[pyo3(text_signature = "($self, l, m, n)")]
+    fn with_twu_alpha(&self, l: f64, m: f64, n: f64) -> Self {
+        Self(
+            self.0
+                .clone()
+                .with_alpha_function(AlphaFunction::Twu91 { l, m, n }),
+        )
+    }
+
+    /// Return a copy of the record with the alpha function
+    /// of Mathias and Copeman (1983).
+    ///
+    /// Parameters
+    /// ----------
+    /// c1 : float
+    ///     The first parameter of the alpha function.
+    /// c2 : float
+    ///     The second parameter of the alpha function.
+    /// c3 : float
+    ///     The third parameter of the alpha function.
+    ///
+    /// Returns
+    /// -------
+    /// PengRobinsonRecord
+    #[pyo3(text_signature = "($self, c1, c2, c3)")]
+    fn with_mathias_copeman_alpha(&self, c1: f64, c2: f64, c3: f64) -> Self {
+        Self(
+            self.0
+                .clone()
+                .with_alpha_function(AlphaFunction::MathiasCopeman { c1, c2, c3 }),
+        )
+    }
+
+    /// The volume translation in m³/mol.
+    #[getter]
+    fn get_c(&self) -> Option<f64> {
+        self.0.volume_translation()
+    }
+
+    /// The influence parameter in J m⁵/mol².
+    #[getter]
+    fn get_influence_parameter(&self) -> Option<f64> {
+        self.0.influence_parameter()
     }
 
     fn __repr__(&self) -> PyResult<String> {
@@ -63,9 +170,69 @@ impl_binary_record!();
 pub struct PyPengRobinsonParameters(pub Rc<PengRobinsonParameters>);
 
 impl_parameter!(PengRobinsonParameters, PyPengRobinsonParameters);
+impl_parameter_database!(PengRobinsonParameters, PyPengRobinsonParameters);
 
 #[pymethods]
 impl PyPengRobinsonParameters {
+    /// Return a copy of the parameters that uses the Huron-Vidal
+    /// mixing rule with the given activity coefficient model.
+    ///
+    /// Parameters
+    /// ----------
+    /// model : Nrtl
+    ///     The activity coefficient model.
+    ///
+    /// Returns
+    /// -------
+    /// PengRobinsonParameters
+    #[pyo3(text_signature = "($self, model)")]
+    fn with_huron_vidal(&self, model: PyNrtl) -> PyResult<Self> {
+        let mixing_rule = MixingRule::HuronVidal(Rc::new(model.0));
+        Ok(Self(Rc::new(
+            self.0.as_ref().clone().with_mixing_rule(mixing_rule)?,
+        )))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(self.0.to_string())
+    }
+}
+
+/// The NRTL activity coefficient model for the
+/// Huron-Vidal mixing rule of cubic equations of state.
+///
+/// Parameters
+/// ----------
+/// a : numpy.ndarray[float]
+///     The dimensionless parameters a_ij of tau_ij = a_ij + b_ij / T.
+/// b : numpy.ndarray[float]
+///     The parameters b_ij in Kelvin.
+/// alpha : numpy.ndarray[float]
+///     The non-randomness parameters.
+///
+/// Returns
+/// -------
+/// Nrtl
+#[pyclass(name = "Nrtl", unsendable)]
+#[pyo3(text_signature = "(a, b, alpha)")]
+#[derive(Clone)]
+pub struct PyNrtl(pub Nrtl);
+
+#[pymethods]
+impl PyNrtl {
+    #[new]
+    fn new(
+        a: PyReadonlyArray2<f64>,
+        b: PyReadonlyArray2<f64>,
+        alpha: PyReadonlyArray2<f64>,
+    ) -> PyResult<Self> {
+        Ok(Self(Nrtl::new(
+            a.to_owned_array(),
+            b.to_owned_array(),
+            alpha.to_owned_array(),
+        )?))
+    }
+
     fn __repr__(&self) -> PyResult<String> {
         Ok(self.0.to_string())
     }
@@ -0,0 +1,203 @@
+use crate::ideal_gas::{Dippr, DipprRecord};
+use crate::impl_json_handling;
+use crate::parameter::{IdentifierOption, ParameterError};
+use crate::EosError;
+use numpy::PyReadonlyArray1;
+use pyo3::prelude::*;
+use quantity::python::PySINumber;
+use quantity::si::SIUnit;
+
+/// A DIPPR correlation for the ideal gas heat capacity
+/// of a pure component.
+///
+/// The coefficients are given in the units of the DIPPR
+/// database, i.e., for heat capacities in J/(kmol K).
+/// Use `DipprRecord.dippr100`, `DipprRecord.dippr107` or
+/// `DipprRecord.dippr127` to create a record. The record can be
+/// used as the ideal gas record in the pure records of models
+/// with a DIPPR ideal gas contribution.
+#[pyclass(name = "DipprRecord")]
+#[derive(Clone)]
+pub struct PyDipprRecord(pub DipprRecord);
+
+#[pymethods]
+impl PyDipprRecord {
+    /// Create a record for DIPPR equation 100 (polynomial).
+    ///
+    /// Parameters
+    /// ----------
+    /// a : float
+    ///     zeroth order coefficient
+    /// b : float
+    ///     first order coefficient
+    /// c : float, optional
+    ///     second order coefficient
+    /// d : float, optional
+    ///     third order coefficient
+    /// e : float, optional
+    ///     fourth order coefficient
+    ///
+    /// Returns
+    /// -------
+    /// DipprRecord
+    #[staticmethod]
+    #[args(c = "0.0", d = "0.0", e = "0.0")]
+    #[pyo3(text_signature = "(a, b, c=0.0, d=0.0, e=0.0)")]
+    fn dippr100(a: f64, b: f64, c: f64, d: f64, e: f64) -> Self {
+        Self(DipprRecord::dippr100(a, b, c, d, e))
+    }
+
+    /// Create a record for DIPPR equation 107 (Aly-Lee).
+    ///
+    /// Parameters
+    /// ----------
+    /// a : float
+    /// b : float
+    /// c : float
+    /// d : float
+    /// e : float
+    ///
+    /// Returns
+    /// -------
+    /// DipprRecord
+    #[staticmethod]
+    #[pyo3(text_signature = "(a, b, c, d, e)")]
+    fn dippr107(a: f64, b: f64, c: f64, d: f64, e: f64) -> Self {
+        Self(DipprRecord::dippr107(a, b, c, d, e))
+    }
+
+    /// Create a record for DIPPR equation 127 (Einstein functions).
+    ///
+    /// Parameters
+    /// ----------
+    /// a : float
+    /// b : float
+    /// c : float
+    /// d : float
+    /// e : float
+    /// f : float
+    /// g : float
+    ///
+    /// Returns
+    /// -------
+    /// DipprRecord
+    #[staticmethod]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(text_signature = "(a, b, c, d, e, f, g)")]
+    fn dippr127(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64, g: f64) -> Self {
+        Self(DipprRecord::dippr127(a, b, c, d, e, f, g))
+    }
+
+    /// The number of the DIPPR equation.
+    #[getter]
+    fn get_equation(&self) -> usize {
+        self.0.equation()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(self.0.to_string())
+    }
+}
+
+impl_json_handling!(PyDipprRecord);
+
+/// The ideal gas model from DIPPR correlations.
+///
+/// Can be used to evaluate the ideal gas heat capacity
+/// independently of an equation of state.
+///
+/// Parameters
+/// ----------
+/// records : [DipprRecord]
+///     The DIPPR correlations of every component.
+///
+/// Returns
+/// -------
+/// Dippr
+#[pyclass(name = "Dippr", unsendable)]
+#[derive(Clone)]
+#[pyo3(text_signature = "(records)")]
+pub struct PyDippr(pub Dippr);
+
+#[pymethods]
+impl PyDippr {
+    #[new]
+    fn new(records: Vec<PyDipprRecord>) -> Self {
+        Self(Dippr::new(records.into_iter().map(|r| r.0).collect()))
+    }
+
+    /// Read the DIPPR records of the given substances from the
+    /// ideal gas records of a json file of pure records.
+    ///
+    /// Parameters
+    /// ----------
+    /// substances : List[str]
+    ///     The substances to search.
+    /// path : str
+    ///     Path to file containing the pure records.
+    /// search_option : IdentifierOption, optional, defaults to IdentifierOption.Name
+    ///     Identifier that is used to search substance.
+    ///
+    /// Returns
+    /// -------
+    /// Dippr
+    #[staticmethod]
+    #[pyo3(text_signature = "(substances, path, search_option=None)")]
+    fn from_json(
+        substances: Vec<&str>,
+        path: String,
+        search_option: Option<IdentifierOption>,
+    ) -> Result<Self, ParameterError> {
+        Ok(Self(Dippr::from_json(
+            substances,
+            path,
+            search_option.unwrap_or(IdentifierOption::Name),
+        )?))
+    }
+
+    /// Calculate the ideal gas heat capacity.
+    ///
+    /// Parameters
+    /// ----------
+    /// temperature : SINumber
+    ///     The temperature.
+    /// molefracs : numpy.ndarray[float], optional
+    ///     The mole fractions of the mixture.
+    ///     Only optional for a pure component.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    #[args(molefracs = "None")]
+    #[pyo3(text_signature = "($self, temperature, molefracs=None)")]
+    fn c_p_ideal_gas(
+        &self,
+        temperature: PySINumber,
+        molefracs: Option<PyReadonlyArray1<f64>>,
+    ) -> PyResult<PySINumber> {
+        let molefracs = match molefracs {
+            Some(x) => x.to_owned_array(),
+            None if self.0.records().len() == 1 => ndarray::arr1(&[1.0]),
+            None => {
+                return Err(EosError::UndeterminedState(String::from(
+                    "Missing mole fractions for a mixture",
+                ))
+                .into())
+            }
+        };
+        Ok(self.0.c_p::<SIUnit>(temperature.into(), &molefracs)?.into())
+    }
+
+    #[getter]
+    fn get_records(&self) -> Vec<PyDipprRecord> {
+        self.0
+            .records()
+            .iter()
+            .map(|r| PyDipprRecord(r.clone()))
+            .collect()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(self.0.to_string())
+    }
+}
@@ -18,6 +18,35 @@ macro_rules! impl_equation_of_state {
                 let m = moles.as_deref();
                 Ok(self.0.max_density(m)?.into())
             }
+
+            /// Return True if the equation of state describes a pure component.
+            ///
+            /// Returns
+            /// -------
+            /// bool
+            #[pyo3(text_signature = "($self)")]
+            fn is_pure(&self) -> bool {
+                self.0.is_pure()
+            }
+
+            /// Return the optional features supported by the equation of state.
+            ///
+            /// Returns
+            /// -------
+            /// dict
+            ///     Whether the model provides molar weights (`molar_weight`)
+            ///     and entropy scaling correlations (`entropy_scaling`),
+            ///     and the maximum number of components (`max_components`),
+            ///     which is None if the number of components is not limited.
+            #[pyo3(text_signature = "($self)")]
+            fn capabilities(&self, py: Python) -> PyResult<PyObject> {
+                let capabilities = self.0.capabilities();
+                let dict = pyo3::types::PyDict::new(py);
+                dict.set_item("molar_weight", capabilities.molar_weight)?;
+                dict.set_item("entropy_scaling", capabilities.entropy_scaling)?;
+                dict.set_item("max_components", capabilities.max_components)?;
+                Ok(dict.into())
+            }
         }
     };
 }
@@ -0,0 +1,98 @@
+use crate::parameter::ParameterError;
+use crate::EosError;
+use pyo3::create_exception;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+
+create_exception!(feos_core, EosException, PyRuntimeError);
+create_exception!(feos_core, NotConvergedError, EosException);
+create_exception!(feos_core, TerminatedError, EosException);
+create_exception!(feos_core, IterationFailedError, EosException);
+create_exception!(feos_core, TrivialSolutionError, EosException);
+create_exception!(feos_core, IncompatibleComponentsError, EosException);
+create_exception!(
+    feos_core,
+    MissingCompositionError,
+    IncompatibleComponentsError
+);
+create_exception!(feos_core, IncompatiblePhasesError, EosException);
+create_exception!(feos_core, InvalidStateError, EosException);
+create_exception!(feos_core, UndeterminedStateError, EosException);
+create_exception!(feos_core, SuperCriticalError, EosException);
+create_exception!(feos_core, UnknownPropertyError, EosException);
+create_exception!(feos_core, NoPhaseSplitError, EosException);
+create_exception!(feos_core, WrongUnitsError, EosException);
+create_exception!(feos_core, ParameterException, EosException);
+
+impl From<EosError> for PyErr {
+    fn from(e: EosError) -> PyErr {
+        let message = e.to_string();
+        match e {
+            EosError::NotConverged(_) => NotConvergedError::new_err(message),
+            EosError::Terminated(_) => TerminatedError::new_err(message),
+            EosError::IterationFailed(_) => IterationFailedError::new_err(message),
+            EosError::TrivialSolution => TrivialSolutionError::new_err(message),
+            EosError::IncompatibleComponents(_, _) => IncompatibleComponentsError::new_err(message),
+            EosError::MissingComposition { .. } => MissingCompositionError::new_err(message),
+            EosError::IncompatiblePhases(_, _) => IncompatiblePhasesError::new_err(message),
+            EosError::InvalidState(_, _, _) => InvalidStateError::new_err(message),
+            EosError::UndeterminedState(_) => UndeterminedStateError::new_err(message),
+            EosError::SuperCritical { .. } => SuperCriticalError::new_err(message),
+            EosError::UnknownProperty(_) => UnknownPropertyError::new_err(message),
+            EosError::NoPhaseSplit => NoPhaseSplitError::new_err(message),
+            EosError::WrongUnits(_, _) => WrongUnitsError::new_err(message),
+            EosError::ParameterError(_) => ParameterException::new_err(message),
+            _ => EosException::new_err(message),
+        }
+    }
+}
+
+impl From<ParameterError> for PyErr {
+    fn from(e: ParameterError) -> PyErr {
+        ParameterException::new_err(e.to_string())
+    }
+}
+
+/// Add the exception classes to a Python module.
+///
+/// All exceptions are derived from `EosException`, which itself
+/// is derived from `RuntimeError`.
+pub fn add_exceptions(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add("EosException", py.get_type::<EosException>())?;
+    m.add("NotConvergedError", py.get_type::<NotConvergedError>())?;
+    m.add("TerminatedError", py.get_type::<TerminatedError>())?;
+    m.add(
+        "IterationFailedError",
+        py.get_type::<IterationFailedError>(),
+    )?;
+    m.add(
+        "TrivialSolutionError",
+        py.get_type::<TrivialSolutionError>(),
+    )?;
+    m.add(
+        "IncompatibleComponentsError",
+        py.get_type::<IncompatibleComponentsError>(),
+    )?;
+    m.add(
+        "MissingCompositionError",
+        py.get_type::<MissingCompositionError>(),
+    )?;
+    m.add(
+        "IncompatiblePhasesError",
+        py.get_type::<IncompatiblePhasesError>(),
+    )?;
+    m.add("InvalidStateError", py.get_type::<InvalidStateError>())?;
+    m.add(
+        "UndeterminedStateError",
+        py.get_type::<UndeterminedStateError>(),
+    )?;
+    m.add("SuperCriticalError", py.get_type::<SuperCriticalError>())?;
+    m.add(
+        "UnknownPropertyError",
+        py.get_type::<UnknownPropertyError>(),
+    )?;
+    m.add("NoPhaseSplitError", py.get_type::<NoPhaseSplitError>())?;
+    m.add("WrongUnitsError", py.get_type::<WrongUnitsError>())?;
+    m.add("ParameterException", py.get_type::<ParameterException>())?;
+    Ok(())
+}
@@ -1,7 +1,11 @@
 use crate::impl_json_handling;
-use crate::joback::JobackRecord;
-use crate::parameter::ParameterError;
+use crate::joback::{Joback, JobackRecord};
+use crate::parameter::{FromSegments, ParameterError};
+use crate::EosError;
+use numpy::PyReadonlyArray1;
 use pyo3::prelude::*;
+use quantity::python::PySINumber;
+use quantity::si::SIUnit;
 
 /// Create a set of Joback ideal gas heat capacity parameters
 /// for a segment or a pure component.
@@ -39,9 +43,135 @@ impl PyJobackRecord {
         Self(JobackRecord::new(a, b, c, d, e))
     }
 
+    /// Create the Joback parameters of a pure component from
+    /// the parameters and counts of its groups.
+    ///
+    /// The result can be used as ideal gas record of a `PureRecord`.
+    ///
+    /// Parameters
+    /// ----------
+    /// segments : [(JobackRecord, float)]
+    ///     The parameters of every group and the number of
+    ///     occurrences of the group in the molecule.
+    ///
+    /// Returns
+    /// -------
+    /// JobackRecord
+    #[staticmethod]
+    #[pyo3(text_signature = "(segments)")]
+    fn from_segments(segments: Vec<(PyJobackRecord, f64)>) -> Result<Self, ParameterError> {
+        let segments: Vec<_> = segments.into_iter().map(|(r, n)| (r.0, n)).collect();
+        Ok(Self(JobackRecord::from_segments(&segments)?))
+    }
+
+    fn __add__(&self, other: PyJobackRecord) -> Self {
+        Self(self.0.clone() + other.0)
+    }
+
+    fn __mul__(&self, factor: f64) -> Self {
+        Self(self.0.clone() * factor)
+    }
+
+    fn __rmul__(&self, factor: f64) -> Self {
+        Self(self.0.clone() * factor)
+    }
+
     fn __repr__(&self) -> PyResult<String> {
         Ok(self.0.to_string())
     }
 }
 
 impl_json_handling!(PyJobackRecord);
+
+/// The ideal gas model according to Joback and Reid.
+///
+/// Can be used to evaluate the ideal gas heat capacity
+/// independently of an equation of state.
+///
+/// Parameters
+/// ----------
+/// records : [JobackRecord]
+///     The Joback parameters of every component.
+///
+/// Returns
+/// -------
+/// Joback
+#[pyclass(name = "Joback", unsendable)]
+#[derive(Clone)]
+#[pyo3(text_signature = "(records)")]
+pub struct PyJoback(pub Joback);
+
+#[pymethods]
+impl PyJoback {
+    #[new]
+    fn new(records: Vec<PyJobackRecord>) -> Self {
+        Self(Joback::new(records.into_iter().map(|r| r.0).collect()))
+    }
+
+    /// Create the Joback model from the group counts of every component.
+    ///
+    /// Parameters
+    /// ----------
+    /// segments : [[(JobackRecord, float)]]
+    ///     For every component, the parameters of every group and
+    ///     the number of occurrences of the group in the molecule.
+    ///
+    /// Returns
+    /// -------
+    /// Joback
+    #[staticmethod]
+    #[pyo3(text_signature = "(segments)")]
+    fn from_segments(segments: Vec<Vec<(PyJobackRecord, f64)>>) -> Result<Self, ParameterError> {
+        let records = segments
+            .into_iter()
+            .map(|s| Ok(PyJobackRecord::from_segments(s)?.0))
+            .collect::<Result<_, ParameterError>>()?;
+        Ok(Self(Joback::new(records)))
+    }
+
+    /// Calculate the ideal gas heat capacity.
+    ///
+    /// Parameters
+    /// ----------
+    /// temperature : SINumber
+    ///     The temperature.
+    /// molefracs : numpy.ndarray[float], optional
+    ///     The mole fractions of the mixture.
+    ///     Only optional for a pure component.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    #[args(molefracs = "None")]
+    #[pyo3(text_signature = "($self, temperature, molefracs=None)")]
+    fn c_p_ideal_gas(
+        &self,
+        temperature: PySINumber,
+        molefracs: Option<PyReadonlyArray1<f64>>,
+    ) -> PyResult<PySINumber> {
+        let molefracs = match molefracs {
+            Some(x) => x.to_owned_array(),
+            None if self.0.records.len() == 1 => ndarray::arr1(&[1.0]),
+            None => {
+                return Err(EosError::UndeterminedState(String::from(
+                    "Missing mole fractions for a mixture",
+                ))
+                .into())
+            }
+        };
+        Ok(self.0.c_p::<SIUnit>(temperature.into(), &molefracs)?.into())
+    }
+
+    #[getter]
+    fn get_records(&self) -> Vec<PyJobackRecord> {
+        self.0
+            .records
+            .iter()
+            .map(|r| PyJobackRecord(r.clone()))
+            .collect()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(self.0.to_string())
+    }
+}
@@ -1,18 +1,15 @@
-use crate::EosError;
-use pyo3::exceptions::PyRuntimeError;
-use pyo3::PyErr;
-
 pub mod cubic;
+pub mod dippr;
 mod equation_of_state;
+pub mod errors;
 pub mod joback;
+pub mod nasa;
+pub mod options;
 pub mod parameter;
 mod phase_equilibria;
-mod state;
+pub mod srk;
+pub mod state;
 pub mod statehd;
+mod surface_tension;
+pub mod units;
 pub mod user_defined;
-
-impl From<EosError> for PyErr {
-    fn from(e: EosError) -> PyErr {
-        PyRuntimeError::new_err(e.to_string())
-    }
-}
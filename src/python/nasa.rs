@@ -0,0 +1,144 @@
+use crate::ideal_gas::{Nasa, NasaRange, NasaRecord};
+use crate::impl_json_handling;
+use crate::parameter::ParameterError;
+use crate::EosError;
+use numpy::PyReadonlyArray1;
+use pyo3::prelude::*;
+use quantity::python::PySINumber;
+use quantity::si::SIUnit;
+
+/// NASA polynomials for the ideal gas heat capacity,
+/// enthalpy and entropy of a pure component.
+///
+/// Use `NasaRecord.nasa7` or `NasaRecord.nasa9` to create a record.
+#[pyclass(name = "NasaRecord")]
+#[derive(Clone)]
+pub struct PyNasaRecord(pub NasaRecord);
+
+#[pymethods]
+impl PyNasaRecord {
+    /// Create a record from NASA 7 polynomials.
+    ///
+    /// Parameters
+    /// ----------
+    /// t_low : float
+    ///     The lower limit of the low temperature range in Kelvin.
+    /// t_mid : float
+    ///     The upper limit of the low temperature range
+    ///     and lower limit of the high temperature range in Kelvin.
+    /// t_high : float
+    ///     The upper limit of the high temperature range in Kelvin.
+    /// low : [float]
+    ///     The seven coefficients of the low temperature range.
+    /// high : [float]
+    ///     The seven coefficients of the high temperature range.
+    ///
+    /// Returns
+    /// -------
+    /// NasaRecord
+    #[staticmethod]
+    #[pyo3(text_signature = "(t_low, t_mid, t_high, low, high)")]
+    fn nasa7(t_low: f64, t_mid: f64, t_high: f64, low: [f64; 7], high: [f64; 7]) -> Self {
+        Self(NasaRecord::nasa7(t_low, t_mid, t_high, low, high))
+    }
+
+    /// Create a record from NASA 9 polynomials.
+    ///
+    /// Parameters
+    /// ----------
+    /// ranges : [(float, float, [float], [float])]
+    ///     For every temperature range in ascending order: the lower
+    ///     and upper limit in Kelvin, the seven coefficients of the
+    ///     heat capacity and the two integration constants.
+    ///
+    /// Returns
+    /// -------
+    /// NasaRecord
+    #[staticmethod]
+    #[pyo3(text_signature = "(ranges)")]
+    fn nasa9(ranges: Vec<(f64, f64, [f64; 7], [f64; 2])>) -> Self {
+        let ranges = ranges
+            .into_iter()
+            .map(|(t_min, t_max, a, b)| NasaRange::new(t_min, t_max, a, b))
+            .collect();
+        Self(NasaRecord::nasa9(ranges))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(self.0.to_string())
+    }
+}
+
+impl_json_handling!(PyNasaRecord);
+
+/// The ideal gas model from NASA polynomials.
+///
+/// Can be used to evaluate the ideal gas heat capacity
+/// independently of an equation of state.
+///
+/// Parameters
+/// ----------
+/// records : [NasaRecord]
+///     The NASA polynomials of every component.
+///
+/// Returns
+/// -------
+/// Nasa
+#[pyclass(name = "Nasa", unsendable)]
+#[derive(Clone)]
+#[pyo3(text_signature = "(records)")]
+pub struct PyNasa(pub Nasa);
+
+#[pymethods]
+impl PyNasa {
+    #[new]
+    fn new(records: Vec<PyNasaRecord>) -> Result<Self, ParameterError> {
+        Ok(Self(Nasa::new(records.into_iter().map(|r| r.0).collect())?))
+    }
+
+    /// Calculate the ideal gas heat capacity.
+    ///
+    /// Parameters
+    /// ----------
+    /// temperature : SINumber
+    ///     The temperature.
+    /// molefracs : numpy.ndarray[float], optional
+    ///     The mole fractions of the mixture.
+    ///     Only optional for a pure component.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    #[args(molefracs = "None")]
+    #[pyo3(text_signature = "($self, temperature, molefracs=None)")]
+    fn c_p_ideal_gas(
+        &self,
+        temperature: PySINumber,
+        molefracs: Option<PyReadonlyArray1<f64>>,
+    ) -> PyResult<PySINumber> {
+        let molefracs = match molefracs {
+            Some(x) => x.to_owned_array(),
+            None if self.0.records().len() == 1 => ndarray::arr1(&[1.0]),
+            None => {
+                return Err(EosError::UndeterminedState(String::from(
+                    "Missing mole fractions for a mixture",
+                ))
+                .into())
+            }
+        };
+        Ok(self.0.c_p::<SIUnit>(temperature.into(), &molefracs)?.into())
+    }
+
+    #[getter]
+    fn get_records(&self) -> Vec<PyNasaRecord> {
+        self.0
+            .records()
+            .iter()
+            .map(|r| PyNasaRecord(r.clone()))
+            .collect()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(self.0.to_string())
+    }
+}
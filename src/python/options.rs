@@ -0,0 +1,223 @@
+use crate::{
+    Acceleration, IterationHistory, IterationRecord, JacobianUpdate, SolverObserver, SolverOptions,
+    Verbosity,
+};
+use pyo3::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Set default solver options for all subsequent calculations.
+///
+/// Options that are not specified explicitly in a function call
+/// are taken from these defaults before falling back to solver
+/// specific values. Used as a context manager, the previous
+/// defaults are restored when the context is left.
+///
+/// Parameters
+/// ----------
+/// max_iter : int, optional
+///     The maximum number of iterations.
+/// tol: float, optional
+///     The solution tolerance.
+/// verbosity : Verbosity, optional
+///     The verbosity.
+/// jacobian : JacobianUpdate, optional
+///     The treatment of the Jacobian in repeated Newton steps,
+///     e.g., in bubble and dew point calculations.
+/// damping : float, optional
+///     Factor (between 0 and 1) by which Newton steps are damped.
+/// history : bool, optional
+///     Record the residuals and step sizes of all solver iterations
+///     within the context. Defaults to False.
+/// callback : callable, optional
+///     Function that is called with the arguments (solver, iteration,
+///     residual) after every solver iteration within the context.
+///     If the function returns False, the solver is terminated
+///     with a TerminatedError.
+///
+/// Returns
+/// -------
+/// options
+///
+/// Examples
+/// --------
+/// >>> with options(verbosity=Verbosity.Iter):
+/// ...     vle = PhaseEquilibrium.pure(eos, 300 * KELVIN)
+///
+/// >>> with options(history=True) as opt:
+/// ...     vle = PhaseEquilibrium.pure(eos, 300 * KELVIN)
+/// >>> opt.history  # [(solver, iteration, residual, step_size), ...]
+///
+/// >>> with options(callback=lambda solver, i, res: print(solver, i, res)):
+/// ...     vle = PhaseEquilibrium.pure(eos, 300 * KELVIN)
+#[pyclass(name = "options", unsendable)]
+#[pyo3(
+    text_signature = "(max_iter=None, tol=None, verbosity=None, jacobian=None, damping=None, history=False, callback=None)"
+)]
+pub struct PyOptions {
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+    verbosity: Option<Verbosity>,
+    jacobian: Option<JacobianUpdate>,
+    damping: Option<f64>,
+    history: Option<Rc<RefCell<IterationHistory>>>,
+    callback: Option<PyObject>,
+    previous: Option<SolverOptions>,
+}
+
+/// Observer that records the iterations and calls a Python function
+/// after every solver iteration.
+///
+/// The observer of an enclosing context is notified first, so that
+/// nested contexts do not disable the history or the callbacks of
+/// outer contexts.
+struct PyObserver {
+    history: Option<Rc<RefCell<IterationHistory>>>,
+    callback: Option<PyObject>,
+    outer: Option<Rc<RefCell<dyn SolverObserver>>>,
+}
+
+impl SolverObserver for PyObserver {
+    fn on_iteration(&mut self, info: &IterationRecord) -> bool {
+        if let Some(Ok(mut outer)) = self.outer.as_ref().map(|o| o.try_borrow_mut()) {
+            if !outer.on_iteration(info) {
+                return false;
+            }
+        }
+        if let Some(history) = &self.history {
+            history.borrow_mut().on_iteration(info);
+        }
+        match &self.callback {
+            Some(callback) => Python::with_gil(|py| {
+                match callback.call1(py, (info.solver, info.iteration, info.residual)) {
+                    // only an explicit False terminates the solver
+                    Ok(result) => result.extract::<bool>(py).unwrap_or(true),
+                    // exceptions in the callback terminate the solver
+                    Err(e) => {
+                        e.print(py);
+                        false
+                    }
+                }
+            }),
+            None => true,
+        }
+    }
+}
+
+impl PyOptions {
+    /// The options combined with the current defaults.
+    fn options(&self) -> SolverOptions {
+        let mut options: SolverOptions = (self.max_iter, self.tol, self.verbosity).into();
+        if let Some(jacobian) = self.jacobian {
+            options = options.jacobian(jacobian);
+        }
+        if let Some(damping) = self.damping {
+            options = options.damping(damping);
+        }
+        if self.history.is_some() || self.callback.is_some() {
+            let observer = PyObserver {
+                history: self.history.clone(),
+                callback: self.callback.clone(),
+                outer: options.observer.take(),
+            };
+            options = options.observer(Rc::new(RefCell::new(observer)));
+        }
+        options
+    }
+}
+
+#[pymethods]
+impl PyOptions {
+    #[new]
+    #[args(
+        max_iter = "None",
+        tol = "None",
+        verbosity = "None",
+        jacobian = "None",
+        damping = "None",
+        history = "false",
+        callback = "None"
+    )]
+    fn new(
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<Verbosity>,
+        jacobian: Option<JacobianUpdate>,
+        damping: Option<f64>,
+        history: bool,
+        callback: Option<PyObject>,
+    ) -> Self {
+        Self {
+            max_iter,
+            tol,
+            verbosity,
+            jacobian,
+            damping,
+            history: history.then(|| Rc::new(RefCell::new(IterationHistory::default()))),
+            callback,
+            previous: None,
+        }
+    }
+
+    /// Set the options as defaults permanently.
+    #[pyo3(text_signature = "($self)")]
+    fn set(&self) {
+        SolverOptions::set_default(self.options());
+    }
+
+    /// Reset the defaults to solver specific values.
+    #[staticmethod]
+    #[pyo3(text_signature = "()")]
+    fn reset() {
+        SolverOptions::set_default(SolverOptions {
+            max_iter: None,
+            tol: None,
+            verbosity: Verbosity::None,
+            jacobian: JacobianUpdate::Exact,
+            acceleration: Acceleration::Gdem,
+            damping: None,
+            observer: None,
+        });
+    }
+
+    fn __enter__(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        if let Some(history) = &slf.history {
+            history.borrow_mut().0.clear();
+        }
+        let options = slf.options();
+        slf.previous = Some(SolverOptions::set_default(options));
+        slf
+    }
+
+    fn __exit__(&mut self, _exc_type: &PyAny, _exc_value: &PyAny, _traceback: &PyAny) {
+        if let Some(previous) = self.previous.take() {
+            SolverOptions::set_default(previous);
+        }
+    }
+
+    /// The iterations recorded within the context as a list of
+    /// (solver, iteration, residual, step_size) tuples.
+    #[getter]
+    fn get_history(&self) -> Vec<(&'static str, usize, f64, Option<f64>)> {
+        self.history.as_ref().map_or_else(Vec::new, |history| {
+            history
+                .borrow()
+                .0
+                .iter()
+                .map(|r| (r.solver, r.iteration, r.residual, r.step_size))
+                .collect()
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "options(max_iter={:?}, tol={:?}, verbosity={:?}, jacobian={:?}, damping={:?}, history={})",
+            self.max_iter,
+            self.tol,
+            self.verbosity,
+            self.jacobian,
+            self.damping,
+            self.history.is_some()
+        )
+    }
+}
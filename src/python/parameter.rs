@@ -1,13 +1,8 @@
 use crate::impl_json_handling;
-use crate::parameter::{BinaryRecord, ChemicalRecord, Identifier, ParameterError};
-use pyo3::exceptions::PyRuntimeError;
+use crate::parameter::{BinaryRecord, ChemicalRecord, Identifier, ParameterError, SegmentCount};
+use pyo3::exceptions::{PyIndexError, PyValueError};
 use pyo3::prelude::*;
-
-impl From<ParameterError> for PyErr {
-    fn from(e: ParameterError) -> PyErr {
-        PyRuntimeError::new_err(e.to_string())
-    }
-}
+use std::collections::HashMap;
 
 /// Create an identifier for a pure substance.
 ///
@@ -119,6 +114,68 @@ impl PyIdentifier {
 
 impl_json_handling!(PyIdentifier);
 
+/// Validate sparse binary interaction parameters given as
+/// `{(i, j): value}` and return the entries of the symmetric matrix.
+pub fn sparse_binary_entries(
+    n: usize,
+    entries: HashMap<(usize, usize), f64>,
+) -> PyResult<Vec<((usize, usize), f64)>> {
+    let mut keys: Vec<_> = entries.keys().copied().collect();
+    keys.sort_unstable();
+    let mut matrix_entries = Vec::with_capacity(2 * keys.len());
+    for (i, j) in keys {
+        let value = entries[&(i, j)];
+        if i >= n || j >= n {
+            return Err(PyIndexError::new_err(format!(
+                "Binary parameter ({}, {}) is out of range for {} components.",
+                i, j, n
+            )));
+        }
+        if i == j {
+            return Err(PyValueError::new_err(format!(
+                "Binary parameter ({}, {}) has to be specified for two different components.",
+                i, j
+            )));
+        }
+        if let Some(&value_ji) = entries.get(&(j, i)) {
+            if value_ji != value {
+                return Err(PyValueError::new_err(format!(
+                    "Binary parameters are not symmetric: ({}, {}) = {}, ({}, {}) = {}.",
+                    i, j, value, j, i, value_ji
+                )));
+            }
+        }
+        matrix_entries.push(((i, j), value));
+        matrix_entries.push(((j, i), value));
+    }
+    Ok(matrix_entries)
+}
+
+/// Validate sparse binary interaction parameters given as
+/// `{(identifier1, identifier2): value}` and return the entries
+/// of the symmetric matrix.
+pub fn sparse_binary_entries_from_identifiers(
+    identifiers: &[Option<String>],
+    entries: HashMap<(String, String), f64>,
+) -> PyResult<Vec<((usize, usize), f64)>> {
+    let index = |identifier: &str| {
+        identifiers
+            .iter()
+            .position(|i| i.as_deref() == Some(identifier))
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Substance '{}' is not part of the pure records.",
+                    identifier
+                ))
+            })
+    };
+    let entries = entries
+        .into_iter()
+        .map(|((id1, id2), value)| Ok(((index(&id1)?, index(&id2)?), value)))
+        .collect::<PyResult<_>>()?;
+    sparse_binary_entries(identifiers.len(), entries)
+}
+
 /// Create a chemical record for a pure substance.
 ///
 /// Parameters
@@ -167,6 +224,17 @@ impl PyChemicalRecord {
         self.0.bonds.clone()
     }
 
+    /// The number of occurences of each segment in the molecule
+    /// as used in the group contribution methods.
+    #[getter]
+    fn get_segment_count(&self) -> HashMap<String, usize> {
+        self.0
+            .segment_count()
+            .iter()
+            .map(|(s, &c)| (s.clone(), c))
+            .collect()
+    }
+
     fn __repr__(&self) -> PyResult<String> {
         Ok(self.0.to_string())
     }
@@ -542,9 +610,13 @@ macro_rules! impl_parameter {
             /// ----------
             /// pure_records : [PureRecord]
             ///     A list of pure component parameters.
-            /// binary_records : numpy.ndarray[float] or List[BinaryRecord]
-            ///     A matrix of binary interaction parameters or a list
-            ///     containing records for binary interactions.
+            /// binary_records : numpy.ndarray[float] or List[BinaryRecord] or dict
+            ///     A matrix of binary interaction parameters, a list
+            ///     containing records for binary interactions, or a dict
+            ///     containing binary interaction parameters for selected pairs,
+            ///     with either the indices of the components, e.g. {(0, 1): 0.1},
+            ///     or their identifiers, e.g. {("methane", "ethane"): 0.1}, as keys.
+            ///     Interactions that are not specified are set to their default value.
             /// search_option : IdentifierOption, optional, defaults to IdentifierOption.Name
             ///     Identifier that is used to search binary records.
             #[staticmethod]
@@ -554,7 +626,26 @@ macro_rules! impl_parameter {
                 binary_records: &PyAny,
                 search_option: Option<IdentifierOption>,
             ) -> PyResult<Self> {
-                let prs = pure_records.into_iter().map(|pr| pr.0).collect();
+                let prs: Vec<_> = pure_records.into_iter().map(|pr| pr.0).collect();
+                let search_option = search_option.unwrap_or(IdentifierOption::Name);
+                let sparse_entries = if let Ok(br) = binary_records
+                    .extract::<std::collections::HashMap<(usize, usize), f64>>()
+                {
+                    Some($crate::python::parameter::sparse_binary_entries(prs.len(), br)?)
+                } else if let Ok(br) = binary_records
+                    .extract::<std::collections::HashMap<(String, String), f64>>()
+                {
+                    let identifiers: Vec<_> = prs
+                        .iter()
+                        .map(|pr| pr.identifier.as_string(search_option))
+                        .collect();
+                    Some($crate::python::parameter::sparse_binary_entries_from_identifiers(
+                        &identifiers,
+                        br,
+                    )?)
+                } else {
+                    None
+                };
                 let brs = if let Ok(br) = binary_records.extract::<PyReadonlyArray2<f64>>() {
                     Ok(br.to_owned_array().mapv(|r| r.try_into().unwrap()))
                 } else if let Ok(br) = binary_records.extract::<Vec<PyBinaryRecord>>() {
@@ -562,14 +653,21 @@ macro_rules! impl_parameter {
                     Ok(<$parameter>::binary_matrix_from_records(
                         &prs,
                         &brs,
-                        search_option.unwrap_or(IdentifierOption::Name),
+                        search_option,
                     ))
+                } else if let Some(entries) = sparse_entries {
+                    let n = prs.len();
+                    let mut brs = ndarray::Array2::default((n, n));
+                    for ((i, j), r) in entries {
+                        brs[(i, j)] = r.try_into()?;
+                    }
+                    Ok(brs)
                 } else {
                     Err(PyErr::new::<PyTypeError, _>(format!(
                         "Could not parse binary input!"
                     )))
                 };
-                Ok(Self(Rc::new(<$parameter>::from_records(prs, brs.unwrap()))))
+                Ok(Self(Rc::new(<$parameter>::from_records(prs, brs.unwrap())?)))
             }
 
             /// Creates parameters for a pure component from a pure record.
@@ -580,8 +678,8 @@ macro_rules! impl_parameter {
             ///     The pure component parameters.
             #[staticmethod]
             #[pyo3(text_signature = "(pure_record)")]
-            fn new_pure(pure_record: PyPureRecord) -> Self {
-                Self(Rc::new(<$parameter>::new_pure(pure_record.0)))
+            fn new_pure(pure_record: PyPureRecord) -> PyResult<Self> {
+                Ok(Self(Rc::new(<$parameter>::new_pure(pure_record.0)?)))
             }
 
             /// Creates parameters for a binary system from pure records and an optional
@@ -599,7 +697,7 @@ macro_rules! impl_parameter {
                 pure_records: Vec<PyPureRecord>,
                 binary_record: Option<&PyAny>,
             ) -> PyResult<Self> {
-                let prs = pure_records.into_iter().map(|pr| pr.0).collect();
+                let prs: Vec<_> = pure_records.into_iter().map(|pr| pr.0).collect();
                 let br = binary_record
                     .map(|br| {
                         if let Ok(r) = br.extract::<f64>() {
@@ -613,7 +711,7 @@ macro_rules! impl_parameter {
                         }
                     })
                     .transpose()?;
-                Ok(Self(Rc::new(<$parameter>::new_binary(prs, br))))
+                Ok(Self(Rc::new(<$parameter>::new_binary(prs, br)?)))
             }
 
             /// Creates parameters from json files.
@@ -655,17 +753,60 @@ macro_rules! impl_parameter {
             ///     Path to file containing binary substance parameters.
             /// search_option : IdentifierOption, optional, defaults to IdentifierOption.Name
             ///     Identifier that is used to search substance.
+            /// duplicates : DuplicatePolicy, optional, defaults to DuplicatePolicy.PreferFirst
+            ///     Treatment of substances that are defined in multiple files.
             #[staticmethod]
-            #[pyo3(text_signature = "(input, binary_path=None, search_option='Name')")]
+            #[pyo3(
+                text_signature = "(input, binary_path=None, search_option='Name', duplicates='PreferFirst')"
+            )]
             fn from_multiple_json(
                 input: Vec<(Vec<&str>, &str)>,
                 binary_path: Option<&str>,
                 search_option: Option<IdentifierOption>,
+                duplicates: Option<$crate::parameter::DuplicatePolicy>,
             ) -> Result<Self, ParameterError> {
-                Ok(Self(Rc::new(<$parameter>::from_multiple_json(
+                Ok(Self(Rc::new(<$parameter>::from_multiple_json_with_policy(
                     &input,
                     binary_path,
                     search_option.unwrap_or(IdentifierOption::Name),
+                    duplicates.unwrap_or_default(),
+                )?)))
+            }
+
+            /// Creates parameters from CSV files.
+            ///
+            /// The first line of the files contains the column names.
+            /// Pure substance files contain the identifier columns
+            /// (cas, name, iupac_name, smiles, inchi, formula), the column
+            /// molarweight, the fields of the model record and, optionally,
+            /// the fields of the ideal gas record with the prefix
+            /// "ideal_gas_record.". Binary files contain the identifiers
+            /// in the columns id1 and id2 and either the fields of the binary
+            /// record or the binary record itself in the column model_record.
+            ///
+            /// Parameters
+            /// ----------
+            /// substances : List[str]
+            ///     The substances to search.
+            /// pure_path : str
+            ///     Path to file containing pure substance parameters.
+            /// binary_path : str, optional
+            ///     Path to file containing binary substance parameters.
+            /// search_option : IdentifierOption, optional, defaults to IdentifierOption.Name
+            ///     Identifier that is used to search substance.
+            #[staticmethod]
+            #[pyo3(text_signature = "(substances, pure_path, binary_path=None, search_option='Name')")]
+            fn from_csv(
+                substances: Vec<&str>,
+                pure_path: String,
+                binary_path: Option<String>,
+                search_option: Option<IdentifierOption>,
+            ) -> Result<Self, ParameterError> {
+                Ok(Self(Rc::new(<$parameter>::from_csv(
+                    substances,
+                    pure_path,
+                    binary_path,
+                    search_option.unwrap_or(IdentifierOption::Name),
                 )?)))
             }
 
@@ -678,6 +819,82 @@ macro_rules! impl_parameter {
                     .map(|r| PyPureRecord(r.clone()))
                     .collect()
             }
+
+            /// The binary records of all pairs of components
+            /// (in the order of the pure records) that were
+            /// used to construct the parameters.
+            #[getter]
+            fn get_binary_records(&self) -> Vec<PyBinaryRecord> {
+                let (pure_records, binary_records) = self.0.records();
+                let n = pure_records.len();
+                (0..n)
+                    .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                    .map(|(i, j)| {
+                        PyBinaryRecord(BinaryRecord::new(
+                            pure_records[i].identifier.clone(),
+                            pure_records[j].identifier.clone(),
+                            binary_records[(i, j)].clone(),
+                        ))
+                    })
+                    .collect()
+            }
+
+            /// Return the parameters of a subset of the components.
+            ///
+            /// Parameters
+            /// ----------
+            /// component_list : List[int]
+            ///     The indices of the components in the subset.
+            #[pyo3(text_signature = "($self, component_list)")]
+            fn subset(&self, component_list: Vec<usize>) -> PyResult<Self> {
+                let n = self.0.records().0.len();
+                if let Some(&i) = component_list.iter().find(|&&i| i >= n) {
+                    return Err(pyo3::exceptions::PyIndexError::new_err(format!(
+                        "Component index {} out of range for {} components!",
+                        i, n
+                    )));
+                }
+                Ok(Self(Rc::new(self.0.subset(&component_list))))
+            }
+
+            fn __copy__(&self) -> Self {
+                self.clone()
+            }
+
+            /// Creates an independent copy of the parameters.
+            fn __deepcopy__(&self, _memo: &PyAny) -> Self {
+                Self(Rc::new((*self.0).clone()))
+            }
+
+            fn __richcmp__(
+                &self,
+                other: &Self,
+                op: pyo3::basic::CompareOp,
+                py: Python,
+            ) -> PyResult<PyObject> {
+                Ok(match op {
+                    pyo3::basic::CompareOp::Eq => self.parameters_eq(other)?.into_py(py),
+                    pyo3::basic::CompareOp::Ne => (!self.parameters_eq(other)?).into_py(py),
+                    _ => py.NotImplemented(),
+                })
+            }
+        }
+
+        impl $py_parameter {
+            /// Structural comparison of the pure and binary records
+            /// that were used to construct the parameters and of
+            /// settings that are not part of the records (see
+            /// [Parameter::settings_eq]).
+            pub fn parameters_eq(&self, other: &Self) -> Result<bool, ParameterError> {
+                let (pure_records, binary_records) = self.0.records();
+                let (other_pure_records, other_binary_records) = other.0.records();
+                Ok(
+                    serde_json::to_value(pure_records)? == serde_json::to_value(other_pure_records)?
+                        && serde_json::to_value(binary_records)?
+                            == serde_json::to_value(other_binary_records)?
+                        && self.0.settings_eq(&other.0),
+                )
+            }
         }
     };
 }
@@ -765,6 +982,155 @@ macro_rules! impl_json_handling {
             fn to_json_str(&self) -> Result<String, ParameterError> {
                 Ok(serde_json::to_string(&self.0)?)
             }
+
+            fn __copy__(&self) -> Self {
+                self.clone()
+            }
+
+            fn __deepcopy__(&self, _memo: &PyAny) -> Self {
+                self.clone()
+            }
+
+            fn __richcmp__(
+                &self,
+                other: &Self,
+                op: pyo3::basic::CompareOp,
+                py: Python,
+            ) -> Result<PyObject, ParameterError> {
+                let eq = || -> Result<bool, ParameterError> {
+                    Ok(serde_json::to_value(&self.0)? == serde_json::to_value(&other.0)?)
+                };
+                Ok(match op {
+                    pyo3::basic::CompareOp::Eq => eq()?.into_py(py),
+                    pyo3::basic::CompareOp::Ne => (!eq()?).into_py(py),
+                    _ => py.NotImplemented(),
+                })
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_parameter_database {
+    ($parameter:ty, $py_parameter:ident) => {
+        /// A collection of parameter records that is read once and can be used
+        /// to construct parameters for arbitrary subsets of the substances.
+        ///
+        /// Parameters
+        /// ----------
+        /// pure_records : List[PureRecord]
+        ///     A list of pure component parameters.
+        /// binary_records : List[BinaryRecord], optional
+        ///     A list of binary interaction records.
+        ///
+        /// Returns
+        /// -------
+        /// ParameterDatabase
+        #[pyclass(name = "ParameterDatabase", unsendable)]
+        #[pyo3(text_signature = "(pure_records, binary_records=None)")]
+        pub struct PyParameterDatabase(pub $crate::parameter::ParameterDatabaseOf<$parameter>);
+
+        #[pymethods]
+        impl PyParameterDatabase {
+            #[new]
+            fn new(
+                pure_records: Vec<PyPureRecord>,
+                binary_records: Option<Vec<PyBinaryRecord>>,
+            ) -> Self {
+                Self($crate::parameter::ParameterDatabase::new(
+                    pure_records.into_iter().map(|pr| pr.0).collect(),
+                    binary_records
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|br| br.0)
+                        .collect(),
+                ))
+            }
+
+            /// Read a parameter database from json files.
+            ///
+            /// Parameters
+            /// ----------
+            /// pure_paths : List[str]
+            ///     Paths to files containing pure substance parameters.
+            ///     Records in earlier files take precedence.
+            /// binary_path : str, optional
+            ///     Path to file containing binary substance parameters.
+            ///
+            /// Returns
+            /// -------
+            /// ParameterDatabase
+            #[staticmethod]
+            #[pyo3(text_signature = "(pure_paths, binary_path=None)")]
+            fn from_json(
+                pure_paths: Vec<String>,
+                binary_path: Option<String>,
+            ) -> Result<Self, ParameterError> {
+                Ok(Self($crate::parameter::ParameterDatabase::from_json(
+                    &pure_paths,
+                    binary_path,
+                )?))
+            }
+
+            /// Return the pure substance record with the given identifier.
+            ///
+            /// Parameters
+            /// ----------
+            /// identifier : str
+            ///     The identifier of the substance.
+            /// search_option : IdentifierOption, optional, defaults to IdentifierOption.Name
+            ///     Identifier that is used to search the substance.
+            ///
+            /// Returns
+            /// -------
+            /// PureRecord, optional
+            #[pyo3(text_signature = "($self, identifier, search_option='Name')")]
+            fn pure_record(
+                &self,
+                identifier: &str,
+                search_option: Option<IdentifierOption>,
+            ) -> Option<PyPureRecord> {
+                self.0
+                    .pure_record(identifier, search_option.unwrap_or(IdentifierOption::Name))
+                    .map(|r| PyPureRecord(r.clone()))
+            }
+
+            /// Create parameters for a subset of the substances in the database.
+            ///
+            /// Parameters
+            /// ----------
+            /// substances : List[str]
+            ///     The substances to search.
+            /// search_option : IdentifierOption, optional, defaults to IdentifierOption.Name
+            ///     Identifier that is used to search substances and binary records.
+            #[pyo3(text_signature = "($self, substances, search_option='Name')")]
+            fn build(
+                &self,
+                substances: Vec<&str>,
+                search_option: Option<IdentifierOption>,
+            ) -> Result<$py_parameter, ParameterError> {
+                Ok($py_parameter(Rc::new(self.0.build::<$parameter>(
+                    &substances,
+                    search_option.unwrap_or(IdentifierOption::Name),
+                )?)))
+            }
+
+            #[getter]
+            fn get_pure_records(&self) -> Vec<PyPureRecord> {
+                self.0
+                    .pure_records()
+                    .iter()
+                    .map(|r| PyPureRecord(r.clone()))
+                    .collect()
+            }
+
+            fn __repr__(&self) -> PyResult<String> {
+                Ok(format!(
+                    "ParameterDatabase(pure_records={}, binary_records={})",
+                    self.0.pure_records().len(),
+                    self.0.binary_records().len()
+                ))
+            }
         }
     };
 }
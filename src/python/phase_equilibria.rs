@@ -77,6 +77,11 @@ macro_rules! impl_phase_equilibrium {
             ///     The solution tolerance.
             /// verbosity : Verbosity, optional
             ///     The verbosity.
+            /// non_volatile_components : list[int], optional
+            ///     The indices of components that are only present in the liquid phase.
+            /// acceleration : Acceleration, optional
+            ///     The acceleration of the successive substitution.
+            ///     Defaults to Acceleration.Gdem.
             ///
             /// Returns
             /// -------
@@ -87,7 +92,7 @@ macro_rules! impl_phase_equilibrium {
             /// RuntimeError
             ///     When pressure iteration fails or no phase equilibrium is found.
             #[staticmethod]
-            #[pyo3(text_signature = "(eos, temperature, pressure, feed, initial_state=None, max_iter=None, tol=None, verbosity=None, non_volatile_components=None)")]
+            #[pyo3(text_signature = "(eos, temperature, pressure, feed, initial_state=None, max_iter=None, tol=None, verbosity=None, non_volatile_components=None, acceleration=None)")]
             pub fn tp_flash(
                 eos: $py_eos,
                 temperature: PySINumber,
@@ -98,34 +103,579 @@ macro_rules! impl_phase_equilibrium {
                 tol: Option<f64>,
                 verbosity: Option<Verbosity>,
                 non_volatile_components: Option<Vec<usize>>,
+                acceleration: Option<Acceleration>,
             ) -> PyResult<Self> {
+                let options: SolverOptions = (max_iter, tol, verbosity).into();
                 Ok(Self(PhaseEquilibrium::tp_flash(
                     &eos.0,
                     temperature.into(),
                     pressure.into(),
                     feed,
                     initial_state.and_then(|s| Some(&s.0)),
-                    (max_iter, tol, verbosity).into(), non_volatile_components
+                    match acceleration {
+                        Some(a) => options.acceleration(a),
+                        None => options,
+                    },
+                    non_volatile_components
+                )?))
+            }
+
+            /// Create a liquid and vapor state in equilibrium
+            /// for given pressure, molar enthalpy and feed composition.
+            ///
+            /// The temperature is iterated on top of a Tp-flash.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// pressure : SINumber
+            ///     The system pressure.
+            /// molar_enthalpy : SINumber
+            ///     The molar enthalpy of the feed.
+            /// feed : SIArray1
+            ///     Feed composition (units of amount of substance).
+            /// initial_temperature : SINumber, optional
+            ///     An initial guess for the temperature.
+            ///     Defaults to 298.15 K.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseEquilibrium
+            ///
+            /// Raises
+            /// ------
+            /// RuntimeError
+            ///     When the temperature iteration fails or the specification
+            ///     is in a single phase region.
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, pressure, molar_enthalpy, feed, initial_temperature=None, max_iter=None, tol=None, verbosity=None)")]
+            pub fn ph_flash(
+                eos: $py_eos,
+                pressure: PySINumber,
+                molar_enthalpy: PySINumber,
+                feed: &PySIArray1,
+                initial_temperature: Option<PySINumber>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(Self(PhaseEquilibrium::ph_flash(
+                    &eos.0,
+                    pressure.into(),
+                    molar_enthalpy.into(),
+                    feed,
+                    initial_temperature.map(|t| t.into()),
+                    (max_iter, tol, verbosity).into(),
+                )?))
+            }
+
+            /// Create a liquid and vapor state in equilibrium
+            /// for given pressure, molar entropy and feed composition.
+            ///
+            /// The temperature is iterated on top of a Tp-flash.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// pressure : SINumber
+            ///     The system pressure.
+            /// molar_entropy : SINumber
+            ///     The molar entropy of the feed.
+            /// feed : SIArray1
+            ///     Feed composition (units of amount of substance).
+            /// initial_temperature : SINumber, optional
+            ///     An initial guess for the temperature.
+            ///     Defaults to 298.15 K.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseEquilibrium
+            ///
+            /// Raises
+            /// ------
+            /// RuntimeError
+            ///     When the temperature iteration fails or the specification
+            ///     is in a single phase region.
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, pressure, molar_entropy, feed, initial_temperature=None, max_iter=None, tol=None, verbosity=None)")]
+            pub fn ps_flash(
+                eos: $py_eos,
+                pressure: PySINumber,
+                molar_entropy: PySINumber,
+                feed: &PySIArray1,
+                initial_temperature: Option<PySINumber>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(Self(PhaseEquilibrium::ps_flash(
+                    &eos.0,
+                    pressure.into(),
+                    molar_entropy.into(),
+                    feed,
+                    initial_temperature.map(|t| t.into()),
+                    (max_iter, tol, verbosity).into(),
+                )?))
+            }
+
+            /// Create a liquid and vapor state in equilibrium
+            /// for given temperature, total volume and amount of substance.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature : SINumber
+            ///     The system temperature.
+            /// volume : SINumber
+            ///     The total volume of the system.
+            /// moles : SIArray1
+            ///     The amount of substance of each component.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseEquilibrium
+            ///
+            /// Raises
+            /// ------
+            /// RuntimeError
+            ///     When the iteration fails or no phase split is found.
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature, volume, moles, max_iter=None, tol=None, verbosity=None)")]
+            pub fn tv_flash(
+                eos: $py_eos,
+                temperature: PySINumber,
+                volume: PySINumber,
+                moles: &PySIArray1,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(Self(PhaseEquilibrium::tv_flash(
+                    &eos.0,
+                    temperature.into(),
+                    volume.into(),
+                    moles,
+                    (max_iter, tol, verbosity).into(),
+                )?))
+            }
+
+            /// Compute the cloud point of a feed for given temperature
+            /// or pressure, i.e., the equilibrium between the feed
+            /// and an incipient (shadow) phase of negligible amount.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature_or_pressure : SINumber
+            ///     The system temperature_or_pressure.
+            /// feed_molefracs : numpy.ndarray
+            ///     The mole fraction of the feed.
+            /// tp_init : SINumber
+            ///     The system pressure/temperature used as starting
+            ///     condition for the iteration.
+            /// shadow_molefracs : numpy.ndarray, optional
+            ///     The mole fraction of the shadow phase used as
+            ///     starting condition for iteration. If not provided,
+            ///     a stability analysis of the feed at tp_init is used.
+            /// max_iter_inner : int, optional
+            ///     The maximum number of inner iterations.
+            /// max_iter_outer : int, optional
+            ///     The maximum number of outer iterations.
+            /// tol_inner : float, optional
+            ///     The solution tolerance in the inner loop.
+            /// tol_outer : float, optional
+            ///     The solution tolerance in the outer loop.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseEquilibrium
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature_or_pressure, feed_molefracs, tp_init, shadow_molefracs=None, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None)")]
+            pub fn cloud_point(
+                eos: $py_eos,
+                temperature_or_pressure: PySINumber,
+                feed_molefracs: &PyArray1<f64>,
+                tp_init: PySINumber,
+                shadow_molefracs: Option<&PyArray1<f64>>,
+                max_iter_inner: Option<usize>,
+                max_iter_outer: Option<usize>,
+                tol_inner: Option<f64>,
+                tol_outer: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                let x = shadow_molefracs.and_then(|m| Some(m.to_owned_array()));
+                Ok(Self(PhaseEquilibrium::cloud_point(
+                    &eos.0,
+                    temperature_or_pressure.into(),
+                    &feed_molefracs.to_owned_array(),
+                    tp_init.into(),
+                    x.as_ref(),
+                    (
+                        (max_iter_inner, tol_inner, verbosity).into(),
+                        (max_iter_outer, tol_outer, verbosity).into()
+                    )
+                )?))
+            }
+
+            /// Compute a phase equilibrium for given temperature
+            /// or pressure and liquid mole fractions.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature_or_pressure : SINumber
+            ///     The system temperature_or_pressure.
+            /// liquid_molefracs : numpy.ndarray
+            ///     The mole fraction of the liquid phase.
+            /// tp_init : SINumber, optional
+            ///     The system pressure/temperature used as starting
+            ///     condition for the iteration.
+            /// vapor_molefracs : numpy.ndarray, optional
+            ///     The mole fraction of the vapor phase used as
+            ///     starting condition for iteration.
+            /// max_iter_inner : int, optional
+            ///     The maximum number of inner iterations.
+            /// max_iter_outer : int, optional
+            ///     The maximum number of outer iterations.
+            /// tol_inner : float, optional
+            ///     The solution tolerance in the inner loop.
+            /// tol_outer : float, optional
+            ///     The solution tolerance in the outer loop.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseEquilibrium
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature_or_pressure, liquid_molefracs, tp_init=None, vapor_molefracs=None, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None)")]
+            pub fn bubble_point(
+                eos: $py_eos,
+                temperature_or_pressure: PySINumber,
+                liquid_molefracs: &PyArray1<f64>,
+                tp_init: Option<PySINumber>,
+                vapor_molefracs: Option<&PyArray1<f64>>,
+                max_iter_inner: Option<usize>,
+                max_iter_outer: Option<usize>,
+                tol_inner: Option<f64>,
+                tol_outer: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                let x = vapor_molefracs.and_then(|m| Some(m.to_owned_array()));
+                Ok(Self(PhaseEquilibrium::bubble_point(
+                    &eos.0,
+                    temperature_or_pressure.into(),
+                    &liquid_molefracs.to_owned_array(),
+                    tp_init.map(|p| p.into()),
+                    x.as_ref(),
+                    (
+                        (max_iter_inner, tol_inner, verbosity).into(),
+                        (max_iter_outer, tol_outer, verbosity).into()
+                    )
+                )?))
+            }
+
+            /// Compute a phase equilibrium for given temperature
+            /// or pressure and vapor mole fractions.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature_or_pressure : SINumber
+            ///     The system temperature or pressure.
+            /// vapor_molefracs : numpy.ndarray
+            ///     The mole fraction of the vapor phase.
+            /// tp_init : SINumber, optional
+            ///     The system pressure/temperature used as starting
+            ///     condition for the iteration.
+            /// liquid_molefracs : numpy.ndarray, optional
+            ///     The mole fraction of the liquid phase used as
+            ///     starting condition for iteration.
+            /// max_iter_inner : int, optional
+            ///     The maximum number of inner iterations.
+            /// max_iter_outer : int, optional
+            ///     The maximum number of outer iterations.
+            /// tol_inner : float, optional
+            ///     The solution tolerance in the inner loop.
+            /// tol_outer : float, optional
+            ///     The solution tolerance in the outer loop.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseEquilibrium
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature_or_pressure, vapor_molefracs, tp_init=None, liquid_molefracs=None, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None)")]
+            pub fn dew_point(
+                eos: $py_eos,
+                temperature_or_pressure: PySINumber,
+                vapor_molefracs: &PyArray1<f64>,
+                tp_init: Option<PySINumber>,
+                liquid_molefracs: Option<&PyArray1<f64>>,
+                max_iter_inner: Option<usize>,
+                max_iter_outer: Option<usize>,
+                tol_inner: Option<f64>,
+                tol_outer: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                let x = liquid_molefracs.and_then(|m| Some(m.to_owned_array()));
+                Ok(Self(PhaseEquilibrium::dew_point(
+                    &eos.0,
+                    temperature_or_pressure.into(),
+                    &vapor_molefracs.to_owned_array(),
+                    tp_init.map(|p| p.into()),
+                    x.as_ref(),
+                    (
+                        (max_iter_inner, tol_inner, verbosity).into(),
+                        (max_iter_outer, tol_outer, verbosity).into()
+                    )
+                )?))
+            }
+
+            /// Compute the bubble point pressure and vapor mole fractions
+            /// for given temperature and liquid mole fractions.
+            ///
+            /// Uses successive substitution and does not construct
+            /// a phase equilibrium, which is faster than `bubble_point`
+            /// if only the pressure and vapor composition are required.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature : SINumber
+            ///     The system temperature.
+            /// liquid_molefracs : numpy.ndarray
+            ///     The mole fraction of the liquid phase.
+            /// p_init : SINumber, optional
+            ///     The pressure used as starting condition for the iteration.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// (SINumber, numpy.ndarray)
+            ///     The pressure and the vapor mole fractions.
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature, liquid_molefracs, p_init=None, max_iter=None, tol=None, verbosity=None)")]
+            pub fn bubble_point_py<'py>(
+                py: Python<'py>,
+                eos: $py_eos,
+                temperature: PySINumber,
+                liquid_molefracs: &PyArray1<f64>,
+                p_init: Option<PySINumber>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<(PySINumber, &'py PyArray1<f64>)> {
+                let (p, y) = PhaseEquilibrium::bubble_point_py(
+                    &eos.0,
+                    temperature.into(),
+                    &liquid_molefracs.to_owned_array(),
+                    p_init.map(|p| p.into()),
+                    (max_iter, tol, verbosity).into(),
+                )?;
+                Ok((p.into(), y.view().to_pyarray(py)))
+            }
+
+            /// Compute the dew point pressure and liquid mole fractions
+            /// for given temperature and vapor mole fractions.
+            ///
+            /// Uses successive substitution and does not construct
+            /// a phase equilibrium, which is faster than `dew_point`
+            /// if only the pressure and liquid composition are required.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature : SINumber
+            ///     The system temperature.
+            /// vapor_molefracs : numpy.ndarray
+            ///     The mole fraction of the vapor phase.
+            /// p_init : SINumber, optional
+            ///     The pressure used as starting condition for the iteration.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// (SINumber, numpy.ndarray)
+            ///     The pressure and the liquid mole fractions.
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature, vapor_molefracs, p_init=None, max_iter=None, tol=None, verbosity=None)")]
+            pub fn dew_point_px<'py>(
+                py: Python<'py>,
+                eos: $py_eos,
+                temperature: PySINumber,
+                vapor_molefracs: &PyArray1<f64>,
+                p_init: Option<PySINumber>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<(PySINumber, &'py PyArray1<f64>)> {
+                let (p, x) = PhaseEquilibrium::dew_point_px(
+                    &eos.0,
+                    temperature.into(),
+                    &vapor_molefracs.to_owned_array(),
+                    p_init.map(|p| p.into()),
+                    (max_iter, tol, verbosity).into(),
+                )?;
+                Ok((p.into(), x.view().to_pyarray(py)))
+            }
+
+            /// Compute the phase equilibrium of a binary mixture
+            /// for given temperature and pressure, starting from
+            /// the composition of the liquid phase.
+            ///
+            /// If multiple phase equilibria exist (e.g., on both sides
+            /// of an azeotrope), the initial liquid composition
+            /// determines which one is found.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature : SINumber
+            ///     The system temperature.
+            /// pressure : SINumber
+            ///     The system pressure.
+            /// liquid_molefracs : numpy.ndarray, optional
+            ///     The mole fraction of the liquid phase used as
+            ///     starting condition for the iteration.
+            ///     Defaults to an equimolar liquid.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseEquilibrium
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature, pressure, liquid_molefracs=None, max_iter=None, tol=None, verbosity=None)")]
+            pub fn bubble_point_tp(
+                eos: $py_eos,
+                temperature: PySINumber,
+                pressure: PySINumber,
+                liquid_molefracs: Option<&PyArray1<f64>>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                let x = liquid_molefracs.map(|m| m.to_owned_array());
+                Ok(Self(PhaseEquilibrium::bubble_point_tp(
+                    &eos.0,
+                    temperature.into(),
+                    pressure.into(),
+                    x.as_ref(),
+                    (max_iter, tol, verbosity).into(),
+                )?))
+            }
+
+            /// Compute the phase equilibrium of a binary mixture
+            /// for given temperature and pressure, starting from
+            /// the composition of the vapor phase.
+            ///
+            /// If multiple phase equilibria exist (e.g., on both sides
+            /// of an azeotrope), the initial vapor composition
+            /// determines which one is found.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature : SINumber
+            ///     The system temperature.
+            /// pressure : SINumber
+            ///     The system pressure.
+            /// vapor_molefracs : numpy.ndarray, optional
+            ///     The mole fraction of the vapor phase used as
+            ///     starting condition for the iteration.
+            ///     Defaults to an equimolar vapor.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseEquilibrium
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature, pressure, vapor_molefracs=None, max_iter=None, tol=None, verbosity=None)")]
+            pub fn dew_point_tp(
+                eos: $py_eos,
+                temperature: PySINumber,
+                pressure: PySINumber,
+                vapor_molefracs: Option<&PyArray1<f64>>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                let x = vapor_molefracs.map(|m| m.to_owned_array());
+                Ok(Self(PhaseEquilibrium::dew_point_tp(
+                    &eos.0,
+                    temperature.into(),
+                    pressure.into(),
+                    x.as_ref(),
+                    (max_iter, tol, verbosity).into(),
                 )?))
             }
 
-            /// Compute a phase equilibrium for given temperature
-            /// or pressure and liquid mole fractions.
+            /// Compute phase equilibria for arrays of temperatures
+            /// or pressures and liquid mole fractions.
+            ///
+            /// Every converged phase equilibrium is used as starting
+            /// condition for the next point.
             ///
             /// Parameters
             /// ----------
             /// eos : EquationOfState
             ///     The equation of state.
-            /// temperature_or_pressure : SINumber
-            ///     The system temperature_or_pressure.
-            /// liquid_molefracs : numpy.ndarray
-            ///     The mole fraction of the liquid phase.
-            /// tp_init : SINumber, optional
-            ///     The system pressure/temperature used as starting
-            ///     condition for the iteration.
-            /// vapor_molefracs : numpy.ndarray, optional
-            ///     The mole fraction of the vapor phase used as
-            ///     starting condition for iteration.
+            /// temperature_or_pressure : SIArray1
+            ///     The system temperatures or pressures.
+            /// liquid_molefracs : numpy.ndarray[float]
+            ///     The mole fractions of the liquid phase. Either one row
+            ///     for every point or a single row for all points.
             /// max_iter_inner : int, optional
             ///     The maximum number of inner iterations.
             /// max_iter_outer : int, optional
@@ -139,52 +689,49 @@ macro_rules! impl_phase_equilibrium {
             ///
             /// Returns
             /// -------
-            /// PhaseEquilibrium
+            /// List[PhaseEquilibrium]
+            ///     The phase equilibria with None for points that did not converge.
             #[staticmethod]
-            #[pyo3(text_signature = "(eos, temperature_or_pressure, liquid_molefracs, tp_init=None, vapor_molefracs=None, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None)")]
-            pub fn bubble_point(
+            #[pyo3(text_signature = "(eos, temperature_or_pressure, liquid_molefracs, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None)")]
+            pub fn bubble_points(
                 eos: $py_eos,
-                temperature_or_pressure: PySINumber,
-                liquid_molefracs: &PyArray1<f64>,
-                tp_init: Option<PySINumber>,
-                vapor_molefracs: Option<&PyArray1<f64>>,
+                temperature_or_pressure: PySIArray1,
+                liquid_molefracs: &PyArray2<f64>,
                 max_iter_inner: Option<usize>,
                 max_iter_outer: Option<usize>,
                 tol_inner: Option<f64>,
                 tol_outer: Option<f64>,
                 verbosity: Option<Verbosity>,
-            ) -> PyResult<Self> {
-                let x = vapor_molefracs.and_then(|m| Some(m.to_owned_array()));
-                Ok(Self(PhaseEquilibrium::bubble_point(
+            ) -> PyResult<Vec<Option<Self>>> {
+                Ok(PhaseEquilibrium::bubble_points(
                     &eos.0,
-                    temperature_or_pressure.into(),
+                    &temperature_or_pressure,
                     &liquid_molefracs.to_owned_array(),
-                    tp_init.map(|p| p.into()),
-                    x.as_ref(),
                     (
                         (max_iter_inner, tol_inner, verbosity).into(),
                         (max_iter_outer, tol_outer, verbosity).into()
                     )
-                )?))
+                )?
+                .into_iter()
+                .map(|vle| vle.ok().map(Self))
+                .collect())
             }
 
-            /// Compute a phase equilibrium for given temperature
-            /// or pressure and vapor mole fractions.
+            /// Compute phase equilibria for arrays of temperatures
+            /// or pressures and vapor mole fractions.
+            ///
+            /// Every converged phase equilibrium is used as starting
+            /// condition for the next point.
             ///
             /// Parameters
             /// ----------
             /// eos : EquationOfState
             ///     The equation of state.
-            /// temperature_or_pressure : SINumber
-            ///     The system temperature or pressure.
-            /// vapor_molefracs : numpy.ndarray
-            ///     The mole fraction of the vapor phase.
-            /// tp_init : SINumber, optional
-            ///     The system pressure/temperature used as starting
-            ///     condition for the iteration.
-            /// liquid_molefracs : numpy.ndarray, optional
-            ///     The mole fraction of the liquid phase used as
-            ///     starting condition for iteration.
+            /// temperature_or_pressure : SIArray1
+            ///     The system temperatures or pressures.
+            /// vapor_molefracs : numpy.ndarray[float]
+            ///     The mole fractions of the vapor phase. Either one row
+            ///     for every point or a single row for all points.
             /// max_iter_inner : int, optional
             ///     The maximum number of inner iterations.
             /// max_iter_outer : int, optional
@@ -198,33 +745,32 @@ macro_rules! impl_phase_equilibrium {
             ///
             /// Returns
             /// -------
-            /// PhaseEquilibrium
+            /// List[PhaseEquilibrium]
+            ///     The phase equilibria with None for points that did not converge.
             #[staticmethod]
-            #[pyo3(text_signature = "(eos, temperature_or_pressure, vapor_molefracs, tp_init=None, liquid_molefracs=None, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None)")]
-            pub fn dew_point(
+            #[pyo3(text_signature = "(eos, temperature_or_pressure, vapor_molefracs, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None)")]
+            pub fn dew_points(
                 eos: $py_eos,
-                temperature_or_pressure: PySINumber,
-                vapor_molefracs: &PyArray1<f64>,
-                tp_init: Option<PySINumber>,
-                liquid_molefracs: Option<&PyArray1<f64>>,
+                temperature_or_pressure: PySIArray1,
+                vapor_molefracs: &PyArray2<f64>,
                 max_iter_inner: Option<usize>,
                 max_iter_outer: Option<usize>,
                 tol_inner: Option<f64>,
                 tol_outer: Option<f64>,
                 verbosity: Option<Verbosity>,
-            ) -> PyResult<Self> {
-                let x = liquid_molefracs.and_then(|m| Some(m.to_owned_array()));
-                Ok(Self(PhaseEquilibrium::dew_point(
+            ) -> PyResult<Vec<Option<Self>>> {
+                Ok(PhaseEquilibrium::dew_points(
                     &eos.0,
-                    temperature_or_pressure.into(),
+                    &temperature_or_pressure,
                     &vapor_molefracs.to_owned_array(),
-                    tp_init.map(|p| p.into()),
-                    x.as_ref(),
                     (
                         (max_iter_inner, tol_inner, verbosity).into(),
                         (max_iter_outer, tol_outer, verbosity).into()
                     )
-                )?))
+                )?
+                .into_iter()
+                .map(|vle| vle.ok().map(Self))
+                .collect())
             }
 
             #[getter]
@@ -237,6 +783,44 @@ macro_rules! impl_phase_equilibrium {
                 PyState(self.0.liquid().clone())
             }
 
+            /// User defined metadata, e.g., an experiment ID or a branch label.
+            #[getter]
+            fn get_metadata(&self) -> std::collections::BTreeMap<String, String> {
+                self.0.metadata().clone()
+            }
+
+            #[setter]
+            fn set_metadata(&mut self, metadata: std::collections::BTreeMap<String, String>) {
+                *self.0.metadata_mut() = metadata;
+            }
+
+            /// Return temperature, pressure and chemical potentials shared by
+            /// both phases together with the partial densities of the phases,
+            /// e.g., as boundary conditions of interfacial models.
+            ///
+            /// Returns
+            /// -------
+            /// dict[str, Any]
+            ///     Keys: 'temperature', 'pressure', 'chemical_potential' and
+            ///     'partial_densities' in SI units and the same keys with the
+            ///     prefix 'reduced_' in reduced units.
+            #[pyo3(text_signature = "($self)")]
+            fn bulk_conditions(&self, py: Python) -> PyResult<std::collections::HashMap<String, PyObject>> {
+                let bulk = self.0.bulk_conditions()?;
+                let mut dict = std::collections::HashMap::with_capacity(8);
+                dict.insert(String::from("temperature"), PySINumber::from(bulk.temperature).into_py(py));
+                dict.insert(String::from("pressure"), PySINumber::from(bulk.pressure).into_py(py));
+                dict.insert(String::from("chemical_potential"), PySIArray1::from(bulk.chemical_potential).into_py(py));
+                let partial_densities: Vec<_> = bulk.partial_densities.into_iter().map(PySIArray1::from).collect();
+                dict.insert(String::from("partial_densities"), partial_densities.into_py(py));
+                dict.insert(String::from("reduced_temperature"), bulk.reduced_temperature.into_py(py));
+                dict.insert(String::from("reduced_pressure"), bulk.reduced_pressure.into_py(py));
+                dict.insert(String::from("reduced_chemical_potential"), bulk.reduced_chemical_potential.view().to_pyarray(py).into_py(py));
+                let reduced_partial_densities: Vec<PyObject> = bulk.reduced_partial_densities.iter().map(|rho| rho.view().to_pyarray(py).into_py(py)).collect();
+                dict.insert(String::from("reduced_partial_densities"), reduced_partial_densities.into_py(py));
+                Ok(dict)
+            }
+
             /// Calculate a new PhaseEquilibrium with the given chemical potential.
             /// The temperature remains constant, but the states are not in
             /// a mechanical equilibrium anymore.
@@ -318,6 +902,31 @@ macro_rules! impl_phase_equilibrium {
                     .collect()
             }
 
+            /// Calculate the boiling temperatures of a pure component
+            /// for all given pressures.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state of a pure component.
+            /// pressure : SIArray1
+            ///     The pressures.
+            ///
+            /// Returns
+            /// -------
+            /// list[SINumber]
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, pressure)")]
+            fn boiling_temperatures(
+                eos: $py_eos,
+                pressure: PySIArray1,
+            ) -> PyResult<Vec<Option<PySINumber>>> {
+                Ok(PhaseEquilibrium::boiling_temperatures(&eos.0, &pressure)?
+                    .into_iter()
+                    .map(|o| o.map(|n| n.into()))
+                    .collect())
+            }
+
             fn _repr_markdown_(&self) -> String {
                 self.0._repr_markdown_()
             }
@@ -325,15 +934,204 @@ macro_rules! impl_phase_equilibrium {
             fn __repr__(&self) -> PyResult<String> {
                 Ok(self.0.to_string())
             }
-        }
-
-        /// A thermodynamic three phase equilibrium state.
-        #[pyclass(name = "ThreePhaseEquilibrium", unsendable)]
-        #[derive(Clone)]
-        struct PyThreePhaseEquilibrium(PhaseEquilibrium<SIUnit, $eos, 3>);
+        }
+
+        /// A thermodynamic three phase equilibrium state.
+        #[pyclass(name = "ThreePhaseEquilibrium", unsendable)]
+        #[derive(Clone)]
+        struct PyThreePhaseEquilibrium(PhaseEquilibrium<SIUnit, $eos, 3>);
+
+        /// A thermodynamic equilibrium state with an arbitrary number of phases.
+        #[pyclass(name = "MultiPhaseEquilibrium", unsendable)]
+        #[derive(Clone)]
+        struct PyMultiPhaseEquilibrium(PhaseEquilibriumN<SIUnit, $eos>);
+
+        #[pymethods]
+        impl PyPhaseEquilibrium {
+            /// Calculate a homogeneous azeotrope in a binary mixture for a given
+            /// temperature or pressure.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature_or_pressure : SINumber
+            ///     The system temperature or pressure. Whether the azeotrope
+            ///     is calculated for a given temperature or pressure
+            ///     is determined from the unit of the value.
+            /// x_bracket : list[float]
+            ///     Two liquid molefracs of component 1 that bracket
+            ///     the azeotropic composition.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            /// max_iter_bd_inner : int, optional
+            ///     The maximum number of inner iterations in the bubble/dew point iteration.
+            /// max_iter_bd_outer : int, optional
+            ///     The maximum number of outer iterations in the bubble/dew point iteration.
+            /// tol_bd_inner : float, optional
+            ///     The solution tolerance in the inner loop of the bubble/dew point iteration.
+            /// tol_bd_outer : float, optional
+            ///     The solution tolerance in the outer loop of the bubble/dew point iteration.
+            /// verbosity_bd : Verbosity, optional
+            ///     The verbosity of the bubble/dew point iteration.
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature_or_pressure, x_bracket, max_iter=None, tol=None, verbosity=None, max_iter_bd_inner=None, max_iter_bd_outer=None, tol_bd_inner=None, tol_bd_outer=None, verbosity_bd=None)")]
+            fn azeotrope(
+                eos: $py_eos,
+                temperature_or_pressure: PySINumber,
+                x_bracket: (f64, f64),
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+                max_iter_bd_inner: Option<usize>,
+                max_iter_bd_outer: Option<usize>,
+                tol_bd_inner: Option<f64>,
+                tol_bd_outer: Option<f64>,
+                verbosity_bd: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(Self(PhaseEquilibrium::azeotrope(
+                    &eos.0,
+                    temperature_or_pressure.into(),
+                    x_bracket,
+                    (max_iter, tol, verbosity).into(),
+                    (
+                        (max_iter_bd_inner, tol_bd_inner, verbosity_bd).into(),
+                        (max_iter_bd_outer, tol_bd_outer, verbosity_bd).into(),
+                    )
+                )?))
+            }
+
+            /// Calculate a homogeneous azeotrope in a binary mixture for a given
+            /// temperature with a Newton scheme.
+            ///
+            /// In contrast to `azeotrope`, no bracket of the azeotropic
+            /// composition is required.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature : SINumber
+            ///     The system temperature.
+            /// x_init : float
+            ///     The initial liquid molefrac of component 1.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            /// max_iter_bd_inner : int, optional
+            ///     The maximum number of inner iterations in the bubble/dew point iteration.
+            /// max_iter_bd_outer : int, optional
+            ///     The maximum number of outer iterations in the bubble/dew point iteration.
+            /// tol_bd_inner : float, optional
+            ///     The solution tolerance in the inner loop of the bubble/dew point iteration.
+            /// tol_bd_outer : float, optional
+            ///     The solution tolerance in the outer loop of the bubble/dew point iteration.
+            /// verbosity_bd : Verbosity, optional
+            ///     The verbosity of the bubble/dew point iteration.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseEquilibrium
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature, x_init, max_iter=None, tol=None, verbosity=None, max_iter_bd_inner=None, max_iter_bd_outer=None, tol_bd_inner=None, tol_bd_outer=None, verbosity_bd=None)")]
+            fn azeotrope_t(
+                eos: $py_eos,
+                temperature: PySINumber,
+                x_init: f64,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+                max_iter_bd_inner: Option<usize>,
+                max_iter_bd_outer: Option<usize>,
+                tol_bd_inner: Option<f64>,
+                tol_bd_outer: Option<f64>,
+                verbosity_bd: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(Self(PhaseEquilibrium::azeotrope_t(
+                    &eos.0,
+                    temperature.into(),
+                    x_init,
+                    (max_iter, tol, verbosity).into(),
+                    (
+                        (max_iter_bd_inner, tol_bd_inner, verbosity_bd).into(),
+                        (max_iter_bd_outer, tol_bd_outer, verbosity_bd).into(),
+                    )
+                )?))
+            }
+
+            /// Calculate a homogeneous azeotrope in a binary mixture for a given
+            /// pressure with a Newton scheme.
+            ///
+            /// In contrast to `azeotrope`, no bracket of the azeotropic
+            /// composition is required.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// pressure : SINumber
+            ///     The system pressure.
+            /// x_init : float
+            ///     The initial liquid molefrac of component 1.
+            /// t_init : SINumber, optional
+            ///     The system temperature used as starting
+            ///     condition for the iteration.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            /// max_iter_bd_inner : int, optional
+            ///     The maximum number of inner iterations in the bubble/dew point iteration.
+            /// max_iter_bd_outer : int, optional
+            ///     The maximum number of outer iterations in the bubble/dew point iteration.
+            /// tol_bd_inner : float, optional
+            ///     The solution tolerance in the inner loop of the bubble/dew point iteration.
+            /// tol_bd_outer : float, optional
+            ///     The solution tolerance in the outer loop of the bubble/dew point iteration.
+            /// verbosity_bd : Verbosity, optional
+            ///     The verbosity of the bubble/dew point iteration.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseEquilibrium
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, pressure, x_init, t_init=None, max_iter=None, tol=None, verbosity=None, max_iter_bd_inner=None, max_iter_bd_outer=None, tol_bd_inner=None, tol_bd_outer=None, verbosity_bd=None)")]
+            fn azeotrope_p(
+                eos: $py_eos,
+                pressure: PySINumber,
+                x_init: f64,
+                t_init: Option<PySINumber>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+                max_iter_bd_inner: Option<usize>,
+                max_iter_bd_outer: Option<usize>,
+                tol_bd_inner: Option<f64>,
+                tol_bd_outer: Option<f64>,
+                verbosity_bd: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(Self(PhaseEquilibrium::azeotrope_p(
+                    &eos.0,
+                    pressure.into(),
+                    x_init,
+                    t_init.map(|t| t.into()),
+                    (max_iter, tol, verbosity).into(),
+                    (
+                        (max_iter_bd_inner, tol_bd_inner, verbosity_bd).into(),
+                        (max_iter_bd_outer, tol_bd_outer, verbosity_bd).into(),
+                    )
+                )?))
+            }
 
-        #[pymethods]
-        impl PyPhaseEquilibrium {
             /// Calculate a heteroazeotrope in a binary mixture for a given temperature
             /// or pressure.
             ///
@@ -342,7 +1140,9 @@ macro_rules! impl_phase_equilibrium {
             /// eos : EquationOfState
             ///     The equation of state.
             /// temperature_or_pressure : SINumber
-            ///     The system temperature or pressure.
+            ///     The system temperature or pressure. Whether the heteroazeotrope
+            ///     is calculated for a given temperature or pressure
+            ///     is determined from the unit of the value.
             /// x_init : list[float]
             ///     Initial guesses for the liquid molefracs of component 1
             ///     at the heteroazeotropic point.
@@ -388,6 +1188,57 @@ macro_rules! impl_phase_equilibrium {
                     )
                 )?))
             }
+
+            /// Perform a three-phase (vapor-liquid-liquid) Tp-flash
+            /// calculation.
+            ///
+            /// The calculation is initialized with a two-phase Tp-flash
+            /// and a stability analysis of the resulting phases.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature : SINumber
+            ///     The system temperature.
+            /// pressure : SINumber
+            ///     The system pressure.
+            /// feed : SIArray1
+            ///     The amount of substance in mol of each component.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// ThreePhaseEquilibrium
+            ///
+            /// Raises
+            /// ------
+            /// RuntimeError
+            ///     When the iteration fails or the feed does not split into three phases.
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature, pressure, feed, max_iter=None, tol=None, verbosity=None)")]
+            fn tp_flash_vlle(
+                eos: $py_eos,
+                temperature: PySINumber,
+                pressure: PySINumber,
+                feed: &PySIArray1,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<PyThreePhaseEquilibrium> {
+                Ok(PyThreePhaseEquilibrium(PhaseEquilibrium::tp_flash_vlle(
+                    &eos.0,
+                    temperature.into(),
+                    pressure.into(),
+                    feed,
+                    (max_iter, tol, verbosity).into(),
+                )?))
+            }
         }
 
         #[pymethods]
@@ -407,6 +1258,17 @@ macro_rules! impl_phase_equilibrium {
                 PyState(self.0.liquid2().clone())
             }
 
+            /// User defined metadata, e.g., an experiment ID or a branch label.
+            #[getter]
+            fn get_metadata(&self) -> std::collections::BTreeMap<String, String> {
+                self.0.metadata().clone()
+            }
+
+            #[setter]
+            fn set_metadata(&mut self, metadata: std::collections::BTreeMap<String, String>) {
+                *self.0.metadata_mut() = metadata;
+            }
+
             fn _repr_markdown_(&self) -> String {
                 self.0._repr_markdown_()
             }
@@ -416,6 +1278,102 @@ macro_rules! impl_phase_equilibrium {
             }
         }
 
+        #[pymethods]
+        impl PyMultiPhaseEquilibrium {
+            /// Perform a Tp-flash calculation for an arbitrary number of phases.
+            ///
+            /// Phases are added one at a time from a stability analysis
+            /// of the current phases until all phases are stable or the
+            /// maximum number of phases is reached.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperature : SINumber
+            ///     The system temperature.
+            /// pressure : SINumber
+            ///     The system pressure.
+            /// feed : SIArray1
+            ///     The amount of substance in mol of each component.
+            /// max_phases : int, optional
+            ///     The maximum number of phases.
+            ///     Defaults to the number of components.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// MultiPhaseEquilibrium
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature, pressure, feed, max_phases=None, max_iter=None, tol=None, verbosity=None)")]
+            fn tp_flash(
+                eos: $py_eos,
+                temperature: PySINumber,
+                pressure: PySINumber,
+                feed: &PySIArray1,
+                max_phases: Option<usize>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(Self(PhaseEquilibriumN::tp_flash(
+                    &eos.0,
+                    temperature.into(),
+                    pressure.into(),
+                    feed,
+                    max_phases,
+                    (max_iter, tol, verbosity).into(),
+                )?))
+            }
+
+            #[getter]
+            fn get_states(&self) -> Vec<PyState> {
+                self.0.iter().map(|s| PyState(s.clone())).collect()
+            }
+
+            #[getter]
+            fn get_phase_fractions<'py>(&self, py: Python<'py>) -> &'py PyArray1<f64> {
+                self.0.phase_fractions().view().to_pyarray(py)
+            }
+
+            /// User defined metadata, e.g., an experiment ID or a branch label.
+            #[getter]
+            fn get_metadata(&self) -> std::collections::BTreeMap<String, String> {
+                self.0.metadata().clone()
+            }
+
+            #[setter]
+            fn set_metadata(&mut self, metadata: std::collections::BTreeMap<String, String>) {
+                *self.0.metadata_mut() = metadata;
+            }
+
+            fn __len__(&self) -> PyResult<usize> {
+                Ok(self.0.len())
+            }
+
+            fn __getitem__(&self, idx: isize) -> PyResult<PyState> {
+                let i = if idx < 0 {
+                    self.0.len() as isize + idx
+                } else {
+                    idx
+                };
+                if (0..self.0.len()).contains(&(i as usize)) {
+                    Ok(PyState(self.0[i as usize].clone()))
+                } else {
+                    Err(PyIndexError::new_err(format!("MultiPhaseEquilibrium index out of range")))
+                }
+            }
+
+            fn __repr__(&self) -> PyResult<String> {
+                Ok(self.0.to_string())
+            }
+        }
+
         #[pymethods]
         impl PyState {
             /// Calculates a two phase Tp-flash with the state as feed.
@@ -431,6 +1389,11 @@ macro_rules! impl_phase_equilibrium {
             ///     The solution tolerance.
             /// verbosity : Verbosity, optional
             ///     The verbosity.
+            /// non_volatile_components : list[int], optional
+            ///     The indices of components that are only present in the liquid phase.
+            /// acceleration : Acceleration, optional
+            ///     The acceleration of the successive substitution.
+            ///     Defaults to Acceleration.Gdem.
             ///
             /// Returns
             /// -------
@@ -440,7 +1403,7 @@ macro_rules! impl_phase_equilibrium {
             /// ------
             /// RuntimeError
             ///     When pressure iteration fails or no phase equilibrium is found.
-            #[pyo3(text_signature = "($self, initial_state=None, max_iter=None, tol=None, verbosity=None, non_volatile_components=None)")]
+            #[pyo3(text_signature = "($self, initial_state=None, max_iter=None, tol=None, verbosity=None, non_volatile_components=None, acceleration=None)")]
             pub fn tp_flash(
                 &self,
                 initial_state: Option<&PyPhaseEquilibrium>,
@@ -448,16 +1411,24 @@ macro_rules! impl_phase_equilibrium {
                 tol: Option<f64>,
                 verbosity: Option<Verbosity>,
                 non_volatile_components: Option<Vec<usize>>,
+                acceleration: Option<Acceleration>,
             ) -> PyResult<PyPhaseEquilibrium> {
+                let options: SolverOptions = (max_iter, tol, verbosity).into();
                 Ok(PyPhaseEquilibrium(self.0.tp_flash(
                     initial_state.and_then(|s| Some(&s.0)),
-                    (max_iter, tol, verbosity).into(),
+                    match acceleration {
+                        Some(a) => options.acceleration(a),
+                        None => options,
+                    },
                     non_volatile_components
                 )?))
             }
         }
 
         /// Phase diagram for a pure component or a binary mixture.
+        ///
+        /// Temperatures and pressures passed to the constructors can also be
+        /// given as a tuple of a value and a unit string, e.g., `(5.0, "bar")`.
         #[pyclass(name = "PhaseDiagram", unsendable)]
         pub struct PyPhaseDiagram(PhaseDiagram<SIUnit, $eos>);
 
@@ -491,9 +1462,9 @@ macro_rules! impl_phase_equilibrium {
             #[pyo3(text_signature = "(eos, min_temperature, npoints, critical_temperature=None, max_iter=None, tol=None, verbosity=None)")]
             pub fn pure(
                 eos: &$py_eos,
-                min_temperature: PySINumber,
+                min_temperature: $crate::python::units::PyQuantity,
                 npoints: usize,
-                critical_temperature: Option<PySINumber>,
+                critical_temperature: Option<$crate::python::units::PyQuantity>,
                 max_iter: Option<usize>,
                 tol: Option<f64>,
                 verbosity: Option<Verbosity>,
@@ -517,16 +1488,28 @@ macro_rules! impl_phase_equilibrium {
                     .collect()
             }
 
+            /// The vapor states of the phase diagram.
+            ///
+            /// Works for pure components and mixtures, e.g., to
+            /// evaluate enthalpies or mass densities along the diagram.
             #[getter]
             pub fn get_vapor(&self) -> PyStateVec {
                 self.0.vapor().into()
             }
 
+            /// The liquid states of the phase diagram.
             #[getter]
             pub fn get_liquid(&self) -> PyStateVec {
                 self.0.liquid().into()
             }
 
+            /// The homogeneous azeotrope of a binary phase diagram
+            /// or None if the diagram does not contain one.
+            #[getter]
+            pub fn get_azeotrope(&self) -> Option<PyPhaseEquilibrium> {
+                self.0.azeotrope().map(|vle| PyPhaseEquilibrium(vle.clone()))
+            }
+
             /// Returns the phase diagram as dictionary.
             ///
             /// Units
@@ -539,15 +1522,16 @@ macro_rules! impl_phase_equilibrium {
             ///
             /// Returns
             /// -------
-            /// dict[str, list[float]]
+            /// dict[str, list]
             ///     Keys: property names. Values: property for each state.
-            /// 
+            ///
             /// Notes
             /// -----
             /// xi: liquid molefraction of component i
             /// yi: vapor molefraction of component i
             /// i: component index according to order in parameters.
-            pub fn to_dict(&self) -> PyResult<HashMap<String, Vec<f64>>> {
+            /// metadata: user defined metadata of each phase equilibrium
+            pub fn to_dict(&self, py: Python) -> PyResult<HashMap<String, PyObject>> {
                 let n = self.0.states[0].liquid().eos.components();
                 let mut dict = HashMap::with_capacity(8 + 2 * n);
                 if n != 1 {
@@ -566,9 +1550,96 @@ macro_rules! impl_phase_equilibrium {
                 dict.insert(String::from("molar enthalpy vapor"), (self.0.vapor().molar_enthalpy() / (KILO*JOULE / MOL)).into_value()?.into_raw_vec());
                 dict.insert(String::from("molar entropy liquid"), (self.0.liquid().molar_entropy() / (KILO*JOULE / KELVIN / MOL)).into_value()?.into_raw_vec());
                 dict.insert(String::from("molar entropy vapor"), (self.0.vapor().molar_entropy() / (KILO*JOULE / KELVIN / MOL)).into_value()?.into_raw_vec());
+                let metadata: Vec<_> = self.0.states.iter().map(|vle| vle.metadata().clone()).collect();
+                let mut dict: HashMap<String, PyObject> = dict.into_iter().map(|(k, v)| (k, v.into_py(py))).collect();
+                dict.insert(String::from("metadata"), metadata.into_py(py));
+                Ok(dict)
+            }
+
+            /// Compare the phase diagram to another phase diagram that
+            /// was calculated for the same specifications.
+            ///
+            /// Pure component diagrams are aligned at the temperatures,
+            /// diagrams of mixtures at the liquid mole fractions of the
+            /// first component of this (the reference) phase diagram.
+            /// The properties of `other` are interpolated linearly.
+            ///
+            /// Parameters
+            /// ----------
+            /// other : PhaseDiagram
+            ///     The phase diagram that is compared to the reference.
+            ///
+            /// Units
+            /// -----
+            /// temperature : K
+            /// pressure : Pa
+            /// densities : mol / m³
+            ///
+            /// Returns
+            /// -------
+            /// dict[str, list[float]]
+            ///     Keys: property names. Values: difference (other - reference)
+            ///     of the property for each point of the reference diagram
+            ///     within the range of `other`. The key "index" contains
+            ///     the indices of these points in the reference diagram.
+            #[pyo3(text_signature = "($self, other)")]
+            pub fn compare(&self, other: PyRef<PyPhaseDiagram>) -> PyResult<HashMap<String, Vec<f64>>> {
+                let diff = self.0.compare(&other.0)?;
+                let n = diff.liquid_molefracs.ncols();
+                let mut dict = HashMap::with_capacity(5 + 2 * n);
+                if n != 1 {
+                    for i in 0..n {
+                        dict.insert(format!("x{}", i), diff.liquid_molefracs.column(i).to_vec());
+                        dict.insert(format!("y{}", i), diff.vapor_molefracs.column(i).to_vec());
+                    }
+                }
+                dict.insert(String::from("index"), diff.indices.iter().map(|&i| i as f64).collect());
+                dict.insert(String::from("temperature"), (diff.temperature / KELVIN).into_value()?.into_raw_vec());
+                dict.insert(String::from("pressure"), (diff.pressure / PASCAL).into_value()?.into_raw_vec());
+                dict.insert(String::from("density liquid"), (diff.liquid_density / (MOL / METER.powi(3))).into_value()?.into_raw_vec());
+                dict.insert(String::from("density vapor"), (diff.vapor_density / (MOL / METER.powi(3))).into_value()?.into_raw_vec());
                 Ok(dict)
             }
 
+            /// Trace the pressure-temperature phase envelope of a mixture
+            /// with fixed composition.
+            ///
+            /// The envelope is traced from the dew point at 1 bar over
+            /// the critical point to the bubble point at 1 bar.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// moles : SIArray1
+            ///     The amount of substance of each component.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations for every point.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// PhaseDiagram
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, moles, max_iter=None, tol=None, verbosity=None)")]
+            pub fn phase_envelope(
+                eos: $py_eos,
+                moles: $crate::python::units::PyQuantityArray1,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                let dia = PhaseDiagram::phase_envelope(
+                    &eos.0,
+                    &moles,
+                    (max_iter, tol, verbosity).into(),
+                )?;
+                Ok(Self(dia))
+            }
+
             /// Binary phase diagram calculated using bubble/dew point iterations.
             ///
             /// Parameters
@@ -600,7 +1671,7 @@ macro_rules! impl_phase_equilibrium {
             #[pyo3(text_signature = "(eos, temperature_or_pressure, npoints=None, x_lle=None, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None)")]
             pub fn binary_vle(
                 eos: $py_eos,
-                temperature_or_pressure: PySINumber,
+                temperature_or_pressure: $crate::python::units::PyQuantity,
                 npoints: Option<usize>,
                 x_lle: Option<(f64, f64)>,
                 max_iter_inner: Option<usize>,
@@ -622,6 +1693,50 @@ macro_rules! impl_phase_equilibrium {
                 Ok(Self(dia))
             }
 
+            /// Recalculate a binary phase diagram for a different equation of state.
+            ///
+            /// Every point is converged using the old point as initial value.
+            /// Points that cannot be recalculated are omitted and their indices
+            /// in the old diagram are returned together with the new diagram.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The (perturbed) equation of state.
+            /// max_iter_inner : int, optional
+            ///     The maximum number of inner iterations in the bubble point iteration.
+            /// max_iter_outer : int, optional
+            ///     The maximum number of outer iterations in the bubble point iteration.
+            /// tol_inner : float, optional
+            ///     The solution tolerance in the inner loop of the bubble point iteration.
+            /// tol_outer : float, optional
+            ///     The solution tolerance in the outer loop of the bubble point iteration.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity of the bubble point iteration.
+            ///
+            /// Returns
+            /// -------
+            /// (PhaseDiagram, [int])
+            #[pyo3(text_signature = "($self, eos, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None)")]
+            pub fn recalculate(
+                &self,
+                eos: $py_eos,
+                max_iter_inner: Option<usize>,
+                max_iter_outer: Option<usize>,
+                tol_inner: Option<f64>,
+                tol_outer: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<(Self, Vec<usize>)> {
+                let (dia, dropped) = self.0.recalculate(
+                    &eos.0,
+                    (
+                        (max_iter_inner, tol_inner, verbosity).into(),
+                        (max_iter_outer, tol_outer, verbosity).into(),
+                    )
+                )?;
+                Ok((Self(dia), dropped))
+            }
+
             /// Create a new phase diagram using Tp flash calculations.
             ///
             /// The usual use case for this function is the calculation of
@@ -651,10 +1766,10 @@ macro_rules! impl_phase_equilibrium {
             #[pyo3(text_signature = "(eos, temperature_or_pressure, feed, min_tp, max_tp, npoints=None)")]
             pub fn lle(
                 eos: $py_eos,
-                temperature_or_pressure: PySINumber,
-                feed: PySIArray1,
-                min_tp: PySINumber,
-                max_tp: PySINumber,
+                temperature_or_pressure: $crate::python::units::PyQuantity,
+                feed: $crate::python::units::PyQuantityArray1,
+                min_tp: $crate::python::units::PyQuantity,
+                max_tp: $crate::python::units::PyQuantity,
                 npoints: Option<usize>,
             ) -> PyResult<Self> {
                 let dia = PhaseDiagram::lle(
@@ -667,6 +1782,72 @@ macro_rules! impl_phase_equilibrium {
                 )?;
                 Ok(Self(dia))
             }
+
+            /// Trace the homogeneous azeotrope of a binary mixture
+            /// over the given temperatures or pressures.
+            ///
+            /// Every azeotrope is used as starting condition for the
+            /// next point. The locus ends if the azeotrope merges
+            /// with a pure component. The indices of the temperatures
+            /// or pressures without an azeotrope are returned together
+            /// with the locus.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos : EquationOfState
+            ///     The equation of state.
+            /// temperatures_or_pressures : SIArray1
+            ///     The system temperatures or pressures.
+            /// x_init : float
+            ///     The initial liquid molefrac of component 1 at the
+            ///     first temperature or pressure.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            /// max_iter_bd_inner : int, optional
+            ///     The maximum number of inner iterations in the bubble/dew point iteration.
+            /// max_iter_bd_outer : int, optional
+            ///     The maximum number of outer iterations in the bubble/dew point iteration.
+            /// tol_bd_inner : float, optional
+            ///     The solution tolerance in the inner loop of the bubble/dew point iteration.
+            /// tol_bd_outer : float, optional
+            ///     The solution tolerance in the outer loop of the bubble/dew point iteration.
+            /// verbosity_bd : Verbosity, optional
+            ///     The verbosity of the bubble/dew point iteration.
+            ///
+            /// Returns
+            /// -------
+            /// (PhaseDiagram, [int])
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperatures_or_pressures, x_init, max_iter=None, tol=None, verbosity=None, max_iter_bd_inner=None, max_iter_bd_outer=None, tol_bd_inner=None, tol_bd_outer=None, verbosity_bd=None)")]
+            pub fn azeotropic_locus(
+                eos: $py_eos,
+                temperatures_or_pressures: &PySIArray1,
+                x_init: f64,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+                max_iter_bd_inner: Option<usize>,
+                max_iter_bd_outer: Option<usize>,
+                tol_bd_inner: Option<f64>,
+                tol_bd_outer: Option<f64>,
+                verbosity_bd: Option<Verbosity>,
+            ) -> PyResult<(Self, Vec<usize>)> {
+                let (dia, missing) = PhaseDiagram::azeotropic_locus(
+                    &eos.0,
+                    temperatures_or_pressures,
+                    x_init,
+                    (max_iter, tol, verbosity).into(),
+                    (
+                        (max_iter_bd_inner, tol_bd_inner, verbosity_bd).into(),
+                        (max_iter_bd_outer, tol_bd_outer, verbosity_bd).into(),
+                    )
+                )?;
+                Ok((Self(dia), missing))
+            }
         }
 
         /// Phase diagram for a binary mixture exhibiting a heteroazeotrope.
@@ -711,9 +1892,9 @@ macro_rules! impl_phase_equilibrium {
             #[pyo3(text_signature = "(eos, pressure, x_lle, min_temperature_lle=None, npoints_vle=None, npoints_lle=None, max_iter_bd_inner=None, max_iter_bd_outer=None, tol_bd_inner=None, tol_bd_outer=None, verbosity_bd=None)")]
             pub fn binary_vlle(
                 eos: $py_eos,
-                pressure: PySINumber,
+                pressure: $crate::python::units::PyQuantity,
                 x_lle: (f64, f64),
-                min_temperature_lle: Option<PySINumber>,
+                min_temperature_lle: Option<$crate::python::units::PyQuantity>,
                 npoints_vle: Option<usize>,
                 npoints_lle: Option<usize>,
                 max_iter_inner: Option<usize>,
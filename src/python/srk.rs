@@ -0,0 +1,64 @@
+use crate::cubic::{MixingRule, PengRobinsonRecord, SoaveRedlichKwongParameters};
+use crate::joback::JobackRecord;
+use crate::parameter::{
+    BinaryRecord, Identifier, IdentifierOption, Parameter, ParameterError, PureRecord,
+};
+use crate::python::cubic::{PyNrtl, PyPengRobinsonRecord};
+use crate::python::joback::PyJobackRecord;
+use crate::python::parameter::PyIdentifier;
+use crate::*;
+use numpy::PyReadonlyArray2;
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use std::convert::{TryFrom, TryInto};
+use std::rc::Rc;
+
+impl_pure_record!(
+    PengRobinsonRecord,
+    PyPengRobinsonRecord,
+    JobackRecord,
+    PyJobackRecord
+);
+
+impl_binary_record!();
+
+/// Create a set of Soave-Redlich-Kwong parameters from records.
+///
+/// The pure substance records are the same as for
+/// the Peng-Robinson equation of state.
+///
+/// Returns
+/// -------
+/// SoaveRedlichKwongParameters
+#[pyclass(name = "SoaveRedlichKwongParameters", unsendable)]
+#[derive(Clone)]
+pub struct PySoaveRedlichKwongParameters(pub Rc<SoaveRedlichKwongParameters>);
+
+impl_parameter!(SoaveRedlichKwongParameters, PySoaveRedlichKwongParameters);
+impl_parameter_database!(SoaveRedlichKwongParameters, PySoaveRedlichKwongParameters);
+
+#[pymethods]
+impl PySoaveRedlichKwongParameters {
+    /// Return a copy of the parameters that uses the Huron-Vidal
+    /// mixing rule with the given activity coefficient model.
+    ///
+    /// Parameters
+    /// ----------
+    /// model : Nrtl
+    ///     The activity coefficient model.
+    ///
+    /// Returns
+    /// -------
+    /// SoaveRedlichKwongParameters
+    #[pyo3(text_signature = "($self, model)")]
+    fn with_huron_vidal(&self, model: PyNrtl) -> PyResult<Self> {
+        let mixing_rule = MixingRule::HuronVidal(Rc::new(model.0));
+        Ok(Self(Rc::new(
+            self.0.as_ref().clone().with_mixing_rule(mixing_rule)?,
+        )))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(self.0.to_string())
+    }
+}
@@ -1,8 +1,32 @@
+use crate::state::DensityInitialization;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use quantity::si::SIUnit;
+
+/// Parse the density initialization `'vapor'`, `'liquid'` or `None`
+/// of the states along paths and of arrays of states.
+pub fn density_initialization(
+    density_initialization: Option<&str>,
+) -> PyResult<DensityInitialization<SIUnit>> {
+    match density_initialization {
+        Some("vapor") => Ok(DensityInitialization::Vapor),
+        Some("liquid") => Ok(DensityInitialization::Liquid),
+        None => Ok(DensityInitialization::None),
+        _ => Err(PyErr::new::<PyValueError, _>(String::from(
+            "`density_initialization` must be 'vapor' or 'liquid'.",
+        ))),
+    }
+}
+
 #[macro_export]
 macro_rules! impl_state {
     ($eos:ty, $py_eos:ty) => {
         /// A thermodynamic state at given conditions.
         ///
+        /// Instead of `SINumber` and `SIArray1`, all quantities can be given
+        /// as a tuple of a value (or an array) and a unit string,
+        /// e.g., `temperature=(300.0, "K")` or `density=(1.2, "kmol/m^3")`.
+        ///
         /// Parameters
         /// ----------
         /// eos : Eos
@@ -21,6 +45,9 @@ macro_rules! impl_state {
         ///     Amount of substance for each component.
         /// molefracs : numpy.ndarray[float]
         ///     Molar fraction of each component.
+        /// molefracs_normalization : MolefracsNormalization, optional
+        ///     Treatment of molefracs that do not sum up to one.
+        ///     By default, molefracs are only checked for negative entries.
         /// pressure : SINumber, optional
         ///     Pressure.
         /// molar_enthalpy : SINumber, optional
@@ -48,7 +75,7 @@ macro_rules! impl_state {
         ///     When the state cannot be created using the combination of input.
         #[pyclass(name = "State", unsendable)]
         #[derive(Clone)]
-        #[pyo3(text_signature = "(eos, temperature=None, volume=None, density=None, partial_density=None, total_moles=None, moles=None, molefracs=None, pressure=None, molar_enthalpy=None, molar_entropy=None, molar_internal_energy=None, density_initialization=None, initial_temperature=None)")]
+        #[pyo3(text_signature = "(eos, temperature=None, volume=None, density=None, partial_density=None, total_moles=None, moles=None, molefracs=None, molefracs_normalization=None, pressure=None, molar_enthalpy=None, molar_entropy=None, molar_internal_energy=None, density_initialization=None, initial_temperature=None)")]
         pub struct PyState(pub State<SIUnit, $eos>);
 
         #[pymethods]
@@ -56,21 +83,26 @@ macro_rules! impl_state {
             #[new]
             pub fn new(
                 eos: $py_eos,
-                temperature: Option<PySINumber>,
-                volume: Option<PySINumber>,
-                density: Option<PySINumber>,
-                partial_density: Option<PySIArray1>,
-                total_moles: Option<PySINumber>,
-                moles: Option<PySIArray1>,
+                temperature: Option<$crate::python::units::PyQuantity>,
+                volume: Option<$crate::python::units::PyQuantity>,
+                density: Option<$crate::python::units::PyQuantity>,
+                partial_density: Option<$crate::python::units::PyQuantityArray1>,
+                total_moles: Option<$crate::python::units::PyQuantity>,
+                moles: Option<$crate::python::units::PyQuantityArray1>,
                 molefracs: Option<&PyArray1<f64>>,
-                pressure: Option<PySINumber>,
-                molar_enthalpy: Option<PySINumber>,
-                molar_entropy: Option<PySINumber>,
-                molar_internal_energy: Option<PySINumber>,
+                molefracs_normalization: Option<MolefracsNormalization>,
+                pressure: Option<$crate::python::units::PyQuantity>,
+                molar_enthalpy: Option<$crate::python::units::PyQuantity>,
+                molar_entropy: Option<$crate::python::units::PyQuantity>,
+                molar_internal_energy: Option<$crate::python::units::PyQuantity>,
                 density_initialization: Option<&PyAny>,
-                initial_temperature: Option<PySINumber>,
+                initial_temperature: Option<$crate::python::units::PyQuantity>,
             ) -> PyResult<Self> {
                 let x = molefracs.and_then(|m| Some(m.to_owned_array()));
+                let x = match (x, molefracs_normalization) {
+                    (Some(x), Some(normalization)) => Some(normalization.apply(&x)?),
+                    (x, _) => x,
+                };
                 let density_init = if let Some(di) = density_initialization {
                     if let Ok(d) = di.extract::<&str>() {
                         match d {
@@ -189,7 +221,9 @@ macro_rules! impl_state {
             /// eos: EquationOfState
             ///     The equation of state to use.
             /// temperature_or_pressure: SINumber
-            ///     temperature_or_pressure.
+            ///     The system temperature or pressure. Whether the critical
+            ///     point is calculated for a given temperature or pressure
+            ///     is determined from the unit of the value.
             /// initial_temperature: SINumber, optional
             ///     An initial guess for the temperature.
             /// initial_molefracs: [float], optional
@@ -205,7 +239,7 @@ macro_rules! impl_state {
             /// -------
             /// State : State at critical conditions.
             #[staticmethod]
-            #[pyo3(text_signature = "(eos, temperature_or_pressure, initial_molefracs=None, max_iter=None, tol=None, verbosity=None)")]
+            #[pyo3(text_signature = "(eos, temperature_or_pressure, initial_temperature=None, initial_molefracs=None, max_iter=None, tol=None, verbosity=None)")]
             fn critical_point_binary(
                 eos: $py_eos,
                 temperature_or_pressure: PySINumber,
@@ -224,6 +258,359 @@ macro_rules! impl_state {
                 )?))
             }
 
+            /// Calculate the liquid state at given temperature and pressure
+            /// in which a component has the given activity.
+            ///
+            /// The remaining components keep the ratio given by `moles`.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos: EquationOfState
+            ///     The equation of state to use.
+            /// temperature: SINumber
+            ///     The system temperature.
+            /// pressure: SINumber
+            ///     The system pressure.
+            /// moles: SIArray1
+            ///     Amount of substance of each component. The entry of
+            ///     the specified component is used as initial guess.
+            /// component: int
+            ///     The index of the component.
+            /// activity: float
+            ///     The activity of the component.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// State
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, temperature, pressure, moles, component, activity, max_iter=None, tol=None, verbosity=None)")]
+            fn new_activity_composition(
+                eos: $py_eos,
+                temperature: PySINumber,
+                pressure: PySINumber,
+                moles: PySIArray1,
+                component: usize,
+                activity: f64,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(PyState(State::new_activity_composition(
+                    &eos.0,
+                    temperature.into(),
+                    pressure.into(),
+                    &moles,
+                    component,
+                    activity,
+                    (max_iter, tol, verbosity).into(),
+                )?))
+            }
+
+            /// Calculate the liquid state at given pressure and composition
+            /// in which a component has the given activity.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos: EquationOfState
+            ///     The equation of state to use.
+            /// pressure: SINumber
+            ///     The system pressure.
+            /// moles: SIArray1
+            ///     Amount of substance of each component.
+            /// component: int
+            ///     The index of the component.
+            /// activity: float
+            ///     The activity of the component.
+            /// initial_temperature: SINumber
+            ///     The initial temperature.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// State
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, pressure, moles, component, activity, initial_temperature, max_iter=None, tol=None, verbosity=None)")]
+            fn new_activity_temperature(
+                eos: $py_eos,
+                pressure: PySINumber,
+                moles: PySIArray1,
+                component: usize,
+                activity: f64,
+                initial_temperature: PySINumber,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(PyState(State::new_activity_temperature(
+                    &eos.0,
+                    pressure.into(),
+                    &moles,
+                    component,
+                    activity,
+                    initial_temperature.into(),
+                    (max_iter, tol, verbosity).into(),
+                )?))
+            }
+
+            /// Calculate the states along an isochore.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos: EquationOfState
+            ///     The equation of state to use.
+            /// density: SINumber
+            ///     The molar density.
+            /// min_temperature: SINumber
+            ///     The temperature of the first state.
+            /// max_temperature: SINumber
+            ///     The temperature of the last state.
+            /// npoints: int
+            ///     The number of equidistant states.
+            /// moles: SIArray1
+            ///     Amount of substance of each component.
+            ///
+            /// Returns
+            /// -------
+            /// StateVec
+            #[staticmethod]
+            #[pyo3(text_signature = "(eos, density, min_temperature, max_temperature, npoints, moles)")]
+            fn isochore(
+                eos: $py_eos,
+                density: PySINumber,
+                min_temperature: PySINumber,
+                max_temperature: PySINumber,
+                npoints: usize,
+                moles: PySIArray1,
+            ) -> PyResult<PyStateVec> {
+                Ok(PyStateVec(
+                    State::isochore(
+                        &eos.0,
+                        density.into(),
+                        min_temperature.into(),
+                        max_temperature.into(),
+                        npoints,
+                        &moles,
+                    )?
+                    .states,
+                ))
+            }
+
+            /// Calculate the states along an isobar.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos: EquationOfState
+            ///     The equation of state to use.
+            /// pressure: SINumber
+            ///     The pressure.
+            /// min_temperature: SINumber
+            ///     The temperature of the first state.
+            /// max_temperature: SINumber
+            ///     The temperature of the last state.
+            /// npoints: int
+            ///     The number of equidistant states.
+            /// moles: SIArray1
+            ///     Amount of substance of each component.
+            /// density_initialization : {'vapor', 'liquid'}, optional
+            ///     Phase of the first state. All further states are
+            ///     initialized with the density of the previous state.
+            ///
+            /// Returns
+            /// -------
+            /// StateVec
+            #[staticmethod]
+            #[args(density_initialization = "None")]
+            #[pyo3(text_signature = "(eos, pressure, min_temperature, max_temperature, npoints, moles, density_initialization=None)")]
+            fn isobar(
+                eos: $py_eos,
+                pressure: PySINumber,
+                min_temperature: PySINumber,
+                max_temperature: PySINumber,
+                npoints: usize,
+                moles: PySIArray1,
+                density_initialization: Option<&str>,
+            ) -> PyResult<PyStateVec> {
+                let density_initialization =
+                    $crate::python::state::density_initialization(density_initialization)?;
+                Ok(PyStateVec(
+                    State::isobar(
+                        &eos.0,
+                        pressure.into(),
+                        min_temperature.into(),
+                        max_temperature.into(),
+                        npoints,
+                        &moles,
+                        density_initialization,
+                    )?
+                    .states,
+                ))
+            }
+
+            /// Calculate the states along an isotherm.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos: EquationOfState
+            ///     The equation of state to use.
+            /// temperature: SINumber
+            ///     The temperature.
+            /// min_pressure: SINumber
+            ///     The pressure of the first state.
+            /// max_pressure: SINumber
+            ///     The pressure of the last state.
+            /// npoints: int
+            ///     The number of equidistant states.
+            /// moles: SIArray1
+            ///     Amount of substance of each component.
+            /// density_initialization : {'vapor', 'liquid'}, optional
+            ///     Phase of the first state. All further states are
+            ///     initialized with the density of the previous state.
+            ///
+            /// Returns
+            /// -------
+            /// StateVec
+            #[staticmethod]
+            #[args(density_initialization = "None")]
+            #[pyo3(text_signature = "(eos, temperature, min_pressure, max_pressure, npoints, moles, density_initialization=None)")]
+            fn isotherm(
+                eos: $py_eos,
+                temperature: PySINumber,
+                min_pressure: PySINumber,
+                max_pressure: PySINumber,
+                npoints: usize,
+                moles: PySIArray1,
+                density_initialization: Option<&str>,
+            ) -> PyResult<PyStateVec> {
+                let density_initialization =
+                    $crate::python::state::density_initialization(density_initialization)?;
+                Ok(PyStateVec(
+                    State::isotherm(
+                        &eos.0,
+                        temperature.into(),
+                        min_pressure.into(),
+                        max_pressure.into(),
+                        npoints,
+                        &moles,
+                        density_initialization,
+                    )?
+                    .states,
+                ))
+            }
+
+            /// Calculate states for arrays of temperatures, pressures
+            /// and compositions.
+            ///
+            /// Every state contains the reference amount of substance
+            /// of 1/N_A mol, i.e., extensive properties are given per
+            /// molecule.
+            /// In contrast to `State.isobar` and `State.isotherm`, the
+            /// states do not have to be located on a path.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos: EquationOfState
+            ///     The equation of state to use.
+            /// temperature: SIArray1
+            ///     The temperatures of the states.
+            /// pressure: SIArray1
+            ///     The pressures of the states.
+            /// molefracs: numpy.ndarray[float]
+            ///     The mole fractions of the states. Either one row
+            ///     for every state or a single row for all states.
+            /// density_initialization : {'vapor', 'liquid'}, optional
+            ///     Phase of all states.
+            ///
+            /// Returns
+            /// -------
+            /// StateVec
+            #[staticmethod]
+            #[args(density_initialization = "None")]
+            #[pyo3(text_signature = "(eos, temperature, pressure, molefracs, density_initialization=None)")]
+            fn from_arrays(
+                eos: $py_eos,
+                temperature: PySIArray1,
+                pressure: PySIArray1,
+                molefracs: &PyArray2<f64>,
+                density_initialization: Option<&str>,
+            ) -> PyResult<PyStateVec> {
+                let density_initialization =
+                    $crate::python::state::density_initialization(density_initialization)?;
+                Ok(PyStateVec(State::from_arrays(
+                    &eos.0,
+                    &temperature,
+                    &pressure,
+                    &molefracs.to_owned_array(),
+                    density_initialization,
+                )?))
+            }
+
+            /// Calculate the vapor and liquid spinodal states at given
+            /// temperature and composition.
+            ///
+            /// Parameters
+            /// ----------
+            /// eos: EquationOfState
+            ///     The equation of state to use.
+            /// temperature: SINumber
+            ///     The system temperature.
+            /// moles: SIArray1, optional
+            ///     Amount of substance of each component.
+            ///     Only optional for a pure component.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// (State, State) : The vapor and the liquid spinodal state.
+            #[staticmethod]
+            #[args(moles = "None")]
+            #[pyo3(text_signature = "(eos, temperature, moles=None, max_iter=None, tol=None, verbosity=None)")]
+            fn spinodal(
+                eos: $py_eos,
+                temperature: PySINumber,
+                moles: Option<PySIArray1>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<(Self, Self)> {
+                let [vapor, liquid] = State::spinodal(
+                    &eos.0,
+                    temperature.into(),
+                    moles.as_deref(),
+                    (max_iter, tol, verbosity).into(),
+                )?;
+                Ok((PyState(vapor), PyState(liquid)))
+            }
+
+            /// Calculate the derivatives of the critical temperature and the
+            /// critical pressure with respect to the composition.
+            ///
+            /// The state has to be a critical point.
+            ///
+            /// Returns
+            /// -------
+            /// (SIArray1, SIArray1) : The derivatives N*dTc/dN_i and N*dpc/dN_i.
+            #[pyo3(text_signature = "($self)")]
+            fn critical_point_derivatives(&self) -> PyResult<(PySIArray1, PySIArray1)> {
+                let (dtc, dpc) = self.0.critical_point_derivatives()?;
+                Ok((dtc.into(), dpc.into()))
+            }
+
             /// Performs a stability analysis and returns a list of stable
             /// candidate states.
             ///
@@ -253,6 +640,39 @@ macro_rules! impl_state {
                     .collect())
             }
 
+            /// Performs a stability analysis and returns the results of the
+            /// tangent plane distance minimization for all trial phases.
+            ///
+            /// In contrast to `stability_analysis`, trivial and not converged
+            /// solutions are included in the result.
+            ///
+            /// Parameters
+            /// ----------
+            /// max_iter : int, optional
+            ///     The maximum number of iterations.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// List[StabilityResult]
+            ///     The results of the minimization for every trial phase.
+            #[pyo3(text_signature = "(max_iter=None, tol=None, verbosity=None)")]
+            fn stability_analysis_detailed(&self,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Vec<PyStabilityResult>> {
+                Ok(self
+                    .0
+                    .stability_analysis_detailed((max_iter, tol, verbosity).into())?
+                    .into_iter()
+                    .map(PyStabilityResult)
+                    .collect())
+            }
+
             /// Performs a stability analysis and returns whether the state
             /// is stable
             ///
@@ -308,6 +728,30 @@ macro_rules! impl_state {
                     .collect()
             }
 
+            /// Compare the derivatives of all residual Helmholtz energy
+            /// contributions to finite difference approximations.
+            ///
+            /// Useful to validate user defined equations of state for
+            /// all types of (hyper-) dual numbers.
+            ///
+            /// Returns
+            /// -------
+            /// List[Tuple[str, str, str, float, float, float]]
+            ///     The contribution, the derivative, the dual number, the
+            ///     derivative calculated with the dual number, the finite
+            ///     difference approximation and the relative deviation.
+            #[pyo3(text_signature = "($self)")]
+            fn verify_derivatives(&self) -> Vec<(String, String, String, f64, f64, f64)> {
+                self.0
+                    .verify_derivatives()
+                    .into_iter()
+                    .map(|c| {
+                        let deviation = c.deviation();
+                        (c.contribution, c.derivative, c.dual_number.to_owned(), c.value, c.reference, deviation)
+                    })
+                    .collect()
+            }
+
             /// Return compressibility.
             ///
             /// Parameters
@@ -461,6 +905,28 @@ macro_rules! impl_state {
                 PySIArray1::from(self.0.chemical_potential(contributions))
             }
 
+            /// Return the residual chemical potential of each component
+            /// w.r.t. an ideal gas at the same temperature and pressure.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray1
+            #[pyo3(text_signature = "($self)")]
+            fn chemical_potential_residual_tp(&self) -> PySIArray1 {
+                PySIArray1::from(self.0.chemical_potential_residual_tp())
+            }
+
+            /// Return the chemical potential of each component in
+            /// an ideal gas at the same temperature and pressure.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray1
+            #[pyo3(text_signature = "($self)")]
+            fn ideal_chemical_potential(&self) -> PySIArray1 {
+                PySIArray1::from(self.0.ideal_chemical_potential())
+            }
+
             /// Return chemical potential contributions.
             ///
             /// Parameters
@@ -548,6 +1014,50 @@ macro_rules! impl_state {
                 Ok(self.0.ln_symmetric_activity_coefficient()?.view().to_pyarray(py))
             }
 
+            /// Return the activities of all components.
+            ///
+            /// The pure liquids at mixture temperature and pressure
+            /// are used as reference states.
+            ///
+            /// Returns
+            /// -------
+            /// numpy.ndarray
+            #[pyo3(text_signature = "($self)")]
+            fn activity<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+                Ok(self.0.activity()?.view().to_pyarray(py))
+            }
+
+            /// Return the molal osmotic coefficient of the solvent.
+            ///
+            /// Parameters
+            /// ----------
+            /// solvent: int
+            ///     The index of the solvent.
+            ///
+            /// Returns
+            /// -------
+            /// float
+            #[pyo3(text_signature = "($self, solvent)")]
+            fn osmotic_coefficient(&self, solvent: usize) -> PyResult<f64> {
+                Ok(self.0.osmotic_coefficient(solvent)?)
+            }
+
+            /// Return the osmotic pressure of the solution with respect
+            /// to the pure solvent.
+            ///
+            /// Parameters
+            /// ----------
+            /// solvent: int
+            ///     The index of the solvent.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            #[pyo3(text_signature = "($self, solvent)")]
+            fn osmotic_pressure(&self, solvent: usize) -> PyResult<PySINumber> {
+                Ok(PySINumber::from(self.0.osmotic_pressure(solvent)?))
+            }
+
             /// Return derivative of logarithmic fugacity coefficient w.r.t. temperature.
             ///
             /// Returns
@@ -760,6 +1270,44 @@ macro_rules! impl_state {
                 PySIArray1::from(self.0.partial_molar_enthalpy(contributions))
             }
 
+            /// Return partial molar isobaric heat capacity of each component.
+            ///
+            /// Equal to the temperature derivative of the partial
+            /// molar enthalpy at constant pressure.
+            ///
+            /// Parameters
+            /// ----------
+            /// contributions: Contributions, optional
+            ///     the contributions of the helmholtz energy.
+            ///     Defaults to Contributions.Total.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray1
+            #[args(contributions = "Contributions::Total")]
+            #[pyo3(text_signature = "($self, contributions)")]
+            fn partial_molar_heat_capacity(&self, contributions: Contributions) -> PySIArray1 {
+                PySIArray1::from(self.0.partial_molar_heat_capacity(contributions))
+            }
+
+            /// Return derivative of the partial molar enthalpy of each
+            /// component w.r.t. pressure at constant temperature.
+            ///
+            /// Parameters
+            /// ----------
+            /// contributions: Contributions, optional
+            ///     the contributions of the helmholtz energy.
+            ///     Defaults to Contributions.Total.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray1
+            #[args(contributions = "Contributions::Total")]
+            #[pyo3(text_signature = "($self, contributions)")]
+            fn dh_i_dp(&self, contributions: Contributions) -> PySIArray1 {
+                PySIArray1::from(self.0.dh_i_dp(contributions))
+            }
+
             /// Return helmholtz_energy.
             ///
             /// Parameters
@@ -937,6 +1485,17 @@ macro_rules! impl_state {
                 PySINumber::from(self.0.density)
             }
 
+            /// User defined metadata, e.g., an experiment ID or a branch label.
+            #[getter]
+            fn get_metadata(&self) -> std::collections::BTreeMap<String, String> {
+                self.0.metadata.clone()
+            }
+
+            #[setter]
+            fn set_metadata(&mut self, metadata: std::collections::BTreeMap<String, String>) {
+                self.0.metadata = metadata;
+            }
+
             #[getter]
             fn get_moles(&self) -> PySIArray1 {
                 PySIArray1::from(self.0.moles.clone())
@@ -971,6 +1530,75 @@ macro_rules! impl_state {
             }
         }
 
+        /// Result of the tangent plane distance minimization
+        /// for a single trial phase of a stability analysis.
+        #[pyclass(name = "StabilityResult", unsendable)]
+        #[derive(Clone)]
+        pub struct PyStabilityResult(StabilityResult<SIUnit, $eos>);
+
+        #[pymethods]
+        impl PyStabilityResult {
+            /// The component that dominates the initial trial phase
+            /// or None for the trial phase that is initialized as
+            /// an ideal vapor.
+            #[getter]
+            fn get_dominant_component(&self) -> Option<usize> {
+                self.0.dominant_component
+            }
+
+            /// The trial state at the end of the minimization.
+            #[getter]
+            fn get_state(&self) -> PyState {
+                PyState(self.0.state.clone())
+            }
+
+            /// The tangent plane distance of the trial state.
+            #[getter]
+            fn get_tpd(&self) -> f64 {
+                self.0.tpd
+            }
+
+            /// Whether the minimization converged.
+            #[getter]
+            fn get_converged(&self) -> bool {
+                self.0.converged
+            }
+
+            /// Whether the minimization converged to the
+            /// composition of the analyzed state.
+            #[getter]
+            fn get_trivial(&self) -> bool {
+                self.0.trivial
+            }
+
+            /// The number of iterations.
+            #[getter]
+            fn get_iterations(&self) -> usize {
+                self.0.iterations
+            }
+
+            /// Whether the trial state has a negative tangent plane
+            /// distance and indicates an instability of the analyzed state.
+            ///
+            /// Returns
+            /// -------
+            /// bool
+            #[pyo3(text_signature = "($self)")]
+            fn is_candidate(&self) -> bool {
+                self.0.is_candidate()
+            }
+
+            fn __repr__(&self) -> String {
+                format!(
+                    "StabilityResult(dominant_component={:?}, tpd={}, converged={}, trivial={}, iterations={})",
+                    self.0.dominant_component,
+                    self.0.tpd,
+                    self.0.converged,
+                    self.0.trivial,
+                    self.0.iterations
+                )
+            }
+        }
 
         #[pyclass(name = "StateVec", unsendable)]
         pub struct PyStateVec(Vec<State<SIUnit, $eos>>);
@@ -1050,6 +1678,35 @@ macro_rules! impl_state {
 #[macro_export]
 macro_rules! impl_state_molarweight {
     ($eos:ty, $py_eos:ty) => {
+        impl PyState {
+            /// Raise an error if the equation of state does not provide
+            /// molar weights, instead of failing in the model.
+            fn check_molar_weight(&self) -> PyResult<()> {
+                if self.0.eos.capabilities().molar_weight {
+                    Ok(())
+                } else {
+                    Err(PyErr::new::<PyValueError, _>(
+                        "this model does not provide molar weights; mass-based properties unavailable",
+                    ))
+                }
+            }
+        }
+
+        impl PyStateVec {
+            /// Raise an error if the equation of state does not provide
+            /// molar weights, instead of failing in the model.
+            fn check_molar_weight(&self) -> PyResult<()> {
+                match self.0.first() {
+                    Some(state) if !state.eos.capabilities().molar_weight => {
+                        Err(PyErr::new::<PyValueError, _>(
+                            "this model does not provide molar weights; mass-based properties unavailable",
+                        ))
+                    }
+                    _ => Ok(()),
+                }
+            }
+        }
+
         #[pymethods]
         impl PyState {
             /// Return total molar weight.
@@ -1058,8 +1715,9 @@ macro_rules! impl_state_molarweight {
             /// -------
             /// SINumber
             #[pyo3(text_signature = "($self)")]
-            fn total_molar_weight(&self) -> PySINumber {
-                PySINumber::from(self.0.total_molar_weight())
+            fn total_molar_weight(&self) -> PyResult<PySINumber> {
+                self.check_molar_weight()?;
+                Ok(PySINumber::from(self.0.total_molar_weight()))
             }
 
             /// Return speed of sound.
@@ -1068,8 +1726,46 @@ macro_rules! impl_state_molarweight {
             /// -------
             /// SINumber
             #[pyo3(text_signature = "($self)")]
-            fn speed_of_sound(&self) -> PySINumber {
-                PySINumber::from(self.0.speed_of_sound())
+            fn speed_of_sound(&self) -> PyResult<PySINumber> {
+                self.check_molar_weight()?;
+                Ok(PySINumber::from(self.0.speed_of_sound()))
+            }
+
+            /// Check the homogeneity of all properties by scaling the system size.
+            ///
+            /// Volume and amount of substance are multiplied by `factor`.
+            /// Extensive properties have to scale by the same factor,
+            /// intensive properties have to remain unchanged. Useful to
+            /// find errors in the implementation of new models.
+            ///
+            /// Parameters
+            /// ----------
+            /// factor: float, optional
+            ///     The scaling factor. Defaults to 2.
+            /// tol: float, optional
+            ///     The maximum deviation relative to the value of the
+            ///     property or, if it is close to zero, relative to a
+            ///     characteristic value of the ideal gas. Defaults to 1e-10.
+            ///
+            /// Returns
+            /// -------
+            /// dict[str, float]
+            ///     The deviations of all properties that violate
+            ///     the homogeneity condition.
+            #[args(factor = "2.0", tol = "1e-10")]
+            #[pyo3(text_signature = "($self, factor, tol)")]
+            fn homogeneity_violations(
+                &self,
+                factor: f64,
+                tol: f64,
+            ) -> PyResult<std::collections::HashMap<String, f64>> {
+                self.check_molar_weight()?;
+                Ok(self
+                    .0
+                    .homogeneity_violations(factor, tol)?
+                    .into_iter()
+                    .map(|(p, d)| (p.to_string(), d))
+                    .collect())
             }
 
             /// Returns mass of each component in the system.
@@ -1078,8 +1774,9 @@ macro_rules! impl_state_molarweight {
             /// -------
             /// SIArray1
             #[pyo3(text_signature = "($self)")]
-            fn mass(&self) -> PySIArray1 {
-                PySIArray1::from(self.0.mass())
+            fn mass(&self) -> PyResult<PySIArray1> {
+                self.check_molar_weight()?;
+                Ok(PySIArray1::from(self.0.mass()))
             }
 
             /// Returns system's total mass.
@@ -1088,8 +1785,9 @@ macro_rules! impl_state_molarweight {
             /// -------
             /// SINumber
             #[pyo3(text_signature = "($self)")]
-            fn total_mass(&self) -> PySINumber {
-                PySINumber::from(self.0.total_mass())
+            fn total_mass(&self) -> PyResult<PySINumber> {
+                self.check_molar_weight()?;
+                Ok(PySINumber::from(self.0.total_mass()))
             }
 
             /// Returns system's mass density.
@@ -1098,8 +1796,9 @@ macro_rules! impl_state_molarweight {
             /// -------
             /// SINumber
             #[pyo3(text_signature = "($self)")]
-            fn mass_density(&self) -> PySINumber {
-                PySINumber::from(self.0.mass_density())
+            fn mass_density(&self) -> PyResult<PySINumber> {
+                self.check_molar_weight()?;
+                Ok(PySINumber::from(self.0.mass_density()))
             }
 
             /// Returns mass fractions for each component.
@@ -1108,8 +1807,9 @@ macro_rules! impl_state_molarweight {
             /// -------
             /// numpy.ndarray[Float64]
             #[pyo3(text_signature = "($self)")]
-            fn massfracs<'py>(&self, py: Python<'py>) -> &'py PyArray1<f64> {
-                self.0.massfracs().view().to_pyarray(py)
+            fn massfracs<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+                self.check_molar_weight()?;
+                Ok(self.0.massfracs().view().to_pyarray(py))
             }
 
             /// Return mass specific helmholtz_energy.
@@ -1125,8 +1825,9 @@ macro_rules! impl_state_molarweight {
             /// SINumber
             #[args(contributions = "Contributions::Total")]
             #[pyo3(text_signature = "($self, contributions)")]
-            fn specific_helmholtz_energy(&self, contributions: Contributions) -> PySINumber {
-                PySINumber::from(self.0.specific_helmholtz_energy(contributions))
+            fn specific_helmholtz_energy(&self, contributions: Contributions) -> PyResult<PySINumber> {
+                self.check_molar_weight()?;
+                Ok(PySINumber::from(self.0.specific_helmholtz_energy(contributions)))
             }
 
             /// Return mass specific entropy.
@@ -1142,8 +1843,9 @@ macro_rules! impl_state_molarweight {
             /// SINumber
             #[args(contributions = "Contributions::Total")]
             #[pyo3(text_signature = "($self, contributions)")]
-            fn specific_entropy(&self, contributions: Contributions) -> PySINumber {
-                PySINumber::from(self.0.specific_entropy(contributions))
+            fn specific_entropy(&self, contributions: Contributions) -> PyResult<PySINumber> {
+                self.check_molar_weight()?;
+                Ok(PySINumber::from(self.0.specific_entropy(contributions)))
             }
 
             /// Return mass specific internal_energy.
@@ -1159,8 +1861,9 @@ macro_rules! impl_state_molarweight {
             /// SINumber
             #[args(contributions = "Contributions::Total")]
             #[pyo3(text_signature = "($self, contributions)")]
-            fn specific_internal_energy(&self, contributions: Contributions) -> PySINumber {
-                PySINumber::from(self.0.specific_internal_energy(contributions))
+            fn specific_internal_energy(&self, contributions: Contributions) -> PyResult<PySINumber> {
+                self.check_molar_weight()?;
+                Ok(PySINumber::from(self.0.specific_internal_energy(contributions)))
             }
 
             /// Return mass specific gibbs_energy.
@@ -1176,8 +1879,9 @@ macro_rules! impl_state_molarweight {
             /// SINumber
             #[args(contributions = "Contributions::Total")]
             #[pyo3(text_signature = "($self, contributions)")]
-            fn specific_gibbs_energy(&self, contributions: Contributions) -> PySINumber {
-                PySINumber::from(self.0.specific_gibbs_energy(contributions))
+            fn specific_gibbs_energy(&self, contributions: Contributions) -> PyResult<PySINumber> {
+                self.check_molar_weight()?;
+                Ok(PySINumber::from(self.0.specific_gibbs_energy(contributions)))
             }
 
             /// Return mass specific enthalpy.
@@ -1193,31 +1897,68 @@ macro_rules! impl_state_molarweight {
             /// SINumber
             #[args(contributions = "Contributions::Total")]
             #[pyo3(text_signature = "($self, contributions)")]
-            fn specific_enthalpy(&self, contributions: Contributions) -> PySINumber {
-                PySINumber::from(self.0.specific_enthalpy(contributions))
+            fn specific_enthalpy(&self, contributions: Contributions) -> PyResult<PySINumber> {
+                self.check_molar_weight()?;
+                Ok(PySINumber::from(self.0.specific_enthalpy(contributions)))
             }
         }
 
         #[pymethods]
         impl PyStateVec {
             #[getter]
-            fn get_mass_density(&self) -> PySIArray1 {
-                StateVec::from(self).mass_density().into()
+            fn get_mass_density(&self) -> PyResult<PySIArray1> {
+                self.check_molar_weight()?;
+                Ok(StateVec::from(self).mass_density().into())
             }
 
             #[getter]
-            fn get_massfracs<'py>(&self, py: Python<'py>) -> &'py PyArray2<f64> {
-                StateVec::from(self).massfracs().view().to_pyarray(py)
+            fn get_massfracs<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
+                self.check_molar_weight()?;
+                Ok(StateVec::from(self).massfracs().view().to_pyarray(py))
             }
 
             #[getter]
-            fn get_specific_enthalpy(&self) -> PySIArray1 {
-                StateVec::from(self).specific_enthalpy().into()
+            fn get_specific_enthalpy(&self) -> PyResult<PySIArray1> {
+                self.check_molar_weight()?;
+                Ok(StateVec::from(self).specific_enthalpy().into())
             }
 
             #[getter]
-            fn get_specific_entropy(&self) -> PySIArray1 {
-                StateVec::from(self).specific_entropy().into()
+            fn get_specific_entropy(&self) -> PyResult<PySIArray1> {
+                self.check_molar_weight()?;
+                Ok(StateVec::from(self).specific_entropy().into())
+            }
+
+            /// Return the properties of all states with respect to a common basis.
+            ///
+            /// Parameters
+            /// ----------
+            /// basis : Basis
+            ///     Molar, mass (specific) or reduced properties.
+            ///
+            /// Returns
+            /// -------
+            /// dict
+            ///     Temperature, pressure, density, composition (mole or mass
+            ///     fractions), enthalpy and entropy of all states.
+            #[pyo3(text_signature = "($self, basis)")]
+            fn to_basis<'py>(
+                &self,
+                basis: Basis,
+                py: Python<'py>,
+            ) -> PyResult<&'py pyo3::types::PyDict> {
+                if basis == Basis::Mass {
+                    self.check_molar_weight()?;
+                }
+                let properties = StateVec::from(self).to_basis(basis)?;
+                let dict = pyo3::types::PyDict::new(py);
+                dict.set_item("temperature", PySIArray1::from(properties.temperature))?;
+                dict.set_item("pressure", PySIArray1::from(properties.pressure))?;
+                dict.set_item("density", PySIArray1::from(properties.density))?;
+                dict.set_item("composition", properties.composition.view().to_pyarray(py))?;
+                dict.set_item("enthalpy", PySIArray1::from(properties.enthalpy))?;
+                dict.set_item("entropy", PySIArray1::from(properties.entropy))?;
+                Ok(dict)
             }
         }
     };
@@ -0,0 +1,76 @@
+#[macro_export]
+macro_rules! impl_planar_interface {
+    ($eos:ty) => {
+        /// A planar interface between the two phases of a phase equilibrium
+        /// calculated with the square gradient theory.
+        #[pyclass(name = "PlanarInterface", unsendable)]
+        #[derive(Clone)]
+        pub struct PyPlanarInterface($crate::surface_tension::PlanarInterface<SIUnit, $eos>);
+
+        #[pymethods]
+        impl PyPlanarInterface {
+            /// Calculate the planar interface between the two phases
+            /// of a phase equilibrium.
+            ///
+            /// Parameters
+            /// ----------
+            /// vle : PhaseEquilibrium
+            ///     The phase equilibrium between the bulk phases.
+            /// n_grid : int, optional
+            ///     The number of points on the path between the bulk phases.
+            ///     Defaults to 200.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations at every point of the path.
+            /// tol: float, optional
+            ///     The solution tolerance.
+            /// verbosity : Verbosity, optional
+            ///     The verbosity.
+            ///
+            /// Returns
+            /// -------
+            /// PlanarInterface
+            #[staticmethod]
+            #[pyo3(text_signature = "(vle, n_grid=None, max_iter=None, tol=None, verbosity=None)")]
+            fn from_phase_equilibrium(
+                vle: &PyPhaseEquilibrium,
+                n_grid: Option<usize>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(Self(
+                    $crate::surface_tension::PlanarInterface::from_phase_equilibrium(
+                        &vle.0,
+                        n_grid,
+                        (max_iter, tol, verbosity).into(),
+                    )?,
+                ))
+            }
+
+            /// The surface tension of the interface.
+            #[getter]
+            fn get_surface_tension(&self) -> PySINumber {
+                PySINumber::from(self.0.surface_tension())
+            }
+
+            /// The partial densities (rows) at every point of
+            /// the path (columns) from the vapor to the liquid.
+            #[getter]
+            fn get_partial_density(&self) -> PySIArray2 {
+                PySIArray2::from(self.0.partial_density().clone())
+            }
+
+            /// The grand potential density relative to the
+            /// bulk phases at every point of the path.
+            #[getter]
+            fn get_grand_potential_density(&self) -> PySIArray1 {
+                PySIArray1::from(self.0.grand_potential_density().clone())
+            }
+
+            #[getter]
+            fn get_phase_equilibrium(&self) -> PyPhaseEquilibrium {
+                PyPhaseEquilibrium(self.0.phase_equilibrium().clone())
+            }
+        }
+    };
+}
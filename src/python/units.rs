@@ -0,0 +1,64 @@
+use ndarray::Array1;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use quantity::python::{PySIArray1, PySINumber};
+use quantity::si::*;
+use std::ops::Deref;
+
+/// Parse a unit string like `"K"`, `"kJ/mol"` or `"mol/m^3"`.
+pub fn parse_unit(unit: &str) -> PyResult<SINumber> {
+    crate::parameter::parse_unit(unit).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// A scalar quantity given either as `SINumber` or as a
+/// tuple of a value and a unit string, e.g., `(300.0, "K")`.
+#[derive(Clone, Copy)]
+pub struct PyQuantity(pub SINumber);
+
+impl<'source> FromPyObject<'source> for PyQuantity {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(q) = ob.extract::<PySINumber>() {
+            return Ok(Self(q.into()));
+        }
+        let (value, unit): (f64, &str) = ob.extract().map_err(|_| {
+            PyValueError::new_err("Expected an SINumber or a tuple of a float and a unit string")
+        })?;
+        Ok(Self(value * parse_unit(unit)?))
+    }
+}
+
+impl From<PyQuantity> for SINumber {
+    fn from(quantity: PyQuantity) -> Self {
+        quantity.0
+    }
+}
+
+/// An array quantity given either as `SIArray1` or as a tuple of a
+/// numpy array (or list) and a unit string, e.g., `(np.array([1.0, 2.0]), "mol")`.
+#[derive(Clone)]
+pub struct PyQuantityArray1(pub SIArray1);
+
+impl<'source> FromPyObject<'source> for PyQuantityArray1 {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(q) = ob.extract::<PySIArray1>() {
+            return Ok(Self(q.deref().clone()));
+        }
+        let (value, unit): (Vec<f64>, &str) = ob.extract().map_err(|_| {
+            PyValueError::new_err("Expected an SIArray1 or a tuple of an array and a unit string")
+        })?;
+        Ok(Self(Array1::from(value) * parse_unit(unit)?))
+    }
+}
+
+impl Deref for PyQuantityArray1 {
+    type Target = SIArray1;
+    fn deref(&self) -> &SIArray1 {
+        &self.0
+    }
+}
+
+impl From<PyQuantityArray1> for SIArray1 {
+    fn from(quantity: PyQuantityArray1) -> Self {
+        quantity.0
+    }
+}
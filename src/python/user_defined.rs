@@ -4,6 +4,7 @@ use ndarray::prelude::*;
 use num_dual::python::{PyDual3Dual64, PyDual3_64, PyDual64, PyHyperDual64, PyHyperDualDual64};
 use num_dual::{Dual, Dual3, Dual3_64, Dual64, DualVec64, HyperDual, HyperDual64};
 use numpy::convert::IntoPyArray;
+use pyo3::exceptions::PyAttributeError;
 use pyo3::prelude::*;
 use quantity::python::PySIArray1;
 use std::fmt;
@@ -12,6 +13,7 @@ struct PyHelmholtzEnergy(Py<PyAny>);
 
 pub struct PyEoSObj {
     obj: Py<PyAny>,
+    molar_weight: bool,
     contributions: Vec<Box<dyn HelmholtzEnergy>>,
 }
 
@@ -20,26 +22,26 @@ impl PyEoSObj {
         Python::with_gil(|py| {
             let attr = obj.as_ref(py).hasattr("components")?;
             if !attr {
-                panic!("Python Class has to have a method 'components' with signature:\n\tdef signature(self) -> int")
+                return Err(PyAttributeError::new_err("Python Class has to have a method 'components' with signature:\n\tdef signature(self) -> int"));
             }
             let attr = obj.as_ref(py).hasattr("subset")?;
             if !attr {
-                panic!("Python Class has to have a method 'subset' with signature:\n\tdef subset(self, component_list: List[int]) -> Self")
-            }
-            let attr = obj.as_ref(py).hasattr("molar_weight")?;
-            if !attr {
-                panic!("Python Class has to have a method 'molar_weight' with signature:\n\tdef molar_weight(self) -> SIArray1\nwhere the size of the returned array has to be 'components'.")
+                return Err(PyAttributeError::new_err("Python Class has to have a method 'subset' with signature:\n\tdef subset(self, component_list: List[int]) -> Self"));
             }
+            // the molar weight is optional, without it mass-based
+            // properties are not available
+            let molar_weight = obj.as_ref(py).hasattr("molar_weight")?;
             let attr = obj.as_ref(py).hasattr("max_density")?;
             if !attr {
-                panic!("Python Class has to have a method 'max_density' with signature:\n\tdef max_density(self, moles: numpy.ndarray[float]) -> float\nwhere the size of the input array has to be 'components'.")
+                return Err(PyAttributeError::new_err("Python Class has to have a method 'max_density' with signature:\n\tdef max_density(self, moles: numpy.ndarray[float]) -> float\nwhere the size of the input array has to be 'components'."));
             }
             let attr = obj.as_ref(py).hasattr("helmholtz_energy")?;
             if !attr {
-                panic!("{}", "Python Class has to have a method 'helmholtz_energy' with signature:\n\tdef helmholtz_energy(self, state: StateHD) -> HD\nwhere 'HD' has to be any of {{float, Dual64, HyperDual64, HyperDualDual64, Dual3Dual64, Dual3_64}}.")
+                return Err(PyAttributeError::new_err("Python Class has to have a method 'helmholtz_energy' with signature:\n\tdef helmholtz_energy(self, state: StateHD) -> HD\nwhere 'HD' has to be any of {float, Dual64, HyperDual64, HyperDualDual64, Dual3Dual64, Dual3_64}."));
             }
             Ok(Self {
                 obj: obj.clone(),
+                molar_weight,
                 contributions: vec![Box::new(PyHelmholtzEnergy(obj))],
             })
         })
@@ -103,6 +105,13 @@ impl EquationOfState for PyEoSObj {
         py_result.extract().unwrap()
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            molar_weight: self.molar_weight,
+            ..Default::default()
+        }
+    }
+
     fn residual(&self) -> &[Box<dyn HelmholtzEnergy>] {
         &self.contributions
     }
@@ -0,0 +1,247 @@
+//! Export of computed data to Apache Arrow record batches, Arrow IPC
+//! files and Parquet files.
+//!
+//! The column names and units are the same as in the CSV files of
+//! [PhaseDiagramData::to_csv], so the files can be read directly with
+//! pandas or polars.
+use crate::equation_of_state::EquationOfState;
+use crate::errors::EosResult;
+use crate::phase_equilibria::{
+    PhaseDiagram, PhaseDiagramData, PhaseDiagramHetero, PhaseDiagramHeteroData,
+};
+use crate::state::StateVec;
+use arrow::array::{ArrayRef, Float64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use quantity::si::*;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::Arc;
+
+fn record_batch(header: Vec<String>, columns: Vec<Vec<f64>>) -> EosResult<RecordBatch> {
+    let fields = header
+        .into_iter()
+        .map(|name| Field::new(name, DataType::Float64, false))
+        .collect::<Vec<_>>();
+    let columns = columns
+        .into_iter()
+        .map(|c| Arc::new(Float64Array::from(c)) as ArrayRef)
+        .collect();
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+fn write_ipc<P: AsRef<Path>>(batch: &RecordBatch, path: P) -> EosResult<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut writer = FileWriter::try_new(file, &batch.schema())?;
+    writer.write(batch)?;
+    writer.finish()?;
+    Ok(())
+}
+
+fn write_parquet<P: AsRef<Path>>(batch: &RecordBatch, path: P) -> EosResult<()> {
+    let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), None)?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+impl PhaseDiagramData {
+    /// Return the data as record batch with one row per point of the diagram.
+    pub fn to_record_batch(&self) -> EosResult<RecordBatch> {
+        let n = self.liquid_molefracs.first().map_or(0, |x| x.len());
+        let component = |x: &[Vec<f64>], i: usize| x.iter().map(|x| x[i]).collect();
+        let mut columns = vec![
+            self.temperature.clone(),
+            self.pressure.clone(),
+            self.vapor_density.clone(),
+            self.liquid_density.clone(),
+        ];
+        columns.extend((0..n).map(|i| component(&self.vapor_molefracs, i)));
+        columns.extend((0..n).map(|i| component(&self.liquid_molefracs, i)));
+        columns.push(self.vapor_molar_enthalpy.clone());
+        columns.push(self.liquid_molar_enthalpy.clone());
+        record_batch(self.csv_header(), columns)
+    }
+
+    /// Write the data to an Arrow IPC file.
+    pub fn to_ipc<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        write_ipc(&self.to_record_batch()?, path)
+    }
+
+    /// Write the data to a Parquet file.
+    pub fn to_parquet<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        write_parquet(&self.to_record_batch()?, path)
+    }
+}
+
+impl PhaseDiagramHeteroData {
+    /// Return the data as record batch with one row per point.
+    ///
+    /// The first column contains the part of the diagram
+    /// (`vle1`, `vle2` or `lle`) to which the point belongs.
+    pub fn to_record_batch(&self) -> EosResult<RecordBatch> {
+        let parts = [("vle1", Some(&self.vle1)), ("vle2", Some(&self.vle2))];
+        let batches = parts
+            .iter()
+            .copied()
+            .chain([("lle", self.lle.as_ref())])
+            .filter_map(|(name, data)| data.map(|d| Ok((name, d.to_record_batch()?))))
+            .collect::<EosResult<Vec<_>>>()?;
+        let diagram: StringArray = batches
+            .iter()
+            .flat_map(|(name, b)| (0..b.num_rows()).map(move |_| Some(*name)))
+            .collect();
+        let schema = batches[0].1.schema();
+        let data = arrow::compute::concat_batches(&schema, batches.iter().map(|(_, b)| b))?;
+
+        let mut fields = vec![Field::new("diagram", DataType::Utf8, false)];
+        fields.extend(schema.fields().iter().map(|f| f.as_ref().clone()));
+        let mut columns = vec![Arc::new(diagram) as ArrayRef];
+        columns.extend(data.columns().iter().cloned());
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+
+    /// Write the data to an Arrow IPC file.
+    pub fn to_ipc<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        write_ipc(&self.to_record_batch()?, path)
+    }
+
+    /// Write the data to a Parquet file.
+    pub fn to_parquet<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        write_parquet(&self.to_record_batch()?, path)
+    }
+}
+
+impl<E: EquationOfState> PhaseDiagram<SIUnit, E> {
+    /// Write the data of the diagram to an Arrow IPC file.
+    pub fn to_ipc<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        self.data()?.to_ipc(path)
+    }
+
+    /// Write the data of the diagram to a Parquet file.
+    pub fn to_parquet<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        self.data()?.to_parquet(path)
+    }
+}
+
+impl<E: EquationOfState> PhaseDiagramHetero<SIUnit, E> {
+    /// Write the data of the diagram to an Arrow IPC file.
+    pub fn to_ipc<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        self.data()?.to_ipc(path)
+    }
+
+    /// Write the data of the diagram to a Parquet file.
+    pub fn to_parquet<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        self.data()?.to_parquet(path)
+    }
+}
+
+impl<'a, E: EquationOfState> StateVec<'a, SIUnit, E> {
+    /// Return temperatures, pressures, densities, compositions, molar
+    /// enthalpies and molar entropies of all states in SI units as record
+    /// batch with one row per state.
+    pub fn to_record_batch(&self) -> EosResult<RecordBatch> {
+        let n = self.first().map_or(0, |s| s.eos.components());
+        let mut header = vec![
+            "temperature [K]".to_owned(),
+            "pressure [Pa]".to_owned(),
+            "density [mol/m^3]".to_owned(),
+        ];
+        header.extend((0..n).map(|i| format!("x{}", i)));
+        header.push("molar enthalpy [J/mol]".to_owned());
+        header.push("molar entropy [J/mol/K]".to_owned());
+
+        let molefracs = self.molefracs();
+        let mut columns = vec![
+            (self.temperature() / KELVIN).into_value()?.to_vec(),
+            (self.pressure() / PASCAL).into_value()?.to_vec(),
+            (self.density() / (MOL / METER.powi(3)))
+                .into_value()?
+                .to_vec(),
+        ];
+        columns.extend(molefracs.columns().into_iter().map(|x| x.to_vec()));
+        columns.push(
+            (self.molar_enthalpy() / (JOULE / MOL))
+                .into_value()?
+                .to_vec(),
+        );
+        columns.push(
+            (self.molar_entropy() / (JOULE / MOL / KELVIN))
+                .into_value()?
+                .to_vec(),
+        );
+        record_batch(header, columns)
+    }
+
+    /// Write the properties of all states to an Arrow IPC file.
+    pub fn to_ipc<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        write_ipc(&self.to_record_batch()?, path)
+    }
+
+    /// Write the properties of all states to a Parquet file.
+    pub fn to_parquet<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        write_parquet(&self.to_record_batch()?, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use crate::state::{DensityInitialization, State};
+    use arrow::ipc::reader::FileReader;
+    use ndarray::arr1;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn test_record_batch() -> EosResult<()> {
+        let eos = propane_butane();
+        let dia =
+            PhaseDiagram::binary_vle(&eos, 300.0 * KELVIN, Some(11), None, Default::default())?;
+        let batch = dia.data()?.to_record_batch()?;
+        assert_eq!(batch.num_rows(), dia.states.len());
+        assert_eq!(batch.num_columns(), 10);
+        assert_eq!(batch.schema().field(0).name(), "temperature [K]");
+
+        let ipc = crate::temp_path("record_batch.arrow");
+        dia.to_ipc(&ipc)?;
+        let reread = FileReader::try_new(File::open(&ipc)?, None)?
+            .next()
+            .unwrap()?;
+        assert_eq!(reread, batch);
+        std::fs::remove_file(ipc)?;
+
+        let parquet = crate::temp_path("record_batch.parquet");
+        dia.to_parquet(&parquet)?;
+        let reread = ParquetRecordBatchReaderBuilder::try_new(File::open(&parquet)?)?
+            .build()?
+            .next()
+            .unwrap()?;
+        assert_eq!(reread.columns(), batch.columns());
+        std::fs::remove_file(parquet)?;
+
+        let moles = arr1(&[0.5, 0.5]) * MOL;
+        let isotherm = State::isotherm(
+            &eos,
+            300.0 * KELVIN,
+            BAR,
+            3.0 * BAR,
+            3,
+            &moles,
+            DensityInitialization::Vapor,
+        )?;
+        let batch = isotherm.state_vec().to_record_batch()?;
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.schema().field(3).name(), "x0");
+        Ok(())
+    }
+}
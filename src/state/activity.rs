@@ -0,0 +1,328 @@
+use super::{Contributions, DensityInitialization, State};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::phase_equilibria::{SolverOptions, Verbosity};
+use crate::EosUnit;
+use ndarray::Array1;
+use quantity::{QuantityArray1, QuantityScalar};
+use std::rc::Rc;
+
+const MAX_ITER_ACTIVITY: usize = 50;
+const TOL_ACTIVITY: f64 = 1e-10;
+const MAX_TEMPERATURE_STEP: f64 = 0.1;
+
+/// # Activities
+impl<U: EosUnit, E: EquationOfState> State<U, E> {
+    /// Activities $a_i=x_i\gamma_i$ of all components with the pure liquids
+    /// at mixture temperature and pressure as reference states.
+    pub fn activity(&self) -> EosResult<Array1<f64>> {
+        Ok(
+            (self.molefracs.mapv(f64::ln) + self.ln_symmetric_activity_coefficient()?)
+                .mapv(f64::exp),
+        )
+    }
+
+    /// Calculate the liquid state at given temperature and pressure in which
+    /// the specified component has the given activity, e.g., a water activity
+    /// of 0.9.
+    ///
+    /// The mole fraction of `component` is iterated with a Newton method, while
+    /// the remaining components keep the ratio given by `moles`. The entry of
+    /// `component` in `moles` is only used as initial guess and the total
+    /// amount of substance of the resulting state equals `moles.sum()`.
+    pub fn new_activity_composition(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+        component: usize,
+        activity: f64,
+        options: SolverOptions,
+    ) -> EosResult<Self> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_ACTIVITY, TOL_ACTIVITY);
+        let (n, total_moles) = Self::validate_activity(eos, moles, component, activity)?;
+
+        // ratio of the remaining components
+        let mut r = n.clone();
+        r[component] = 0.0;
+        if r.sum() <= 0.0 {
+            return Err(EosError::UndeterminedState(String::from(
+                "the composition of the remaining components is undefined",
+            )));
+        }
+        r /= r.sum();
+        let mut direction = -&r;
+        direction[component] += 1.0;
+
+        let mut x = n[component] / total_moles;
+        if !(x > 0.0 && x < 1.0) {
+            x = activity.min(0.5);
+        }
+
+        log_iter!(verbosity, " iter |    residual    |  mole fraction  ");
+        log_iter!(verbosity, "{:-<38}", "");
+
+        let mut density = DensityInitialization::Liquid;
+        for i in 1..=max_iter {
+            let moles = (x * &direction + &r) * total_moles * U::reference_moles();
+            let state = State::new_npt(eos, temperature, pressure, &moles, density)?;
+            let res = state.ln_activity(component)? - activity.ln();
+            log_iter!(verbosity, " {:4} | {:14.8e} | {:.10}", i, res, x);
+            if res.abs() < tol {
+                log_result!(
+                    verbosity,
+                    "Activity: calculation converged in {} step(s)\n",
+                    i
+                );
+                return Ok(state);
+            }
+
+            // derivative of the activity along the direction of the iteration
+            let dln_phi_dnj = state
+                .dln_phi_dnj()
+                .to_reduced(U::reference_moles().powi(-1))?;
+            let dres = 1.0 / x + total_moles * dln_phi_dnj.row(component).dot(&direction);
+
+            // Newton step that keeps the mole fraction between 0 and 1
+            let x_new = x - res / dres;
+            if dres <= 0.0 || !x_new.is_finite() {
+                return Err(EosError::IterationFailed(String::from("Activity")));
+            }
+            x = x_new.clamp(0.1 * x, x + 0.9 * (1.0 - x));
+            density = DensityInitialization::InitialDensity(state.density);
+        }
+        Err(EosError::NotConverged(String::from("Activity")))
+    }
+
+    /// Calculate the liquid state at given pressure and composition in which
+    /// the specified component has the given activity.
+    ///
+    /// The temperature is iterated with a Newton method starting from
+    /// `initial_temperature`.
+    pub fn new_activity_temperature(
+        eos: &Rc<E>,
+        pressure: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+        component: usize,
+        activity: f64,
+        initial_temperature: QuantityScalar<U>,
+        options: SolverOptions,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_ACTIVITY, TOL_ACTIVITY);
+        Self::validate_activity(eos, moles, component, activity)?;
+        let pure_eos = Rc::new(eos.subset(&[component]));
+        let pure_moles = Array1::ones(1) * U::reference_moles();
+
+        log_iter!(verbosity, " iter |    residual    |  temperature  ");
+        log_iter!(verbosity, "{:-<36}", "");
+
+        let mut t = initial_temperature;
+        for i in 1..=max_iter {
+            let state = State::new_npt(eos, t, pressure, moles, DensityInitialization::Liquid)?;
+            let pure = State::new_npt(
+                &pure_eos,
+                t,
+                pressure,
+                &pure_moles,
+                DensityInitialization::Liquid,
+            )?;
+            let res = state.molefracs[component].ln() + state.ln_phi()[component]
+                - pure.ln_phi()[0]
+                - activity.ln();
+            log_iter!(verbosity, " {:4} | {:14.8e} | {:13.5}", i, res, t);
+            if res.abs() < tol {
+                log_result!(
+                    verbosity,
+                    "Activity: calculation converged in {} step(s)\n",
+                    i
+                );
+                return Ok(state);
+            }
+
+            // Newton step with limited step size
+            let dres = state.dln_phi_dt().get(component) - pure.dln_phi_dt().get(0);
+            let dt = -res / dres;
+            let dt_max = MAX_TEMPERATURE_STEP * t;
+            t += if dt.abs() > dt_max {
+                dt_max * dt.signum()
+            } else {
+                dt
+            };
+        }
+        Err(EosError::NotConverged(String::from("Activity")))
+    }
+
+    fn validate_activity(
+        eos: &Rc<E>,
+        moles: &QuantityArray1<U>,
+        component: usize,
+        activity: f64,
+    ) -> EosResult<(Array1<f64>, f64)> {
+        let n = moles.to_reduced(U::reference_moles())?;
+        if n.len() != eos.components() {
+            return Err(EosError::IncompatibleComponents(eos.components(), n.len()));
+        }
+        if component >= n.len() {
+            return Err(EosError::InvalidState(
+                String::from("Activity"),
+                String::from("component"),
+                component as f64,
+            ));
+        }
+        if !(activity > 0.0 && activity < 1.0) {
+            return Err(EosError::InvalidState(
+                String::from("Activity"),
+                String::from("activity"),
+                activity,
+            ));
+        }
+        let total_moles = n.sum();
+        Ok((n, total_moles))
+    }
+
+    /// Pure liquid of a single component at mixture temperature and pressure.
+    fn pure_liquid(&self, component: usize) -> EosResult<Self> {
+        State::new_npt(
+            &Rc::new(self.eos.subset(&[component])),
+            self.temperature,
+            self.pressure(Contributions::Total),
+            &(Array1::ones(1) * U::reference_moles()),
+            DensityInitialization::Liquid,
+        )
+    }
+
+    /// Logarithm of the activity of a single component.
+    fn ln_activity(&self, component: usize) -> EosResult<f64> {
+        let pure = self.pure_liquid(component)?;
+        Ok(self.molefracs[component].ln() + self.ln_phi()[component] - pure.ln_phi()[0])
+    }
+
+    /// Molal osmotic coefficient of the solvent:
+    /// $\phi=-\frac{\ln a_s}{M_s\sum_{j\neq s}m_j}=-\frac{x_s\ln a_s}{1-x_s}$
+    pub fn osmotic_coefficient(&self, solvent: usize) -> EosResult<f64> {
+        let x_s = self.molefracs[solvent];
+        Ok(-x_s * self.ln_activity(solvent)? / (1.0 - x_s))
+    }
+
+    /// Osmotic pressure $\Pi=p-p_s$ of the solution with respect to the pure
+    /// solvent.
+    ///
+    /// The pressure $p_s$ of the pure solvent that is in equilibrium with the
+    /// solvent in the solution, i.e., $\mu_s^0(T,p_s)=\mu_s(T,p,\mathbf{x})$, is
+    /// calculated with a Newton iteration in the density of the pure solvent,
+    /// starting from the pure liquid at the pressure of the solution. Thus, the
+    /// pure solvent can be in a metastable state or at negative pressure.
+    pub fn osmotic_pressure(&self, solvent: usize) -> EosResult<QuantityScalar<U>> {
+        let rt = U::gas_constant() * self.temperature;
+        let mu = self.chemical_potential(Contributions::Total).get(solvent);
+        let mut pure = self.pure_liquid(solvent)?;
+        for _ in 0..MAX_ITER_ACTIVITY {
+            let res =
+                ((pure.chemical_potential(Contributions::Total).get(0) - mu) / rt).into_value()?;
+            if res.abs() < TOL_ACTIVITY {
+                return Ok(
+                    self.pressure(Contributions::Total) - pure.pressure(Contributions::Total)
+                );
+            }
+
+            // dmu/drho = 1/rho dp/drho
+            let dres = pure.dp_drho(Contributions::Total) / (pure.density * rt);
+            let density = (pure.density - res / dres).to_reduced(U::reference_density())?;
+            if density <= 0.0 || !density.is_finite() {
+                return Err(EosError::IterationFailed(String::from("Osmotic pressure")));
+            }
+            pure = State::new_pure(
+                &pure.eos,
+                self.temperature,
+                density * U::reference_density(),
+            )?;
+        }
+        Err(EosError::NotConverged(String::from("Osmotic pressure")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters, PengRobinsonRecord};
+    use crate::parameter::{Identifier, Parameter, PureRecord};
+    use approx::assert_relative_eq;
+    use ndarray::{arr1, arr2};
+    use quantity::si::*;
+
+    #[test]
+    fn test_activity() -> EosResult<()> {
+        // propane and n-decane with a binary interaction parameter
+        let records = vec![
+            PureRecord::new(
+                Identifier::default(),
+                44.0962,
+                PengRobinsonRecord::new(369.96, 4250000.0, 0.153),
+                None,
+            ),
+            PureRecord::new(
+                Identifier::default(),
+                142.285,
+                PengRobinsonRecord::new(617.7, 2110000.0, 0.49),
+                None,
+            ),
+        ];
+        let k_ij = arr2(&[[0.0, 0.1], [0.1, 0.0]]);
+        let parameters = PengRobinsonParameters::from_records(records, k_ij)?;
+        let eos = Rc::new(PengRobinson::new(Rc::new(parameters)));
+        let (t, p) = (300.0 * KELVIN, 20.0 * BAR);
+        let options = SolverOptions::default();
+
+        let moles = arr1(&[0.3, 0.7]) * MOL;
+        let state = State::new_npt(&eos, t, p, &moles, DensityInitialization::Liquid)?;
+        let a = state.activity()?;
+        assert!(a[0] > 0.3);
+
+        let moles_init = arr1(&[0.0, 2.0]) * MOL;
+        let activity =
+            State::new_activity_composition(&eos, t, p, &moles_init, 0, a[0], options.clone())?;
+        assert_relative_eq!(activity.molefracs, state.molefracs, max_relative = 1e-8);
+        assert_relative_eq!(activity.total_moles, 2.0 * MOL, max_relative = 1e-14);
+
+        let state = State::new_npt(
+            &eos,
+            280.0 * KELVIN,
+            p,
+            &moles,
+            DensityInitialization::Liquid,
+        )?;
+        let a = state.activity()?;
+        let activity = State::new_activity_temperature(&eos, p, &moles, 0, a[0], t, options)?;
+        assert_relative_eq!(activity.temperature, 280.0 * KELVIN, max_relative = 1e-8);
+
+        // osmotic pressure of n-decane as solvent
+        let a = state.activity()?;
+        let pi = state.osmotic_pressure(1)?;
+        let pure = state.pure_liquid(1)?;
+        let v = pure.molar_volume(Contributions::Total).get(0);
+        let rt = RGAS * state.temperature;
+        assert!(pi > 0.0 * BAR);
+        assert_relative_eq!(pi, -rt * a[1].ln() / v, max_relative = 0.05);
+        let pure = State::new_npt(
+            &pure.eos,
+            state.temperature,
+            p - pi,
+            &(arr1(&[1.0]) * MOL),
+            DensityInitialization::Liquid,
+        )?;
+        assert_relative_eq!(
+            pure.chemical_potential(Contributions::Total).get(0),
+            state.chemical_potential(Contributions::Total).get(1),
+            max_relative = 1e-10
+        );
+        assert_relative_eq!(
+            state.osmotic_coefficient(1)?,
+            -0.7 * a[1].ln() / 0.3,
+            max_relative = 1e-10
+        );
+        Ok(())
+    }
+}
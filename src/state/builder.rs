@@ -1,4 +1,4 @@
-use super::{DensityInitialization, State};
+use super::{DensityInitialization, MolefracsNormalization, State};
 use crate::equation_of_state::EquationOfState;
 use crate::errors::EosResult;
 use crate::EosUnit;
@@ -62,6 +62,7 @@ pub struct StateBuilder<'a, U: EosUnit, E: EquationOfState> {
     total_moles: Option<QuantityScalar<U>>,
     moles: Option<&'a QuantityArray1<U>>,
     molefracs: Option<&'a Array1<f64>>,
+    molefracs_normalization: Option<MolefracsNormalization>,
     pressure: Option<QuantityScalar<U>>,
     molar_enthalpy: Option<QuantityScalar<U>>,
     molar_entropy: Option<QuantityScalar<U>>,
@@ -82,6 +83,7 @@ impl<'a, U: EosUnit, E: EquationOfState> StateBuilder<'a, U, E> {
             total_moles: None,
             moles: None,
             molefracs: None,
+            molefracs_normalization: None,
             pressure: None,
             molar_enthalpy: None,
             molar_entropy: None,
@@ -133,6 +135,13 @@ impl<'a, U: EosUnit, E: EquationOfState> StateBuilder<'a, U, E> {
         self
     }
 
+    /// Specify how molefracs that do not sum up to one are treated.
+    /// By default, they are only checked for negative entries.
+    pub fn molefracs_normalization(mut self, normalization: MolefracsNormalization) -> Self {
+        self.molefracs_normalization = Some(normalization);
+        self
+    }
+
     /// Provide the pressure for the new state.
     pub fn pressure(mut self, pressure: QuantityScalar<U>) -> Self {
         self.pressure = Some(pressure);
@@ -183,6 +192,10 @@ impl<'a, U: EosUnit, E: EquationOfState> StateBuilder<'a, U, E> {
 
     /// Try to build the state with the given inputs.
     pub fn build(self) -> EosResult<State<U, E>> {
+        let molefracs = match (self.molefracs, self.molefracs_normalization) {
+            (Some(x), Some(normalization)) => Some(normalization.apply(x)?),
+            (x, _) => x.cloned(),
+        };
         State::new(
             &self.eos,
             self.temperature,
@@ -191,7 +204,7 @@ impl<'a, U: EosUnit, E: EquationOfState> StateBuilder<'a, U, E> {
             self.partial_density,
             self.total_moles,
             self.moles,
-            self.molefracs,
+            molefracs.as_ref(),
             self.pressure,
             self.molar_enthalpy,
             self.molar_entropy,
@@ -213,6 +226,7 @@ impl<'a, U: EosUnit, E: EquationOfState> Clone for StateBuilder<'a, U, E> {
             total_moles: self.total_moles,
             moles: self.moles,
             molefracs: self.molefracs,
+            molefracs_normalization: self.molefracs_normalization,
             pressure: self.pressure,
             molar_enthalpy: self.molar_enthalpy,
             molar_entropy: self.molar_entropy,
@@ -96,4 +96,58 @@ impl Cache {
             value.v3
         }
     }
+
+    pub fn get_or_insert_with_hd3d64<F: FnOnce() -> HyperDual<Dual64, f64>>(
+        &mut self,
+        derivative1: Derivative,
+        derivative2: Derivative,
+        derivative3: Derivative,
+        f: F,
+    ) -> f64 {
+        let mut d = [derivative1, derivative2, derivative3];
+        d.sort_unstable();
+        if let Some(&value) = self
+            .map
+            .get(&PartialDerivative::ThirdMixed(d[0], d[1], d[2]))
+        {
+            self.hit += 1;
+            value
+        } else {
+            self.miss += 1;
+            let value = f();
+            self.map.insert(PartialDerivative::Zeroth, value.re.re);
+            self.map
+                .insert(PartialDerivative::First(derivative1), value.eps1[0].re);
+            self.map
+                .insert(PartialDerivative::First(derivative2), value.eps2[0].re);
+            self.map
+                .insert(PartialDerivative::First(derivative3), value.re.eps[0]);
+            self.map.insert(
+                PartialDerivative::Second(
+                    min(derivative1, derivative2),
+                    max(derivative1, derivative2),
+                ),
+                value.eps1eps2[(0, 0)].re,
+            );
+            self.map.insert(
+                PartialDerivative::Second(
+                    min(derivative1, derivative3),
+                    max(derivative1, derivative3),
+                ),
+                value.eps1[0].eps[0],
+            );
+            self.map.insert(
+                PartialDerivative::Second(
+                    min(derivative2, derivative3),
+                    max(derivative2, derivative3),
+                ),
+                value.eps2[0].eps[0],
+            );
+            self.map.insert(
+                PartialDerivative::ThirdMixed(d[0], d[1], d[2]),
+                value.eps1eps2[(0, 0)].eps[0],
+            );
+            value.eps1eps2[(0, 0)].eps[0]
+        }
+    }
 }
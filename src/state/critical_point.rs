@@ -1,7 +1,7 @@
-use super::{State, StateHD, TPSpec};
+use super::{Contributions, State, StateHD, TPSpec};
 use crate::equation_of_state::EquationOfState;
 use crate::errors::{EosError, EosResult};
-use crate::phase_equilibria::{SolverOptions, Verbosity};
+use crate::phase_equilibria::{RetryPolicy, SolverOptions, Verbosity};
 use crate::EosUnit;
 use ndarray::{arr1, arr2, Array1, Array2};
 use num_dual::linalg::{norm, smallest_ev, LU};
@@ -13,6 +13,7 @@ use std::rc::Rc;
 
 const MAX_ITER_CRIT_POINT: usize = 50;
 const TOL_CRIT_POINT: f64 = 1e-8;
+const TOL_CRIT_POINT_DERIVATIVES: f64 = 1e-6;
 
 /// # Critical points
 impl<U: EosUnit, E: EquationOfState> State<U, E> {
@@ -31,12 +32,17 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                     &Rc::new(eos.subset(&[i])),
                     None,
                     initial_temperature,
-                    options,
+                    options.clone(),
                 )
             })
             .collect()
     }
 
+    /// Calculate the critical point of a binary system for given temperature or pressure.
+    ///
+    /// Temperature (or pressure) and composition are solved for simultaneously
+    /// with a Newton method. If no initial mole fractions are provided, the
+    /// calculation is repeated for several trial compositions until it converges.
     pub fn critical_point_binary(
         eos: &Rc<E>,
         temperature_or_pressure: QuantityScalar<U>,
@@ -47,18 +53,40 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
     where
         QuantityScalar<U>: std::fmt::Display,
     {
-        match TPSpec::try_from(temperature_or_pressure)? {
-            TPSpec::Temperature(t) => {
-                Self::critical_point_binary_t(eos, t, initial_molefracs, options)
+        let policy = match initial_molefracs {
+            Some(x) => RetryPolicy::new().attempt(options, x),
+            None => [[0.5, 0.5], [0.2, 0.8], [0.8, 0.2]]
+                .iter()
+                .fold(RetryPolicy::new(), |policy, &x| {
+                    policy.attempt(options.clone(), x)
+                }),
+        };
+        Self::critical_point_binary_with_retry(
+            eos,
+            temperature_or_pressure,
+            initial_temperature,
+            &policy,
+        )
+    }
+
+    /// Calculate the critical point of a binary system for given temperature
+    /// or pressure with the options and initial mole fractions of a [RetryPolicy].
+    pub fn critical_point_binary_with_retry(
+        eos: &Rc<E>,
+        temperature_or_pressure: QuantityScalar<U>,
+        initial_temperature: Option<QuantityScalar<U>>,
+        policy: &RetryPolicy<[f64; 2]>,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let spec = TPSpec::try_from(temperature_or_pressure)?;
+        policy.run(|options, &x| match spec {
+            TPSpec::Temperature(t) => Self::critical_point_binary_t(eos, t, Some(x), options),
+            TPSpec::Pressure(p) => {
+                Self::critical_point_binary_p(eos, p, initial_temperature, Some(x), options)
             }
-            TPSpec::Pressure(p) => Self::critical_point_binary_p(
-                eos,
-                p,
-                initial_temperature,
-                initial_molefracs,
-                options,
-            ),
-        }
+        })
     }
 
     /// Calculate the critical point of a system for given moles.
@@ -68,25 +96,208 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         initial_temperature: Option<QuantityScalar<U>>,
         options: SolverOptions,
     ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let policy = match initial_temperature {
+            Some(t) => RetryPolicy::new().attempt(options, t),
+            None => [300.0, 700.0, 500.0]
+                .iter()
+                .fold(RetryPolicy::new(), |policy, &t| {
+                    policy.attempt(options.clone(), t * U::reference_temperature())
+                }),
+        };
+        Self::critical_point_with_retry(eos, moles, &policy)
+    }
+
+    /// Calculate the critical point of a system for given moles with
+    /// the options and initial temperatures of a [RetryPolicy].
+    pub fn critical_point_with_retry(
+        eos: &Rc<E>,
+        moles: Option<&QuantityArray1<U>>,
+        policy: &RetryPolicy<QuantityScalar<U>>,
+    ) -> EosResult<Self>
     where
         QuantityScalar<U>: std::fmt::Display,
     {
         let moles = eos.validate_moles(moles)?;
-        let trial_temperatures = [
-            300.0 * U::reference_temperature(),
-            700.0 * U::reference_temperature(),
-            500.0 * U::reference_temperature(),
-        ];
-        if let Some(t) = initial_temperature {
-            return Self::critical_point_hkm(eos, &moles, t, options);
+        policy.run(|options, &t| Self::critical_point_hkm(eos, &moles, t, options))
+    }
+
+    /// Calculate the derivatives of the critical temperature and the
+    /// critical pressure with respect to the composition.
+    ///
+    /// The state has to be a critical point, e.g., the result of
+    /// [critical_point](Self::critical_point). The derivatives are obtained
+    /// by implicit differentiation of the criticality conditions and are
+    /// returned as $N\frac{\partial T_c}{\partial N_i}$ and $N\frac{\partial p_c}{\partial N_i}$,
+    /// i.e., the change of the critical point for a change in composition
+    /// $\Delta x$ is $\Delta T_c=\sum_iN\frac{\partial T_c}{\partial N_i}\Delta x_i$.
+    pub fn critical_point_derivatives(&self) -> EosResult<(QuantityArray1<U>, QuantityArray1<U>)> {
+        let t = self.temperature.to_reduced(U::reference_temperature())?;
+        let rho = self.density.to_reduced(U::reference_density())?;
+        let n = self.moles.to_reduced(U::reference_moles())?;
+        let n_dual = n.mapv(Dual64::from);
+
+        // derivatives of the criticality conditions w.r.t. temperature and density
+        let res_t = critical_point_objective(
+            &self.eos,
+            Dual64::from(t).derive(),
+            Dual64::from(rho),
+            &n_dual,
+        )?;
+        let res_r = critical_point_objective(
+            &self.eos,
+            Dual64::from(t),
+            Dual64::from(rho).derive(),
+            &n_dual,
+        )?;
+        if norm(&res_t.map(Dual64::re)) > TOL_CRIT_POINT_DERIVATIVES {
+            return Err(EosError::UndeterminedState(String::from(
+                "The state is not a critical point",
+            )));
         }
-        for &t in trial_temperatures.iter() {
-            let s = Self::critical_point_hkm(eos, &moles, t, options);
-            if s.is_ok() {
-                return s;
+        let lu = LU::new(arr2(&[
+            [res_t[0].eps[0], res_r[0].eps[0]],
+            [res_t[1].eps[0], res_r[1].eps[0]],
+        ]))?;
+
+        // partial derivatives of the pressure at constant T, V and N
+        let dp_dt = self
+            .dp_dt(Contributions::Total)
+            .to_reduced(U::reference_pressure() / U::reference_temperature())?;
+        let dp_dv = self
+            .dp_dv(Contributions::Total)
+            .to_reduced(U::reference_pressure() / U::reference_volume())?;
+        let dp_dni = self
+            .dp_dni(Contributions::Total)
+            .to_reduced(U::reference_pressure() / U::reference_moles())?;
+
+        // implicit differentiation w.r.t. the moles of every component
+        let n_total = n.sum();
+        let mut dtc = Array1::zeros(n.len());
+        let mut dpc = Array1::zeros(n.len());
+        for k in 0..n.len() {
+            let mut n_k = n_dual.clone();
+            n_k[k] = n_k[k].derive();
+            let res_n =
+                critical_point_objective(&self.eos, Dual64::from(t), Dual64::from(rho), &n_k)?;
+            let d = lu.solve(&res_n.map(|r| -r.eps[0]));
+            let dv = 1.0 / rho - n_total * d[1] / (rho * rho);
+            dtc[k] = n_total * d[0];
+            dpc[k] = n_total * (dp_dt * d[0] + dp_dv * dv + dp_dni[k]);
+        }
+        Ok((
+            dtc * U::reference_temperature(),
+            dpc * U::reference_pressure(),
+        ))
+    }
+
+    /// Calculate the vapor and liquid spinodal of a system for given temperature and moles.
+    ///
+    /// The spinodal densities are the densities at which the smallest
+    /// eigenvalue of the Hessian of the Helmholtz energy w.r.t. the moles
+    /// vanishes. They are obtained with a Newton method starting from a
+    /// low and a high density, respectively. The states are returned in
+    /// the order `[vapor, liquid]`.
+    pub fn spinodal(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        moles: Option<&QuantityArray1<U>>,
+        options: SolverOptions,
+    ) -> EosResult<[Self; 2]>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let moles = eos.validate_moles(moles)?;
+        let max_density = eos
+            .max_density_at_temperature(temperature, Some(&moles))?
+            .to_reduced(U::reference_density())?;
+        let vapor = Self::calculate_spinodal(
+            eos,
+            temperature,
+            &moles,
+            1e-5 * max_density,
+            options.clone(),
+        )?;
+        let liquid = Self::calculate_spinodal(eos, temperature, &moles, max_density, options)?;
+        if vapor.density >= liquid.density {
+            return Err(EosError::SuperCritical {
+                component: None,
+                given: format!("T = {}", temperature),
+                critical: None,
+            });
+        }
+        Ok([vapor, liquid])
+    }
+
+    fn calculate_spinodal(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+        initial_density: f64,
+        options: SolverOptions,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_CRIT_POINT, TOL_CRIT_POINT);
+
+        let t = temperature.to_reduced(U::reference_temperature())?;
+        let max_density = eos
+            .max_density_at_temperature(temperature, Some(moles))?
+            .to_reduced(U::reference_density())?;
+        let mut rho = initial_density;
+        let n = moles.to_reduced(U::reference_moles())?.mapv(Dual64::from);
+
+        log_iter!(verbosity, " iter |    residual    |       density        ");
+        log_iter!(verbosity, "{:-<46}", "");
+        log_iter!(
+            verbosity,
+            " {:4} |                | {:12.8}",
+            0,
+            rho * U::reference_density(),
+        );
+
+        for i in 1..=max_iter {
+            // calculate smallest eigenvalue and its derivative w.r.t. density
+            let res =
+                critical_point_objective(eos, Dual64::from(t), Dual64::from(rho).derive(), &n)?;
+            let mut delta = res[0].re / res[0].eps[0];
+
+            // reduce step if necessary
+            if delta.abs() > 0.03 * max_density {
+                delta *= 0.03 * max_density / delta.abs()
+            }
+
+            // apply step
+            rho -= delta;
+            rho = f64::max(rho, 1e-4 * initial_density);
+
+            log_iter!(
+                verbosity,
+                " {:4} | {:14.8e} | {:12.8}",
+                i,
+                (delta / rho).abs(),
+                rho * U::reference_density(),
+            );
+
+            // check convergence
+            if (delta / rho).abs() < tol {
+                log_result!(
+                    verbosity,
+                    "Spinodal calculation converged in {} step(s)\n",
+                    i
+                );
+                return State::new_nvt(
+                    eos,
+                    temperature,
+                    moles.sum() / (rho * U::reference_density()),
+                    moles,
+                );
             }
         }
-        Err(EosError::NotConverged(String::from("Critical point")))
+        Err(EosError::NotConverged(String::from("Spinodal")))
     }
 
     fn critical_point_hkm(
@@ -102,10 +313,10 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
 
         let mut t = initial_temperature.to_reduced(U::reference_temperature())?;
         let max_density = eos
-            .max_density(Some(moles))?
+            .max_density_at_temperature(initial_temperature, Some(moles))?
             .to_reduced(U::reference_density())?;
         let mut rho = 0.3 * max_density;
-        let n = moles.to_reduced(U::reference_moles())?;
+        let n = moles.to_reduced(U::reference_moles())?.mapv(Dual64::from);
 
         log_iter!(
             verbosity,
@@ -190,7 +401,10 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         let t = temperature.to_reduced(U::reference_temperature())?;
         let x = StaticVec::new_vec(initial_molefracs.unwrap_or([0.5, 0.5]));
         let max_density = eos
-            .max_density(Some(&(arr1(x.raw_array()) * U::reference_moles())))?
+            .max_density_at_temperature(
+                temperature,
+                Some(&(arr1(x.raw_array()) * U::reference_moles())),
+            )?
             .to_reduced(U::reference_density())?;
         let mut rho = x * 0.3 * max_density;
 
@@ -280,7 +494,10 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
             .unwrap_or(300.0);
         let x = StaticVec::new_vec(initial_molefracs.unwrap_or([0.5, 0.5]));
         let max_density = eos
-            .max_density(Some(&(arr1(x.raw_array()) * U::reference_moles())))?
+            .max_density_at_temperature(
+                t * U::reference_temperature(),
+                Some(&(arr1(x.raw_array()) * U::reference_moles())),
+            )?
             .to_reduced(U::reference_density())?;
         let mut rho = x * 0.3 * max_density;
 
@@ -365,13 +582,13 @@ pub fn critical_point_objective<E: EquationOfState>(
     eos: &Rc<E>,
     temperature: Dual64,
     density: Dual64,
-    moles: &Array1<f64>,
+    moles: &Array1<Dual64>,
 ) -> EosResult<Array1<Dual64>> {
     // calculate second partial derivatives w.r.t. moles
     let t = HyperDual::from_re(temperature);
     let v = HyperDual::from_re(density.recip() * moles.sum());
     let qij = Array2::from_shape_fn((eos.components(), eos.components()), |(i, j)| {
-        let mut m = moles.mapv(HyperDual::from);
+        let mut m = moles.mapv(HyperDual::from_re);
         m[i].eps1[0] = Dual64::one();
         m[j].eps2[0] = Dual64::one();
         let state = StateHD::new(t, v, m);
@@ -386,7 +603,7 @@ pub fn critical_point_objective<E: EquationOfState>(
     // evaluate third partial derivative w.r.t. s
     let moles_hd = Array1::from_shape_fn(eos.components(), |i| {
         Dual3::new(
-            Dual64::from(moles[i]),
+            moles[i],
             evec[i] * moles[i].sqrt(),
             Dual64::zero(),
             Dual64::zero(),
@@ -482,3 +699,41 @@ fn critical_point_objective_p<E: EquationOfState>(
         p.eps[0] * temperature + pressure,
     ]))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::{propane, propane_butane};
+    use approx::assert_relative_eq;
+    use quantity::si::*;
+
+    #[test]
+    fn test_critical_point_binary() -> EosResult<()> {
+        let eos = propane_butane();
+        let options = SolverOptions::default();
+        let cp_t = State::critical_point_binary(&eos, 400.0 * KELVIN, None, None, options.clone())?;
+        let p = cp_t.pressure(Contributions::Total);
+        let cp_p =
+            State::critical_point_binary(&eos, p, Some(390.0 * KELVIN), None, options.clone())?;
+        assert_relative_eq!(cp_p.temperature, 400.0 * KELVIN, max_relative = 1e-6);
+        assert_relative_eq!(cp_p.molefracs, cp_t.molefracs, max_relative = 1e-6);
+        let cp = State::critical_point(&eos, Some(&cp_t.moles), None, options)?;
+        assert_relative_eq!(cp.temperature, 400.0 * KELVIN, max_relative = 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_spinodal() -> EosResult<()> {
+        let eos = propane();
+        let options = SolverOptions::default();
+        let [vapor, liquid] = State::spinodal(&eos, 300.0 * KELVIN, None, options.clone())?;
+        assert!(vapor.density < liquid.density);
+        for s in [vapor, liquid] {
+            let dp_drho =
+                s.dp_drho(Contributions::Total) * s.density / s.pressure(Contributions::Total);
+            assert!(dp_drho.into_value()?.abs() < 1e-6);
+        }
+        assert!(State::spinodal(&eos, 400.0 * KELVIN, None, options).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+use super::{Derivative, State, StateHD};
+use crate::equation_of_state::{EquationOfState, HelmholtzEnergy};
+use crate::EosUnit;
+use ndarray::Array1;
+use num_dual::{Dual3_64, Dual64, DualNum, HyperDual, HyperDual64};
+use std::fmt;
+
+/// Relative step size of the central finite differences.
+const STEP: f64 = 1e-4;
+
+/// Comparison of a derivative of a Helmholtz energy contribution
+/// calculated with (hyper-) dual numbers to a finite difference
+/// approximation.
+///
+/// All derivatives are derivatives of the reduced Helmholtz energy with
+/// respect to the reduced temperature (`T`), volume (`V`) and moles (`N0`,
+/// `N1`, ...), e.g., `d2A/dTdV`. The finite differences of each derivative are calculated
+/// from the next lower derivative, so every dual number is checked on its
+/// own.
+#[derive(Clone, Debug)]
+pub struct DerivativeCheck {
+    /// String representation of the contribution
+    pub contribution: String,
+    /// The derivative
+    pub derivative: String,
+    /// The type of dual number that was used to calculate the derivative
+    pub dual_number: &'static str,
+    /// The derivative calculated with dual numbers
+    pub value: f64,
+    /// The finite difference approximation of the derivative
+    pub reference: f64,
+    /// The characteristic magnitude of the derivative, i.e., the magnitude
+    /// of the Helmholtz energy (at least 1) divided by the variables
+    pub magnitude: f64,
+}
+
+impl DerivativeCheck {
+    /// The absolute deviation between the dual number result and the finite
+    /// difference approximation divided by the magnitude of the approximation
+    /// or, if it is smaller, the characteristic magnitude of the derivative.
+    pub fn deviation(&self) -> f64 {
+        (self.value - self.reference).abs() / self.reference.abs().max(self.magnitude)
+    }
+}
+
+impl fmt::Display for DerivativeCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} ({}) = {:e}, finite difference: {:e}",
+            self.contribution, self.derivative, self.dual_number, self.value, self.reference
+        )
+    }
+}
+
+impl fmt::Display for Derivative {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DT => write!(f, "T"),
+            Self::DV => write!(f, "V"),
+            Self::DN(i) => write!(f, "N{}", i),
+        }
+    }
+}
+
+/// Reduced temperature, volume and moles of a state.
+#[derive(Clone)]
+struct Point {
+    temperature: f64,
+    volume: f64,
+    moles: Array1<f64>,
+}
+
+impl Point {
+    fn get(&self, derivative: Derivative) -> f64 {
+        match derivative {
+            Derivative::DT => self.temperature,
+            Derivative::DV => self.volume,
+            Derivative::DN(i) => self.moles[i],
+        }
+    }
+
+    fn shift(&self, derivative: Derivative, h: f64) -> Self {
+        let mut point = self.clone();
+        match derivative {
+            Derivative::DT => point.temperature += h,
+            Derivative::DV => point.volume += h,
+            Derivative::DN(i) => point.moles[i] += h,
+        }
+        point
+    }
+
+    fn state<D: DualNum<f64>>(&self, seed: impl Fn(Derivative, &mut D)) -> StateHD<D> {
+        let mut t = D::from(self.temperature);
+        seed(Derivative::DT, &mut t);
+        let mut v = D::from(self.volume);
+        seed(Derivative::DV, &mut v);
+        let n = self
+            .moles
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| {
+                let mut n = D::from(n);
+                seed(Derivative::DN(i), &mut n);
+                n
+            })
+            .collect();
+        StateHD::new(t, v, n)
+    }
+
+    /// Central finite difference of `f` with respect to `derivative`.
+    fn finite_difference(&self, derivative: Derivative, f: impl Fn(&Self) -> f64) -> f64 {
+        let h = STEP * self.get(derivative).abs();
+        (f(&self.shift(derivative, h)) - f(&self.shift(derivative, -h))) / (2.0 * h)
+    }
+}
+
+fn zeroth(c: &dyn HelmholtzEnergy, point: &Point) -> f64 {
+    c.helmholtz_energy(&point.state(|_, _: &mut f64| ()))
+}
+
+fn first(c: &dyn HelmholtzEnergy, point: &Point, d: Derivative) -> f64 {
+    let state = point.state(|di, x: &mut Dual64| {
+        if di == d {
+            x.eps[0] = 1.0
+        }
+    });
+    c.helmholtz_energy(&state).eps[0]
+}
+
+fn second(c: &dyn HelmholtzEnergy, point: &Point, d1: Derivative, d2: Derivative) -> f64 {
+    let state = point.state(|di, x: &mut HyperDual64| {
+        if di == d1 {
+            x.eps1[0] = 1.0
+        }
+        if di == d2 {
+            x.eps2[0] = 1.0
+        }
+    });
+    c.helmholtz_energy(&state).eps1eps2[(0, 0)]
+}
+
+fn third(c: &dyn HelmholtzEnergy, point: &Point, d: Derivative) -> f64 {
+    let state = point.state(|di, x: &mut Dual3_64| {
+        if di == d {
+            *x = x.derive()
+        }
+    });
+    c.helmholtz_energy(&state).v3
+}
+
+fn third_mixed(
+    c: &dyn HelmholtzEnergy,
+    point: &Point,
+    d1: Derivative,
+    d2: Derivative,
+    d3: Derivative,
+) -> f64 {
+    let state = point.state(|di, x: &mut HyperDual<Dual64, f64>| {
+        if di == d1 {
+            x.eps1[0] = Dual64::from(1.0)
+        }
+        if di == d2 {
+            x.eps2[0] = Dual64::from(1.0)
+        }
+        if di == d3 {
+            x.re.eps[0] = 1.0;
+        }
+    });
+    c.helmholtz_energy(&state).eps1eps2[(0, 0)].eps[0]
+}
+
+impl<U: EosUnit, E: EquationOfState> State<U, E> {
+    /// Compare the derivatives of all residual Helmholtz energy contributions
+    /// to finite difference approximations.
+    ///
+    /// The derivatives are calculated with the dual numbers that are used
+    /// for the evaluation of first, second and third derivatives in the
+    /// state properties. This function is mainly intended to validate new
+    /// (e.g., user defined) implementations of Helmholtz energy contributions.
+    /// Deviations of the order of the relative step size of the finite
+    /// differences (1e-4) indicate an inconsistent implementation for the
+    /// respective dual number. Variables that are zero in the state (e.g.,
+    /// the moles of absent components) are skipped.
+    pub fn verify_derivatives(&self) -> Vec<DerivativeCheck> {
+        let point = Point {
+            temperature: self.reduced_temperature,
+            volume: self.reduced_volume,
+            moles: self.reduced_moles.clone(),
+        };
+        let variables: Vec<_> = [Derivative::DT, Derivative::DV]
+            .iter()
+            .copied()
+            .chain((0..point.moles.len()).map(Derivative::DN))
+            .filter(|&d| point.get(d) != 0.0)
+            .collect();
+
+        let mut checks = Vec::new();
+        for c in self.eos.residual() {
+            let c = c.as_ref();
+            let a = zeroth(c, &point).abs().max(1.0);
+            let mut check = |derivatives: &[Derivative], dual_number, value, reference| {
+                let order = match derivatives.len() {
+                    1 => String::new(),
+                    n => n.to_string(),
+                };
+                let variables: String = derivatives.iter().map(|d| format!("d{}", d)).collect();
+                checks.push(DerivativeCheck {
+                    contribution: c.to_string(),
+                    derivative: format!("d{}A/{}", order, variables),
+                    dual_number,
+                    value,
+                    reference,
+                    magnitude: derivatives.iter().fold(a, |m, &d| m / point.get(d).abs()),
+                })
+            };
+            for (i, &d1) in variables.iter().enumerate() {
+                check(
+                    &[d1],
+                    "Dual64",
+                    first(c, &point, d1),
+                    point.finite_difference(d1, |p| zeroth(c, p)),
+                );
+                check(
+                    &[d1, d1, d1],
+                    "Dual3_64",
+                    third(c, &point, d1),
+                    point.finite_difference(d1, |p| second(c, p, d1, d1)),
+                );
+                for &d2 in &variables[i..] {
+                    check(
+                        &[d1, d2],
+                        "HyperDual64",
+                        second(c, &point, d1, d2),
+                        point.finite_difference(d2, |p| first(c, p, d1)),
+                    );
+                    for &d3 in &variables {
+                        check(
+                            &[d1, d2, d3],
+                            "HyperDual<Dual64>",
+                            third_mixed(c, &point, d1, d2, d3),
+                            point.finite_difference(d3, |p| second(c, p, d1, d2)),
+                        );
+                    }
+                }
+            }
+        }
+        checks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane_butane;
+    use crate::equation_of_state::HelmholtzEnergyDual;
+    use crate::errors::EosResult;
+    use crate::state::DensityInitialization;
+    use ndarray::arr1;
+    use quantity::si::*;
+    use std::rc::Rc;
+
+    /// A contribution that drops the derivatives with respect to the volume.
+    struct ConstantVolume;
+
+    impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for ConstantVolume {
+        fn helmholtz_energy(&self, state: &StateHD<D>) -> D {
+            state.moles.sum() * state.temperature.recip() * state.volume.re().recip()
+        }
+    }
+
+    impl fmt::Display for ConstantVolume {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "Constant volume")
+        }
+    }
+
+    struct Inconsistent(Vec<Box<dyn HelmholtzEnergy>>);
+
+    impl EquationOfState for Inconsistent {
+        fn components(&self) -> usize {
+            1
+        }
+
+        fn subset(&self, _: &[usize]) -> Self {
+            Self(vec![Box::new(ConstantVolume)])
+        }
+
+        fn compute_max_density(&self, _: &Array1<f64>) -> f64 {
+            1.0
+        }
+
+        fn residual(&self) -> &[Box<dyn HelmholtzEnergy>] {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn test_verify_derivatives() -> EosResult<()> {
+        let eos = propane_butane();
+        let moles = arr1(&[0.3, 0.7]) * MOL;
+        let state = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            20.0 * BAR,
+            &moles,
+            DensityInitialization::Liquid,
+        )?;
+        let checks = state.verify_derivatives();
+        assert_eq!(checks.len(), 4 * 2 + 10 * 5);
+        for check in checks {
+            assert!(check.deviation() < 1e-6, "{}", check);
+        }
+
+        // the derivatives with respect to the volume are missing
+        let eos = Rc::new(Inconsistent(vec![Box::new(ConstantVolume)]));
+        let state = State::new_nvt(
+            &eos,
+            300.0 * KELVIN,
+            1e-3 * METER.powi(3),
+            &(arr1(&[1.0]) * MOL),
+        )?;
+        let failed: Vec<_> = state
+            .verify_derivatives()
+            .into_iter()
+            .filter(|c| c.deviation() > 1e-6)
+            .map(|c| c.derivative)
+            .collect();
+        assert!(failed.contains(&String::from("dA/dV")));
+        assert!(!failed.contains(&String::from("dA/dT")));
+        Ok(())
+    }
+}
@@ -6,9 +6,10 @@
 //! * the volume
 //!
 //! Internally, all properties are computed using such states as input.
-use crate::density_iteration::density_iteration;
+use crate::density_iteration::{density_iteration, is_single_branch};
 use crate::equation_of_state::EquationOfState;
 use crate::errors::{EosError, EosResult};
+use crate::property_package::PhaseLabel;
 use crate::EosUnit;
 use cache::Cache;
 use ndarray::prelude::*;
@@ -16,22 +17,34 @@ use num_dual::linalg::{norm, LU};
 use num_dual::*;
 use quantity::{QuantityArray1, QuantityScalar};
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::fmt;
 use std::rc::Rc;
 
+mod activity;
 mod builder;
 mod cache;
+mod derivative_check;
+mod path;
 mod properties;
+mod property;
+mod widom_line;
 pub use builder::StateBuilder;
-pub use properties::{Contributions, StateVec};
+pub use derivative_check::DerivativeCheck;
+pub use path::StatePath;
+pub use properties::{Basis, Contributions, StateVec, StateVecProperties};
+pub use property::Property;
+pub use widom_line::ResponseFunction;
 
 /// Initial values in a density iteration.
 #[derive(Clone, Copy)]
 pub enum DensityInitialization<U: EosUnit> {
-    /// Calculate a vapor phase by initializing using the ideal gas.
+    /// Calculate a vapor phase by initializing using the ideal gas
+    /// or the [EquationOfState::vapor_density_estimate] of the model.
     Vapor,
-    /// Calculate a liquid phase by using the `max_density`.
+    /// Calculate a liquid phase by using the `max_density`
+    /// or the [EquationOfState::liquid_density_estimate] of the model.
     Liquid,
     /// Use the given density as initial value.
     InitialDensity(QuantityScalar<U>),
@@ -40,6 +53,28 @@ pub enum DensityInitialization<U: EosUnit> {
     None,
 }
 
+/// Treatment of mole fractions that do not sum up to one.
+///
+/// Deviations below a tolerance of $10^{-8}$ are always corrected silently.
+/// If no treatment is specified, e.g., in [State::new], the mole fractions
+/// are only checked for finite, non-negative entries.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "python", pyo3::pyclass)]
+pub enum MolefracsNormalization {
+    /// Return an error.
+    Error,
+    /// Normalize the mole fractions silently.
+    Normalize,
+}
+
+impl MolefracsNormalization {
+    /// Validate the mole fractions and normalize them or return
+    /// an error if they do not sum up to one.
+    pub fn apply(self, molefracs: &Array1<f64>) -> EosResult<Array1<f64>> {
+        validate_molefracs(molefracs, self)
+    }
+}
+
 /// Thermodynamic state of the system in reduced variables
 /// including their derivatives.
 ///
@@ -138,6 +173,8 @@ pub struct State<U, E> {
     pub density: QuantityScalar<U>,
     /// Mole fractions $x_i=\frac{N_i}{N}=\frac{\rho_i}{\rho}$
     pub molefracs: Array1<f64>,
+    /// User defined metadata, e.g., an experiment ID or a branch label
+    pub metadata: BTreeMap<String, String>,
     /// Reduced temperature
     reduced_temperature: f64,
     /// Reduced volume,
@@ -159,6 +196,7 @@ impl<U: Clone, E> Clone for State<U, E> {
             partial_density: self.partial_density.clone(),
             density: self.density.clone(),
             molefracs: self.molefracs.clone(),
+            metadata: self.metadata.clone(),
             reduced_temperature: self.reduced_temperature,
             reduced_volume: self.reduced_volume,
             reduced_moles: self.reduced_moles.clone(),
@@ -214,6 +252,7 @@ pub(crate) enum PartialDerivative {
     First(Derivative),
     Second(Derivative, Derivative),
     Third(Derivative),
+    ThirdMixed(Derivative, Derivative, Derivative),
 }
 
 /// # State constructors
@@ -231,7 +270,17 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
     ) -> EosResult<Self> {
         eos.validate_moles(Some(moles))?;
         validate(temperature, volume, moles)?;
+        Self::new_nvt_unchecked(eos, temperature, volume, moles)
+    }
 
+    /// Return a new `State` given a temperature, an array of mole numbers and a volume
+    /// without validating them.
+    pub(crate) fn new_nvt_unchecked(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        volume: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+    ) -> EosResult<Self> {
         let t = temperature.to_reduced(U::reference_temperature())?;
         let v = volume.to_reduced(U::reference_volume())?;
         let m = moles.to_reduced(U::reference_moles())?;
@@ -250,6 +299,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
             partial_density,
             density,
             molefracs,
+            metadata: BTreeMap::new(),
             reduced_temperature: t,
             reduced_volume: v,
             reduced_moles: m,
@@ -257,6 +307,12 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         })
     }
 
+    /// Add an entry to the user defined metadata of the state.
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.insert(key.to_owned(), value.to_owned());
+        self
+    }
+
     /// Return a new `State` for a pure component given a temperature and a density. The moles
     /// are set to the reference value for each component.
     ///
@@ -366,13 +422,12 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                 )))
             }
             (Some(x), None, _) => x,
-            (None, Some(x), _) => x.clone(),
-            (None, None, 1) => arr1(&[1.0]),
-            _ => {
-                return Err(EosError::UndeterminedState(String::from(
-                    "Missing composition.",
-                )))
+            (None, Some(x), _) => {
+                check_molefracs(x)?;
+                x.clone()
             }
+            (None, None, 1) => arr1(&[1.0]),
+            (None, None, components) => return Err(EosError::MissingComposition { components }),
         };
 
         // If no extensive property is given, moles is set to the reference value.
@@ -416,6 +471,47 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         )))
     }
 
+    /// Return a new `State` in the given phase for temperature, pressure and moles.
+    ///
+    /// The stability of the phase is not considered. Inside the two-phase
+    /// region, the result is a metastable state (a subcooled vapor or a
+    /// superheated liquid). Such hypothetical single phase states are
+    /// required, e.g., for departure functions in correlations. If the phase
+    /// does not exist at the given conditions, i.e., if the density lies
+    /// beyond the spinodal, an error is returned. For [PhaseLabel::Stable],
+    /// the stable state is returned.
+    pub fn new_npt_forced_phase(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+        phase: PhaseLabel,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let state = Self::new_npt(eos, temperature, pressure, moles, phase.into())?;
+        // the vapor is connected to the ideal gas and the liquid to the
+        // maximum density without crossing a spinodal
+        let exists = match phase {
+            PhaseLabel::Vapor => {
+                is_single_branch(eos, temperature, moles, 1e-3 * state.density, state.density)?
+            }
+            PhaseLabel::Liquid => {
+                let max_density = eos.max_density_at_temperature(temperature, Some(moles))?;
+                is_single_branch(eos, temperature, moles, state.density, max_density)?
+            }
+            PhaseLabel::Stable => true,
+        };
+        if !exists {
+            return Err(EosError::UndeterminedState(format!(
+                "no {:?} phase exists at T = {} and p = {} (beyond the spinodal)",
+                phase, temperature, pressure
+            )));
+        }
+        Ok(state)
+    }
+
     /// Return a new `State` using a density iteration. [DensityInitialization] is used to
     /// influence the calculation with respect to the possible solutions.
     pub fn new_npt(
@@ -424,6 +520,20 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         pressure: QuantityScalar<U>,
         moles: &QuantityArray1<U>,
         density_initialization: DensityInitialization<U>,
+    ) -> EosResult<Self> {
+        eos.validate_moles(Some(moles))?;
+        validate_npt(temperature, pressure, moles)?;
+        Self::new_npt_unchecked(eos, temperature, pressure, moles, density_initialization)
+    }
+
+    /// Return a new `State` using a density iteration without validating
+    /// the temperature, pressure and mole numbers.
+    pub(crate) fn new_npt_unchecked(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+        density_initialization: DensityInitialization<U>,
     ) -> EosResult<Self> {
         // calculate state from initial density or given phase
         match density_initialization {
@@ -436,7 +546,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                     temperature,
                     pressure,
                     moles,
-                    pressure / temperature / U::gas_constant(),
+                    eos.initial_vapor_density(temperature, pressure, Some(moles))?,
                 )
             }
             DensityInitialization::Liquid => {
@@ -445,15 +555,21 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                     temperature,
                     pressure,
                     moles,
-                    eos.max_density(Some(moles))?,
+                    eos.initial_liquid_density(temperature, Some(moles))?,
                 )
             }
             DensityInitialization::None => (),
         }
 
         // calculate stable phase
-        let max_density = eos.max_density(Some(moles))?;
-        let liquid = density_iteration(eos, temperature, pressure, moles, max_density);
+        let max_density = eos.max_density_at_temperature(temperature, Some(moles))?;
+        let liquid = density_iteration(
+            eos,
+            temperature,
+            pressure,
+            moles,
+            eos.initial_liquid_density(temperature, Some(moles))?,
+        );
 
         if pressure < max_density * temperature * U::gas_constant() {
             let vapor = density_iteration(
@@ -461,7 +577,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                 temperature,
                 pressure,
                 moles,
-                pressure / temperature / U::gas_constant(),
+                eos.initial_vapor_density(temperature, pressure, Some(moles))?,
             );
             match (&liquid, &vapor) {
                 (Ok(_), Err(_)) => liquid,
@@ -493,6 +609,7 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         molefracs: &Array1<f64>,
         density_initialization: DensityInitialization<U>,
     ) -> EosResult<Self> {
+        check_molefracs(molefracs)?;
         let moles = molefracs * U::reference_moles();
         let state = Self::new_npt(eos, temperature, pressure, &moles, density_initialization)?;
         let moles = state.partial_density * volume;
@@ -530,9 +647,15 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
     ) -> EosResult<Self> {
         let rho0 = match density_initialization {
             DensityInitialization::InitialDensity(r) => r,
-            DensityInitialization::Liquid => eos.max_density(Some(moles))?,
-            DensityInitialization::Vapor => 1.0e-5 * eos.max_density(Some(moles))?,
-            DensityInitialization::None => 0.01 * eos.max_density(Some(moles))?,
+            DensityInitialization::Liquid => {
+                eos.initial_liquid_density(temperature, Some(moles))?
+            }
+            DensityInitialization::Vapor => {
+                1.0e-5 * eos.max_density_at_temperature(temperature, Some(moles))?
+            }
+            DensityInitialization::None => {
+                0.01 * eos.max_density_at_temperature(temperature, Some(moles))?
+            }
         };
         let n_inv = 1.0 / moles.sum();
         let f = |x0| {
@@ -557,9 +680,15 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
     ) -> EosResult<Self> {
         let rho0 = match density_initialization {
             DensityInitialization::InitialDensity(r) => r,
-            DensityInitialization::Liquid => eos.max_density(Some(moles))?,
-            DensityInitialization::Vapor => 1.0e-5 * eos.max_density(Some(moles))?,
-            DensityInitialization::None => 0.01 * eos.max_density(Some(moles))?,
+            DensityInitialization::Liquid => {
+                eos.initial_liquid_density(temperature, Some(moles))?
+            }
+            DensityInitialization::Vapor => {
+                1.0e-5 * eos.max_density_at_temperature(temperature, Some(moles))?
+            }
+            DensityInitialization::None => {
+                0.01 * eos.max_density_at_temperature(temperature, Some(moles))?
+            }
         };
         let n_inv = 1.0 / moles.sum();
         let f = |x0| {
@@ -710,6 +839,33 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         };
         StateHD::new(t, v, n)
     }
+
+    fn derive3_mixed(
+        &self,
+        derivative1: Derivative,
+        derivative2: Derivative,
+        derivative3: Derivative,
+    ) -> StateHD<HyperDual<Dual64, f64>> {
+        let mut t = HyperDual::from(self.reduced_temperature);
+        let mut v = HyperDual::from(self.reduced_volume);
+        let mut n = self.reduced_moles.mapv(HyperDual::from);
+        match derivative1 {
+            Derivative::DT => t.eps1[0] = Dual64::from(1.0),
+            Derivative::DV => v.eps1[0] = Dual64::from(1.0),
+            Derivative::DN(i) => n[i].eps1[0] = Dual64::from(1.0),
+        }
+        match derivative2 {
+            Derivative::DT => t.eps2[0] = Dual64::from(1.0),
+            Derivative::DV => v.eps2[0] = Dual64::from(1.0),
+            Derivative::DN(i) => n[i].eps2[0] = Dual64::from(1.0),
+        }
+        match derivative3 {
+            Derivative::DT => t.re.eps[0] = 1.0,
+            Derivative::DV => v.re.eps[0] = 1.0,
+            Derivative::DN(i) => n[i].re.eps[0] = 1.0,
+        }
+        StateHD::new(t, v, n)
+    }
 }
 
 fn is_close<U: EosUnit>(
@@ -759,30 +915,106 @@ fn validate<U: EosUnit>(
     let t = temperature.to_reduced(U::reference_temperature())?;
     let v = volume.to_reduced(U::reference_volume())?;
     let m = moles.to_reduced(U::reference_moles())?;
-    if !t.is_finite() || t.is_sign_negative() {
+    check_positive("temperature", t)?;
+    check_positive("volume", v)?;
+    for &n in m.iter() {
+        check_positive("moles", n)?;
+    }
+    Ok(())
+}
+
+/// Validate the given temperature, pressure and mole numbers.
+///
+/// In contrast to the temperature and the mole numbers, the
+/// pressure only has to be finite, because (metastable) liquids
+/// can be under tension.
+fn validate_npt<U: EosUnit>(
+    temperature: QuantityScalar<U>,
+    pressure: QuantityScalar<U>,
+    moles: &QuantityArray1<U>,
+) -> EosResult<()> {
+    let t = temperature.to_reduced(U::reference_temperature())?;
+    let p = pressure.to_reduced(U::reference_pressure())?;
+    let m = moles.to_reduced(U::reference_moles())?;
+    check_positive("temperature", t)?;
+    if !p.is_finite() {
         return Err(EosError::InvalidState(
             String::from("validate"),
-            String::from("temperature"),
-            t,
+            String::from("pressure"),
+            p,
         ));
     }
-    if !v.is_finite() || v.is_sign_negative() {
+    for &n in m.iter() {
+        check_positive("moles", n)?;
+    }
+    Ok(())
+}
+
+/// Check that a (reduced) property is finite and has a positive sign.
+fn check_positive(property: &str, value: f64) -> EosResult<()> {
+    if !value.is_finite() || value.is_sign_negative() {
         return Err(EosError::InvalidState(
             String::from("validate"),
-            String::from("volume"),
-            v,
+            property.to_owned(),
+            value,
         ));
     }
-    for &n in m.iter() {
-        if !n.is_finite() || n.is_sign_negative() {
+    Ok(())
+}
+
+const MOLEFRACS_TOL: f64 = 1e-8;
+
+/// Check that the given mole fractions are finite and non-negative
+/// and that not all of them are zero. Returns the sum of the mole fractions.
+pub(crate) fn check_molefracs(molefracs: &Array1<f64>) -> EosResult<f64> {
+    for &x in molefracs.iter() {
+        if !x.is_finite() || x < 0.0 {
             return Err(EosError::InvalidState(
                 String::from("validate"),
-                String::from("moles"),
-                n,
+                String::from("molefracs"),
+                x,
             ));
         }
     }
-    Ok(())
+    let sum = molefracs.sum();
+    if sum == 0.0 {
+        return Err(EosError::InvalidState(
+            String::from("validate"),
+            String::from("sum of molefracs"),
+            sum,
+        ));
+    }
+    Ok(sum)
+}
+
+/// Check that the given amounts of substance are finite and non-negative
+/// and that not all of them are zero.
+pub(crate) fn check_moles<U: EosUnit>(moles: &QuantityArray1<U>) -> EosResult<()> {
+    check_molefracs(&moles.to_reduced(U::reference_moles())?).map(|_| ())
+}
+
+/// Validate the given mole fractions.
+///
+/// Mole fractions are valid if
+/// * they are finite
+/// * they are not negative
+/// * they sum up to one
+///
+/// Depending on the [MolefracsNormalization], mole fractions that do
+/// not sum up to one are normalized or rejected.
+pub(crate) fn validate_molefracs(
+    molefracs: &Array1<f64>,
+    normalization: MolefracsNormalization,
+) -> EosResult<Array1<f64>> {
+    let sum = check_molefracs(molefracs)?;
+    if (sum - 1.0).abs() > MOLEFRACS_TOL && normalization == MolefracsNormalization::Error {
+        return Err(EosError::InvalidState(
+            String::from("validate"),
+            String::from("sum of molefracs"),
+            sum,
+        ));
+    }
+    Ok(molefracs / sum)
 }
 
 #[derive(Clone, Copy)]
@@ -815,8 +1047,118 @@ mod critical_point;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cubic::fixtures::{propane, propane_butane};
+    use crate::cubic::PengRobinson;
+    use crate::phase_equilibria::{PhaseDiagram, PhaseEquilibrium, SolverOptions};
+    use approx::assert_relative_eq;
     use quantity::si::*;
-    use std::f64::NAN;
+
+    /// Peng-Robinson equation of state with a maximum density that
+    /// is unsuitable as initial value in a density iteration.
+    struct BadMaxDensity {
+        eos: Rc<PengRobinson>,
+        estimate: bool,
+        temperature_dependent: bool,
+    }
+
+    impl EquationOfState for BadMaxDensity {
+        fn components(&self) -> usize {
+            self.eos.components()
+        }
+
+        fn subset(&self, component_list: &[usize]) -> Self {
+            Self {
+                eos: Rc::new(self.eos.subset(component_list)),
+                estimate: self.estimate,
+                temperature_dependent: self.temperature_dependent,
+            }
+        }
+
+        fn compute_max_density(&self, moles: &Array1<f64>) -> f64 {
+            2.0 * self.eos.compute_max_density(moles)
+        }
+
+        fn compute_max_density_at_temperature(&self, _: f64, moles: &Array1<f64>) -> f64 {
+            if self.temperature_dependent {
+                self.eos.compute_max_density(moles)
+            } else {
+                self.compute_max_density(moles)
+            }
+        }
+
+        fn liquid_density_estimate(&self, _: f64, moles: &Array1<f64>) -> Option<f64> {
+            self.estimate.then(|| self.eos.compute_max_density(moles))
+        }
+
+        fn residual(&self) -> &[Box<dyn crate::HelmholtzEnergy>] {
+            self.eos.residual()
+        }
+    }
+
+    #[test]
+    fn test_density_estimate() -> EosResult<()> {
+        let pr = propane();
+        let (t, p) = (250.0 * KELVIN, 10.0 * BAR);
+        let moles = arr1(&[1.0]) * MOL;
+        let reference = State::new_npt(&pr, t, p, &moles, DensityInitialization::Liquid)?;
+
+        let eos = |estimate, temperature_dependent| {
+            Rc::new(BadMaxDensity {
+                eos: pr.clone(),
+                estimate,
+                temperature_dependent,
+            })
+        };
+        let liquid = State::new_npt(
+            &eos(true, false),
+            t,
+            p,
+            &moles,
+            DensityInitialization::Liquid,
+        )?;
+        assert_relative_eq!(liquid.density, reference.density, max_relative = 1e-10);
+
+        // the temperature dependent maximum density is used as well
+        let liquid = State::new_npt(
+            &eos(false, true),
+            t,
+            p,
+            &moles,
+            DensityInitialization::Liquid,
+        )?;
+        assert_relative_eq!(liquid.density, reference.density, max_relative = 1e-10);
+
+        // without the estimate, the density iteration ends up in the vapor phase
+        let vapor = State::new_npt(
+            &eos(false, false),
+            t,
+            p,
+            &moles,
+            DensityInitialization::Liquid,
+        )?;
+        assert!(vapor.density < 0.1 * reference.density);
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata() -> EosResult<()> {
+        let eos = propane();
+        let state = State::new_nvt(&eos, 300.0 * KELVIN, METER.powi(3), &(arr1(&[1.0]) * MOL))?
+            .with_metadata("experiment", "42");
+        assert_eq!(state.clone().metadata["experiment"], "42");
+        assert!(State::new_pure(&eos, 300.0 * KELVIN, MOL / METER.powi(3))?
+            .metadata
+            .is_empty());
+
+        let mut vle = PhaseEquilibrium::pure(&eos, 300.0 * KELVIN, None, SolverOptions::default())?
+            .with_metadata("branch", "vle");
+        vle.metadata_mut()
+            .insert("experiment".to_owned(), "42".to_owned());
+        let vle = vle.clone();
+        assert_eq!(vle.metadata()["branch"], "vle");
+        assert_eq!(vle.metadata().len(), 2);
+        Ok(())
+    }
 
     #[test]
     fn test_validate() {
@@ -836,7 +1178,7 @@ mod tests {
 
     #[test]
     fn test_nan_temperature() {
-        let temperature = NAN * KELVIN;
+        let temperature = f64::NAN * KELVIN;
         let volume = 3000.0 * METER.powi(3);
         let moles = arr1(&[0.03, 0.02, 0.05]) * MOL;
         assert!(validate(temperature, volume, &moles).is_err());
@@ -854,7 +1196,7 @@ mod tests {
     fn test_nan_mole_number() {
         let temperature = 298.15 * KELVIN;
         let volume = 3000.0 * METER.powi(3);
-        let moles = arr1(&[NAN, 0.02, 0.05]) * MOL;
+        let moles = arr1(&[f64::NAN, 0.02, 0.05]) * MOL;
         assert!(validate(temperature, volume, &moles).is_err());
     }
 
@@ -865,4 +1207,129 @@ mod tests {
         let moles = arr1(&[0.01, 0.02, 0.05]) * MOL;
         assert!(validate(temperature, volume, &moles).is_err());
     }
+
+    #[test]
+    fn test_validate_molefracs() {
+        let molefracs = arr1(&[0.2, 0.3, 0.6]);
+        assert!(validate_molefracs(&molefracs, MolefracsNormalization::Error).is_err());
+        let x = validate_molefracs(&molefracs, MolefracsNormalization::Normalize).unwrap();
+        assert!((x.sum() - 1.0).abs() < 1e-15);
+        let molefracs = arr1(&[-0.2, 0.6, 0.6]);
+        assert!(validate_molefracs(&molefracs, MolefracsNormalization::Normalize).is_err());
+        let molefracs = arr1(&[-0.0, 1.0]);
+        assert!(validate_molefracs(&molefracs, MolefracsNormalization::Error).is_ok());
+    }
+
+    #[test]
+    fn test_state_vec() -> EosResult<()> {
+        let eos = propane();
+        let dia = PhaseDiagram::pure(&eos, 250.0 * KELVIN, 11, None, SolverOptions::default())?;
+        let vapor = StateVec::vapor(&dia.states);
+        let liquid = StateVec::liquid(&dia.states);
+        assert_eq!(vapor.len(), 11);
+        assert_eq!(vapor[3].temperature, dia.states[3].vapor().temperature);
+        let sliced = vapor.slice(2..5);
+        assert_eq!(sliced.len(), 3);
+        assert_eq!(sliced[0].temperature, vapor[2].temperature);
+
+        let cold = vapor.filter(|s| s.temperature < 300.0 * KELVIN);
+        assert!(!cold.is_empty());
+        assert!(cold.into_iter().all(|s| s.temperature < 300.0 * KELVIN));
+
+        let both = StateVec::concat(&[vapor.clone(), liquid]);
+        assert_eq!(both.len(), 22);
+        assert_eq!(both[11].density, dia.states[0].liquid().density);
+        Ok(())
+    }
+
+    #[test]
+    fn test_chemical_potential_tp() -> EosResult<()> {
+        let eos = propane_butane();
+        let moles = arr1(&[0.3, 0.7]) * MOL;
+        let (t, p) = (300.0 * KELVIN, 20.0 * BAR);
+        let state = State::new_npt(&eos, t, p, &moles, DensityInitialization::Liquid)?;
+        let mu_res = state.chemical_potential_residual_tp();
+        let ln_phi = (&mu_res / (RGAS * t)).into_value()?;
+        assert_relative_eq!(ln_phi, state.ln_phi(), max_relative = 1e-12);
+
+        // the ideal gas reference is evaluated at the same temperature and pressure
+        let ideal_gas = State::new_nvt(&eos, t, moles.sum() * RGAS * t / p, &moles)?;
+        assert_relative_eq!(
+            state.ideal_chemical_potential(),
+            ideal_gas.chemical_potential(Contributions::IdealGas),
+            max_relative = 1e-10
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_npt_forced_phase() -> EosResult<()> {
+        let eos = propane();
+        let moles = arr1(&[1.0]) * MOL;
+        let (t, p) = (250.0 * KELVIN, 3.0 * BAR);
+
+        // the liquid is stable, the vapor is metastable
+        let stable = State::new_npt(&eos, t, p, &moles, DensityInitialization::None)?;
+        let liquid = State::new_npt_forced_phase(&eos, t, p, &moles, PhaseLabel::Liquid)?;
+        let vapor = State::new_npt_forced_phase(&eos, t, p, &moles, PhaseLabel::Vapor)?;
+        assert_relative_eq!(stable.density, liquid.density, max_relative = 1e-10);
+        assert!(vapor.density < liquid.density * 0.1);
+        assert_relative_eq!(vapor.pressure(Contributions::Total), p, max_relative = 1e-8);
+        assert!(
+            vapor.molar_gibbs_energy(Contributions::Total)
+                > liquid.molar_gibbs_energy(Contributions::Total)
+        );
+
+        // beyond the vapor spinodal, no vapor phase exists
+        for p in [50.0 * BAR, 100.0 * BAR] {
+            assert!(State::new_npt_forced_phase(&eos, t, p, &moles, PhaseLabel::Vapor).is_err());
+            State::new_npt_forced_phase(&eos, t, p, &moles, PhaseLabel::Liquid)?;
+        }
+
+        // above the critical temperature, both phases exist
+        let t = 400.0 * KELVIN;
+        let vapor = State::new_npt_forced_phase(&eos, t, 50.0 * BAR, &moles, PhaseLabel::Vapor)?;
+        let liquid = State::new_npt_forced_phase(&eos, t, 50.0 * BAR, &moles, PhaseLabel::Liquid)?;
+        assert_relative_eq!(vapor.density, liquid.density, max_relative = 1e-8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_composition() -> EosResult<()> {
+        let eos = propane_butane();
+        let missing =
+            |r: EosResult<_>| matches!(r, Err(EosError::MissingComposition { components: 2 }));
+        assert!(missing(eos.max_density::<SIUnit>(None).map(|_| ())));
+        assert!(missing(
+            StateBuilder::new(&eos)
+                .temperature(300.0 * KELVIN)
+                .pressure(BAR)
+                .build()
+                .map(|_| ())
+        ));
+        assert!(matches!(
+            eos.max_density(Some(&(arr1(&[1.0]) * MOL))),
+            Err(EosError::IncompatibleComponents(2, 1))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_vec_to_basis() -> EosResult<()> {
+        let eos = propane();
+        let dia = PhaseDiagram::pure(&eos, 250.0 * KELVIN, 5, None, SolverOptions::default())?;
+        let liquid = dia.liquid();
+        let mass = liquid.to_basis(Basis::Mass)?;
+        assert_eq!(mass.basis, Basis::Mass);
+        assert_relative_eq!(mass.density, liquid.mass_density());
+        assert_relative_eq!(mass.enthalpy, liquid.specific_enthalpy());
+        let reduced = liquid.to_basis(Basis::Reduced)?;
+        assert_relative_eq!(
+            reduced.temperature.into_value()?,
+            liquid.temperature().to_reduced(KELVIN)?
+        );
+        assert_relative_eq!(reduced.composition, liquid.molefracs());
+        assert!(liquid.filter(|_| false).to_basis(Basis::Molar).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,490 @@
+use super::{
+    validate_molefracs, Contributions, DensityInitialization, MolefracsNormalization, State,
+    StateHD, StateVec,
+};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::state::Property;
+use crate::EosUnit;
+use ndarray::{Array1, Array2, Zip};
+use num_dual::{DualVec64, HyperDual, HyperDual64, StaticVec};
+use quantity::{QuantityArray1, QuantityScalar};
+use std::rc::Rc;
+
+/// States along an isochore, an isobar, an isotherm or a Widom line.
+pub struct StatePath<U, E> {
+    /// The states in the order in which they were generated.
+    pub states: Vec<State<U, E>>,
+}
+
+impl<U, E> StatePath<U, E> {
+    /// Return the states as a [StateVec] to evaluate their properties.
+    pub fn state_vec(&self) -> StateVec<'_, U, E> {
+        self.states.iter().collect()
+    }
+}
+
+impl<U: EosUnit, E: EquationOfState> State<U, E> {
+    /// Return the first and second derivative of a property along
+    /// the path $T(s)=T+s\Delta T$, $p(s)=p+s\Delta p$, $n_i(s)=n_i+s\Delta n_i$.
+    ///
+    /// The path is parametrized by the dimensionless variable $s$, i.e.,
+    /// the results have the units of the property. The volume along the path
+    /// follows from the implicit function theorem and all derivatives are
+    /// evaluated exactly with (hyper) dual numbers.
+    ///
+    /// Only properties that depend on the Helmholtz energy and its first
+    /// partial derivatives are available. Heat capacities, compressibilities
+    /// and mass specific properties return an error.
+    pub fn directional_derivative(
+        &self,
+        property: Property,
+        dt: QuantityScalar<U>,
+        dp: QuantityScalar<U>,
+        dn: &QuantityArray1<U>,
+    ) -> EosResult<(QuantityScalar<U>, QuantityScalar<U>)> {
+        if dn.len() != self.eos.components() {
+            return Err(EosError::IncompatibleComponents(
+                self.eos.components(),
+                dn.len(),
+            ));
+        }
+        let dt = dt.to_reduced(U::reference_temperature())?;
+        let dp = dp.to_reduced(U::reference_pressure())?;
+        let dn = dn.to_reduced(U::reference_moles())?;
+        let dp_dv = self
+            .dp_dv(Contributions::Total)
+            .to_reduced(U::reference_pressure() / U::reference_volume())?;
+
+        // the pressure changes linearly along the path, which determines
+        // the first and second derivative of the volume
+        let [_, _, a_v] = self.path_helmholtz_energy(dt, &dn, 0.0, 0.0);
+        let dv = (dp + a_v.eps1[0]) / dp_dv;
+        let [_, _, a_v] = self.path_helmholtz_energy(dt, &dn, dv, 0.0);
+        let d2v = a_v.eps1eps2[(0, 0)] / dp_dv;
+
+        let [a, a_t, a_v] = self.path_helmholtz_energy(dt, &dn, dv, d2v);
+        let t = HyperDual64::new_scalar(self.reduced_temperature, dt, dt, 0.0);
+        let v = HyperDual64::new_scalar(self.reduced_volume, dv, dv, d2v);
+        let n = HyperDual64::new_scalar(self.reduced_moles.sum(), dn.sum(), dn.sum(), 0.0);
+        let p = -a_v;
+        let s = -a_t;
+
+        let (x, reference) = match property {
+            Property::Temperature => (t, U::reference_temperature()),
+            Property::Volume => (v, U::reference_volume()),
+            Property::Density => (n / v, U::reference_density()),
+            Property::TotalMoles => (n, U::reference_moles()),
+            Property::Pressure => (p, U::reference_pressure()),
+            Property::Entropy => (s, U::reference_entropy()),
+            Property::Enthalpy => (a + t * s + p * v, U::reference_energy()),
+            Property::HelmholtzEnergy => (a, U::reference_energy()),
+            Property::InternalEnergy => (a + t * s, U::reference_energy()),
+            Property::GibbsEnergy => (a + p * v, U::reference_energy()),
+            Property::MolarEntropy => (s / n, U::reference_molar_entropy()),
+            Property::MolarEnthalpy => ((a + t * s + p * v) / n, U::reference_molar_energy()),
+            Property::MolarHelmholtzEnergy => (a / n, U::reference_molar_energy()),
+            Property::MolarInternalEnergy => ((a + t * s) / n, U::reference_molar_energy()),
+            Property::MolarGibbsEnergy => ((a + p * v) / n, U::reference_molar_energy()),
+            _ => {
+                return Err(EosError::UnsupportedProperty(
+                    property.to_string(),
+                    String::from("State::directional_derivative"),
+                ))
+            }
+        };
+        Ok((x.eps1[0] * reference, x.eps1eps2[(0, 0)] * reference))
+    }
+
+    /// Return the total Helmholtz energy and its partial derivatives with
+    /// respect to temperature and volume (in reduced units) as hyper dual
+    /// numbers in the path variable, where the volume along the path is
+    /// $V(s)=V+sV'+\frac{s^2}{2}V''$.
+    fn path_helmholtz_energy(
+        &self,
+        dt: f64,
+        dn: &Array1<f64>,
+        dv: f64,
+        d2v: f64,
+    ) -> [HyperDual64; 3] {
+        let path = |x: f64, dx: f64, d2x: f64, eps: [f64; 2]| {
+            HyperDual::new_scalar(
+                DualVec64::new(x, StaticVec::new_vec(eps)),
+                DualVec64::from(dx),
+                DualVec64::from(dx),
+                DualVec64::from(d2x),
+            )
+        };
+        let t = path(self.reduced_temperature, dt, 0.0, [1.0, 0.0]);
+        let v = path(self.reduced_volume, dv, d2v, [0.0, 1.0]);
+        let n = Zip::from(&self.reduced_moles)
+            .and(dn)
+            .map_collect(|&n, &dn| path(n, dn, 0.0, [0.0, 0.0]));
+        let state = StateHD::new(t, v, n);
+        let a = (self.eos.evaluate_residual(&state) + self.eos.ideal_gas().evaluate(&state)) * t;
+
+        let part = |f: &dyn Fn(&DualVec64<2>) -> f64| {
+            HyperDual64::new_scalar(
+                f(&a.re),
+                f(&a.eps1[0]),
+                f(&a.eps2[0]),
+                f(&a.eps1eps2[(0, 0)]),
+            )
+        };
+        [part(&|x| x.re), part(&|x| x.eps[0]), part(&|x| x.eps[1])]
+    }
+
+    /// Return `npoints` states along an isochore for equidistant
+    /// temperatures between `min_temperature` and `max_temperature`.
+    ///
+    /// The pressure $p(T)$ along the isochore and other properties are
+    /// available from [StatePath::state_vec].
+    pub fn isochore(
+        eos: &Rc<E>,
+        density: QuantityScalar<U>,
+        min_temperature: QuantityScalar<U>,
+        max_temperature: QuantityScalar<U>,
+        npoints: usize,
+        moles: &QuantityArray1<U>,
+    ) -> EosResult<StatePath<U, E>> {
+        let volume = moles.sum() / density;
+        let temperature = QuantityArray1::linspace(min_temperature, max_temperature, npoints)?;
+        let states = temperature
+            .into_iter()
+            .map(|t| State::new_nvt(eos, t, volume, moles))
+            .collect::<EosResult<_>>()?;
+        Ok(StatePath { states })
+    }
+
+    /// Return `npoints` states along an isobar for equidistant
+    /// temperatures between `min_temperature` and `max_temperature`.
+    ///
+    /// The first state is calculated with the given density initialization.
+    /// All further density iterations start at the density of the previous
+    /// state, so the phase of the first state is retained as long as it is
+    /// (meta)stable.
+    pub fn isobar(
+        eos: &Rc<E>,
+        pressure: QuantityScalar<U>,
+        min_temperature: QuantityScalar<U>,
+        max_temperature: QuantityScalar<U>,
+        npoints: usize,
+        moles: &QuantityArray1<U>,
+        density_initialization: DensityInitialization<U>,
+    ) -> EosResult<StatePath<U, E>> {
+        let temperature = QuantityArray1::linspace(min_temperature, max_temperature, npoints)?;
+        Self::continuation(
+            npoints,
+            |i, density| State::new_npt(eos, temperature.get(i), pressure, moles, density),
+            density_initialization,
+        )
+    }
+
+    /// Return `npoints` states along an isotherm for equidistant
+    /// pressures between `min_pressure` and `max_pressure`.
+    ///
+    /// The first state is calculated with the given density initialization.
+    /// All further density iterations start at the density of the previous
+    /// state, so the phase of the first state is retained as long as it is
+    /// (meta)stable.
+    pub fn isotherm(
+        eos: &Rc<E>,
+        temperature: QuantityScalar<U>,
+        min_pressure: QuantityScalar<U>,
+        max_pressure: QuantityScalar<U>,
+        npoints: usize,
+        moles: &QuantityArray1<U>,
+        density_initialization: DensityInitialization<U>,
+    ) -> EosResult<StatePath<U, E>> {
+        let pressure = QuantityArray1::linspace(min_pressure, max_pressure, npoints)?;
+        Self::continuation(
+            npoints,
+            |i, density| State::new_npt(eos, temperature, pressure.get(i), moles, density),
+            density_initialization,
+        )
+    }
+
+    /// Return the states for arrays of temperatures, pressures and compositions.
+    ///
+    /// Each row of `molefracs` corresponds to one entry of `temperature` and
+    /// `pressure`. A single row is used for all states. Every state contains
+    /// the reference amount of substance `U::reference_moles()` (1/N_A mol
+    /// in SI units) and is calculated with the given density initialization,
+    /// i.e., in contrast to [State::isobar] and [State::isotherm], the states
+    /// do not have to be located on a path. The properties of all states can
+    /// be obtained by collecting the states into a [StateVec](super::StateVec).
+    ///
+    /// The arrays are validated once as a whole and every composition is
+    /// normalized once before the density iterations.
+    pub fn from_arrays(
+        eos: &Rc<E>,
+        temperature: &QuantityArray1<U>,
+        pressure: &QuantityArray1<U>,
+        molefracs: &Array2<f64>,
+        density_initialization: DensityInitialization<U>,
+    ) -> EosResult<Vec<Self>> {
+        let n = temperature.len();
+        if pressure.len() != n {
+            return Err(EosError::UndeterminedState(format!(
+                "{} pressures given for {} temperatures",
+                pressure.len(),
+                n
+            )));
+        }
+        if molefracs.nrows() != n && molefracs.nrows() != 1 {
+            return Err(EosError::UndeterminedState(format!(
+                "{} compositions given for {} states",
+                molefracs.nrows(),
+                n
+            )));
+        }
+        if molefracs.ncols() != eos.components() {
+            return Err(EosError::IncompatibleComponents(
+                eos.components(),
+                molefracs.ncols(),
+            ));
+        }
+        let t = temperature.to_reduced(U::reference_temperature())?;
+        if let Some(&t) = t.iter().find(|t| !t.is_finite() || t.is_sign_negative()) {
+            return Err(EosError::InvalidState(
+                String::from("from_arrays"),
+                String::from("temperature"),
+                t,
+            ));
+        }
+        let p = pressure.to_reduced(U::reference_pressure())?;
+        if let Some(&p) = p.iter().find(|p| !p.is_finite()) {
+            return Err(EosError::InvalidState(
+                String::from("from_arrays"),
+                String::from("pressure"),
+                p,
+            ));
+        }
+        let moles = molefracs
+            .outer_iter()
+            .map(|x| {
+                Ok(
+                    validate_molefracs(&x.to_owned(), MolefracsNormalization::Normalize)?
+                        * U::reference_moles(),
+                )
+            })
+            .collect::<EosResult<Vec<_>>>()?;
+        (0..n)
+            .map(|i| {
+                State::new_npt_unchecked(
+                    eos,
+                    temperature.get(i),
+                    pressure.get(i),
+                    &moles[if moles.len() == 1 { 0 } else { i }],
+                    density_initialization,
+                )
+            })
+            .collect()
+    }
+
+    fn continuation<F>(
+        points: usize,
+        state: F,
+        density_initialization: DensityInitialization<U>,
+    ) -> EosResult<StatePath<U, E>>
+    where
+        F: Fn(usize, DensityInitialization<U>) -> EosResult<Self>,
+    {
+        let mut states: Vec<Self> = Vec::with_capacity(points);
+        for i in 0..points {
+            let density = states.last().map_or(density_initialization, |s| {
+                DensityInitialization::InitialDensity(s.density)
+            });
+            states.push(state(i, density)?);
+        }
+        Ok(StatePath { states })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::{propane, propane_butane};
+    use crate::state::StateVec;
+    use approx::assert_relative_eq;
+    use ndarray::{arr1, arr2, Array1};
+    use quantity::si::*;
+
+    #[test]
+    fn test_directional_derivative() -> EosResult<()> {
+        let eos = propane();
+        let moles = arr1(&[1.0]) * MOL;
+        let state = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            BAR,
+            &moles,
+            DensityInitialization::Vapor,
+        )?;
+
+        // isobaric heating: dh/ds = c_p dT
+        let dt = KELVIN;
+        let (dh, _) = state.directional_derivative(
+            Property::MolarEnthalpy,
+            dt,
+            0.0 * BAR,
+            &(arr1(&[0.0]) * MOL),
+        )?;
+        assert_relative_eq!(
+            dh,
+            state.c_p(Contributions::Total) * dt,
+            max_relative = 1e-6
+        );
+
+        // isothermal compression: the pressure changes linearly along the path
+        let (dp, d2p) = state.directional_derivative(
+            Property::Pressure,
+            0.0 * KELVIN,
+            BAR,
+            &(arr1(&[0.0]) * MOL),
+        )?;
+        assert_relative_eq!(dp, BAR, max_relative = 1e-10);
+        assert!(d2p.to_reduced(BAR)?.abs() < 1e-10);
+
+        // the volume along the isotherm: dV/dp = -V kappa_T
+        let (dv, _) = state.directional_derivative(
+            Property::Volume,
+            0.0 * KELVIN,
+            BAR,
+            &(arr1(&[0.0]) * MOL),
+        )?;
+        assert_relative_eq!(
+            dv,
+            -state.volume * state.isothermal_compressibility() * BAR,
+            max_relative = 1e-10
+        );
+
+        // properties that require second derivatives are not available
+        assert!(state
+            .directional_derivative(Property::IsobaricHeatCapacity, dt, BAR, &moles)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_isobar_isotherm() -> EosResult<()> {
+        let eos = propane();
+        let moles = arr1(&[1.0]) * MOL;
+
+        // liquid isobar, the density decreases with temperature
+        let isobar = State::isobar(
+            &eos,
+            10.0 * BAR,
+            250.0 * KELVIN,
+            280.0 * KELVIN,
+            7,
+            &moles,
+            DensityInitialization::Liquid,
+        )?;
+        assert_eq!(isobar.states.len(), 7);
+        let density = isobar
+            .state_vec()
+            .density()
+            .to_reduced(MOL / METER.powi(3))?;
+        assert!(density.windows(2).into_iter().all(|w| w[1] < w[0]));
+        let temperature = Array1::linspace(250.0, 280.0, 7);
+        for (s, &t) in isobar.states.iter().zip(temperature.iter()) {
+            assert_relative_eq!(s.temperature, t * KELVIN);
+            assert_relative_eq!(
+                s.pressure(Contributions::Total),
+                10.0 * BAR,
+                max_relative = 1e-8
+            );
+        }
+
+        // vapor isotherm, the density increases with pressure
+        let isotherm = State::isotherm(
+            &eos,
+            300.0 * KELVIN,
+            BAR,
+            5.0 * BAR,
+            5,
+            &moles,
+            DensityInitialization::Vapor,
+        )?;
+        let vapor = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            5.0 * BAR,
+            &moles,
+            DensityInitialization::Vapor,
+        )?;
+        assert_relative_eq!(
+            isotherm.states[4].density,
+            vapor.density,
+            max_relative = 1e-10
+        );
+
+        // supercritical isochore, the pressure increases with temperature
+        let density = 5000.0 * MOL / METER.powi(3);
+        let isochore = State::isochore(&eos, density, 380.0 * KELVIN, 420.0 * KELVIN, 5, &moles)?;
+        let pressure = isochore.state_vec().pressure().to_reduced(BAR)?;
+        assert!(pressure.windows(2).into_iter().all(|w| w[1] > w[0]));
+        assert_relative_eq!(isochore.states[4].temperature, 420.0 * KELVIN);
+        assert_relative_eq!(isochore.states[4].density, density);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_arrays() -> EosResult<()> {
+        let eos = propane_butane();
+        let temperature = arr1(&[300.0, 350.0, 400.0]) * KELVIN;
+        let pressure = arr1(&[1.0, 2.0, 3.0]) * BAR;
+        let molefracs = arr2(&[[0.2, 0.8], [0.5, 0.5], [0.8, 0.2]]);
+        let states = State::from_arrays(
+            &eos,
+            &temperature,
+            &pressure,
+            &molefracs,
+            DensityInitialization::Vapor,
+        )?;
+        assert_eq!(states.len(), 3);
+        let state = State::new_npt(
+            &eos,
+            350.0 * KELVIN,
+            2.0 * BAR,
+            &(arr1(&[0.5, 0.5]) * MOL),
+            DensityInitialization::Vapor,
+        )?;
+        assert_relative_eq!(states[1].density, state.density, max_relative = 1e-10);
+        assert_relative_eq!(states[1].total_moles, MOL / 6.02214076e23);
+        let states: StateVec<_, _> = states.iter().collect();
+        assert_relative_eq!(states.molefracs(), molefracs);
+
+        // a single composition is used for all states
+        let states = State::from_arrays(
+            &eos,
+            &temperature,
+            &pressure,
+            &arr2(&[[0.5, 0.5]]),
+            DensityInitialization::Vapor,
+        )?;
+        assert_relative_eq!(states[1].density, state.density, max_relative = 1e-10);
+
+        // the sizes of the arrays have to match
+        assert!(State::from_arrays(
+            &eos,
+            &temperature,
+            &(arr1(&[1.0, 2.0]) * BAR),
+            &molefracs,
+            DensityInitialization::None,
+        )
+        .is_err());
+
+        // the arrays are validated before any density iteration
+        assert!(State::from_arrays(
+            &eos,
+            &(arr1(&[300.0, -350.0, 400.0]) * KELVIN),
+            &pressure,
+            &molefracs,
+            DensityInitialization::None,
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -1,12 +1,13 @@
 use super::{Derivative::*, PartialDerivative, State};
 use crate::equation_of_state::{EntropyScaling, EquationOfState, MolarWeight};
-use crate::errors::EosResult;
+use crate::errors::{EosError, EosResult};
+use crate::phase_equilibria::PhaseEquilibrium;
 use crate::EosUnit;
 use ndarray::{arr1, Array1, Array2};
 use num_dual::DualNum;
 use quantity::{QuantityArray, QuantityArray1, QuantityArray2, QuantityScalar};
 use std::iter::FromIterator;
-use std::ops::{Add, Deref, Sub};
+use std::ops::{Add, Deref, Index, RangeBounds, Sub};
 use std::rc::Rc;
 
 #[derive(Clone, Copy)]
@@ -61,6 +62,14 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                     -(new_state.moles.sum() * new_state.temperature * new_state.volume.ln()).v3
                         * (U::reference_energy() / (v.reference() * v.reference() * v.reference()))
                 }
+                PartialDerivative::ThirdMixed(v1, v2, v3) => {
+                    let new_state = self.derive3_mixed(v1, v2, v3);
+                    -(new_state.moles.sum() * new_state.temperature * new_state.volume.ln())
+                        .eps1eps2[(0, 0)]
+                        .eps[0]
+                        * (U::reference_energy()
+                            / (v1.reference() * v2.reference() * v3.reference()))
+                }
             };
         }
 
@@ -73,29 +82,36 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                     let new_state = self.derive0();
                     let computation =
                         || self.eos.evaluate_residual(&new_state) * new_state.temperature;
-                    cache.get_or_insert_with_f64(&computation) * U::reference_energy()
+                    cache.get_or_insert_with_f64(computation) * U::reference_energy()
                 }
                 PartialDerivative::First(v) => {
                     let new_state = self.derive1(v);
                     let computation =
                         || self.eos.evaluate_residual(&new_state) * new_state.temperature;
-                    cache.get_or_insert_with_d64(v, &computation) * U::reference_energy()
+                    cache.get_or_insert_with_d64(v, computation) * U::reference_energy()
                         / v.reference()
                 }
                 PartialDerivative::Second(v1, v2) => {
                     let new_state = self.derive2(v1, v2);
                     let computation =
                         || self.eos.evaluate_residual(&new_state) * new_state.temperature;
-                    cache.get_or_insert_with_hd64(v1, v2, &computation) * U::reference_energy()
+                    cache.get_or_insert_with_hd64(v1, v2, computation) * U::reference_energy()
                         / (v1.reference() * v2.reference())
                 }
                 PartialDerivative::Third(v) => {
                     let new_state = self.derive3(v);
                     let computation =
                         || self.eos.evaluate_residual(&new_state) * new_state.temperature;
-                    cache.get_or_insert_with_hd364(v, &computation) * U::reference_energy()
+                    cache.get_or_insert_with_hd364(v, computation) * U::reference_energy()
                         / (v.reference() * v.reference() * v.reference())
                 }
+                PartialDerivative::ThirdMixed(v1, v2, v3) => {
+                    let new_state = self.derive3_mixed(v1, v2, v3);
+                    let computation =
+                        || self.eos.evaluate_residual(&new_state) * new_state.temperature;
+                    cache.get_or_insert_with_hd3d64(v1, v2, v3, computation) * U::reference_energy()
+                        / (v1.reference() * v2.reference() * v3.reference())
+                }
             }),
         };
 
@@ -127,6 +143,14 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
                         * U::reference_energy()
                         / (v.reference() * v.reference() * v.reference())
                 }
+                PartialDerivative::ThirdMixed(v1, v2, v3) => {
+                    let new_state = self.derive3_mixed(v1, v2, v3);
+                    (self.eos.ideal_gas().evaluate(&new_state) * new_state.temperature).eps1eps2
+                        [(0, 0)]
+                        .eps[0]
+                        * U::reference_energy()
+                        / (v1.reference() * v2.reference() * v3.reference())
+                }
             }),
         };
 
@@ -229,6 +253,32 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         -self.get_or_compute_derivative(PartialDerivative::Third(DT), evaluate)
     }
 
+    fn d2s_dtdv_(&self, evaluate: Evaluate) -> QuantityScalar<U> {
+        -self.get_or_compute_derivative(PartialDerivative::ThirdMixed(DT, DT, DV), evaluate)
+    }
+
+    fn d2p_dtdv_(&self, evaluate: Evaluate) -> QuantityScalar<U> {
+        -self.get_or_compute_derivative(PartialDerivative::ThirdMixed(DV, DT, DV), evaluate)
+    }
+
+    fn d2s_dtdni_(&self, evaluate: Evaluate) -> QuantityArray1<U> {
+        QuantityArray::from_shape_fn(self.eos.components(), |i| {
+            -self.get_or_compute_derivative(PartialDerivative::ThirdMixed(DT, DT, DN(i)), evaluate)
+        })
+    }
+
+    fn d2p_dtdni_(&self, evaluate: Evaluate) -> QuantityArray1<U> {
+        QuantityArray::from_shape_fn(self.eos.components(), |i| {
+            -self.get_or_compute_derivative(PartialDerivative::ThirdMixed(DV, DT, DN(i)), evaluate)
+        })
+    }
+
+    fn d2p_dvdni_(&self, evaluate: Evaluate) -> QuantityArray1<U> {
+        QuantityArray::from_shape_fn(self.eos.components(), |i| {
+            -self.get_or_compute_derivative(PartialDerivative::ThirdMixed(DV, DV, DN(i)), evaluate)
+        })
+    }
+
     /// Pressure: $p=-\left(\frac{\partial A}{\partial V}\right)_{T,N_i}$
     pub fn pressure(&self, contributions: Contributions) -> QuantityScalar<U> {
         self.evaluate_property(Self::pressure_, contributions, true)
@@ -283,6 +333,21 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         self.evaluate_property(Self::chemical_potential_, contributions, true)
     }
 
+    /// Residual chemical potential w.r.t. an ideal gas mixture at the same temperature, pressure and composition: $\mu_i^\mathrm{res}(T,p)=RT\ln\varphi_i$
+    ///
+    /// Identical to the chemical potential evaluated with [Contributions::ResidualNpt].
+    pub fn chemical_potential_residual_tp(&self) -> QuantityArray1<U> {
+        self.chemical_potential(Contributions::ResidualNpt)
+    }
+
+    /// Chemical potential of the ideal gas mixture at the same temperature, pressure and composition: $\mu_i^\mathrm{ig}(T,p)=\mu_i-\mu_i^\mathrm{res}(T,p)$
+    ///
+    /// In contrast to [Contributions::IdealGas], the ideal gas is not evaluated at
+    /// the volume of the state but at the volume $V^\mathrm{ig}=NRT/p$.
+    pub fn ideal_chemical_potential(&self) -> QuantityArray1<U> {
+        self.chemical_potential(Contributions::Total) - self.chemical_potential_residual_tp()
+    }
+
     /// Partial derivative of chemical potential w.r.t. temperature: $\left(\frac{\partial\mu_i}{\partial T}\right)_{V,N_i}$
     pub fn dmu_dt(&self, contributions: Contributions) -> QuantityArray1<U> {
         self.evaluate_property(Self::dmu_dt_, contributions, true)
@@ -472,6 +537,52 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
         s * self.temperature + mu
     }
 
+    /// Partial molar isobaric heat capacity: $c_{p,i}=\left(\frac{\partial C_p}{\partial N_i}\right)_{T,p,N_j}=\left(\frac{\partial h_i}{\partial T}\right)_{p,N_j}$
+    pub fn partial_molar_heat_capacity(&self, contributions: Contributions) -> QuantityArray1<U> {
+        let func = |s: &Self, evaluate: Evaluate| {
+            // C_p = T (dS/dT - (dp/dT)^2 / (dp/dV)) and its derivatives at constant T
+            let (dp_dt, dp_dv) = (s.dp_dt_(evaluate), s.dp_dv_(evaluate));
+            let dcp = |ds_dt_dx, dp_dt_dx, dp_dv_dx| {
+                s.temperature
+                    * (ds_dt_dx - 2.0 * dp_dt * dp_dt_dx / dp_dv
+                        + dp_dt * dp_dt * dp_dv_dx / (dp_dv * dp_dv))
+            };
+            let dcp_dv = dcp(
+                s.d2s_dtdv_(evaluate),
+                s.d2p_dtdv_(evaluate),
+                s.d2p_dv2_(evaluate),
+            );
+            let molar_volume = -s.dp_dni_(evaluate) / dp_dv;
+            let d2s_dtdni = s.d2s_dtdni_(evaluate);
+            let d2p_dtdni = s.d2p_dtdni_(evaluate);
+            let d2p_dvdni = s.d2p_dvdni_(evaluate);
+            QuantityArray::from_shape_fn(s.eos.components(), |i| {
+                dcp(d2s_dtdni.get(i), d2p_dtdni.get(i), d2p_dvdni.get(i))
+                    + dcp_dv * molar_volume.get(i)
+            })
+        };
+        self.evaluate_property(func, contributions, false)
+    }
+
+    /// Pressure derivative of the partial molar enthalpy: $\left(\frac{\partial h_i}{\partial p}\right)_{T,N_j}=v_i-T\left(\frac{\partial v_i}{\partial T}\right)_{p,N_j}$
+    pub fn dh_i_dp(&self, contributions: Contributions) -> QuantityArray1<U> {
+        let func = |s: &Self, evaluate: Evaluate| {
+            let (dp_dt, dp_dv) = (s.dp_dt_(evaluate), s.dp_dv_(evaluate));
+            let (d2p_dtdv, d2p_dv2) = (s.d2p_dtdv_(evaluate), s.d2p_dv2_(evaluate));
+            let dp_dni = s.dp_dni_(evaluate);
+            let d2p_dtdni = s.d2p_dtdni_(evaluate);
+            let d2p_dvdni = s.d2p_dvdni_(evaluate);
+            let dv_dt = -dp_dt / dp_dv;
+            QuantityArray::from_shape_fn(s.eos.components(), |i| {
+                let dp_dni = dp_dni.get(i);
+                let dvi_dt = -d2p_dtdni.get(i) / dp_dv + dp_dni * d2p_dtdv / (dp_dv * dp_dv);
+                let dvi_dv = -d2p_dvdni.get(i) / dp_dv + dp_dni * d2p_dv2 / (dp_dv * dp_dv);
+                -dp_dni / dp_dv - s.temperature * (dvi_dt + dvi_dv * dv_dt)
+            })
+        };
+        self.evaluate_property(func, contributions, false)
+    }
+
     /// Joule Thomson coefficient: $\mu_{JT}=\left(\frac{\partial T}{\partial p}\right)_{H,N_i}$
     pub fn joule_thomson(&self) -> QuantityScalar<U> {
         let c = Contributions::Total;
@@ -628,17 +739,6 @@ impl<U: EosUnit, E: EquationOfState> State<U, E> {
             (-self.volume * dp_dv / self.density),
         )
     }
-
-    // This function is designed specifically for use in spinodal iterations
-    pub(crate) fn d2pdrho2(&self) -> (QuantityScalar<U>, QuantityScalar<U>, QuantityScalar<U>) {
-        let d2p_dv2 = self.d2p_dv2(Contributions::Total);
-        let dp_dv = self.dp_dv(Contributions::Total);
-        (
-            self.pressure(Contributions::Total),
-            (-self.volume * dp_dv / self.density),
-            (self.volume / (self.density * self.density) * (2.0 * dp_dv + self.volume * d2p_dv2)),
-        )
-    }
 }
 
 /// # Transport properties
@@ -754,6 +854,15 @@ impl<'a, U, E> IntoIterator for StateVec<'a, U, E> {
     }
 }
 
+impl<'a, 'b, U, E> IntoIterator for &'b StateVec<'a, U, E> {
+    type Item = &'a State<U, E>;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'b, &'a State<U, E>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().copied()
+    }
+}
+
 impl<'a, U, E> Deref for StateVec<'a, U, E> {
     type Target = Vec<&'a State<U, E>>;
 
@@ -762,6 +871,50 @@ impl<'a, U, E> Deref for StateVec<'a, U, E> {
     }
 }
 
+impl<'a, U, E> Index<usize> for StateVec<'a, U, E> {
+    type Output = State<U, E>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.0[index]
+    }
+}
+
+impl<'a, U, E> Clone for StateVec<'a, U, E> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<'a, U, E> StateVec<'a, U, E> {
+    /// Return the states in the given range.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        Self(self.0[range].to_vec())
+    }
+
+    /// Return all states for which the predicate is true.
+    pub fn filter<P: FnMut(&State<U, E>) -> bool>(&self, mut predicate: P) -> Self {
+        self.into_iter().filter(|s| predicate(s)).collect()
+    }
+
+    /// Concatenate several lists of states.
+    pub fn concat(state_vecs: &[Self]) -> Self {
+        state_vecs.iter().flatten().collect()
+    }
+}
+
+impl<'a, U: EosUnit, E: EquationOfState> StateVec<'a, U, E> {
+    /// Collect the vapor states of a list of phase equilibria.
+    pub fn vapor(phase_equilibria: &'a [PhaseEquilibrium<U, E, 2>]) -> Self {
+        phase_equilibria.iter().map(|vle| vle.vapor()).collect()
+    }
+
+    /// Collect the liquid states of a list of phase equilibria.
+    pub fn liquid(phase_equilibria: &'a [PhaseEquilibrium<U, E, 2>]) -> Self {
+        phase_equilibria.iter().map(|vle| vle.liquid()).collect()
+    }
+}
+
 impl<'a, U: EosUnit, E: EquationOfState> StateVec<'a, U, E> {
     pub fn temperature(&self) -> QuantityArray1<U> {
         QuantityArray1::from_shape_fn(self.0.len(), |i| self.0[i].temperature)
@@ -823,3 +976,80 @@ impl<'a, U: EosUnit, E: EquationOfState + MolarWeight<U>> StateVec<'a, U, E> {
         })
     }
 }
+
+/// Basis of the properties in [StateVec::to_basis].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "python", pyo3::pyclass)]
+pub enum Basis {
+    /// Molar densities, mole fractions and molar properties
+    Molar,
+    /// Mass densities, mass fractions and specific properties
+    Mass,
+    /// Molar properties in reduced units, i.e., divided by the
+    /// reference values of the unit system
+    Reduced,
+}
+
+/// Properties of a list of states with respect to a common [Basis].
+#[derive(Clone)]
+pub struct StateVecProperties<U> {
+    /// The basis of the properties
+    pub basis: Basis,
+    /// Temperature
+    pub temperature: QuantityArray1<U>,
+    /// Pressure
+    pub pressure: QuantityArray1<U>,
+    /// Molar or mass density
+    pub density: QuantityArray1<U>,
+    /// Mole or mass fractions for every state
+    pub composition: Array2<f64>,
+    /// Molar or specific enthalpy
+    pub enthalpy: QuantityArray1<U>,
+    /// Molar or specific entropy
+    pub entropy: QuantityArray1<U>,
+}
+
+impl<'a, U: EosUnit, E: EquationOfState + MolarWeight<U>> StateVec<'a, U, E> {
+    /// Calculate temperature, pressure, density, composition, enthalpy
+    /// and entropy of all states with respect to the given basis.
+    pub fn to_basis(&self, basis: Basis) -> EosResult<StateVecProperties<U>> {
+        if self.is_empty() {
+            return Err(EosError::UndeterminedState(String::from(
+                "no states to convert",
+            )));
+        }
+        let properties = match basis {
+            Basis::Molar => StateVecProperties {
+                basis,
+                temperature: self.temperature(),
+                pressure: self.pressure(),
+                density: self.density(),
+                composition: self.molefracs(),
+                enthalpy: self.molar_enthalpy(),
+                entropy: self.molar_entropy(),
+            },
+            Basis::Mass => StateVecProperties {
+                basis,
+                temperature: self.temperature(),
+                pressure: self.pressure(),
+                density: self.mass_density(),
+                composition: self.massfracs(),
+                enthalpy: self.specific_enthalpy(),
+                entropy: self.specific_entropy(),
+            },
+            Basis::Reduced => {
+                let reduced = |x: QuantityArray1<U>, unit: QuantityScalar<U>| x / unit;
+                StateVecProperties {
+                    basis,
+                    temperature: reduced(self.temperature(), U::reference_temperature()),
+                    pressure: reduced(self.pressure(), U::reference_pressure()),
+                    density: reduced(self.density(), U::reference_density()),
+                    composition: self.molefracs(),
+                    enthalpy: reduced(self.molar_enthalpy(), U::reference_molar_energy()),
+                    entropy: reduced(self.molar_entropy(), U::reference_molar_entropy()),
+                }
+            }
+        };
+        Ok(properties)
+    }
+}
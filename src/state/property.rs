@@ -0,0 +1,386 @@
+use super::{Contributions, State};
+use crate::equation_of_state::{EquationOfState, MolarWeight};
+use crate::errors::{EosError, EosResult};
+use crate::EosUnit;
+use quantity::QuantityScalar;
+use std::fmt;
+use std::str::FromStr;
+
+/// Scalar properties of a [State] that can be requested by name.
+///
+/// The names used in [FromStr] and [Display](fmt::Display) are the names
+/// of the corresponding methods of [State], e.g., `"molar_enthalpy"`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Property {
+    Temperature,
+    Volume,
+    Density,
+    TotalMoles,
+    Pressure,
+    IsochoricHeatCapacity,
+    IsobaricHeatCapacity,
+    Entropy,
+    Enthalpy,
+    HelmholtzEnergy,
+    InternalEnergy,
+    GibbsEnergy,
+    MolarEntropy,
+    MolarEnthalpy,
+    MolarHelmholtzEnergy,
+    MolarInternalEnergy,
+    MolarGibbsEnergy,
+    JouleThomson,
+    IsentropicCompressibility,
+    IsothermalCompressibility,
+    TotalMolarWeight,
+    MassDensity,
+    SpecificEntropy,
+    SpecificEnthalpy,
+    SpecificHelmholtzEnergy,
+    SpecificInternalEnergy,
+    SpecificGibbsEnergy,
+    SpeedOfSound,
+}
+
+impl Property {
+    /// All available properties.
+    pub const ALL: [Self; 28] = [
+        Self::Temperature,
+        Self::Volume,
+        Self::Density,
+        Self::TotalMoles,
+        Self::Pressure,
+        Self::IsochoricHeatCapacity,
+        Self::IsobaricHeatCapacity,
+        Self::Entropy,
+        Self::Enthalpy,
+        Self::HelmholtzEnergy,
+        Self::InternalEnergy,
+        Self::GibbsEnergy,
+        Self::MolarEntropy,
+        Self::MolarEnthalpy,
+        Self::MolarHelmholtzEnergy,
+        Self::MolarInternalEnergy,
+        Self::MolarGibbsEnergy,
+        Self::JouleThomson,
+        Self::IsentropicCompressibility,
+        Self::IsothermalCompressibility,
+        Self::TotalMolarWeight,
+        Self::MassDensity,
+        Self::SpecificEntropy,
+        Self::SpecificEnthalpy,
+        Self::SpecificHelmholtzEnergy,
+        Self::SpecificInternalEnergy,
+        Self::SpecificGibbsEnergy,
+        Self::SpeedOfSound,
+    ];
+
+    /// The name of the property, i.e., the name of the corresponding method of [State].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Temperature => "temperature",
+            Self::Volume => "volume",
+            Self::Density => "density",
+            Self::TotalMoles => "total_moles",
+            Self::Pressure => "pressure",
+            Self::IsochoricHeatCapacity => "c_v",
+            Self::IsobaricHeatCapacity => "c_p",
+            Self::Entropy => "entropy",
+            Self::Enthalpy => "enthalpy",
+            Self::HelmholtzEnergy => "helmholtz_energy",
+            Self::InternalEnergy => "internal_energy",
+            Self::GibbsEnergy => "gibbs_energy",
+            Self::MolarEntropy => "molar_entropy",
+            Self::MolarEnthalpy => "molar_enthalpy",
+            Self::MolarHelmholtzEnergy => "molar_helmholtz_energy",
+            Self::MolarInternalEnergy => "molar_internal_energy",
+            Self::MolarGibbsEnergy => "molar_gibbs_energy",
+            Self::JouleThomson => "joule_thomson",
+            Self::IsentropicCompressibility => "isentropic_compressibility",
+            Self::IsothermalCompressibility => "isothermal_compressibility",
+            Self::TotalMolarWeight => "total_molar_weight",
+            Self::MassDensity => "mass_density",
+            Self::SpecificEntropy => "specific_entropy",
+            Self::SpecificEnthalpy => "specific_enthalpy",
+            Self::SpecificHelmholtzEnergy => "specific_helmholtz_energy",
+            Self::SpecificInternalEnergy => "specific_internal_energy",
+            Self::SpecificGibbsEnergy => "specific_gibbs_energy",
+            Self::SpeedOfSound => "speed_of_sound",
+        }
+    }
+}
+
+impl Property {
+    /// Whether the property depends on the molar weights of the components,
+    /// i.e., whether it is only available for equations of state that
+    /// implement [MolarWeight].
+    pub fn is_mass_based(&self) -> bool {
+        matches!(
+            self,
+            Self::TotalMolarWeight
+                | Self::MassDensity
+                | Self::SpecificEntropy
+                | Self::SpecificEnthalpy
+                | Self::SpecificHelmholtzEnergy
+                | Self::SpecificInternalEnergy
+                | Self::SpecificGibbsEnergy
+                | Self::SpeedOfSound
+        )
+    }
+
+    /// Whether the property is extensive, i.e., proportional to the size of the system.
+    pub fn is_extensive(&self) -> bool {
+        matches!(
+            self,
+            Self::Volume
+                | Self::TotalMoles
+                | Self::Entropy
+                | Self::Enthalpy
+                | Self::HelmholtzEnergy
+                | Self::InternalEnergy
+                | Self::GibbsEnergy
+        )
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Property {
+    type Err = EosError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|p| p.name() == s)
+            .copied()
+            .ok_or_else(|| EosError::UnknownProperty(s.to_owned()))
+    }
+}
+
+impl<U: EosUnit, E: EquationOfState> State<U, E> {
+    /// Return the value of a property that does not depend on the molar weights.
+    ///
+    /// Returns [None] for mass based properties (see [Property::is_mass_based]).
+    fn get_molar_property(
+        &self,
+        property: Property,
+        contributions: Contributions,
+    ) -> Option<QuantityScalar<U>> {
+        let c = contributions;
+        Some(match property {
+            Property::Temperature => self.temperature,
+            Property::Volume => self.volume,
+            Property::Density => self.density,
+            Property::TotalMoles => self.total_moles,
+            Property::Pressure => self.pressure(c),
+            Property::IsochoricHeatCapacity => self.c_v(c),
+            Property::IsobaricHeatCapacity => self.c_p(c),
+            Property::Entropy => self.entropy(c),
+            Property::Enthalpy => self.enthalpy(c),
+            Property::HelmholtzEnergy => self.helmholtz_energy(c),
+            Property::InternalEnergy => self.internal_energy(c),
+            Property::GibbsEnergy => self.gibbs_energy(c),
+            Property::MolarEntropy => self.molar_entropy(c),
+            Property::MolarEnthalpy => self.molar_enthalpy(c),
+            Property::MolarHelmholtzEnergy => self.molar_helmholtz_energy(c),
+            Property::MolarInternalEnergy => self.molar_internal_energy(c),
+            Property::MolarGibbsEnergy => self.molar_gibbs_energy(c),
+            Property::JouleThomson => self.joule_thomson(),
+            Property::IsentropicCompressibility => self.isentropic_compressibility(),
+            Property::IsothermalCompressibility => self.isothermal_compressibility(),
+            _ => return None,
+        })
+    }
+
+    /// Return the value of a property that does not depend on the
+    /// molar weights, which is available for every equation of state.
+    ///
+    /// Mass based properties (see [Property::is_mass_based]) result in an
+    /// [EosError::UnsupportedProperty]; they are available in [State::get]
+    /// for equations of state that implement [MolarWeight].
+    /// `contributions` is ignored for properties that do
+    /// not distinguish between contributions.
+    pub fn get_molar(
+        &self,
+        property: Property,
+        contributions: Contributions,
+    ) -> EosResult<QuantityScalar<U>> {
+        self.get_molar_property(property, contributions)
+            .ok_or_else(|| {
+                EosError::UnsupportedProperty(
+                    property.to_string(),
+                    String::from("State::get_molar"),
+                )
+            })
+    }
+
+    /// Order of magnitude of a property that does not depend on the molar
+    /// weights, derived from the ideal gas at the temperature, volume and
+    /// amount of substance of the state.
+    fn characteristic_value(&self, property: Property) -> Option<QuantityScalar<U>> {
+        let n = self.total_moles;
+        let rt = U::gas_constant() * self.temperature;
+        let p = self.density * rt;
+        Some(match property {
+            Property::Temperature => self.temperature,
+            Property::Volume => self.volume,
+            Property::Density => self.density,
+            Property::TotalMoles => n,
+            Property::Pressure => p,
+            Property::Entropy => n * U::gas_constant(),
+            Property::Enthalpy
+            | Property::HelmholtzEnergy
+            | Property::InternalEnergy
+            | Property::GibbsEnergy => n * rt,
+            Property::IsochoricHeatCapacity
+            | Property::IsobaricHeatCapacity
+            | Property::MolarEntropy => U::gas_constant(),
+            Property::MolarEnthalpy
+            | Property::MolarHelmholtzEnergy
+            | Property::MolarInternalEnergy
+            | Property::MolarGibbsEnergy => rt,
+            Property::JouleThomson => self.temperature / p,
+            Property::IsentropicCompressibility | Property::IsothermalCompressibility => 1.0 / p,
+            _ => return None,
+        })
+    }
+
+    /// Check the homogeneity of all properties that do not depend on
+    /// the molar weights by scaling the system size.
+    ///
+    /// The volume and the amount of substance are multiplied by `factor`.
+    /// Extensive properties have to scale by the same factor, intensive
+    /// properties have to remain unchanged. The deviation of a property is
+    /// measured relative to its value, but at least relative to a
+    /// characteristic value of the ideal gas (e.g. $NRT$ for energies),
+    /// so that properties close to zero do not lead to spurious violations.
+    /// Returns every property with a deviation larger than `tolerance`
+    /// together with that deviation. Useful to find errors in the
+    /// implementation of new models.
+    pub fn homogeneity_violations(
+        &self,
+        factor: f64,
+        tolerance: f64,
+    ) -> EosResult<Vec<(Property, f64)>> {
+        let scaled = State::new_nvt(
+            &self.eos,
+            self.temperature,
+            self.volume * factor,
+            &(&self.moles * factor),
+        )?;
+        let mut violations = Vec::new();
+        for property in Property::ALL {
+            let (value, mut scaled_value, scale) = match (
+                self.get_molar_property(property, Contributions::Total),
+                scaled.get_molar_property(property, Contributions::Total),
+                self.characteristic_value(property),
+            ) {
+                (Some(value), Some(scaled_value), Some(scale)) => (value, scaled_value, scale),
+                _ => continue,
+            };
+            if property.is_extensive() {
+                scaled_value = scaled_value / factor;
+            }
+            let deviation = ((scaled_value - value) / value.abs().max(scale)?)
+                .into_value()?
+                .abs();
+            if deviation > tolerance || deviation.is_nan() {
+                violations.push((property, deviation));
+            }
+        }
+        Ok(violations)
+    }
+}
+
+impl<U: EosUnit, E: EquationOfState + MolarWeight<U>> State<U, E> {
+    /// Return the value of a property.
+    ///
+    /// `contributions` is ignored for properties that do
+    /// not distinguish between contributions.
+    pub fn get(&self, property: Property, contributions: Contributions) -> QuantityScalar<U> {
+        let c = contributions;
+        match property {
+            Property::TotalMolarWeight => self.total_molar_weight(),
+            Property::MassDensity => self.mass_density(),
+            Property::SpecificEntropy => self.specific_entropy(c),
+            Property::SpecificEnthalpy => self.specific_enthalpy(c),
+            Property::SpecificHelmholtzEnergy => self.specific_helmholtz_energy(c),
+            Property::SpecificInternalEnergy => self.specific_internal_energy(c),
+            Property::SpecificGibbsEnergy => self.specific_gibbs_energy(c),
+            Property::SpeedOfSound => self.speed_of_sound(),
+            _ => self
+                .get_molar_property(property, c)
+                .expect("all other properties are independent of the molar weights"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::{propane, propane_butane};
+    use crate::state::DensityInitialization;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    #[test]
+    fn test_property_from_str() -> EosResult<()> {
+        for p in Property::ALL {
+            assert_eq!(p.to_string().parse::<Property>()?, p);
+        }
+        assert!("enthalpy_of_vaporization".parse::<Property>().is_err());
+
+        let eos = propane();
+        let state = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            BAR,
+            &(arr1(&[1.0]) * MOL),
+            DensityInitialization::None,
+        )?;
+        assert_eq!(
+            state.get("molar_enthalpy".parse()?, Contributions::Total),
+            state.molar_enthalpy(Contributions::Total)
+        );
+        assert_eq!(
+            state.get(Property::IsothermalCompressibility, Contributions::Total),
+            state.isothermal_compressibility()
+        );
+        assert_eq!(
+            state.get_molar(Property::Pressure, Contributions::Total)?,
+            state.pressure(Contributions::Total)
+        );
+        assert!(state
+            .get_molar(Property::MassDensity, Contributions::Total)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_homogeneity() -> EosResult<()> {
+        let eos = propane_butane();
+        let state = State::new_nvt(
+            &eos,
+            300.0 * KELVIN,
+            10.0 * METER.powi(3),
+            &(arr1(&[3.0, 2.0]) * MOL),
+        )?;
+        assert!(state.homogeneity_violations(3.0, 1e-10)?.is_empty());
+
+        // the enthalpy and the Joule-Thomson coefficient of the (almost) ideal
+        // gas vanish at the reference temperature
+        let eos = propane();
+        let state = State::new_nvt(
+            &eos,
+            298.15 * KELVIN,
+            1e6 * METER.powi(3),
+            &(arr1(&[1.0]) * MOL),
+        )?;
+        assert!(state.homogeneity_violations(3.0, 1e-10)?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+use super::{Contributions, DensityInitialization, State, StatePath};
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::phase_equilibria::{SolverOptions, Verbosity};
+use crate::EosUnit;
+use quantity::{QuantityArray1, QuantityScalar};
+use std::rc::Rc;
+
+const MAX_ITER_WIDOM_LINE: usize = 50;
+const TOL_WIDOM_LINE: f64 = 1e-8;
+const GRID_POINTS_WIDOM_LINE: usize = 100;
+const MAX_REDUCED_TEMPERATURE: f64 = 2.0;
+
+/// Response functions that can be used to define a Widom line.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "python", pyo3::pyclass)]
+pub enum ResponseFunction {
+    /// Molar isobaric heat capacity $c_p$
+    IsobaricHeatCapacity,
+    /// Molar isochoric heat capacity $c_v$
+    IsochoricHeatCapacity,
+    /// Isothermal compressibility $\kappa_T$
+    IsothermalCompressibility,
+}
+
+impl ResponseFunction {
+    fn evaluate<U: EosUnit, E: EquationOfState>(&self, state: &State<U, E>) -> EosResult<f64> {
+        Ok(match self {
+            Self::IsobaricHeatCapacity => state
+                .c_p(Contributions::Total)
+                .to_reduced(U::reference_molar_entropy())?,
+            Self::IsochoricHeatCapacity => state
+                .c_v(Contributions::Total)
+                .to_reduced(U::reference_molar_entropy())?,
+            Self::IsothermalCompressibility => state
+                .isothermal_compressibility()
+                .to_reduced(U::reference_pressure().powi(-1))?,
+        })
+    }
+}
+
+/// # Supercritical states
+impl<U: EosUnit, E: EquationOfState> State<U, E> {
+    /// Calculate the Widom line, i.e., the locus of the maxima of a response
+    /// function along `npoints` supercritical isobars for equidistant
+    /// pressures between `min_pressure` and `max_pressure`.
+    ///
+    /// For every pressure, the temperature at which the response function is
+    /// maximal is first bracketed on a grid between the critical temperature and
+    /// twice the critical temperature and afterwards refined using a golden-section
+    /// search. The `options` are used for the critical point and for the refinement.
+    pub fn widom_line(
+        eos: &Rc<E>,
+        min_pressure: QuantityScalar<U>,
+        max_pressure: QuantityScalar<U>,
+        npoints: usize,
+        moles: Option<&QuantityArray1<U>>,
+        response: ResponseFunction,
+        options: SolverOptions,
+    ) -> EosResult<StatePath<U, E>>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let cp = Self::critical_point(eos, moles, None, options.clone())?;
+        let pc = cp.pressure(Contributions::Total);
+        let tc = cp.temperature;
+        let mut density = cp.density;
+        let pressure = QuantityArray1::linspace(min_pressure, max_pressure, npoints)?;
+        let mut states = Vec::with_capacity(npoints);
+        for p in pressure.into_iter() {
+            if p <= pc {
+                return Err(EosError::InvalidState(
+                    String::from("widom_line"),
+                    String::from("pressure"),
+                    p.to_reduced(U::reference_pressure())?,
+                ));
+            }
+            let state =
+                Self::widom_point(eos, p, &cp.moles, tc, density, response, options.clone())?;
+            density = state.density;
+            states.push(state);
+        }
+        Ok(StatePath { states })
+    }
+
+    fn widom_point(
+        eos: &Rc<E>,
+        pressure: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+        critical_temperature: QuantityScalar<U>,
+        initial_density: QuantityScalar<U>,
+        response: ResponseFunction,
+        options: SolverOptions,
+    ) -> EosResult<Self>
+    where
+        QuantityScalar<U>: std::fmt::Display,
+    {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_WIDOM_LINE, TOL_WIDOM_LINE);
+        let tc = critical_temperature.to_reduced(U::reference_temperature())?;
+
+        // evaluate the response function at a given (reduced) temperature
+        let mut density = initial_density;
+        let mut f = |t: f64| -> EosResult<(f64, Self)> {
+            let state = State::new_npt(
+                eos,
+                t * U::reference_temperature(),
+                pressure,
+                moles,
+                DensityInitialization::InitialDensity(density),
+            )?;
+            density = state.density;
+            Ok((response.evaluate(&state)?, state))
+        };
+
+        // bracket the maximum on an equidistant temperature grid
+        let dt = (MAX_REDUCED_TEMPERATURE - 1.0) * tc / (GRID_POINTS_WIDOM_LINE - 1) as f64;
+        let mut i_max = 0;
+        let mut f_max = f64::NEG_INFINITY;
+        for i in 0..GRID_POINTS_WIDOM_LINE {
+            let (fx, _) = f(tc + i as f64 * dt)?;
+            if fx > f_max {
+                i_max = i;
+                f_max = fx;
+            }
+        }
+        if i_max == GRID_POINTS_WIDOM_LINE - 1 {
+            return Err(EosError::NotConverged(format!(
+                "widom_line: no maximum found at p = {}",
+                pressure
+            )));
+        }
+        let mut a = tc + (i_max.max(1) - 1) as f64 * dt;
+        let mut b = tc + (i_max + 1) as f64 * dt;
+
+        // refine the maximum using a golden-section search
+        let r = 0.5 * (5f64.sqrt() - 1.0);
+        let mut c = b - r * (b - a);
+        let mut d = a + r * (b - a);
+        let (mut fc, _) = f(c)?;
+        let (mut fd, _) = f(d)?;
+        log_iter!(verbosity, " iter |    residual    |   temperature   ");
+        log_iter!(verbosity, "{:-<40}", "");
+        for i in 1..=max_iter {
+            if fc > fd {
+                b = d;
+                d = c;
+                fd = fc;
+                c = b - r * (b - a);
+                fc = f(c)?.0;
+            } else {
+                a = c;
+                c = d;
+                fc = fd;
+                d = a + r * (b - a);
+                fd = f(d)?.0;
+            }
+            let res = (b - a) / tc;
+            log_iter!(
+                verbosity,
+                " {:4} | {:14.8e} | {:13.8}",
+                i,
+                res,
+                0.5 * (a + b) * U::reference_temperature()
+            );
+            if res < tol {
+                log_result!(
+                    verbosity,
+                    "Widom line calculation converged in {} step(s)\n",
+                    i
+                );
+                return Ok(f(0.5 * (a + b))?.1);
+            }
+        }
+        Err(EosError::NotConverged(String::from("widom_line")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::fixtures::propane;
+    use quantity::si::*;
+
+    #[test]
+    fn test_widom_line() -> EosResult<()> {
+        let eos = propane();
+        let widom_line = State::widom_line(
+            &eos,
+            50.0 * BAR,
+            60.0 * BAR,
+            2,
+            None,
+            ResponseFunction::IsobaricHeatCapacity,
+            SolverOptions::default(),
+        )?;
+        let states = widom_line.states;
+        for (s, p) in states.iter().zip([50.0, 60.0]) {
+            let c_p = s.c_p(Contributions::Total);
+            for t in [s.temperature - 0.1 * KELVIN, s.temperature + 0.1 * KELVIN] {
+                let s2 = State::new_npt(
+                    &eos,
+                    t,
+                    p * BAR,
+                    &s.moles,
+                    DensityInitialization::InitialDensity(s.density),
+                )?;
+                assert!(s2.c_p(Contributions::Total) < c_p);
+            }
+        }
+        assert!(states[1].temperature > states[0].temperature);
+        Ok(())
+    }
+}
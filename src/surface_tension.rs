@@ -0,0 +1,356 @@
+//! Surface tensions of planar interfaces from the square gradient theory.
+//!
+//! In the square gradient theory (also density gradient theory), the
+//! Helmholtz energy of an inhomogeneous fluid is approximated by the
+//! Helmholtz energy density of the homogeneous fluid at the local densities
+//! and a correction that is quadratic in the density gradients
+//! $$F=\int\left(f(\mathbf{\rho})+\frac{1}{2}\sum_{ij}c_{ij}\nabla\rho_i\cdot\nabla\rho_j\right)\mathrm{d}V.$$
+//! The influence parameters of mixtures are calculated with the geometric mean
+//! $c_{ij}=\sqrt{c_ic_j}$ from the influence parameters of the pure substances,
+//! that are provided by equations of state that implement [InfluenceParameter].
+use crate::equation_of_state::EquationOfState;
+use crate::errors::{EosError, EosResult};
+use crate::phase_equilibria::{PhaseEquilibrium, SolverOptions, Verbosity};
+use crate::state::{Contributions, State};
+use crate::EosUnit;
+use ndarray::{Array1, Array2};
+use num_dual::linalg::{norm, LU};
+use quantity::{QuantityArray1, QuantityArray2, QuantityScalar};
+use std::rc::Rc;
+
+const MAX_ITER_SGT: usize = 50;
+const TOL_SGT: f64 = 1e-10;
+const DEFAULT_GRID_POINTS: usize = 200;
+const MAX_STEP_HALVINGS: usize = 30;
+
+/// Influence parameters of the square gradient theory.
+pub trait InfluenceParameter<U: EosUnit> {
+    /// Return the influence parameters of all components.
+    fn influence_parameter(&self) -> EosResult<QuantityArray1<U>>;
+}
+
+/// A planar interface between the two phases of a [PhaseEquilibrium]
+/// calculated with the square gradient theory.
+///
+/// For the geometric mean mixing rule, the partial densities in the interface
+/// only depend on the path coordinate $\xi=\sum_i\sqrt{c_i}\rho_i$ and are
+/// determined from
+/// $$\frac{\mu_i(\mathbf{\rho})-\mu_i^\mathrm{bulk}}{\sqrt{c_i}}=\frac{\mu_j(\mathbf{\rho})-\mu_j^\mathrm{bulk}}{\sqrt{c_j}}$$
+/// for all pairs of components. The surface tension is obtained by integrating
+/// over the path between the bulk phases
+/// $$\gamma=\int_{\xi^\mathrm{V}}^{\xi^\mathrm{L}}\sqrt{2\Delta\omega(\xi)}\,\mathrm{d}\xi$$
+/// with the grand potential density $\Delta\omega=f(\mathbf{\rho})-\sum_i\rho_i\mu_i^\mathrm{bulk}+p^\mathrm{bulk}$.
+///
+/// The path coordinate is discretized on an equidistant grid and has to
+/// change monotonically between the bulk phases, which is the case for
+/// most (but not all) mixtures.
+pub struct PlanarInterface<U, E> {
+    vle: PhaseEquilibrium<U, E, 2>,
+    partial_density: QuantityArray2<U>,
+    grand_potential_density: QuantityArray1<U>,
+    surface_tension: QuantityScalar<U>,
+}
+
+impl<U: Clone, E> Clone for PlanarInterface<U, E> {
+    fn clone(&self) -> Self {
+        Self {
+            vle: self.vle.clone(),
+            partial_density: self.partial_density.clone(),
+            grand_potential_density: self.grand_potential_density.clone(),
+            surface_tension: self.surface_tension.clone(),
+        }
+    }
+}
+
+impl<U: EosUnit, E: EquationOfState + InfluenceParameter<U>> PlanarInterface<U, E> {
+    /// Calculate the planar interface between the two phases of `vle`.
+    ///
+    /// The path between the bulk phases is discretized with `n_grid`
+    /// points (default: 200). The solver options apply to the Newton
+    /// iterations for the partial densities at every point of the path.
+    pub fn from_phase_equilibrium(
+        vle: &PhaseEquilibrium<U, E, 2>,
+        n_grid: Option<usize>,
+        options: SolverOptions,
+    ) -> EosResult<Self> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_SGT, TOL_SGT);
+        let n_grid = n_grid.unwrap_or(DEFAULT_GRID_POINTS);
+        if n_grid < 3 {
+            return Err(EosError::InvalidState(
+                String::from("PlanarInterface"),
+                String::from("number of grid points"),
+                n_grid as f64,
+            ));
+        }
+
+        let eos = &vle.vapor().eos;
+        let c = eos
+            .influence_parameter()?
+            .to_reduced(U::reference_influence_parameter())?;
+        if c.len() != eos.components() {
+            return Err(EosError::IncompatibleComponents(eos.components(), c.len()));
+        }
+        if let Some(&c) = c.iter().find(|&&c| c <= 0.0) {
+            return Err(EosError::InvalidState(
+                String::from("PlanarInterface"),
+                String::from("influence parameter"),
+                c,
+            ));
+        }
+
+        let bulk = vle.bulk_conditions()?;
+        let path = InterfacePath {
+            eos: eos.clone(),
+            temperature: bulk.temperature,
+            sqrt_c: c.mapv(f64::sqrt),
+            mu_bulk: bulk.reduced_chemical_potential,
+            p_bulk: bulk.reduced_pressure,
+            mu_scale: c.fold(0.0, |a: f64, &c| a.max(c)).sqrt() / bulk.reduced_temperature,
+        };
+        let rho_v = &bulk.reduced_partial_densities[0];
+        let rho_l = &bulk.reduced_partial_densities[1];
+        let xi_v = (&path.sqrt_c * rho_v).sum();
+        let xi_l = (&path.sqrt_c * rho_l).sum();
+        let dxi = (xi_l - xi_v) / (n_grid - 1) as f64;
+
+        let mut rho = Array2::zeros((rho_v.len(), n_grid));
+        rho.column_mut(0).assign(rho_v);
+        rho.column_mut(n_grid - 1).assign(rho_l);
+        let mut omega = Array1::zeros(n_grid);
+
+        log_iter!(verbosity, " point |      xi      |      omega     | iter");
+        log_iter!(verbosity, "{:-<46}", "");
+
+        // start every iteration from an extrapolation of the previous points
+        let mut step = (rho_l - rho_v) / (n_grid - 1) as f64;
+        let mut total_iter = 0;
+        for k in 1..n_grid - 1 {
+            let xi = xi_v + k as f64 * dxi;
+            let rho_prev = rho.column(k - 1).to_owned();
+            let (rho_k, omega_k, iter) = path.solve(
+                xi,
+                xi_v.abs().max(xi_l.abs()),
+                &rho_prev + &step,
+                max_iter,
+                tol,
+            )?;
+            log_iter!(
+                verbosity,
+                " {:5} | {:12.6e} | {:14.8e} | {:4}",
+                k,
+                xi,
+                omega_k,
+                iter
+            );
+            step = &rho_k - &rho_prev;
+            rho.column_mut(k).assign(&rho_k);
+            omega[k] = omega_k;
+            total_iter += iter;
+        }
+        log_result!(
+            verbosity,
+            "Square gradient theory: {} points of the path converged in {} step(s)\n",
+            n_grid - 2,
+            total_iter
+        );
+
+        // trapezoidal rule, the integrand vanishes in the bulk phases
+        let gamma = omega.mapv(|o: f64| (2.0 * o.max(0.0)).sqrt()).sum() * dxi.abs();
+
+        Ok(Self {
+            vle: vle.clone(),
+            partial_density: rho * U::reference_density(),
+            grand_potential_density: omega * U::reference_pressure(),
+            surface_tension: gamma * U::reference_surface_tension(),
+        })
+    }
+}
+
+impl<U: Copy, E> PlanarInterface<U, E> {
+    /// The phase equilibrium between the bulk phases.
+    pub fn phase_equilibrium(&self) -> &PhaseEquilibrium<U, E, 2> {
+        &self.vle
+    }
+
+    /// The partial densities (rows) at every point of the path (columns)
+    /// from the vapor to the liquid phase.
+    pub fn partial_density(&self) -> &QuantityArray2<U> {
+        &self.partial_density
+    }
+
+    /// The grand potential density $\Delta\omega$ relative to the bulk
+    /// phases at every point of the path.
+    pub fn grand_potential_density(&self) -> &QuantityArray1<U> {
+        &self.grand_potential_density
+    }
+
+    /// The surface tension of the interface.
+    pub fn surface_tension(&self) -> QuantityScalar<U> {
+        self.surface_tension
+    }
+}
+
+/// The system of equations that determines the partial densities on the path.
+struct InterfacePath<U, E> {
+    eos: Rc<E>,
+    temperature: QuantityScalar<U>,
+    sqrt_c: Array1<f64>,
+    mu_bulk: Array1<f64>,
+    p_bulk: f64,
+    /// Scaling of the chemical potential residuals to the order of $RT$
+    mu_scale: f64,
+}
+
+impl<U: EosUnit, E: EquationOfState> InterfacePath<U, E> {
+    /// Determine the (reduced) partial densities and the grand potential density
+    /// for the path coordinate `xi` with a Newton iteration starting at `rho`.
+    fn solve(
+        &self,
+        xi: f64,
+        xi_scale: f64,
+        mut rho: Array1<f64>,
+        max_iter: usize,
+        tol: f64,
+    ) -> EosResult<(Array1<f64>, f64, usize)> {
+        let n = rho.len();
+        for i in 1..=max_iter {
+            rho.mapv_inplace(|r| r.max(0.0));
+            let state = State::new_nvt(
+                &self.eos,
+                self.temperature,
+                U::reference_volume(),
+                &(rho.clone() * U::reference_moles()),
+            )?;
+            let mu = state
+                .chemical_potential(Contributions::Total)
+                .to_reduced(U::reference_molar_energy())?;
+            let dmu = (&mu - &self.mu_bulk) / &self.sqrt_c;
+
+            // the volume is the reference volume, therefore dmu/drho = dmu/dn
+            let h = state
+                .dmu_dni(Contributions::Total)
+                .to_reduced(U::reference_molar_energy() / U::reference_moles())?;
+            let mut res = Array1::zeros(n);
+            let mut jacobian = Array2::zeros((n, n));
+            for i in 0..n - 1 {
+                res[i] = (dmu[i] - dmu[n - 1]) * self.mu_scale;
+                for j in 0..n {
+                    jacobian[(i, j)] = (h[(i, j)] / self.sqrt_c[i]
+                        - h[(n - 1, j)] / self.sqrt_c[n - 1])
+                        * self.mu_scale;
+                }
+            }
+            res[n - 1] = ((&self.sqrt_c * &rho).sum() - xi) / xi_scale;
+            jacobian.row_mut(n - 1).assign(&(&self.sqrt_c / xi_scale));
+
+            if norm(&res) < tol {
+                let f = state
+                    .helmholtz_energy(Contributions::Total)
+                    .to_reduced(U::reference_energy())?;
+                let omega = f - (&rho * &self.mu_bulk).sum() + self.p_bulk;
+                return Ok((rho, omega, i));
+            }
+
+            // damp the Newton step to keep the densities positive
+            let delta = LU::new(jacobian)?.solve(&res);
+            let mut alpha = 1.0;
+            for _ in 0..MAX_STEP_HALVINGS {
+                if (&rho - &(&delta * alpha)).iter().all(|&r| r >= 0.0) {
+                    break;
+                }
+                alpha *= 0.5;
+            }
+            rho = &rho - &(delta * alpha);
+        }
+        Err(EosError::NotConverged(String::from("PlanarInterface")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters, PengRobinsonRecord};
+    use crate::parameter::{Identifier, Parameter, PureRecord};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::si::*;
+
+    fn eos(influence_parameters: &[Option<f64>]) -> Rc<PengRobinson> {
+        let records = [
+            (369.96, 4250000.0, 0.153, 44.0962),
+            (425.2, 3800000.0, 0.199, 58.123),
+        ]
+        .iter()
+        .zip(influence_parameters)
+        .map(|(&(tc, pc, w, mw), &c)| {
+            let mut record = PengRobinsonRecord::new(tc, pc, w);
+            if let Some(c) = c {
+                record = record.with_influence_parameter(c);
+            }
+            PureRecord::new(Identifier::default(), mw, record, None)
+        })
+        .collect();
+        let n = influence_parameters.len();
+        let parameters =
+            PengRobinsonParameters::from_records(records, Array2::zeros((n, n))).unwrap();
+        Rc::new(PengRobinson::new(Rc::new(parameters)))
+    }
+
+    #[test]
+    fn test_surface_tension_pure() -> EosResult<()> {
+        let options = SolverOptions::default();
+        let t = 300.0 * KELVIN;
+        let surface_tension = |c| -> EosResult<_> {
+            let vle = PhaseEquilibrium::pure(&eos(&[Some(c)]), t, None, options.clone())?;
+            Ok(
+                PlanarInterface::from_phase_equilibrium(&vle, None, options.clone())?
+                    .surface_tension(),
+            )
+        };
+
+        // the surface tension is proportional to the square root of the influence parameter
+        let gamma = surface_tension(1.4e-19)?;
+        assert_relative_eq!(surface_tension(5.6e-19)?, 2.0 * gamma, max_relative = 1e-10);
+        assert!(gamma > 5.0 * MILLI * NEWTON / METER && gamma < 10.0 * MILLI * NEWTON / METER);
+
+        // missing influence parameters are reported
+        let vle = PhaseEquilibrium::pure(&eos(&[None]), t, None, options.clone())?;
+        assert!(PlanarInterface::from_phase_equilibrium(&vle, None, options).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_surface_tension_mixture() -> EosResult<()> {
+        let options = SolverOptions::default();
+        let eos = eos(&[Some(1.4e-19), Some(2.6e-19)]);
+        let vle = PhaseEquilibrium::bubble_point(
+            &eos,
+            300.0 * KELVIN,
+            &arr1(&[0.3, 0.7]),
+            None,
+            None,
+            (options.clone(), options.clone()),
+        )?;
+        let interface = PlanarInterface::from_phase_equilibrium(&vle, None, options.clone())?;
+        let rho = interface.partial_density();
+        assert_relative_eq!(
+            rho.get((1, 199)),
+            vle.liquid().partial_density.get(1),
+            max_relative = 1e-12
+        );
+        assert!(interface
+            .grand_potential_density()
+            .to_reduced(PASCAL)?
+            .iter()
+            .all(|&o| o >= 0.0));
+
+        // the discretization error is small
+        let coarse = PlanarInterface::from_phase_equilibrium(&vle, Some(50), options)?;
+        assert_relative_eq!(
+            coarse.surface_tension(),
+            interface.surface_tension(),
+            max_relative = 1e-3
+        );
+        Ok(())
+    }
+}